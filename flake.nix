@@ -37,6 +37,8 @@
               libxkbcommon
               vulkan-loader
               wayland
+              # for the gamepad feature
+              udev
             ];
 
             shellHook = ''
@@ -0,0 +1,133 @@
+use egui::{
+    vec2, Button, Color32, Response, RichText, Ui, Vec2, WidgetInfo, WidgetText, WidgetType,
+};
+
+/// small enough to fit in a row, big enough to hit on a trackpad
+pub const MIN_TARGET: Vec2 = vec2(24.0, 20.0);
+
+/// a button whose text doesn't say what it does on its own, `accessible_name`
+/// is shown on hover and is what screen readers announce
+pub fn labeled_button(
+    ui: &mut Ui,
+    label: impl Into<WidgetText>,
+    accessible_name: &str,
+) -> Response {
+    let response = ui
+        .add(Button::new(label).min_size(MIN_TARGET))
+        .on_hover_text(accessible_name);
+
+    let enabled = response.enabled();
+
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, enabled, accessible_name));
+
+    response
+}
+
+/// the red X for removing things
+pub fn danger_button(ui: &mut Ui, label: &str, accessible_name: &str) -> Response {
+    labeled_button(
+        ui,
+        RichText::new(label).color(Color32::from_rgb(0xf3, 0x8b, 0xa8)),
+        accessible_name,
+    )
+}
+
+/// names the arrow of a collapsing section, egui only calls it show or hide
+pub fn name_toggle(toggle: &Response, section: &str) {
+    let enabled = toggle.enabled();
+
+    toggle.widget_info(|| {
+        WidgetInfo::labeled(
+            WidgetType::Button,
+            enabled,
+            format!("expand or collapse {section}"),
+        )
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use egui::{Context, Event, Key, Modifiers, RawInput};
+
+    use super::*;
+
+    fn frame(ctx: &Context, input: RawInput, ids: &mut Vec<egui::Id>) -> egui::FullOutput {
+        ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ids.clear();
+                ids.push(danger_button(ui, "X", "remove binding a → IntakeRun").id);
+                ids.push(labeled_button(ui, "reset", "reset speed to the default").id);
+            });
+        })
+    }
+
+    fn tab() -> RawInput {
+        RawInput {
+            events: vec![Event::Key {
+                key: Key::Tab,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Modifiers::NONE,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn screen_readers_get_the_name() {
+        let ctx = Context::default();
+        ctx.enable_accesskit();
+
+        let mut ids = Vec::new();
+
+        let output = frame(&ctx, RawInput::default(), &mut ids);
+
+        let update = output.platform_output.accesskit_update.unwrap();
+
+        let labels = update
+            .nodes
+            .iter()
+            .filter_map(|(_, node)| node.label())
+            .collect::<Vec<_>>();
+
+        assert!(labels.contains(&"remove binding a → IntakeRun"));
+        assert!(labels.contains(&"reset speed to the default"));
+        assert!(!labels.contains(&"X"));
+    }
+
+    #[test]
+    fn targets_have_a_minimum_size() {
+        let ctx = Context::default();
+
+        let mut ids = Vec::new();
+
+        frame(&ctx, RawInput::default(), &mut ids);
+
+        for id in ids {
+            let rect = ctx.read_response(id).unwrap().rect;
+
+            assert!(rect.width() >= MIN_TARGET.x && rect.height() >= MIN_TARGET.y);
+        }
+    }
+
+    #[test]
+    fn tab_goes_in_reading_order() {
+        let ctx = Context::default();
+
+        let mut ids = Vec::new();
+
+        frame(&ctx, RawInput::default(), &mut ids);
+
+        let mut focused = Vec::new();
+
+        for _ in 0..2 {
+            frame(&ctx, tab(), &mut ids);
+            frame(&ctx, RawInput::default(), &mut ids);
+
+            focused.push(ctx.memory(|m| m.focused()).unwrap());
+        }
+
+        assert_eq!(focused, ids);
+    }
+}
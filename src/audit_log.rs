@@ -0,0 +1,141 @@
+use std::{
+    fs::{metadata, read_to_string, rename, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// past this the log is moved to `old_path` and a new one started, so it
+/// never grows past about twice this
+pub const MAX_LOG_SIZE: u64 = 1_000_000;
+
+/// one edit to the project, a line of the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: String,
+    // the account on the computer that made the edit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+    pub kind: String,
+    pub summary: String,
+}
+
+impl Entry {
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+
+        [&self.at, &self.kind, &self.summary]
+            .into_iter()
+            .chain(&self.by)
+            .any(|field| field.to_lowercase().contains(&filter))
+    }
+}
+
+/// who is logged in to this computer, for telling apart the laptops a team
+/// shares the project between
+pub fn current_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+}
+
+pub fn path(deploy_dir: &Path) -> PathBuf {
+    deploy_dir.join("bindings_log.jsonl")
+}
+
+/// where the log goes once it's too big, replacing the one from before
+pub fn old_path(deploy_dir: &Path) -> PathBuf {
+    deploy_dir.join("bindings_log.1.jsonl")
+}
+
+/// adds `entries` to the end of the log, starting a new one first when it's
+/// past `MAX_LOG_SIZE`
+pub fn append(deploy_dir: &Path, entries: &[Entry], max_size: u64) -> Result<()> {
+    let log = path(deploy_dir);
+
+    if metadata(&log).is_ok_and(|m| m.len() > max_size) {
+        rename(&log, old_path(deploy_dir)).with_context(|| "failed to rotate the change log")?;
+    }
+
+    let mut lines = String::new();
+
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).unwrap());
+        lines.push('\n');
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .with_context(|| "failed to write to the change log")
+}
+
+/// the last `count` entries, oldest first. lines that don't parse are skipped
+pub fn tail(deploy_dir: &Path, count: usize) -> Vec<Entry> {
+    let mut entries = [old_path(deploy_dir), path(deploy_dir)]
+        .iter()
+        .filter_map(|log| read_to_string(log).ok())
+        .flat_map(|file| {
+            file.lines()
+                .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    entries.drain(..entries.len().saturating_sub(count));
+
+    entries
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn entry(summary: &str) -> Entry {
+        Entry {
+            at: "2026-10-16T18:00:00Z".to_string(),
+            by: Some("mentor".to_string()),
+            kind: "AddCommand".to_string(),
+            summary: summary.to_string(),
+        }
+    }
+
+    #[test]
+    fn log_rotates_and_tails_across_both_files() {
+        let temp = temp_dir("audit");
+        let dir = temp.path();
+
+        assert!(tail(dir, 10).is_empty());
+
+        append(
+            dir,
+            &[entry("+ command shoot"), entry("+ command climb")],
+            100,
+        )
+        .unwrap();
+
+        // past the limit, the next append starts a new file
+        append(dir, &[entry("- command shoot")], 100).unwrap();
+
+        assert!(old_path(dir).exists());
+        assert_eq!(read_to_string(path(dir)).unwrap().lines().count(), 1);
+
+        std::fs::write(path(dir), read_to_string(path(dir)).unwrap() + "not json\n").unwrap();
+
+        assert_eq!(
+            tail(dir, 2),
+            vec![entry("+ command climb"), entry("- command shoot")]
+        );
+        assert_eq!(tail(dir, 10).len(), 3);
+
+        assert!(entry("+ command climb").matches("CLIMB"));
+        assert!(entry("+ command climb").matches("mentor"));
+        assert!(!entry("+ command climb").matches("shoot"));
+    }
+}
@@ -2,24 +2,71 @@ use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet},
     fmt::Display,
-    fs::{read_dir, read_to_string},
+    fs::read_dir,
+    io::Write,
     path::Path,
     rc::Rc,
 };
 
 use bumpalo::Bump;
-use egui::{ComboBox, Id, Ui};
+use egui::{ComboBox, Id, Key, Response, Ui};
+use flate2::{write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use anyhow::{Context, Result};
 
 use crate::{
+    codegen::KotlinKeys,
+    conflict_rules::{self, Finding, Severity, Trigger},
     constants::Constants,
-    global_state::State,
+    disk_space, file_hashes,
+    global_state::{self, State},
+    maintenance::Retention,
+    name_check,
     search_selector::{self, SingleCache},
     ProgramError,
 };
 
+pub const CONTROLLER_SLOTS: usize = 5;
+
+// the most the driver station will report for a single joystick
+pub const MAX_BUTTONS: i16 = 32;
+pub const MAX_AXISES: i16 = 32;
+
+const XBOX_BUTTONS: [&str; 10] = [
+    "a",
+    "b",
+    "x",
+    "y",
+    "left bumper",
+    "right bumper",
+    "back",
+    "start",
+    "left stick",
+    "right stick",
+];
+
+// longest cooldown a command can have, anything more is surely a typo
+pub const MAX_COOLDOWN: f32 = 10.0;
+
+// what the threshold box starts at for analog bindings
+pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// every pov angle a controller reports and what it's called, -1 is the pov
+/// being let go
+pub const POVS: [(i16, &str); 9] = [
+    (-1, "no pov"),
+    (0, "pov up"),
+    (45, "pov up right"),
+    (90, "pov right"),
+    (135, "pov down right"),
+    (180, "pov down"),
+    (225, "pov down left"),
+    (270, "pov left"),
+    (315, "pov up left"),
+];
+
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Clone, Copy)]
 pub enum ButtonLocation {
     Button,
@@ -29,8 +76,20 @@ pub enum ButtonLocation {
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Clone, Copy)]
 pub struct Button {
-    pub(crate) button: i16,
-    pub(crate) location: ButtonLocation,
+    pub button: i16,
+    pub location: ButtonLocation,
+}
+
+impl Button {
+    /// whether this button can exist on any controller at all, regardless of
+    /// what is currently plugged into the slot
+    pub fn in_range(&self) -> bool {
+        match self.location {
+            ButtonLocation::Button => 1 <= self.button && self.button <= MAX_BUTTONS,
+            ButtonLocation::Pov => POVS.iter().any(|(angle, _)| *angle == self.button),
+            ButtonLocation::Analog => 0 <= self.button && self.button < MAX_AXISES,
+        }
+    }
 }
 
 impl Default for Button {
@@ -79,16 +138,35 @@ impl RunWhen {
         .into_iter()
     }
 
-    pub fn selection_ui(&mut self, ui: &mut Ui, id: impl std::hash::Hash) {
+    /// the arrow keys step through the choices while the box has focus
+    pub fn selection_ui(&mut self, ui: &mut Ui, id: impl std::hash::Hash) -> Response {
         ui.push_id(id, |ui| {
-            ComboBox::from_label("")
+            let response = ComboBox::from_label("")
                 .selected_text(self.get_str())
                 .show_ui(ui, |ui| {
                     for i in RunWhen::enumerate() {
                         ui.selectable_value(self, i, i.get_str());
                     }
-                });
-        });
+                })
+                .response;
+
+            if response.has_focus() {
+                let all = RunWhen::enumerate().collect::<Vec<_>>();
+                let at = all.iter().position(|w| w == self).unwrap_or(0);
+
+                let (up, down) =
+                    ui.input(|i| (i.key_pressed(Key::ArrowUp), i.key_pressed(Key::ArrowDown)));
+
+                if up && at > 0 {
+                    *self = all[at - 1];
+                } else if down && at + 1 < all.len() {
+                    *self = all[at + 1];
+                }
+            }
+
+            response
+        })
+        .inner
     }
 }
 
@@ -98,33 +176,214 @@ impl Display for RunWhen {
     }
 }
 
+/// how long the button has to stay pressed (or released) before the command
+/// runs. kept in whole milliseconds so bindings stay hashable, saved as
+/// seconds for wpilib's `debounce`
+#[derive(Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Debounce {
+    pub millis: u32,
+}
+
+impl Debounce {
+    pub const NONE: Debounce = Debounce { millis: 0 };
+
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self {
+            millis: (seconds.max(0.0) * 1000.0).round() as u32,
+        }
+    }
+
+    pub fn seconds(self) -> f64 {
+        self.millis as f64 / 1000.0
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.millis == 0
+    }
+}
+
+impl Serialize for Debounce {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.seconds())
+    }
+}
+
+impl<'de> Deserialize<'de> for Debounce {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self::from_seconds)
+    }
+}
+
+impl Display for Debounce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "after {} s", self.seconds())
+    }
+}
+
+/// how far an axis has to go before an analog binding counts as pressed,
+/// negative values trigger past that point the other way. kept in hundredths
+/// for the same reason as `Debounce`
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Threshold {
+    pub hundredths: i8,
+}
+
+impl Threshold {
+    pub fn from_value(value: f64) -> Self {
+        Self {
+            hundredths: (value.clamp(-1.0, 1.0) * 100.0).round() as i8,
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        self.hundredths as f64 / 100.0
+    }
+}
+
+impl Serialize for Threshold {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.value())
+    }
+}
+
+impl<'de> Deserialize<'de> for Threshold {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self::from_value)
+    }
+}
+
+impl Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "past {}", self.value())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Clone, Copy)]
 pub struct Binding {
     pub controller: u8,
     pub button: Button,
     pub during: RunWhen, // bad name because "when" is a reserved keyword in kotlin and im lazy
+    // left out of the file when there's none so robot code that doesn't know
+    // about it still reads the bindings
+    #[serde(default, skip_serializing_if = "Debounce::is_none")]
+    pub debounce: Debounce,
+    // only analog bindings have one, none leaves it up to the robot code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<Threshold>,
+    // held on the same controller for the binding to fire, "left bumper + a"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<Button>,
+    // switched off for now without losing it, only written when it's off
+    #[serde(default = "enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
 }
 
 impl Binding {
-    pub fn show<'a>(&self, env: &State, arena: &'a Bump) -> &'a str {
-        bumpalo::format!(in arena,
-            "on {} to {} {}",
+    /// the binding without its debounce or threshold, a command has at most
+    /// one of each per button and `RunWhen`. switched off or on it's the same
+    /// binding
+    pub fn trigger(self) -> Binding {
+        Binding {
+            debounce: Debounce::NONE,
+            threshold: None,
+            enabled: true,
+            ..self
+        }
+    }
+
+    /// the binding switched on, so a disabled copy compares equal to it
+    pub fn identity(self) -> Binding {
+        Binding {
+            enabled: true,
+            ..self
+        }
+    }
+
+    pub fn key(&self) -> BindingKey {
+        ((self.controller, self.button), self.modifier)
+    }
+
+    pub fn in_range(&self) -> bool {
+        (self.controller as usize) < CONTROLLER_SLOTS
+            && self.button.in_range()
+            && self.modifier.is_none_or(|m| m.in_range())
+    }
+
+    /// moves the binding, or its modifier, from `a` to `b` or from `b` to `a`,
+    /// anything else is left alone
+    pub fn swap_button(&mut self, a: PButton, b: PButton) {
+        let swapped = |on: PButton| match on {
+            on if on == a => b,
+            on if on == b => a,
+            on => on,
+        };
+
+        let modifier = self.modifier.map(|m| swapped((self.controller, m)));
+
+        (self.controller, self.button) = swapped((self.controller, self.button));
+
+        // a modifier can only follow when it stays on the binding's controller
+        if let Some((controller, m)) = modifier {
+            if controller == self.controller {
+                self.modifier = Some(m);
+            }
+        }
+    }
+
+    /// how the binding reads in messages about `command`, with the note
+    /// `command` has on it
+    pub fn show<'a>(&self, command: &String, env: &State, arena: &'a Bump) -> &'a str {
+        let shown = self.show_without_note(env, arena);
+
+        match env.binding_note(command, *self) {
+            Some(note) => bumpalo::format!(in arena, "{} ({})", shown, note).into_bump_str(),
+            None => shown,
+        }
+    }
+
+    pub fn show_without_note<'a>(&self, env: &State, arena: &'a Bump) -> &'a str {
+        let mut shown = bumpalo::format!(in arena,
+            "on {} to {}",
             env.controller_name(self.controller),
-            env.controllers[self.controller as usize].button_name(&self.button, arena),
-            self.during
-        )
-        .into_bump_str()
+            env.controllers[self.controller as usize].chord_name(&self.button, self.modifier, arena),
+        );
+
+        if let Some(threshold) = self.threshold {
+            shown.push_str(bumpalo::format!(in arena, " {}", threshold).as_str());
+        }
+
+        shown.push_str(bumpalo::format!(in arena, " {}", self.during).as_str());
+
+        if !self.debounce.is_none() {
+            shown.push_str(bumpalo::format!(in arena, " {}", self.debounce).as_str());
+        }
+
+        shown.into_bump_str()
     }
 }
 
 pub type BoundCommands = Vec<(Rc<String>, RunWhen)>;
 
+/// a command and one of its bindings
+pub type CommandBinding<'a> = (&'a Rc<String>, Binding);
+
 pub type PButton = (u8, Button);
 
+/// a button and the modifier held with it, if any
+pub type BindingKey = (PButton, Option<Button>);
+
 #[derive(Debug, Default)]
-pub(crate) struct BindingsMap {
+pub struct BindingsMap {
     pub command_to_bindings: BTreeMap<Rc<String>, Vec<Binding>>,
-    pub binding_to_commands: BTreeMap<PButton, BoundCommands>,
+    pub binding_to_commands: BTreeMap<BindingKey, BoundCommands>,
 }
 
 impl From<BTreeMap<Rc<String>, Vec<Binding>>> for BindingsMap {
@@ -134,7 +393,7 @@ impl From<BTreeMap<Rc<String>, Vec<Binding>>> for BindingsMap {
         for (command, bindings) in &command_to_bindings {
             for b in bindings {
                 binding_to_command
-                    .entry((b.controller, b.button))
+                    .entry(b.key())
                     .or_insert(Vec::new())
                     .push((command.clone(), b.during));
             }
@@ -148,38 +407,63 @@ impl From<BTreeMap<Rc<String>, Vec<Binding>>> for BindingsMap {
 }
 
 impl BindingsMap {
-    pub(crate) fn add_binding(&mut self, command: Rc<String>, binding: Binding) {
+    pub fn add_binding(&mut self, command: Rc<String>, binding: Binding) {
         println!("got to adding_bindings");
 
-        if !self
+        // binding the same trigger again only changes its debounce
+        if let Some(existing) = self
             .command_to_bindings
-            .get(&command)
-            .unwrap_or(&Vec::new())
-            .contains(&binding)
+            .get_mut(&command)
+            .and_then(|bindings| {
+                bindings
+                    .iter_mut()
+                    .find(|b| b.trigger() == binding.trigger())
+            })
         {
-            self.command_to_bindings
-                .entry(command.clone())
-                .or_default()
-                .push(binding);
-
-            self.binding_to_commands
-                .entry((binding.controller, binding.button))
-                .or_default()
-                .push((command, binding.during));
+            existing.debounce = binding.debounce;
+            existing.threshold = binding.threshold;
+            existing.enabled = binding.enabled;
+            return;
         }
+
+        self.command_to_bindings
+            .entry(command.clone())
+            .or_default()
+            .push(binding);
+
+        self.binding_to_commands
+            .entry(binding.key())
+            .or_default()
+            .push((command, binding.during));
     }
 
-    pub(crate) fn remove_command(&mut self, command: &String) {
+    pub fn remove_command(&mut self, command: &String) {
         self.command_to_bindings.remove(command);
         for commands in self.binding_to_commands.values_mut() {
             commands.retain(|(c, _)| c.as_ref() != command);
         }
     }
 
-    pub(crate) fn bindings_for_command(
-        &self,
-        command: &String,
-    ) -> impl Iterator<Item = Binding> + '_ {
+    /// moves `from`'s bindings over to `to`. a command with no bindings just
+    /// has nothing to move, and if `to` already has bindings the two are merged
+    pub fn rename_command(&mut self, from: &Rc<String>, to: Rc<String>) {
+        let Some(bindings) = self.command_to_bindings.remove(from) else {
+            return;
+        };
+
+        for commands in self.binding_to_commands.values_mut() {
+            commands.retain(|(c, _)| c != from);
+        }
+
+        self.binding_to_commands
+            .retain(|_, commands| !commands.is_empty());
+
+        for binding in bindings {
+            self.add_binding(to.clone(), binding);
+        }
+    }
+
+    pub fn bindings_for_command(&self, command: &String) -> impl Iterator<Item = Binding> + '_ {
         self.command_to_bindings
             .get(command)
             .into_iter()
@@ -187,49 +471,245 @@ impl BindingsMap {
             .cloned()
     }
 
-    pub(crate) fn remove_binding(&mut self, command: &String, binding: Binding) {
+    /// returns false when the binding wasn't there, e.g. a second click on a
+    /// binding another tab already removed
+    pub fn remove_binding(&mut self, command: &String, binding: Binding) -> bool {
+        let Some(bindings) = self.command_to_bindings.get_mut(command) else {
+            return false;
+        };
+
+        let before = bindings.len();
+
+        bindings.retain(|b| b.trigger() != binding.trigger());
+
+        let removed = bindings.len() != before;
+
+        if bindings.is_empty() {
+            self.command_to_bindings.remove(command);
+        }
+
+        let bind = &binding.key();
+
+        if let Some(commands) = self.binding_to_commands.get_mut(bind) {
+            commands.retain(|(c, when): &(Rc<String>, RunWhen)| {
+                !(command == c.as_ref() && *when == binding.during)
+            });
+
+            if commands.is_empty() {
+                self.binding_to_commands.remove(bind);
+            }
+        }
+
+        removed
+    }
+
+    /// removes every binding on `controller`, returning how many there were
+    pub fn clear_controller(&mut self, controller: u8) -> usize {
+        let mut removed = 0;
+
+        for bindings in self.command_to_bindings.values_mut() {
+            let before = bindings.len();
+
+            bindings.retain(|b| b.controller != controller);
+
+            removed += before - bindings.len();
+        }
+
         self.command_to_bindings
-            .get_mut(command)
-            .unwrap()
-            .retain(|b| *b != binding);
+            .retain(|_, bindings| !bindings.is_empty());
+
+        self.binding_to_commands
+            .retain(|((c, _), _), _| *c != controller);
+
+        removed
+    }
 
-        if self
+    /// moves every binding on `from` over to `to`, the ones `valid` rejects
+    /// stay where they are and are returned
+    pub fn move_controller(
+        &mut self,
+        from: u8,
+        to: u8,
+        valid: impl Fn(&Binding) -> bool,
+    ) -> Vec<(Rc<String>, Binding)> {
+        let (moving, skipped): (Vec<_>, Vec<_>) = self
             .command_to_bindings
-            .get(command)
-            .unwrap_or(&Vec::new())
-            .is_empty()
-        {
-            self.command_to_bindings.remove(command);
+            .iter()
+            .flat_map(|(command, bindings)| {
+                bindings
+                    .iter()
+                    .filter(|b| b.controller == from)
+                    .map(|b| (command.clone(), *b))
+            })
+            .partition(|(_, b)| valid(b));
+
+        for (command, binding) in moving {
+            self.remove_binding(&command, binding);
+            self.add_binding(
+                command,
+                Binding {
+                    controller: to,
+                    ..binding
+                },
+            );
         }
 
-        let bind = &(binding.controller, binding.button);
+        skipped
+    }
 
-        self.binding_to_commands.get_mut(bind).unwrap().retain(
-            |(c, when): &(Rc<String>, RunWhen)| !(command == c.as_ref() && *when == binding.during),
-        );
+    /// exchanges everything bound to `a` with everything bound to `b`
+    pub fn swap_buttons(&mut self, a: PButton, b: PButton) {
+        if a == b {
+            return;
+        }
 
-        if self
-            .binding_to_commands
-            .get(bind)
-            .unwrap_or(&Vec::new())
-            .is_empty()
-        {
-            self.binding_to_commands.remove(bind);
+        for binding in self.command_to_bindings.values_mut().flatten() {
+            binding.swap_button(a, b);
+        }
+
+        // chords can have either button as their modifier, so every key is
+        // moved the same way the bindings were
+        self.binding_to_commands = std::mem::take(&mut self.binding_to_commands)
+            .into_iter()
+            .map(|((button, modifier), commands)| {
+                let mut key = Binding {
+                    controller: button.0,
+                    button: button.1,
+                    during: RunWhen::OnTrue,
+                    debounce: Debounce::NONE,
+                    threshold: None,
+                    modifier,
+                    enabled: true,
+                };
+
+                key.swap_button(a, b);
+
+                (key.key(), commands)
+            })
+            .collect();
+    }
+
+    pub fn has_controller(&self, controller: u8) -> bool {
+        self.command_to_bindings
+            .values()
+            .flatten()
+            .any(|b| b.controller == controller)
+    }
+
+    /// every button or chord and `RunWhen` that more than one command is
+    /// bound to
+    pub fn conflicts(&self) -> Vec<(BindingKey, RunWhen)> {
+        let mut conflicts = Vec::new();
+
+        for (button, commands) in &self.binding_to_commands {
+            for when in RunWhen::enumerate() {
+                // a disabled binding never runs alongside anything
+                let running = commands
+                    .iter()
+                    .filter(|(command, w)| *w == when && self.is_enabled(command, *button, when))
+                    .count();
+
+                if running > 1 {
+                    conflicts.push((*button, when));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// the overlaps the conflict rules warn about between enabled bindings on
+    /// different commands, with both bindings. the same button on the same
+    /// `RunWhen` is left to `conflicts`, those can be marked on purpose
+    pub fn chord_findings(
+        &self,
+        describe: impl Fn(&Trigger) -> String,
+    ) -> Vec<([CommandBinding<'_>; 2], Finding)> {
+        let bound = self
+            .command_to_bindings
+            .iter()
+            .flat_map(|(command, bindings)| {
+                bindings
+                    .iter()
+                    .filter(|b| b.enabled)
+                    .map(move |b| (command, *b, Trigger::from(*b)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut found = Vec::new();
+
+        for (i, (command_a, a, trigger_a)) in bound.iter().enumerate() {
+            for (command_b, b, trigger_b) in &bound[i + 1..] {
+                let finding = conflict_rules::finding(
+                    (command_a, trigger_a, a.during),
+                    (command_b, trigger_b, b.during),
+                    &describe,
+                );
+
+                if let Some(finding) = finding.filter(|f| f.severity == Severity::Warning) {
+                    found.push(([(*command_a, *a), (*command_b, *b)], finding));
+                }
+            }
         }
+
+        found
+    }
+
+    /// the `commands` that have no bindings at all
+    pub fn unbound<'a>(
+        &'a self,
+        commands: &'a BTreeSet<Rc<String>>,
+    ) -> impl Iterator<Item = &'a Rc<String>> + 'a {
+        commands
+            .iter()
+            .filter(|command| !self.command_to_bindings.contains_key(*command))
+    }
+
+    /// the buttons on `controller` nothing is bound to, chords included, in
+    /// the order `enumerate_buttons` gives them
+    pub fn free_buttons(
+        &self,
+        controller: u8,
+        ctype: &ControllerType,
+        arena: &Bump,
+    ) -> Vec<Button> {
+        let used = self
+            .binding_to_commands
+            .keys()
+            .filter(|((c, _), _)| *c == controller)
+            .map(|((_, button), _)| *button)
+            .collect::<BTreeSet<_>>();
+
+        ctype
+            .enumerate_buttons(arena)
+            .filter(|button| !used.contains(button))
+            .collect()
+    }
+
+    /// false when `command`'s binding on `key` for `when` is switched off
+    pub fn is_enabled(&self, command: &String, key: BindingKey, when: RunWhen) -> bool {
+        self.command_to_bindings
+            .get(command)
+            .is_none_or(|bindings| {
+                bindings
+                    .iter()
+                    .filter(|b| b.key() == key && b.during == when)
+                    .all(|b| b.enabled)
+            })
     }
 
-    pub(crate) fn has_button(&self, button: PButton) -> bool {
-        self.binding_to_commands.contains_key(&button)
+    pub fn has_button(&self, key: BindingKey) -> bool {
+        self.binding_to_commands.contains_key(&key)
     }
 
-    pub(crate) fn has_binding(&self, command: &String, binding: Binding) -> bool {
+    pub fn has_binding(&self, command: &String, binding: Binding) -> bool {
         self.command_to_bindings
             .get(command)
-            .is_some_and(|bindings| bindings.contains(&binding))
+            .is_some_and(|bindings| bindings.iter().any(|b| b.trigger() == binding.trigger()))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
 pub enum ControllerType {
     Generic {
         buttons: u8,
@@ -243,7 +723,79 @@ pub enum ControllerType {
     NotBound,
 }
 
+/// the button and axis counts a physical joystick reports to the driver station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceCaps {
+    pub buttons: u8,
+    pub axises: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapsMismatch {
+    Buttons { configured: u8, reported: u8 },
+    Axises { configured: u8, reported: u8 },
+}
+
+impl Display for CapsMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapsMismatch::Buttons {
+                configured,
+                reported,
+            } => write!(
+                f,
+                "configured with {configured} buttons but the device reports {reported}"
+            ),
+            CapsMismatch::Axises {
+                configured,
+                reported,
+            } => write!(
+                f,
+                "configured with {configured} axises but the device reports {reported}"
+            ),
+        }
+    }
+}
+
 impl ControllerType {
+    /// only generic controllers have configurable counts, so everything else
+    /// always matches
+    pub fn caps_mismatches(&self, caps: DeviceCaps) -> Vec<CapsMismatch> {
+        let mut mismatches = Vec::new();
+
+        if let ControllerType::Generic {
+            buttons, axises, ..
+        } = *self
+        {
+            if buttons != caps.buttons {
+                mismatches.push(CapsMismatch::Buttons {
+                    configured: buttons,
+                    reported: caps.buttons,
+                });
+            }
+
+            if axises != caps.axises {
+                mismatches.push(CapsMismatch::Axises {
+                    configured: axises,
+                    reported: caps.axises,
+                });
+            }
+        }
+
+        mismatches
+    }
+
+    pub fn adjusted_to(&self, caps: DeviceCaps) -> Self {
+        match *self {
+            ControllerType::Generic { sensitivity, .. } => ControllerType::Generic {
+                buttons: caps.buttons,
+                axises: caps.axises,
+                sensitivity,
+            },
+            other => other,
+        }
+    }
+
     fn num_buttons(&self) -> u8 {
         match self {
             ControllerType::Generic { buttons, .. } => *buttons,
@@ -252,7 +804,7 @@ impl ControllerType {
         }
     }
 
-    fn num_axises(&self) -> u8 {
+    pub fn num_axises(&self) -> u8 {
         match self {
             ControllerType::Generic { axises, .. } => *axises,
             ControllerType::XBox { .. } => 6,
@@ -264,38 +816,49 @@ impl ControllerType {
         !matches!(self, ControllerType::NotBound)
     }
 
+    pub fn valid_axis(&self, axis: u8) -> bool {
+        axis < self.num_axises()
+    }
+
+    /// "left bumper + a" for a chord, just the button's name otherwise
+    pub fn chord_name<'a>(
+        &self,
+        button: &Button,
+        modifier: Option<Button>,
+        arena: &'a Bump,
+    ) -> &'a str {
+        match modifier {
+            Some(modifier) => bumpalo::format!(in arena,
+                "{} + {}",
+                self.button_name(&modifier, arena),
+                self.button_name(button, arena)
+            )
+            .into_bump_str(),
+            None => self.button_name(button, arena),
+        }
+    }
+
     pub fn button_name<'a>(&self, button: &Button, arena: &'a Bump) -> &'a str {
         match button.location {
             ButtonLocation::Button => match self {
                 ControllerType::Generic { .. } => {
                     bumpalo::format!(in arena, "{}", button.button).into_bump_str()
                 }
-                ControllerType::XBox { .. } => [
-                    "a",
-                    "b",
-                    "x",
-                    "y",
-                    "left bumper",
-                    "right bumper",
-                    "back",
-                    "start",
-                    "left stick",
-                    "right stick",
-                ][button.button as usize - 1],
-                ControllerType::NotBound => "ERROR",
-            },
-            ButtonLocation::Pov => match button.button {
-                0 => "pov up",
-                45 => "pov up right",
-                90 => "pov right",
-                135 => "pov down right",
-                180 => "pov down",
-                225 => "pov down left",
-                270 => "pov left",
-                315 => "pov up left",
-                -1 => "no pov",
-                _ => "ERROR",
+                // a profile can hold any number here, e.g. one made for a
+                // generic controller before the slot was switched to xbox
+                ControllerType::XBox { .. } => button
+                    .button
+                    .checked_sub(1)
+                    .and_then(|i| usize::try_from(i).ok())
+                    .and_then(|i| XBOX_BUTTONS.get(i))
+                    .copied()
+                    .unwrap_or("invalid button"),
+                ControllerType::NotBound => "not bound",
             },
+            ButtonLocation::Pov => POVS
+                .iter()
+                .find(|(angle, _)| *angle == button.button)
+                .map_or("invalid pov", |(_, name)| name),
             ButtonLocation::Analog => match self {
                 ControllerType::Generic { .. } => {
                     bumpalo::format!(in &arena, "axis: {}", button.button).into_bump_str()
@@ -305,7 +868,7 @@ impl ControllerType {
                     3 => "right trigger",
                     _ => "invalid trigger",
                 },
-                ControllerType::NotBound => "ERROR",
+                ControllerType::NotBound => "not bound",
             },
         }
     }
@@ -322,9 +885,9 @@ impl ControllerType {
                 3 => "right trigger",
                 4 => "right x axis",
                 5 => "right y axis",
-                _ => "ERROR",
+                _ => "invalid axis",
             },
-            ControllerType::NotBound => "ERROR",
+            ControllerType::NotBound => "not bound",
         }
     }
 
@@ -353,22 +916,13 @@ impl ControllerType {
 
     pub fn enumerate_povs<'a>(&self, arena: &'a Bump) -> &'a mut dyn Iterator<Item = Button> {
         match self {
-            Self::Generic { .. } | Self::XBox { .. } => arena.alloc(
-                [-1, 0, 45, 90, 135, 180, 225, 270, 315]
-                    .into_iter()
-                    .map(|dir| Button {
-                        button: dir,
-                        location: ButtonLocation::Pov,
-                    }),
-            ),
-            _ => arena.alloc(
-                [-1, 0, 45, 90, 135, 180, 225, 270, 315]
-                    .into_iter()
-                    .map(|dir| Button {
-                        button: dir,
-                        location: ButtonLocation::Pov,
-                    }),
-            ),
+            Self::Generic { .. } | Self::XBox { .. } => {
+                arena.alloc(POVS.into_iter().map(|(angle, _)| Button {
+                    button: angle,
+                    location: ButtonLocation::Pov,
+                }))
+            }
+            Self::NotBound => arena.alloc([].into_iter()),
         }
     }
 
@@ -395,7 +949,7 @@ impl ControllerType {
         button: &mut Button,
         ui: &mut Ui,
         arena: &Bump,
-    ) {
+    ) -> search_selector::Selected {
         search_selector::search_selector(
             id,
             filter,
@@ -406,10 +960,44 @@ impl ControllerType {
                     button,
                 )
             }),
+            None,
+            None,
             filter_cache,
             100.0,
             ui,
-        );
+        )
+    }
+
+    /// lets the modifier be left out, it's only shown while `modifier` is some
+    pub fn show_modifier_selector(
+        &self,
+        id: Id,
+        filter: &mut String,
+        filter_cache: &mut SingleCache<String, Vec<(Rc<String>, Button)>>,
+        modifier: &mut Option<Button>,
+        ui: &mut Ui,
+        arena: &Bump,
+    ) {
+        let mut held = modifier.is_some();
+
+        if ui.checkbox(&mut held, "held with").changed() {
+            *modifier = held.then_some(Button::default());
+        }
+
+        if let Some(modifier) = modifier {
+            self.show_button_selector(id, filter, filter_cache, modifier, ui, arena);
+        }
+    }
+
+    /// `button` with `modifier` held. a modifier has to be a button or pov on
+    /// this controller, axes have no single held position
+    pub fn valid_trigger(&self, button: Button, modifier: Option<Button>) -> bool {
+        self.valid_binding(button)
+            && modifier.is_none_or(|modifier| {
+                modifier != button
+                    && modifier.location != ButtonLocation::Analog
+                    && self.valid_binding(modifier)
+            })
     }
 
     pub fn valid_binding(&self, binding: Button) -> bool {
@@ -419,7 +1007,7 @@ impl ControllerType {
             }
             ButtonLocation::Pov => match self {
                 ControllerType::NotBound => false,
-                _ => [-1, 0, 45, 90, 135, 180, 225, 270].contains(&binding.button),
+                _ => binding.in_range(),
             },
             ButtonLocation::Analog => match self {
                 ControllerType::Generic { axises, .. } => binding.button < *axises as i16,
@@ -430,35 +1018,519 @@ impl ControllerType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub(crate) struct Profile<'a> {
-    pub(crate) command_to_bindings: Cow<'a, BTreeMap<Rc<String>, Vec<Binding>>>,
-    pub(crate) stream_to_axis: Cow<'a, BTreeMap<Rc<String>, (u8, u8)>>,
-    pub(crate) controllers: Cow<'a, [ControllerType; 5]>,
-    pub(crate) controller_names: Cow<'a, [Rc<String>; 5]>,
-    pub(crate) constants: Cow<'a, Constants>,
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Profile<'a> {
+    pub command_to_bindings: Cow<'a, BTreeMap<Rc<String>, Vec<Binding>>>,
+    pub stream_to_axis: Cow<'a, BTreeMap<Rc<String>, (u8, u8)>>,
+    pub controllers: Cow<'a, [ControllerType; 5]>,
+    pub controller_names: Cow<'a, [Rc<String>; 5]>,
+    // colors picked in manage controllers, empty slots use the palette
+    #[serde(default)]
+    pub controller_colors: Cow<'a, [Option<[u8; 3]>; CONTROLLER_SLOTS]>,
+    pub constants: Cow<'a, Constants>,
+    #[serde(default, skip_serializing_if = "Quarantine::is_empty")]
+    pub quarantine: Cow<'a, Quarantine>,
+    #[serde(default)]
+    pub description: Cow<'a, str>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    // buttons where several commands running together is on purpose, they
+    // don't count as conflicts
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub intentional: Cow<'a, BTreeSet<Binding>>,
+    // what a binding is for, kept apart from the binding itself so two
+    // bindings that only differ in their note are still the same binding
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub binding_notes: Cow<'a, BTreeMap<Rc<String>, Vec<BindingNote>>>,
+    // switched off bindings moved out of `command_to_bindings` so robot code
+    // never sees them, only filled in while writing. loading puts them back
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub disabled_bindings: Cow<'a, BTreeMap<Rc<String>, Vec<Binding>>>,
 }
 
-impl Profile<'_> {
-    pub fn get_from(deploy: &Path, profile: &str) -> Result<Self> {
-        let mut path = deploy.to_owned();
-
-        path.push("bindings");
-        path.push(format!("{profile}.json"));
+/// how switched off bindings are written for the robot
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisabledBindings {
+    // moved to their own field, robot code reading `command_to_bindings`
+    // never sees them
+    #[default]
+    Omit,
+    // left in place with `"enabled": false`, robot code has to skip them
+    Flag,
+}
 
-        if path.is_dir() {
-            return Err(ProgramError::ExistingDirectoryAt(path))?;
-        }
+/// when edits are written to disk
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SaveMode {
+    // shortly after every change, and synced straight after
+    #[default]
+    Auto,
+    // only when saved by hand, for trying things out without touching the
+    // robot
+    Manual,
+}
 
-        if !path.exists() {
-            return Ok(Default::default());
-        }
+/// what sends the files to the robot
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncBackend {
+    // the scp on the path
+    #[default]
+    Scp,
+    // ssh built in, for computers without scp. only in builds with the
+    // native-ssh feature
+    Native,
+}
 
-        let file = read_to_string(path)?;
+/// a note on one of a command's bindings, matched on the binding's trigger so
+/// changing the debounce or threshold keeps it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BindingNote {
+    pub binding: Binding,
+    pub note: String,
+}
 
-        let profile: Profile = serde_json::from_str(&file)?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeConflict {
+    Command(Rc<String>),
+    Stream(Rc<String>),
+}
 
-        Ok(profile)
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeConflict::Command(command) => {
+                write!(f, "command {command} is bound differently")
+            }
+            MergeConflict::Stream(stream) => {
+                write!(f, "stream {stream} is on a different axis")
+            }
+        }
+    }
+}
+
+/// a binding that can never fire with the controllers its profile has
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadBinding {
+    pub profile: Rc<String>,
+    pub command: Rc<String>,
+    pub binding: Binding,
+    pub controller: Rc<String>,
+    pub not_bound: bool,
+}
+
+impl Display for DeadBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = |button: Button| match button.location {
+            ButtonLocation::Button => "button",
+            ButtonLocation::Analog => "axis",
+            ButtonLocation::Pov => "pov",
+        };
+
+        write!(
+            f,
+            "{}: {} on {} to ",
+            self.profile, self.command, self.controller
+        )?;
+
+        if let Some(modifier) = self.binding.modifier {
+            write!(f, "{} {} + ", location(modifier), modifier.button)?;
+        }
+
+        write!(
+            f,
+            "{} {} {}",
+            location(self.binding.button),
+            self.binding.button.button,
+            self.binding.during
+        )?;
+
+        if self.not_bound {
+            write!(f, " (controller not bound)")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// parts of a profile that don't fit this version's layout (extra controller
+/// slots, buttons no controller can have, ...). they are kept verbatim and
+/// written back out on save so nothing gets lost.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct Quarantine {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub controllers: BTreeMap<usize, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub controller_names: BTreeMap<usize, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub command_to_bindings: BTreeMap<Rc<String>, Vec<Value>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stream_to_axis: BTreeMap<Rc<String>, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub other: BTreeMap<String, Value>,
+}
+
+impl Quarantine {
+    pub fn is_empty(&self) -> bool {
+        self.controllers.is_empty()
+            && self.controller_names.is_empty()
+            && self.command_to_bindings.is_empty()
+            && self.stream_to_axis.is_empty()
+            && self.other.is_empty()
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (slot, controller) in &self.controllers {
+            lines.push(format!("controller in slot {slot}: {controller}"));
+        }
+
+        for (slot, name) in &self.controller_names {
+            lines.push(format!("controller name in slot {slot}: {name}"));
+        }
+
+        for (command, bindings) in &self.command_to_bindings {
+            for binding in bindings {
+                lines.push(format!("binding for {command}: {binding}"));
+            }
+        }
+
+        for (stream, axis) in &self.stream_to_axis {
+            lines.push(format!("axis for stream {stream}: {axis}"));
+        }
+
+        for (key, value) in &self.other {
+            lines.push(format!("unknown field {key}: {value}"));
+        }
+
+        lines
+    }
+
+    fn slots<T>(
+        value: Option<Value>,
+        field: &str,
+        slots: &mut [T; CONTROLLER_SLOTS],
+        quarantined: &mut BTreeMap<usize, Value>,
+        other: &mut BTreeMap<String, Value>,
+    ) where
+        T: for<'de> Deserialize<'de>,
+    {
+        match value {
+            Some(Value::Array(values)) => {
+                for (slot, value) in values.into_iter().enumerate() {
+                    match slots.get_mut(slot) {
+                        Some(s) => match serde_json::from_value(value.clone()) {
+                            Ok(v) => *s = v,
+                            Err(_) => {
+                                quarantined.insert(slot, value);
+                            }
+                        },
+                        None => {
+                            quarantined.insert(slot, value);
+                        }
+                    }
+                }
+            }
+            Some(value) => {
+                other.insert(field.to_string(), value);
+            }
+            None => {}
+        }
+    }
+}
+
+impl Profile<'static> {
+    /// loads a profile without failing on anything this version doesn't
+    /// understand, putting those pieces into the quarantine instead
+    pub fn from_str_lenient(s: &str) -> Result<Self> {
+        let mut fields: Map<String, Value> = serde_json::from_str(s)?;
+
+        let mut quarantine: Quarantine = match fields.remove("quarantine") {
+            Some(q) => serde_json::from_value(q.clone()).unwrap_or_else(|_| Quarantine {
+                other: BTreeMap::from([("quarantine".to_string(), q)]),
+                ..Default::default()
+            }),
+            None => Default::default(),
+        };
+
+        let mut controllers: [ControllerType; CONTROLLER_SLOTS] = Default::default();
+
+        Quarantine::slots(
+            fields.remove("controllers"),
+            "controllers",
+            &mut controllers,
+            &mut quarantine.controllers,
+            &mut quarantine.other,
+        );
+
+        let mut controller_names: [Rc<String>; CONTROLLER_SLOTS] = Default::default();
+
+        Quarantine::slots(
+            fields.remove("controller_names"),
+            "controller_names",
+            &mut controller_names,
+            &mut quarantine.controller_names,
+            &mut quarantine.other,
+        );
+
+        let controller_colors = match fields.remove("controller_colors") {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|_| {
+                quarantine
+                    .other
+                    .insert("controller_colors".to_string(), value);
+                Default::default()
+            }),
+            None => Default::default(),
+        };
+
+        let mut command_to_bindings: BTreeMap<Rc<String>, Vec<Binding>> = BTreeMap::new();
+
+        for (field, enabled) in [("command_to_bindings", true), ("disabled_bindings", false)] {
+            match fields.remove(field) {
+                Some(Value::Object(map)) => {
+                    for (command, bindings) in map {
+                        let command = Rc::new(command);
+
+                        let bindings = match bindings {
+                            Value::Array(bindings) => bindings,
+                            bindings => vec![bindings],
+                        };
+
+                        for value in bindings {
+                            match serde_json::from_value::<Binding>(value.clone()) {
+                                Ok(binding) if binding.in_range() => command_to_bindings
+                                    .entry(command.clone())
+                                    .or_default()
+                                    .push(Binding {
+                                        enabled: binding.enabled && enabled,
+                                        ..binding
+                                    }),
+                                _ => quarantine
+                                    .command_to_bindings
+                                    .entry(command.clone())
+                                    .or_default()
+                                    .push(value),
+                            }
+                        }
+                    }
+                }
+                Some(value) => {
+                    quarantine.other.insert(field.to_string(), value);
+                }
+                None => {}
+            }
+        }
+
+        let mut stream_to_axis: BTreeMap<Rc<String>, (u8, u8)> = BTreeMap::new();
+
+        match fields.remove("stream_to_axis") {
+            Some(Value::Object(map)) => {
+                for (stream, value) in map {
+                    match serde_json::from_value::<(u8, u8)>(value.clone()) {
+                        Ok(axis)
+                            if controllers
+                                .get(axis.0 as usize)
+                                .is_some_and(|c| c.valid_axis(axis.1)) =>
+                        {
+                            stream_to_axis.insert(Rc::new(stream), axis);
+                        }
+                        _ => {
+                            quarantine.stream_to_axis.insert(Rc::new(stream), value);
+                        }
+                    }
+                }
+            }
+            Some(value) => {
+                quarantine.other.insert("stream_to_axis".to_string(), value);
+            }
+            None => {}
+        }
+
+        let constants = match fields.remove("constants") {
+            Some(value) => match serde_json::from_value(value.clone()) {
+                Ok(constants) => constants,
+                Err(_) => {
+                    quarantine.other.insert("constants".to_string(), value);
+                    Constants::None
+                }
+            },
+            None => Constants::None,
+        };
+
+        let description = match fields.remove("description") {
+            Some(Value::String(description)) => description,
+            Some(value) => {
+                quarantine.other.insert("description".to_string(), value);
+                String::new()
+            }
+            None => String::new(),
+        };
+
+        let last_modified = match fields.remove("last_modified") {
+            Some(Value::String(last_modified)) => Some(last_modified),
+            Some(Value::Null) | None => None,
+            Some(value) => {
+                quarantine.other.insert("last_modified".to_string(), value);
+                None
+            }
+        };
+
+        let intentional = match fields.remove("intentional") {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|_| {
+                quarantine.other.insert("intentional".to_string(), value);
+                BTreeSet::new()
+            }),
+            None => BTreeSet::new(),
+        };
+
+        let mut binding_notes: BTreeMap<Rc<String>, Vec<BindingNote>> =
+            match fields.remove("binding_notes") {
+                Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|_| {
+                    quarantine.other.insert("binding_notes".to_string(), value);
+                    BTreeMap::new()
+                }),
+                None => BTreeMap::new(),
+            };
+
+        quarantine.other.extend(fields);
+
+        name_check::normalize_keys(&mut command_to_bindings, |bindings, other| {
+            for binding in other {
+                if !bindings.contains(&binding) {
+                    bindings.push(binding);
+                }
+            }
+        });
+
+        name_check::normalize_keys(&mut stream_to_axis, |_, _| {});
+
+        name_check::normalize_keys(&mut binding_notes, |notes, other| notes.extend(other));
+
+        Ok(Profile {
+            command_to_bindings: Cow::Owned(command_to_bindings),
+            stream_to_axis: Cow::Owned(stream_to_axis),
+            controllers: Cow::Owned(controllers),
+            controller_names: Cow::Owned(controller_names),
+            controller_colors: Cow::Owned(controller_colors),
+            constants: Cow::Owned(constants),
+            quarantine: Cow::Owned(quarantine),
+            description: Cow::Owned(description),
+            last_modified,
+            intentional: Cow::Owned(intentional),
+            binding_notes: Cow::Owned(binding_notes),
+            disabled_bindings: Cow::Owned(BTreeMap::new()),
+        })
+    }
+}
+
+impl Profile<'_> {
+    /// whether `profile` has a file in `deploy`, a broken one counts
+    pub fn exists(deploy: &Path, profile: &str) -> bool {
+        deploy
+            .join("bindings")
+            .join(format!("{profile}.json"))
+            .is_file()
+    }
+
+    /// the profile called `profile`, from its backup when the file is broken.
+    /// the flag is true when the backup was used
+    pub fn get_from(deploy: &Path, profile: &str) -> Result<(Self, bool)> {
+        let mut path = deploy.to_owned();
+
+        path.push("bindings");
+        path.push(format!("{profile}.json"));
+
+        if path.is_dir() {
+            return Err(ProgramError::ExistingDirectoryAt(path))?;
+        }
+
+        if !path.exists() {
+            return Ok((Default::default(), false));
+        }
+
+        file_hashes::read_or_backup(&path, Profile::from_str_lenient)
+    }
+
+    /// bindings on a slot that is not bound or on a button the slot's
+    /// controller doesn't have
+    pub fn dead_bindings(&self, profile: &Rc<String>) -> Vec<DeadBinding> {
+        let mut dead = Vec::new();
+
+        for (command, bindings) in self.command_to_bindings.iter() {
+            for binding in bindings {
+                let slot = binding.controller as usize;
+                let controller = self.controllers.get(slot);
+
+                if controller.is_some_and(|c| c.valid_trigger(binding.button, binding.modifier)) {
+                    continue;
+                }
+
+                let name = match self.controller_names.get(slot) {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ => Rc::new(binding.controller.to_string()),
+                };
+
+                dead.push(DeadBinding {
+                    profile: profile.clone(),
+                    command: command.clone(),
+                    binding: *binding,
+                    controller: name,
+                    not_bound: controller.is_none_or(|c| *c == ControllerType::NotBound),
+                });
+            }
+        }
+
+        dead
+    }
+
+    /// copies in the commands and streams `other` has that this profile doesn't,
+    /// anything both have set differently keeps this profile's value and is
+    /// returned as a conflict
+    pub fn merge_from(&mut self, other: &Profile) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+
+        let command_to_bindings = self.command_to_bindings.to_mut();
+        let mut copied = Vec::new();
+
+        for (command, bindings) in other.command_to_bindings.iter() {
+            match command_to_bindings.get(command) {
+                Some(existing) if !existing.is_empty() => {
+                    if existing != bindings {
+                        conflicts.push(MergeConflict::Command(command.clone()));
+                    }
+                }
+                _ => {
+                    command_to_bindings.insert(command.clone(), bindings.clone());
+                    copied.push(command);
+                }
+            }
+        }
+
+        // notes come along with the bindings they're on
+        for command in copied {
+            if let Some(notes) = other.binding_notes.get(command) {
+                self.binding_notes
+                    .to_mut()
+                    .insert(command.clone(), notes.clone());
+            }
+        }
+
+        let stream_to_axis = self.stream_to_axis.to_mut();
+
+        for (stream, axis) in other.stream_to_axis.iter() {
+            match stream_to_axis.get(stream) {
+                Some(existing) => {
+                    if existing != axis {
+                        conflicts.push(MergeConflict::Stream(stream.clone()));
+                    }
+                }
+                None => {
+                    stream_to_axis.insert(stream.clone(), *axis);
+                }
+            }
+        }
+
+        conflicts
     }
 
     pub fn get_owned(self) -> Profile<'static> {
@@ -467,8 +1539,60 @@ impl Profile<'_> {
             stream_to_axis: Cow::Owned(self.stream_to_axis.into_owned()),
             controllers: Cow::Owned(self.controllers.into_owned()),
             controller_names: Cow::Owned(self.controller_names.into_owned()),
+            controller_colors: Cow::Owned(self.controller_colors.into_owned()),
             constants: Cow::Owned(self.constants.into_owned()),
+            quarantine: Cow::Owned(self.quarantine.into_owned()),
+            description: Cow::Owned(self.description.into_owned()),
+            last_modified: self.last_modified,
+            intentional: Cow::Owned(self.intentional.into_owned()),
+            binding_notes: Cow::Owned(self.binding_notes.into_owned()),
+            disabled_bindings: Cow::Owned(self.disabled_bindings.into_owned()),
+        }
+    }
+
+    /// the profile as it's written to disk, switched off bindings go where
+    /// `disabled` says
+    pub fn to_json(&self, disabled: DisabledBindings) -> String {
+        let any_disabled = self
+            .command_to_bindings
+            .values()
+            .flatten()
+            .any(|b| !b.enabled);
+
+        if disabled == DisabledBindings::Flag || !any_disabled {
+            return serde_json::to_string_pretty(self).unwrap();
+        }
+
+        let mut enabled = BTreeMap::new();
+        let mut off = BTreeMap::new();
+
+        for (command, bindings) in self.command_to_bindings.iter() {
+            let (on, disabled): (Vec<_>, Vec<_>) = bindings.iter().partition(|b| b.enabled);
+
+            if !on.is_empty() {
+                enabled.insert(command.clone(), on);
+            }
+
+            if !disabled.is_empty() {
+                off.insert(command.clone(), disabled);
+            }
         }
+
+        serde_json::to_string_pretty(&Profile {
+            command_to_bindings: Cow::Owned(enabled),
+            stream_to_axis: Cow::Borrowed(&self.stream_to_axis),
+            controllers: Cow::Borrowed(&self.controllers),
+            controller_names: Cow::Borrowed(&self.controller_names),
+            controller_colors: Cow::Borrowed(&self.controller_colors),
+            constants: Cow::Borrowed(&self.constants),
+            quarantine: Cow::Borrowed(&self.quarantine),
+            description: Cow::Borrowed(&self.description),
+            last_modified: self.last_modified.clone(),
+            intentional: Cow::Borrowed(&self.intentional),
+            binding_notes: Cow::Borrowed(&self.binding_notes),
+            disabled_bindings: Cow::Owned(off),
+        })
+        .unwrap()
     }
 
     pub fn get_profiles(deploy: &Path) -> Result<Vec<Rc<String>>> {
@@ -476,40 +1600,227 @@ impl Profile<'_> {
 
         path.push("bindings");
 
-        let profiles = if path.is_dir() {
-            read_dir(&path)?
-                .map(|path| {
-                    let path = path?;
-
-                    Ok(Rc::new(
-                        path.file_name()
-                            .into_string()
-                            .unwrap()
-                            .split_once('.')
-                            .with_context(|| "failed to get profile name for file")?
-                            .0
-                            .to_string(),
-                    ))
-                })
-                .collect::<Result<Vec<_>>>()?
-        } else {
-            vec![]
-        };
+        if !path.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut profiles = Vec::new();
+
+        // anything else in there (notes, editor backups, hidden files, folders)
+        // isn't a profile
+        for entry in read_dir(&path)? {
+            let entry = entry?;
+
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+
+            match path.file_stem().and_then(|s| s.to_str()) {
+                Some(name) if !name.starts_with('.') => profiles.push(Rc::new(name.to_string())),
+                _ => {}
+            }
+        }
 
         Ok(profiles)
     }
 }
 
+/// a profile with the parts of the project it refers to, so it can be moved to
+/// another laptop as one file
+#[derive(Debug, Serialize, Default)]
+pub struct ProfileBundle<'a> {
+    pub profile: Profile<'a>,
+    pub commands: BTreeSet<Rc<String>>,
+    pub streams: BTreeSet<Rc<String>>,
+    pub constants: Cow<'a, Constants>,
+}
+
+impl ProfileBundle<'static> {
+    pub fn from_str_lenient(s: &str) -> Result<Self> {
+        let mut fields: Map<String, Value> = serde_json::from_str(s)?;
+
+        let profile = fields
+            .remove("profile")
+            .with_context(|| "not a profile bundle")?;
+
+        let mut take = |field: &str| fields.remove(field).unwrap_or_default();
+
+        let mut commands = serde_json::from_value(take("commands")).unwrap_or_default();
+        let mut streams = serde_json::from_value(take("streams")).unwrap_or_default();
+
+        // the profile's own names are normalized as it's read, these have to
+        // match them
+        name_check::normalize_set(&mut commands);
+        name_check::normalize_set(&mut streams);
+
+        Ok(ProfileBundle {
+            profile: Profile::from_str_lenient(&profile.to_string())?,
+            commands,
+            streams,
+            constants: Cow::Owned(serde_json::from_value(take("constants")).unwrap_or_default()),
+        })
+    }
+}
+
+/// extra info about a command that only matters to people reading the list
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct CommandMeta {
+    // the subsystem the command belongs to, commands are grouped by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl CommandMeta {
+    pub fn is_empty(&self) -> bool {
+        self.group.is_none() && self.description.is_none()
+    }
+}
+
+/// a robot the project is synced to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SyncTarget {
+    pub name: String,
+    pub url: String,
+    pub user: String,
+    // always ends with a /
+    pub path: String,
+    // left out of syncs when off, but kept
+    pub enabled: bool,
+}
+
+impl SyncTarget {
+    pub fn new(name: String, url: String) -> Self {
+        Self {
+            name,
+            url,
+            user: global_state::ROBOT_USER.to_string(),
+            path: global_state::ROBOT_DEPLOY_PATH.to_string(),
+            enabled: true,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
-pub(crate) struct SaveData<'a> {
-    pub(crate) url: Cow<'a, Option<String>>,
-    pub(crate) commands: Cow<'a, BTreeSet<Rc<String>>>,
-    pub(crate) constants: Cow<'a, Constants>,
-    pub(crate) streams: Cow<'a, BTreeSet<Rc<String>>>,
+pub struct SaveData<'a> {
+    // the one robot from before there could be several, read into `targets`
+    #[serde(default, skip_serializing)]
+    pub url: Option<String>,
+    pub commands: Cow<'a, BTreeSet<Rc<String>>>,
+    pub constants: Cow<'a, Constants>,
+    // the constants as the robot sees them with the active profile, every
+    // driver constant replaced by its value. only ever written, it comes from
+    // the two above
+    #[serde(default, skip_deserializing)]
+    pub resolved_constants: Cow<'a, Constants>,
+    pub streams: Cow<'a, BTreeSet<Rc<String>>>,
+    #[serde(default)]
+    pub generation: u64,
+    #[serde(default)]
+    pub sync_format: SyncFormat,
+    #[serde(default = "disk_space::default_min_free")]
+    pub min_free_space: u64,
+    #[serde(default)]
+    pub retention: Retention,
+    #[serde(default)]
+    pub required_commands: Cow<'a, BTreeSet<Rc<String>>>,
+    // seconds a command can't run again for after it ran, the robot enforces it
+    #[serde(default)]
+    pub command_cooldowns: Cow<'a, BTreeMap<Rc<String>, f32>>,
+    #[serde(default)]
+    pub command_meta: Cow<'a, BTreeMap<Rc<String>, CommandMeta>>,
+    #[serde(default)]
+    pub required_streams: Cow<'a, BTreeSet<Rc<String>>>,
+    #[serde(default)]
+    pub kotlin_keys: Cow<'a, KotlinKeys>,
+    #[serde(default)]
+    pub disabled_bindings: DisabledBindings,
+    #[serde(default)]
+    pub save_mode: SaveMode,
+    #[serde(default)]
+    pub commit_on_save: bool,
+    #[serde(default)]
+    pub sync_log_file: bool,
+    #[serde(default = "global_state::default_ssh_user", skip_serializing)]
+    pub ssh_user: Cow<'a, String>,
+    #[serde(default = "global_state::default_remote_path", skip_serializing)]
+    pub remote_path: Cow<'a, String>,
+    #[serde(default)]
+    pub targets: Cow<'a, Vec<SyncTarget>>,
+    #[serde(default)]
+    pub team_number: Option<u32>,
+    #[serde(default)]
+    pub sync_backend: SyncBackend,
+}
+
+/// how files are sent to the robot, the copies on disk are always pretty so
+/// they diff nicely. sent pretty too unless compact or gzip is turned on
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SyncFormat {
+    pub compact: bool,
+    pub gzip: bool,
+}
+
+impl SyncFormat {
+    /// whether the files on disk can be sent as they are
+    pub fn is_pretty(&self) -> bool {
+        !self.compact && !self.gzip
+    }
+
+    pub fn extension(&self) -> &'static str {
+        if self.gzip {
+            "json.gz"
+        } else {
+            "json"
+        }
+    }
+
+    /// turns a pretty file from disk into what gets sent to the robot
+    pub fn encode(&self, json: &str) -> Result<Vec<u8>> {
+        let bytes = if self.compact {
+            serde_json::to_vec(&serde_json::from_str::<Value>(json)?)?
+        } else {
+            json.as_bytes().to_vec()
+        };
+
+        if !self.gzip {
+            return Ok(bytes);
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+
+        encoder.write_all(&bytes)?;
+
+        Ok(encoder.finish()?)
+    }
 }
 
 impl SaveData<'_> {
-    pub fn from_directory(deploy: &Path) -> Result<Option<Self>> {
+    /// the robots to sync to, with the one from an older project file when it
+    /// has no list yet
+    pub fn sync_targets(&self) -> Vec<SyncTarget> {
+        match &self.url {
+            Some(url) if self.targets.is_empty() => vec![SyncTarget {
+                user: self.ssh_user.to_string(),
+                // older versions didn't check it
+                path: global_state::normalize_remote_path(&self.remote_path)
+                    .unwrap_or_else(|| global_state::ROBOT_DEPLOY_PATH.to_string()),
+                ..SyncTarget::new("robot".to_string(), url.clone())
+            }],
+            _ => self.targets.to_vec(),
+        }
+    }
+
+    /// bindings.json in `deploy`, from its backup when the file is broken. the
+    /// flag is true when the backup was used
+    pub fn from_directory(deploy: &Path) -> Result<Option<(Self, bool)>> {
         let mut path = deploy.to_owned();
 
         path.push("bindings.json");
@@ -524,10 +1835,1178 @@ impl SaveData<'_> {
             return Ok(None);
         }
 
-        let file = read_to_string(&path)?;
-
-        let bindings: SaveData = serde_json::from_str(&file)?;
+        let bindings =
+            file_hashes::read_or_backup(&path, |file| Ok(serde_json::from_str::<SaveData>(file)?))?;
 
         Ok(Some(bindings))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    const SEVEN_CONTROLLERS: &str = r#"{
+        "command_to_bindings": {
+            "shoot": [
+                {"controller": 0, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"},
+                {"controller": 6, "button": {"button": 2, "location": "Button"}, "during": "OnTrue"},
+                {"controller": 1, "button": {"button": 40, "location": "Button"}, "during": "WhileTrue"}
+            ],
+            "climb": [
+                {"controller": 1, "button": {"button": 90, "location": "Pov"}, "during": "Sideways"}
+            ]
+        },
+        "stream_to_axis": {"drive": [0, 1], "turn": [5, 0], "lift": [1, 40]},
+        "controllers": [
+            {"XBox": {"sensitivity": 0.5}},
+            {"Generic": {"buttons": 12, "axises": 4, "sensitivity": 0.5}},
+            "NotBound",
+            "NotBound",
+            {"PS5": {}},
+            "NotBound",
+            {"XBox": {"sensitivity": 0.5}}
+        ],
+        "controller_names": ["driver", "operator", "", "", "", "", "spare"],
+        "constants": null,
+        "layout_version": 2
+    }"#;
+
+    #[test]
+    fn seven_controllers() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        assert!(matches!(
+            profile.controllers[0],
+            ControllerType::XBox { .. }
+        ));
+        assert!(matches!(
+            profile.controllers[1],
+            ControllerType::Generic { buttons: 12, .. }
+        ));
+        assert!(matches!(profile.controllers[4], ControllerType::NotBound));
+        assert_eq!(profile.controller_names[0].as_str(), "driver");
+
+        let quarantine = &profile.quarantine;
+
+        assert_eq!(
+            quarantine.controllers.keys().copied().collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+        assert_eq!(
+            quarantine
+                .controller_names
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert!(quarantine.other.contains_key("layout_version"));
+    }
+
+    #[test]
+    fn set_aside_data_is_in_the_readiness_report() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        let state = State {
+            quarantine: profile.quarantine.into_owned(),
+            ..Default::default()
+        };
+
+        let report = state.readiness_report();
+
+        assert_eq!(report.len(), 11);
+        assert!(report.contains(&r#"set aside, controller name in slot 6: "spare""#.to_string()));
+        assert!(report.contains(&"set aside, axis for stream turn: [5,0]".to_string()));
+        assert!(report.contains(&"set aside, unknown field layout_version: 2".to_string()));
+    }
+
+    #[test]
+    fn out_of_range_buttons() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        let shoot = Rc::new("shoot".to_string());
+        let climb = Rc::new("climb".to_string());
+
+        assert_eq!(profile.command_to_bindings[&shoot].len(), 1);
+        assert_eq!(profile.quarantine.command_to_bindings[&shoot].len(), 2);
+
+        assert!(!profile.command_to_bindings.contains_key(&climb));
+        assert_eq!(profile.quarantine.command_to_bindings[&climb].len(), 1);
+
+        assert_eq!(profile.stream_to_axis.len(), 1);
+        assert!(profile
+            .quarantine
+            .stream_to_axis
+            .contains_key(&Rc::new("turn".to_string())));
+        assert!(profile
+            .quarantine
+            .stream_to_axis
+            .contains_key(&Rc::new("lift".to_string())));
+    }
+
+    #[test]
+    fn streams_need_a_real_axis() {
+        let profile = Profile::from_str_lenient(
+            r#"{
+                "stream_to_axis": {"drive": [0, 1], "lift": [0, 9], "turn": [1, 0]},
+                "controllers": [{"XBox": {"sensitivity": 0.5}}]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            profile.stream_to_axis.keys().collect::<Vec<_>>(),
+            vec![&Rc::new("drive".to_string())]
+        );
+        assert_eq!(
+            profile
+                .quarantine
+                .stream_to_axis
+                .keys()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>(),
+            vec!["lift", "turn"]
+        );
+    }
+
+    #[test]
+    fn quarantine_round_trips() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        let saved = serde_json::to_string(&profile).unwrap();
+
+        let reloaded = Profile::from_str_lenient(&saved).unwrap();
+
+        assert_eq!(reloaded.quarantine, profile.quarantine);
+        assert_eq!(reloaded.command_to_bindings, profile.command_to_bindings);
+    }
+
+    #[test]
+    fn profiles_exist_once_written() {
+        let temp = temp_dir("exists");
+        let dir = temp.path();
+
+        std::fs::create_dir_all(dir.join("bindings")).unwrap();
+
+        assert!(!Profile::exists(dir, "default"));
+
+        std::fs::write(
+            dir.join("bindings").join("default.json"),
+            serde_json::to_string(&Profile::default()).unwrap(),
+        )
+        .unwrap();
+
+        assert!(Profile::exists(dir, "default"));
+        assert!(!Profile::exists(dir, "practice"));
+    }
+
+    #[test]
+    fn clean_profile_has_no_quarantine() {
+        let profile =
+            Profile::from_str_lenient(include_str!("main/deploy/bindings/default.json")).unwrap();
+
+        assert!(profile.quarantine.is_empty());
+
+        let saved = serde_json::to_string(&profile).unwrap();
+
+        assert!(!saved.contains("quarantine"));
+    }
+
+    #[test]
+    fn generation_round_trips() {
+        let old: SaveData =
+            serde_json::from_str(include_str!("main/deploy/bindings.json")).unwrap();
+
+        assert_eq!(old.generation, 0);
+
+        let data = SaveData {
+            generation: 7,
+            ..Default::default()
+        };
+
+        let reloaded: SaveData =
+            serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+
+        assert_eq!(reloaded.generation, 7);
+    }
+
+    #[test]
+    fn older_projects_sync_where_they_did() {
+        let old: SaveData =
+            serde_json::from_str(include_str!("main/deploy/bindings.json")).unwrap();
+
+        assert_eq!(*old.ssh_user, "admin");
+        assert_eq!(*old.remote_path, "/home/lvuser/deploy/");
+    }
+
+    #[test]
+    fn a_single_url_becomes_a_target() {
+        let old: SaveData = serde_json::from_str(
+            r#"{"url": "10.90.94.2", "ssh_user": "lvuser", "remote_path": "~/deploy",
+                "commands": [], "constants": null, "streams": []}"#,
+        )
+        .unwrap();
+
+        let targets = old.sync_targets();
+
+        assert_eq!(
+            targets,
+            vec![SyncTarget {
+                name: "robot".to_string(),
+                url: "10.90.94.2".to_string(),
+                user: "lvuser".to_string(),
+                path: "~/deploy/".to_string(),
+                enabled: true,
+            }]
+        );
+
+        let saved = serde_json::to_string(&SaveData {
+            targets: Cow::Owned(targets.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // written back as the list only
+        let fields: serde_json::Value = serde_json::from_str(&saved).unwrap();
+
+        assert!(fields.get("url").is_none());
+        assert!(fields.get("ssh_user").is_none());
+
+        let reloaded: SaveData = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(reloaded.sync_targets(), targets);
+
+        let unsynced: SaveData = serde_json::from_str(
+            r#"{"url": null, "commands": [], "constants": null, "streams": []}"#,
+        )
+        .unwrap();
+
+        assert!(unsynced.sync_targets().is_empty());
+    }
+
+    #[test]
+    fn cooldowns_round_trip() {
+        let old: SaveData =
+            serde_json::from_str(include_str!("main/deploy/bindings.json")).unwrap();
+
+        assert!(old.command_cooldowns.is_empty());
+
+        let mut cooldowns = BTreeMap::new();
+        cooldowns.insert(Rc::new("shoot".to_string()), 0.5);
+
+        let data = SaveData {
+            command_cooldowns: Cow::Owned(cooldowns.clone()),
+            ..Default::default()
+        };
+
+        let reloaded: SaveData =
+            serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+
+        assert_eq!(*reloaded.command_cooldowns, cooldowns);
+    }
+
+    #[test]
+    fn caps_match() {
+        let generic = ControllerType::Generic {
+            buttons: 12,
+            axises: 4,
+            sensitivity: 0.5,
+        };
+
+        assert!(generic
+            .caps_mismatches(DeviceCaps {
+                buttons: 12,
+                axises: 4
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn caps_phantom_button() {
+        let generic = ControllerType::Generic {
+            buttons: 12,
+            axises: 4,
+            sensitivity: 0.5,
+        };
+
+        let caps = DeviceCaps {
+            buttons: 11,
+            axises: 3,
+        };
+
+        assert_eq!(
+            generic.caps_mismatches(caps),
+            vec![
+                CapsMismatch::Buttons {
+                    configured: 12,
+                    reported: 11
+                },
+                CapsMismatch::Axises {
+                    configured: 4,
+                    reported: 3
+                }
+            ]
+        );
+
+        assert!(generic.adjusted_to(caps).caps_mismatches(caps).is_empty());
+    }
+
+    #[test]
+    fn caps_ignore_non_generic() {
+        let caps = DeviceCaps {
+            buttons: 3,
+            axises: 1,
+        };
+
+        assert!(ControllerType::XBox { sensitivity: 0.5 }
+            .caps_mismatches(caps)
+            .is_empty());
+        assert!(ControllerType::NotBound.caps_mismatches(caps).is_empty());
+    }
+
+    #[test]
+    fn profile_without_description() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        assert!(profile.description.is_empty());
+        assert_eq!(profile.last_modified, None);
+        assert_eq!(*profile.controller_colors, [None; CONTROLLER_SLOTS]);
+    }
+
+    #[test]
+    fn controller_colors_round_trip() {
+        let mut colors = [None; CONTROLLER_SLOTS];
+        colors[2] = Some([0x12, 0x34, 0x56]);
+
+        let profile = Profile {
+            controller_colors: Cow::Owned(colors),
+            ..Default::default()
+        };
+
+        let loaded = Profile::from_str_lenient(&serde_json::to_string(&profile).unwrap()).unwrap();
+
+        assert_eq!(*loaded.controller_colors, colors);
+        assert!(loaded.quarantine.is_empty());
+    }
+
+    #[test]
+    fn description_round_trips() {
+        let profile = Profile {
+            description: Cow::Owned("demo mode, slow".to_string()),
+            last_modified: Some("2025-03-01T12:00:00Z".to_string()),
+            ..Default::default()
+        };
+
+        let loaded = Profile::from_str_lenient(&serde_json::to_string(&profile).unwrap()).unwrap();
+
+        assert_eq!(loaded.description, "demo mode, slow");
+        assert_eq!(
+            loaded.last_modified.as_deref(),
+            Some("2025-03-01T12:00:00Z")
+        );
+        assert!(loaded.quarantine.is_empty());
+    }
+
+    #[test]
+    fn compact_sync_payload() {
+        let pretty = serde_json::to_string_pretty(
+            &serde_json::from_str::<Value>(SEVEN_CONTROLLERS).unwrap(),
+        )
+        .unwrap();
+
+        assert!(SyncFormat::default().is_pretty());
+
+        let format = SyncFormat {
+            compact: true,
+            gzip: false,
+        };
+
+        let compact = format.encode(&pretty).unwrap();
+
+        assert!(compact.len() < pretty.len());
+        assert_eq!(
+            serde_json::from_slice::<Value>(&compact).unwrap(),
+            serde_json::from_str::<Value>(&pretty).unwrap()
+        );
+    }
+
+    #[test]
+    fn gzip_sync_payload() {
+        let format = SyncFormat {
+            compact: true,
+            gzip: true,
+        };
+
+        let gzipped = format.encode(SEVEN_CONTROLLERS).unwrap();
+
+        let mut json = String::new();
+
+        flate2::read::GzDecoder::new(gzipped.as_slice())
+            .read_to_string(&mut json)
+            .unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            serde_json::from_str::<Value>(SEVEN_CONTROLLERS).unwrap()
+        );
+        assert_eq!(format.extension(), "json.gz");
+    }
+
+    fn bound_map() -> (BindingsMap, Rc<String>, Binding) {
+        let command = Rc::new("shoot".to_string());
+        let binding = Binding {
+            controller: 0,
+            button: Button {
+                button: 1,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::OnTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let mut map = BindingsMap::default();
+        map.add_binding(command.clone(), binding);
+
+        (map, command, binding)
+    }
+
+    #[test]
+    fn rename_unbound_command() {
+        let (mut map, command, binding) = bound_map();
+
+        map.rename_command(&Rc::new("climb".to_string()), Rc::new("hang".to_string()));
+
+        assert!(map.has_binding(&command, binding));
+        assert_eq!(map.command_to_bindings.len(), 1);
+        assert_eq!(map.binding_to_commands.len(), 1);
+    }
+
+    #[test]
+    fn rename_onto_existing_command() {
+        let (mut map, command, binding) = bound_map();
+
+        let other = Binding {
+            controller: 1,
+            ..binding
+        };
+
+        let fire = Rc::new("fire".to_string());
+
+        map.add_binding(fire.clone(), binding);
+        map.add_binding(fire.clone(), other);
+
+        map.rename_command(&fire, command.clone());
+
+        assert!(!map.command_to_bindings.contains_key(&fire));
+        assert_eq!(map.command_to_bindings[&command], vec![binding, other]);
+        assert_eq!(
+            map.binding_to_commands[&((0, binding.button), None)],
+            vec![(command.clone(), RunWhen::OnTrue)]
+        );
+        assert_eq!(
+            map.binding_to_commands[&((1, binding.button), None)],
+            vec![(command, RunWhen::OnTrue)]
+        );
+    }
+
+    #[test]
+    fn conflicts_need_the_same_run_when() {
+        let (mut map, _, binding) = bound_map();
+
+        map.add_binding(
+            Rc::new("intake".to_string()),
+            Binding {
+                during: RunWhen::OnFalse,
+                ..binding
+            },
+        );
+
+        assert!(map.conflicts().is_empty());
+
+        map.add_binding(Rc::new("climb".to_string()), binding);
+
+        assert_eq!(map.conflicts(), vec![(binding.key(), RunWhen::OnTrue)]);
+    }
+
+    #[test]
+    fn clear_controller_leaves_other_slots() {
+        let (mut map, command, binding) = bound_map();
+
+        let other = Binding {
+            controller: 1,
+            ..binding
+        };
+
+        map.add_binding(command.clone(), other);
+        map.add_binding(Rc::new("climb".to_string()), binding);
+
+        assert_eq!(map.clear_controller(0), 2);
+        assert!(!map.has_controller(0));
+        assert!(map.has_controller(1));
+        assert_eq!(map.command_to_bindings.len(), 1);
+        assert_eq!(map.command_to_bindings[&command], vec![other]);
+    }
+
+    #[test]
+    fn move_controller_skips_invalid_buttons() {
+        let (mut map, command, binding) = bound_map();
+
+        let high = Binding {
+            button: Button {
+                button: 12,
+                location: ButtonLocation::Button,
+            },
+            ..binding
+        };
+
+        map.add_binding(command.clone(), high);
+
+        let skipped = map.move_controller(0, 3, |b| b.button.button <= 10);
+
+        assert_eq!(skipped, vec![(command.clone(), high)]);
+        assert_eq!(
+            map.command_to_bindings[&command],
+            vec![
+                high,
+                Binding {
+                    controller: 3,
+                    ..binding
+                }
+            ]
+        );
+        assert!(map
+            .binding_to_commands
+            .contains_key(&((3, binding.button), None)));
+        assert!(!map
+            .binding_to_commands
+            .contains_key(&((0, binding.button), None)));
+    }
+
+    #[test]
+    fn swap_buttons_exchanges_commands() {
+        let (mut map, shoot, a) = bound_map();
+
+        let climb = Rc::new("climb".to_string());
+        let b = Binding {
+            controller: 1,
+            button: Button {
+                button: 4,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::WhileTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        map.add_binding(climb.clone(), b);
+
+        let (a_button, b_button) = ((a.controller, a.button), (b.controller, b.button));
+
+        map.swap_buttons(a_button, a_button);
+
+        assert!(map.has_binding(&shoot, a));
+
+        map.swap_buttons(a_button, b_button);
+
+        assert_eq!(
+            map.command_to_bindings[&shoot],
+            vec![Binding {
+                controller: 1,
+                button: b.button,
+                ..a
+            }]
+        );
+        assert_eq!(
+            map.command_to_bindings[&climb],
+            vec![Binding {
+                controller: 0,
+                button: a.button,
+                ..b
+            }]
+        );
+        assert_eq!(
+            map.binding_to_commands[&(b_button, None)],
+            vec![(shoot.clone(), RunWhen::OnTrue)]
+        );
+        assert_eq!(
+            map.binding_to_commands[&(a_button, None)],
+            vec![(climb.clone(), RunWhen::WhileTrue)]
+        );
+
+        // swapping onto an empty button just moves
+        let empty = (2, a.button);
+
+        map.swap_buttons(b_button, empty);
+
+        assert!(!map.has_button((b_button, None)));
+        assert_eq!(map.command_to_bindings[&shoot][0].controller, 2);
+    }
+
+    #[test]
+    fn debounce_is_saved_in_seconds() {
+        let (_, _, binding) = bound_map();
+
+        let plain = serde_json::to_value(binding).unwrap();
+
+        assert!(plain.get("debounce").is_none());
+        assert_eq!(serde_json::from_value::<Binding>(plain).unwrap(), binding);
+
+        let debounced = Binding {
+            debounce: Debounce::from_seconds(0.25),
+            ..binding
+        };
+
+        let value = serde_json::to_value(debounced).unwrap();
+
+        assert_eq!(value["debounce"], 0.25);
+        assert_eq!(serde_json::from_value::<Binding>(value).unwrap(), debounced);
+    }
+
+    #[test]
+    fn analog_thresholds() {
+        let old = r#"{"controller": 1, "button": {"button": 3, "location": "Analog"}, "during": "WhileTrue"}"#;
+
+        let binding: Binding = serde_json::from_str(old).unwrap();
+
+        assert_eq!(binding.threshold, None);
+        assert!(serde_json::to_value(binding)
+            .unwrap()
+            .get("threshold")
+            .is_none());
+
+        let past = Binding {
+            threshold: Some(Threshold::from_value(-0.7)),
+            ..binding
+        };
+
+        let value = serde_json::to_value(past).unwrap();
+
+        assert_eq!(value["threshold"], -0.7);
+        assert_eq!(serde_json::from_value::<Binding>(value).unwrap(), past);
+        assert_eq!(Threshold::from_value(3.0).value(), 1.0);
+    }
+
+    #[test]
+    fn chords_are_their_own_binding() {
+        let (mut map, command, binding) = bound_map();
+
+        let bumper = Button {
+            button: 5,
+            location: ButtonLocation::Button,
+        };
+
+        let chord = Binding {
+            modifier: Some(bumper),
+            ..binding
+        };
+
+        let old = serde_json::to_value(binding).unwrap();
+
+        assert!(old.get("modifier").is_none());
+        assert_eq!(serde_json::from_value::<Binding>(old).unwrap(), binding);
+
+        let climb = Rc::new("climb".to_string());
+
+        map.add_binding(climb.clone(), chord);
+
+        assert_eq!(
+            map.binding_to_commands[&binding.key()],
+            vec![(command.clone(), RunWhen::OnTrue)]
+        );
+        assert_eq!(
+            map.binding_to_commands[&chord.key()],
+            vec![(climb.clone(), RunWhen::OnTrue)]
+        );
+        assert!(map.conflicts().is_empty());
+
+        assert!(map.remove_binding(&climb, chord));
+        assert!(!map.has_button(chord.key()));
+        assert!(map.has_button(binding.key()));
+
+        let xbox = ControllerType::XBox { sensitivity: 1.0 };
+
+        assert!(xbox.valid_trigger(binding.button, Some(bumper)));
+        assert!(!xbox.valid_trigger(binding.button, Some(binding.button)));
+        assert!(!xbox.valid_trigger(
+            binding.button,
+            Some(Button {
+                button: 2,
+                location: ButtonLocation::Analog,
+            })
+        ));
+
+        let arena = Bump::new();
+
+        assert_eq!(
+            xbox.chord_name(&binding.button, Some(bumper), &arena),
+            "left bumper + a"
+        );
+    }
+
+    #[test]
+    fn swapping_moves_modifiers_too() {
+        let (mut map, command, binding) = bound_map();
+
+        let bumper = Button {
+            button: 5,
+            location: ButtonLocation::Button,
+        };
+        let other = Button {
+            button: 6,
+            location: ButtonLocation::Button,
+        };
+
+        map.remove_binding(&command, binding);
+
+        let chord = Binding {
+            modifier: Some(bumper),
+            ..binding
+        };
+
+        map.add_binding(command.clone(), chord);
+        map.swap_buttons((0, bumper), (0, other));
+
+        let moved = Binding {
+            modifier: Some(other),
+            ..chord
+        };
+
+        assert_eq!(map.command_to_bindings[&command], vec![moved]);
+        assert!(map.has_button(moved.key()));
+        assert!(!map.has_button(chord.key()));
+    }
+
+    #[test]
+    fn unbound_commands() {
+        let (mut map, command, binding) = bound_map();
+
+        let commands = ["climb", "shoot", "intake"]
+            .into_iter()
+            .map(|c| Rc::new(c.to_string()))
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(
+            map.unbound(&commands)
+                .map(|c| c.as_str())
+                .collect::<Vec<_>>(),
+            vec!["climb", "intake"]
+        );
+
+        map.remove_binding(&command, binding);
+
+        assert_eq!(map.unbound(&commands).count(), 3);
+    }
+
+    #[test]
+    fn rebinding_changes_the_debounce() {
+        let (mut map, command, binding) = bound_map();
+
+        let debounced = Binding {
+            debounce: Debounce::from_seconds(0.1),
+            ..binding
+        };
+
+        map.add_binding(command.clone(), debounced);
+
+        assert_eq!(map.command_to_bindings[&command], vec![debounced]);
+        assert_eq!(
+            map.binding_to_commands[&((0, binding.button), None)].len(),
+            1
+        );
+
+        // the tabs remove by button and `RunWhen` without knowing the debounce
+        assert!(map.remove_binding(&command, binding));
+        assert!(map.command_to_bindings.is_empty());
+    }
+
+    #[test]
+    fn every_offered_button_is_valid() {
+        let arena = Bump::new();
+
+        let controllers = [
+            ControllerType::NotBound,
+            ControllerType::XBox { sensitivity: 0.5 },
+            ControllerType::Generic {
+                buttons: 12,
+                axises: 4,
+                sensitivity: 0.5,
+            },
+        ];
+
+        for controller in controllers {
+            for button in controller.enumerate_buttons(&arena) {
+                assert!(
+                    controller.valid_binding(button),
+                    "{controller:?} offers {button:?}"
+                );
+                assert_ne!(controller.button_name(&button, &arena), "invalid pov");
+            }
+        }
+
+        assert!(
+            ControllerType::XBox { sensitivity: 0.5 }.valid_binding(Button {
+                button: 315,
+                location: ButtonLocation::Pov,
+            })
+        );
+    }
+
+    #[test]
+    fn every_button_has_a_name() {
+        let arena = Bump::new();
+
+        let controllers = [
+            ControllerType::NotBound,
+            ControllerType::XBox { sensitivity: 0.5 },
+            ControllerType::Generic {
+                buttons: 12,
+                axises: 4,
+                sensitivity: 0.5,
+            },
+        ];
+
+        let numbers = [i16::MIN, -2, -1, 0, 1, 2, 3, 10, 11, 45, 360, i16::MAX];
+
+        for controller in controllers {
+            for location in [
+                ButtonLocation::Button,
+                ButtonLocation::Analog,
+                ButtonLocation::Pov,
+            ] {
+                for button in numbers {
+                    let name = controller.button_name(&Button { button, location }, &arena);
+
+                    assert!(!name.is_empty());
+                }
+            }
+
+            for axis in 0..=u8::MAX {
+                controller.axis_name(axis, &arena);
+            }
+        }
+
+        let xbox = ControllerType::XBox { sensitivity: 0.5 };
+
+        assert_eq!(
+            xbox.button_name(
+                &Button {
+                    button: 11,
+                    location: ButtonLocation::Button
+                },
+                &arena
+            ),
+            "invalid button"
+        );
+    }
+
+    #[test]
+    fn remove_binding_twice() {
+        let (mut map, command, binding) = bound_map();
+
+        assert!(map.remove_binding(&command, binding));
+        assert!(!map.remove_binding(&command, binding));
+        assert!(map.command_to_bindings.is_empty());
+        assert!(map.binding_to_commands.is_empty());
+    }
+
+    #[test]
+    fn remove_binding_after_remove_command() {
+        let (mut map, command, binding) = bound_map();
+
+        map.remove_command(&command);
+
+        assert!(!map.remove_binding(&command, binding));
+        assert!(!map.has_binding(&command, binding));
+    }
+
+    #[test]
+    fn dead_bindings_on_unbound_slots() {
+        let binding = |controller, button| Binding {
+            controller,
+            button: Button {
+                button,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::OnTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let name = |n: &str| Rc::new(n.to_string());
+
+        let mut controllers = [ControllerType::NotBound; CONTROLLER_SLOTS];
+        controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        let profile = Profile {
+            command_to_bindings: Cow::Owned(BTreeMap::from([
+                (name("shoot"), vec![binding(0, 1), binding(0, 20)]),
+                (name("climb"), vec![binding(1, 1)]),
+            ])),
+            controllers: Cow::Owned(controllers),
+            ..Default::default()
+        };
+
+        let dead = profile.dead_bindings(&name("demo"));
+
+        assert_eq!(dead.len(), 2);
+        assert_eq!(dead[0].command, name("climb"));
+        assert!(dead[0].not_bound);
+        assert_eq!(dead[1].binding, binding(0, 20));
+        assert!(!dead[1].not_bound);
+        assert_eq!(
+            dead[0].to_string(),
+            "demo: climb on 1 to button 1 on true (controller not bound)"
+        );
+    }
+
+    #[test]
+    fn merge_prefers_destination() {
+        let binding = |button| Binding {
+            controller: 0,
+            button: Button {
+                button,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::OnTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let name = |n: &str| Rc::new(n.to_string());
+
+        let mut into = Profile {
+            command_to_bindings: Cow::Owned(BTreeMap::from([
+                (name("shoot"), vec![binding(1)]),
+                (name("climb"), vec![]),
+            ])),
+            stream_to_axis: Cow::Owned(BTreeMap::from([(name("drive"), (0, 1))])),
+            ..Default::default()
+        };
+
+        let from = Profile {
+            command_to_bindings: Cow::Owned(BTreeMap::from([
+                (name("shoot"), vec![binding(2)]),
+                (name("climb"), vec![binding(3)]),
+                (name("intake"), vec![binding(4)]),
+            ])),
+            stream_to_axis: Cow::Owned(BTreeMap::from([
+                (name("drive"), (0, 2)),
+                (name("turn"), (0, 3)),
+            ])),
+            ..Default::default()
+        };
+
+        let conflicts = into.merge_from(&from);
+
+        assert_eq!(
+            conflicts,
+            vec![
+                MergeConflict::Command(name("shoot")),
+                MergeConflict::Stream(name("drive"))
+            ]
+        );
+
+        assert_eq!(into.command_to_bindings[&name("shoot")], vec![binding(1)]);
+        assert_eq!(into.command_to_bindings[&name("climb")], vec![binding(3)]);
+        assert_eq!(into.command_to_bindings[&name("intake")], vec![binding(4)]);
+        assert_eq!(into.stream_to_axis[&name("drive")], (0, 1));
+        assert_eq!(into.stream_to_axis[&name("turn")], (0, 3));
+    }
+
+    #[test]
+    fn profile_discovery_skips_other_files() {
+        let temp = temp_dir("discovery");
+        let deploy = temp.path();
+        let bindings = deploy.join("bindings");
+
+        std::fs::create_dir_all(bindings.join("old.json")).unwrap();
+
+        for file in [
+            "default.json",
+            "notes.txt",
+            "backup.json.bak",
+            "README",
+            ".hidden.json",
+        ] {
+            std::fs::write(bindings.join(file), "{}").unwrap();
+        }
+
+        assert_eq!(
+            Profile::get_profiles(deploy).unwrap(),
+            vec![Rc::new("default".to_string())]
+        );
+    }
+
+    #[test]
+    fn bundle_round_trips() {
+        let profile = Profile::from_str_lenient(SEVEN_CONTROLLERS).unwrap();
+
+        let bundle = ProfileBundle {
+            commands: profile.command_to_bindings.keys().cloned().collect(),
+            streams: profile.stream_to_axis.keys().cloned().collect(),
+            constants: Cow::Owned(Constants::Float(1.5)),
+            profile,
+        };
+
+        let json = serde_json::to_string_pretty(&bundle).unwrap();
+
+        let loaded = ProfileBundle::from_str_lenient(&json).unwrap();
+
+        assert_eq!(
+            serde_json::to_string_pretty(&loaded).unwrap(),
+            json,
+            "export then import should be lossless"
+        );
+        assert!(ProfileBundle::from_str_lenient(SEVEN_CONTROLLERS).is_err());
+    }
+
+    #[test]
+    fn bundle_names_are_normalized() {
+        let json = serde_json::json!({
+            "profile": serde_json::from_str::<Value>(SEVEN_CONTROLLERS).unwrap(),
+            "commands": ["E\u{0301}levate"],
+            "streams": ["E\u{0301}levator"],
+        });
+
+        let bundle = ProfileBundle::from_str_lenient(&json.to_string()).unwrap();
+
+        assert_eq!(
+            bundle.commands,
+            BTreeSet::from([Rc::new("\u{00C9}levate".to_string())])
+        );
+        assert_eq!(
+            bundle.streams,
+            BTreeSet::from([Rc::new("\u{00C9}levator".to_string())])
+        );
+    }
+
+    #[test]
+    fn disabled_bindings_are_the_same_binding() {
+        let shoot = Rc::new("shoot".to_string());
+        let intake = Rc::new("intake".to_string());
+
+        let on = Binding {
+            controller: 0,
+            button: Button {
+                button: 1,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::OnTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+        let off = Binding {
+            enabled: false,
+            ..on
+        };
+
+        let mut map = BindingsMap::default();
+
+        map.add_binding(shoot.clone(), on);
+        map.add_binding(intake.clone(), on);
+
+        assert_eq!(map.conflicts().len(), 1);
+
+        map.add_binding(shoot.clone(), off);
+
+        assert_eq!(
+            map.bindings_for_command(&shoot).collect::<Vec<_>>(),
+            vec![off]
+        );
+        assert!(map.has_binding(&shoot, on));
+        assert!(map.has_binding(
+            &shoot,
+            Binding {
+                debounce: Debounce { millis: 50 },
+                ..on
+            }
+        ));
+        assert!(map.conflicts().is_empty());
+
+        let profile = Profile {
+            command_to_bindings: Cow::Owned(map.command_to_bindings.clone()),
+            ..Default::default()
+        };
+
+        let omitted = profile.to_json(DisabledBindings::Omit);
+        let value = serde_json::from_str::<Value>(&omitted).unwrap();
+
+        assert!(value["command_to_bindings"].get("shoot").is_none());
+        assert_eq!(value["disabled_bindings"]["shoot"][0]["enabled"], false);
+
+        let flagged = profile.to_json(DisabledBindings::Flag);
+        let value = serde_json::from_str::<Value>(&flagged).unwrap();
+
+        assert_eq!(value["command_to_bindings"]["shoot"][0]["enabled"], false);
+        assert!(value["command_to_bindings"]["intake"][0]
+            .get("enabled")
+            .is_none());
+
+        for json in [omitted, flagged] {
+            let loaded = Profile::from_str_lenient(&json).unwrap();
+
+            assert_eq!(*loaded.command_to_bindings, *profile.command_to_bindings);
+            assert!(loaded.disabled_bindings.is_empty());
+        }
+
+        assert!(map.remove_binding(&shoot, on));
+        assert!(map.bindings_for_command(&shoot).next().is_none());
+    }
+
+    #[test]
+    fn free_buttons_skip_bound_ones() {
+        let (mut map, _, binding) = bound_map();
+
+        let arena = Bump::new();
+        let xbox = ControllerType::XBox { sensitivity: 1.0 };
+
+        let everything = xbox.enumerate_buttons(&arena).count();
+        let free = map.free_buttons(binding.controller, &xbox, &arena);
+
+        assert_eq!(free.len(), everything - 1);
+        assert!(!free.contains(&binding.button));
+
+        let bumper = Button {
+            button: 5,
+            location: ButtonLocation::Button,
+        };
+
+        // a chord takes its button too, the modifier is still free on its own
+        map.add_binding(
+            Rc::new("climb".to_string()),
+            Binding {
+                button: Button {
+                    button: 2,
+                    location: ButtonLocation::Button,
+                },
+                modifier: Some(bumper),
+                ..binding
+            },
+        );
+
+        let free = map.free_buttons(binding.controller, &xbox, &arena);
+
+        assert_eq!(free.len(), everything - 2);
+        assert!(free.contains(&bumper));
+        assert_eq!(
+            map.free_buttons(binding.controller + 1, &xbox, &arena)
+                .len(),
+            everything
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs::{create_dir_all, read_to_string},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::file_hashes::FileHashes;
+
+/// where the robot code looks up command and stream names instead of
+/// repeating the strings
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct KotlinKeys {
+    // regenerate every time the bindings are saved
+    #[serde(default)]
+    pub on_save: bool,
+    // relative to the project root, none uses `default_path`
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl KotlinKeys {
+    /// next to deploy, in the kotlin sources
+    pub fn default_path() -> &'static str {
+        "src/main/kotlin/BindingsKeys.kt"
+    }
+
+    pub fn path(&self, project: &Path) -> PathBuf {
+        project.join(
+            self.path
+                .as_deref()
+                .filter(|path| !path.trim().is_empty())
+                .unwrap_or(Self::default_path()),
+        )
+    }
+}
+
+const SOURCE_ROOTS: [&str; 2] = ["src/main/kotlin", "src/main/java"];
+
+/// the package a file at `path` belongs in, from where it sits under the
+/// source roots. none for the default package
+pub fn package(project: &Path, path: &Path) -> Option<String> {
+    let dir = path.parent()?;
+
+    let rest = SOURCE_ROOTS
+        .iter()
+        .find_map(|root| dir.strip_prefix(project.join(root)).ok())?;
+
+    let package = rest
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(".");
+
+    (!package.is_empty()).then_some(package)
+}
+
+/// `name` as a kotlin constant, e.g. "shootHigh" and "shoot high" both become
+/// SHOOT_HIGH
+pub fn constant_name(name: &str) -> String {
+    let mut constant = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            let boundary = previous
+                .is_some_and(|p| p.is_lowercase() && c.is_uppercase() || !p.is_alphanumeric());
+
+            if boundary && !constant.is_empty() {
+                constant.push('_');
+            }
+
+            constant.extend(c.to_uppercase());
+        }
+
+        previous = Some(c);
+    }
+
+    if constant.is_empty() || constant.starts_with(|c: char| c.is_ascii_digit()) {
+        constant.insert(0, '_');
+    }
+
+    constant
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+}
+
+fn section(name: &str, names: &BTreeSet<Rc<String>>, source: &mut String) {
+    source.push_str(&format!("    object {name} {{\n"));
+
+    let mut taken = HashSet::new();
+
+    for value in names {
+        let base = constant_name(value);
+        let mut constant = base.clone();
+        let mut n = 2;
+
+        // names that only differ in punctuation would clash, the later one
+        // (alphabetically) gets a number
+        while !taken.insert(constant.clone()) {
+            constant = format!("{base}_{n}");
+            n += 1;
+        }
+
+        source.push_str(&format!(
+            "        const val {constant} = \"{}\"\n",
+            escape(value)
+        ));
+    }
+
+    source.push_str("    }\n");
+}
+
+/// a kotlin object with a constant for every command and stream, sorted so it
+/// only changes when the names do
+pub fn kotlin_source(
+    package: Option<&str>,
+    commands: &BTreeSet<Rc<String>>,
+    streams: &BTreeSet<Rc<String>>,
+) -> String {
+    let mut source = String::new();
+
+    if let Some(package) = package {
+        source.push_str(&format!("package {package}\n\n"));
+    }
+
+    source.push_str("// generated by bindings-gui from bindings.json, edits are overwritten\n\n");
+    source.push_str("object BindingsKeys {\n");
+
+    section("Commands", commands, &mut source);
+    source.push('\n');
+    section("Streams", streams, &mut source);
+
+    source.push_str("}\n");
+
+    source
+}
+
+/// writes the keys file under `project`, left alone when it's already up to
+/// date so the build doesn't see a change. returns where it went
+pub fn write_kotlin_keys(
+    keys: &KotlinKeys,
+    project: &Path,
+    commands: &BTreeSet<Rc<String>>,
+    streams: &BTreeSet<Rc<String>>,
+    hashes: &mut FileHashes,
+) -> Result<PathBuf> {
+    let path = keys.path(project);
+
+    let source = kotlin_source(package(project, &path).as_deref(), commands, streams);
+
+    if read_to_string(&path).is_ok_and(|existing| existing == source) {
+        return Ok(path);
+    }
+
+    create_dir_all(path.parent().unwrap())
+        .with_context(|| "failed to create the directory for the kotlin keys")?;
+
+    // regenerated from the bindings, there's nothing to back up
+    hashes
+        .write_without_backup(&path, source.as_bytes())
+        .with_context(|| "failed to write the kotlin keys")?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(names: &[&str]) -> BTreeSet<Rc<String>> {
+        names.iter().map(|n| Rc::new(n.to_string())).collect()
+    }
+
+    #[test]
+    fn names_become_constants() {
+        assert_eq!(constant_name("shootHigh"), "SHOOT_HIGH");
+        assert_eq!(constant_name("shoot high"), "SHOOT_HIGH");
+        assert_eq!(constant_name("intake-2"), "INTAKE_2");
+        assert_eq!(constant_name("2piece"), "_2PIECE");
+        assert_eq!(constant_name("!!"), "_");
+    }
+
+    #[test]
+    fn source_is_sorted_and_escaped() {
+        let source = kotlin_source(
+            Some("frc.robot"),
+            &names(&["shoot", "shoot high", "shootHigh", "say \"$hi\""]),
+            &names(&["driveX"]),
+        );
+
+        assert_eq!(
+            source,
+            "package frc.robot\n\
+            \n\
+            // generated by bindings-gui from bindings.json, edits are overwritten\n\
+            \n\
+            object BindingsKeys {\n    \
+                object Commands {\n        \
+                    const val SAY_HI = \"say \\\"\\$hi\\\"\"\n        \
+                    const val SHOOT = \"shoot\"\n        \
+                    const val SHOOT_HIGH = \"shoot high\"\n        \
+                    const val SHOOT_HIGH_2 = \"shootHigh\"\n    \
+                }\n\
+            \n    \
+                object Streams {\n        \
+                    const val DRIVE_X = \"driveX\"\n    \
+                }\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn package_follows_the_path() {
+        let project = Path::new("/robot");
+
+        assert_eq!(
+            package(
+                project,
+                &project.join("src/main/kotlin/frc/robot/BindingsKeys.kt")
+            )
+            .as_deref(),
+            Some("frc.robot")
+        );
+        assert_eq!(
+            package(project, &project.join(KotlinKeys::default_path())),
+            None
+        );
+        assert_eq!(package(project, &project.join("BindingsKeys.kt")), None);
+    }
+}
@@ -2,30 +2,30 @@ use bumpalo::Bump;
 use egui::Ui;
 use std::cell::RefCell;
 
-use crate::{password_lock::PasswordLock, TabType};
+use crate::tabs::{password_lock::PasswordLock, TabType};
 
 #[derive(Debug, Default)]
-pub(crate) struct EventStream<E> {
+pub struct EventStream<E> {
     events: RefCell<Vec<E>>,
 }
 
 impl<E> EventStream<E> {
-    pub(crate) fn add_event(&self, e: E) {
+    pub fn add_event(&self, e: E) {
         self.events.borrow_mut().push(e);
     }
 
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         EventStream {
             events: RefCell::new(Vec::new()),
         }
     }
 
-    pub(crate) fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
+    pub fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
         self.events.get_mut().drain(0..)
     }
 }
 
-pub(crate) trait Component: std::fmt::Debug {
+pub trait Component: std::fmt::Debug {
     type OutputEvents;
     type Environment;
 
@@ -44,5 +44,9 @@ pub(crate) trait Component: std::fmt::Debug {
         PasswordLock::new(self)
     }
 
+    /// called when ctrl+f is pressed while this tab is focused. tabs with a
+    /// filter box should focus it on their next render
+    fn focus_search(&mut self) {}
+
     fn tab_type(&self) -> TabType;
 }
@@ -0,0 +1,116 @@
+use egui::{Align2, Color32, Key, RichText, Ui, Window};
+
+use crate::{accessible, component::EventStream};
+
+/// holds a delete back until it's confirmed. each tab keeps its own, so
+/// "don't ask again" lasts for that tab until the app is closed
+#[derive(Debug, Clone)]
+pub struct ConfirmDelete<E> {
+    // what's about to go, as shown to the user, and the events that delete it
+    pending: Option<(String, Vec<E>)>,
+    pub dont_ask: bool,
+}
+
+impl<E> Default for ConfirmDelete<E> {
+    fn default() -> Self {
+        Self {
+            pending: None,
+            dont_ask: false,
+        }
+    }
+}
+
+impl<E> ConfirmDelete<E> {
+    /// sends `event` straight away when asking is turned off
+    pub fn ask(&mut self, what: impl Into<String>, event: E, output: &EventStream<E>) {
+        self.ask_all(what, vec![event], output);
+    }
+
+    /// one confirmation for several deletes, sent together so they're saved
+    /// once
+    pub fn ask_all(&mut self, what: impl Into<String>, events: Vec<E>, output: &EventStream<E>) {
+        if self.dont_ask {
+            for event in events {
+                output.add_event(event);
+            }
+        } else {
+            self.pending = Some((what.into(), events));
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// draws the dialog while a delete is waiting, call it once per frame
+    /// from the tab that asked
+    pub fn show(&mut self, ui: &Ui, output: &EventStream<E>) {
+        let Some((what, _)) = &self.pending else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = ui.input(|i| i.key_pressed(Key::Escape));
+
+        Window::new("delete?")
+            .id(ui.id().with("confirm delete"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .show(ui.ctx(), |ui| {
+                ui.label("this will delete");
+                ui.label(RichText::new(what.as_str()).strong());
+
+                ui.checkbox(&mut self.dont_ask, "don't ask again this session");
+
+                ui.horizontal(|ui| {
+                    let delete = RichText::new("delete").color(Color32::from_rgb(0xf3, 0x8b, 0xa8));
+
+                    confirmed =
+                        accessible::labeled_button(ui, delete, &format!("delete {what}")).clicked();
+                    cancelled |= ui.button("cancel").clicked();
+                });
+            });
+
+        if confirmed {
+            for event in self
+                .pending
+                .take()
+                .into_iter()
+                .flat_map(|(_, events)| events)
+            {
+                output.add_event(event);
+            }
+        } else if cancelled {
+            self.pending = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn waits_for_confirmation_unless_told_not_to() {
+        let mut output = EventStream::new();
+        let mut confirm = ConfirmDelete::default();
+
+        confirm.ask("command shoot", 1, &output);
+
+        assert!(confirm.is_open());
+        assert_eq!(output.drain().count(), 0);
+
+        confirm.pending = None;
+        confirm.dont_ask = true;
+
+        confirm.ask("command intake", 2, &output);
+
+        assert!(!confirm.is_open());
+        assert_eq!(output.drain().collect::<Vec<_>>(), vec![2]);
+
+        confirm.ask_all("all 2 bindings on a", vec![3, 4], &output);
+
+        assert_eq!(output.drain().collect::<Vec<_>>(), vec![3, 4]);
+    }
+}
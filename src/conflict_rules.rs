@@ -0,0 +1,325 @@
+use std::{collections::BTreeSet, rc::Rc};
+
+use crate::bindings::{Binding, PButton, RunWhen};
+
+/// what has to be held for a binding to fire. bindings have at most one
+/// modifier but the rules don't care how many there are
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trigger {
+    Plain(PButton),
+    Chord {
+        primary: PButton,
+        modifiers: BTreeSet<PButton>,
+    },
+}
+
+impl From<Binding> for Trigger {
+    fn from(binding: Binding) -> Self {
+        let primary = (binding.controller, binding.button);
+
+        match binding.modifier {
+            Some(modifier) => Trigger::Chord {
+                primary,
+                modifiers: BTreeSet::from([(binding.controller, modifier)]),
+            },
+            None => Trigger::Plain(primary),
+        }
+    }
+}
+
+impl Trigger {
+    fn shape(&self) -> Shape {
+        match self {
+            Trigger::Plain(_) => Shape::Plain,
+            Trigger::Chord { .. } => Shape::Chord,
+        }
+    }
+
+    fn primary(&self) -> PButton {
+        match self {
+            Trigger::Plain(button) => *button,
+            Trigger::Chord { primary, .. } => *primary,
+        }
+    }
+
+    fn modifiers(&self) -> Option<&BTreeSet<PButton>> {
+        match self {
+            Trigger::Plain(_) => None,
+            Trigger::Chord { modifiers, .. } => Some(modifiers),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Plain,
+    Chord,
+}
+
+/// how the buttons of two triggers overlap, from the first one's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlap {
+    // exactly the same buttons
+    Same,
+    // the first is held as a modifier of the second
+    IsModifier,
+    // same primary, different modifiers
+    SharedPrimary,
+    Disjoint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Conflict,
+}
+
+/// what two bindings in each combination amount to, anything not listed is
+/// fine. `same_when` rules only apply when both run on the same `RunWhen`
+pub struct Rule {
+    pub first: Shape,
+    pub second: Shape,
+    pub overlap: Overlap,
+    pub same_when: bool,
+    pub severity: Severity,
+    pub explain: &'static str,
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        first: Shape::Plain,
+        second: Shape::Plain,
+        overlap: Overlap::Same,
+        same_when: true,
+        severity: Severity::Conflict,
+        explain: "both run from the same button",
+    },
+    Rule {
+        first: Shape::Chord,
+        second: Shape::Chord,
+        overlap: Overlap::Same,
+        same_when: true,
+        severity: Severity::Conflict,
+        explain: "both run from the same chord",
+    },
+    Rule {
+        first: Shape::Plain,
+        second: Shape::Chord,
+        overlap: Overlap::IsModifier,
+        same_when: false,
+        severity: Severity::Warning,
+        explain: "holding the modifier for the chord also runs the plain binding",
+    },
+    Rule {
+        first: Shape::Plain,
+        second: Shape::Chord,
+        overlap: Overlap::SharedPrimary,
+        same_when: false,
+        severity: Severity::Warning,
+        explain: "pressing the chord also runs the plain binding",
+    },
+];
+
+pub fn overlap(a: &Trigger, b: &Trigger) -> Overlap {
+    if a == b {
+        return Overlap::Same;
+    }
+
+    if b.modifiers()
+        .is_some_and(|modifiers| modifiers.contains(&a.primary()))
+    {
+        return Overlap::IsModifier;
+    }
+
+    if a.primary() == b.primary() {
+        return Overlap::SharedPrimary;
+    }
+
+    Overlap::Disjoint
+}
+
+/// the rule two triggers fall under, trying both orders since the table only
+/// lists each pair once
+pub fn rule_for(
+    a: &Trigger,
+    when_a: RunWhen,
+    b: &Trigger,
+    when_b: RunWhen,
+) -> Option<&'static Rule> {
+    let find = |first: &Trigger, second: &Trigger| {
+        let overlap = overlap(first, second);
+
+        RULES.iter().find(|rule| {
+            rule.first == first.shape()
+                && rule.second == second.shape()
+                && rule.overlap == overlap
+                && (!rule.same_when || when_a == when_b)
+        })
+    };
+
+    find(a, b).or_else(|| find(b, a))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// what the rules say about one pair of bindings, none when they're fine
+/// together or both belong to the same command
+pub fn finding(
+    (command_a, a, when_a): (&Rc<String>, &Trigger, RunWhen),
+    (command_b, b, when_b): (&Rc<String>, &Trigger, RunWhen),
+    describe: &impl Fn(&Trigger) -> String,
+) -> Option<Finding> {
+    if command_a == command_b {
+        return None;
+    }
+
+    rule_for(a, when_a, b, when_b).map(|rule| Finding {
+        severity: rule.severity,
+        message: format!(
+            "{command_a} on {} and {command_b} on {}: {}",
+            describe(a),
+            describe(b),
+            rule.explain
+        ),
+    })
+}
+
+/// checks every pair of bindings against the rules
+pub fn findings(
+    bindings: &[(Rc<String>, Trigger, RunWhen)],
+    describe: impl Fn(&Trigger) -> String,
+) -> Vec<Finding> {
+    let mut found = Vec::new();
+
+    for (i, (command_a, a, when_a)) in bindings.iter().enumerate() {
+        for (command_b, b, when_b) in &bindings[i + 1..] {
+            found.extend(finding(
+                (command_a, a, *when_a),
+                (command_b, b, *when_b),
+                &describe,
+            ));
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bindings::{Button, ButtonLocation};
+
+    fn button(n: i16) -> PButton {
+        (
+            0,
+            Button {
+                button: n,
+                location: ButtonLocation::Button,
+            },
+        )
+    }
+
+    fn plain(n: i16) -> Trigger {
+        Trigger::Plain(button(n))
+    }
+
+    fn chord(primary: i16, modifiers: &[i16]) -> Trigger {
+        Trigger::Chord {
+            primary: button(primary),
+            modifiers: modifiers.iter().map(|m| button(*m)).collect(),
+        }
+    }
+
+    fn severity(a: &Trigger, b: &Trigger) -> Option<Severity> {
+        rule_for(a, RunWhen::OnTrue, b, RunWhen::OnTrue).map(|rule| rule.severity)
+    }
+
+    #[test]
+    fn plain_and_plain() {
+        assert_eq!(severity(&plain(1), &plain(1)), Some(Severity::Conflict));
+        assert_eq!(severity(&plain(1), &plain(2)), None);
+        assert_eq!(
+            rule_for(&plain(1), RunWhen::OnTrue, &plain(1), RunWhen::OnFalse).map(|r| r.severity),
+            None
+        );
+    }
+
+    #[test]
+    fn plain_and_chord() {
+        // left bumper alone and left bumper + a
+        assert_eq!(
+            severity(&plain(5), &chord(1, &[5])),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            severity(&chord(1, &[5]), &plain(5)),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            severity(&plain(1), &chord(1, &[5])),
+            Some(Severity::Warning)
+        );
+        assert_eq!(severity(&plain(2), &chord(1, &[5])), None);
+        assert_eq!(
+            rule_for(
+                &plain(5),
+                RunWhen::OnTrue,
+                &chord(1, &[5]),
+                RunWhen::WhileFalse
+            )
+            .map(|r| r.severity),
+            Some(Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn chord_and_chord() {
+        assert_eq!(
+            severity(&chord(1, &[5]), &chord(1, &[5])),
+            Some(Severity::Conflict)
+        );
+        assert_eq!(severity(&chord(1, &[5]), &chord(1, &[6])), None);
+        assert_eq!(severity(&chord(1, &[5]), &chord(2, &[6])), None);
+    }
+
+    #[test]
+    fn findings_name_both_commands() {
+        let bindings = [
+            (Rc::new("intake".to_string()), plain(5), RunWhen::OnTrue),
+            (
+                Rc::new("shoot".to_string()),
+                chord(1, &[5]),
+                RunWhen::OnTrue,
+            ),
+            (
+                Rc::new("climb".to_string()),
+                chord(1, &[6]),
+                RunWhen::OnTrue,
+            ),
+        ];
+
+        let found = findings(&bindings, |trigger| match trigger {
+            Trigger::Plain(b) => b.1.button.to_string(),
+            Trigger::Chord { primary, modifiers } => {
+                let mut names = modifiers
+                    .iter()
+                    .map(|m| m.1.button.to_string())
+                    .collect::<Vec<_>>();
+                names.push(primary.1.button.to_string());
+                names.join(" + ")
+            }
+        });
+
+        assert_eq!(
+            found,
+            vec![Finding {
+                severity: Severity::Warning,
+                message: "intake on 5 and shoot on 5 + 1: holding the modifier for the chord also runs the plain binding".to_string(),
+            }]
+        );
+    }
+}
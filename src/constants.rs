@@ -150,6 +150,42 @@ impl Constants {
         false
     }
 
+    /// renames the last part of `key` to `to`, keeping its value. returns
+    /// true when there was nothing to rename or `to` is already taken
+    pub fn rename_key(&mut self, key: &[Rc<String>], to: Rc<String>) -> bool {
+        let Some((name, parents)) = key.split_last() else {
+            return true;
+        };
+
+        let mut cloc = self;
+
+        for l in parents {
+            match cloc {
+                Constants::Object { map } => match map.get_mut(l) {
+                    Some(next) => cloc = next,
+                    None => return true,
+                },
+                _ => return true,
+            }
+        }
+
+        let Constants::Object { map } = cloc else {
+            return true;
+        };
+
+        if map.contains_key(&to) {
+            return true;
+        }
+
+        match map.remove(name) {
+            Some(value) => {
+                map.insert(to, value);
+                false
+            }
+            None => true,
+        }
+    }
+
     pub fn remove_key(&mut self, key: &[Rc<String>]) {
         if *self == Constants::None {
             return;
@@ -170,6 +206,90 @@ impl Constants {
     }
 }
 
+/// where the value the robot sees for a driver constant comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    ThisProfile,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::ThisProfile => write!(f, "this profile"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved<'a> {
+    pub value: &'a Constants,
+    pub source: Source,
+    // every place that was looked at, in order, and what it had
+    pub chain: Vec<(Source, Option<&'a Constants>)>,
+}
+
+/// the value a driver constant ends up with: the first of `layers` that sets
+/// it, going from this profile outwards, otherwise the default
+pub fn resolve<'a>(
+    default: &'a Constants,
+    layers: impl IntoIterator<Item = (Source, Option<&'a Constants>)>,
+) -> Resolved<'a> {
+    let mut chain = layers.into_iter().collect::<Vec<_>>();
+
+    chain.push((Source::Default, Some(default)));
+
+    let (source, value) = chain
+        .iter()
+        .find_map(|(source, value)| value.map(|v| (source.clone(), v)))
+        .unwrap_or((Source::Default, default));
+
+    Resolved {
+        value,
+        source,
+        chain,
+    }
+}
+
+impl Constants {
+    pub fn get(&self, key: &[Rc<String>]) -> Option<&Constants> {
+        key.iter().try_fold(self, |cloc, l| match cloc {
+            Constants::Object { map } => map.get(l),
+            _ => None,
+        })
+    }
+
+    /// the constants the way the robot sees them with `overrides` as the
+    /// profile's driver constants, every driver constant replaced by its value
+    pub fn resolved(&self, overrides: &Constants) -> Constants {
+        self.resolved_at(&mut Vec::new(), overrides)
+    }
+
+    fn resolved_at(&self, path: &mut Vec<Rc<String>>, overrides: &Constants) -> Constants {
+        match self {
+            Constants::Object { map } => Constants::Object {
+                map: map
+                    .iter()
+                    .map(|(key, value)| {
+                        path.push(key.clone());
+                        let resolved = value.resolved_at(path, overrides);
+                        path.pop();
+
+                        (key.clone(), resolved)
+                    })
+                    .collect(),
+            },
+            Constants::Driver { default } => {
+                resolve(default, [(Source::ThisProfile, overrides.get(path))])
+                    .value
+                    .clone()
+            }
+            other => other.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ConstantsType {
     Object,
@@ -228,6 +348,7 @@ impl ConstantsType {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn selector_go(
         &mut self,
         filters: &mut Vec<String>,
@@ -248,6 +369,8 @@ impl ConstantsType {
             &mut filters[loc],
             self,
             Self::valid_types(arena, driver).map(|a| (Rc::new(a.to_string()), a)),
+            None,
+            None,
             &mut caches[loc],
             100.0,
             ui,
@@ -282,3 +405,67 @@ impl ConstantsType {
 }
 
 pub type OptionLocation = Rc<Vec<Rc<String>>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(keys: &[&str]) -> Vec<Rc<String>> {
+        keys.iter().map(|k| Rc::new(k.to_string())).collect()
+    }
+
+    #[test]
+    fn first_layer_with_a_value_wins() {
+        let default = Constants::Float(1.0);
+        let mine = Constants::Float(0.8);
+
+        let resolved = resolve(&default, [(Source::ThisProfile, None)]);
+
+        assert_eq!(resolved.value, &default);
+        assert_eq!(resolved.source, Source::Default);
+        assert_eq!(resolved.chain.len(), 2);
+
+        let resolved = resolve(&default, [(Source::ThisProfile, Some(&mine))]);
+
+        assert_eq!(resolved.value, &mine);
+        assert_eq!(resolved.source, Source::ThisProfile);
+    }
+
+    #[test]
+    fn resolved_replaces_driver_constants() {
+        let mut constants = Constants::None;
+
+        constants.add_option(
+            Rc::new(path(&["shooter", "speed"])),
+            Constants::Driver {
+                default: Box::new(Constants::Float(1.0)),
+            },
+        );
+        constants.add_option(
+            Rc::new(path(&["shooter", "angle"])),
+            Constants::Driver {
+                default: Box::new(Constants::Float(30.0)),
+            },
+        );
+        constants.add_option(Rc::new(path(&["name"])), Constants::String("bot".into()));
+
+        let mut overrides = Constants::None;
+
+        overrides.add_option(Rc::new(path(&["shooter", "speed"])), Constants::Float(0.8));
+
+        let resolved = constants.resolved(&overrides);
+
+        assert_eq!(
+            resolved.get(&path(&["shooter", "speed"])),
+            Some(&Constants::Float(0.8))
+        );
+        assert_eq!(
+            resolved.get(&path(&["shooter", "angle"])),
+            Some(&Constants::Float(30.0))
+        );
+        assert_eq!(
+            resolved.get(&path(&["name"])),
+            Some(&Constants::String("bot".into()))
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use egui::{vec2, Color32, Sense, TextStyle, Ui};
+
+use crate::bindings::CONTROLLER_SLOTS;
+
+/// an accent for each controller slot, taken from the catppuccin flavor that
+/// matches the current visuals so it reads on light and dark themes
+pub fn palette(dark_mode: bool) -> [Color32; CONTROLLER_SLOTS] {
+    let theme = if dark_mode {
+        catppuccin_egui::MOCHA
+    } else {
+        catppuccin_egui::LATTE
+    };
+
+    [
+        theme.blue,
+        theme.peach,
+        theme.green,
+        theme.mauve,
+        theme.teal,
+    ]
+}
+
+/// `picked` if there is one, otherwise the palette color for `slot`
+pub fn color(picked: Option<[u8; 3]>, slot: u8, dark_mode: bool) -> Color32 {
+    match picked {
+        Some([r, g, b]) => Color32::from_rgb(r, g, b),
+        None => palette(dark_mode)[slot as usize],
+    }
+}
+
+/// a small square of `color` to put in front of a controller's name
+pub fn chip(ui: &mut Ui, color: Color32) {
+    let size = ui.text_style_height(&TextStyle::Body) * 0.7;
+
+    let (rect, _) = ui.allocate_exact_size(vec2(size, size), Sense::hover());
+
+    ui.painter().rect_filled(rect, 2.0, color);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slots_are_distinct() {
+        for dark_mode in [true, false] {
+            let palette = palette(dark_mode);
+
+            for (i, a) in palette.iter().enumerate() {
+                assert!(!palette[i + 1..].contains(a));
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::ProgramError;
+
+/// how much has to be left over after a save unless the project says otherwise
+pub const DEFAULT_MIN_FREE: u64 = 5 * 1024 * 1024;
+
+pub fn default_min_free() -> u64 {
+    DEFAULT_MIN_FREE
+}
+
+pub trait SpaceProvider {
+    /// bytes available to this user on the drive holding `path`
+    fn free_bytes(&self, path: &Path) -> Result<u64>;
+}
+
+/// asks the os
+pub struct SystemSpace;
+
+impl SpaceProvider for SystemSpace {
+    #[cfg(unix)]
+    fn free_bytes(&self, path: &Path) -> Result<u64> {
+        use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+        // SAFETY: `path` is nul terminated and `stat` is only read after
+        // statvfs reports it filled it in
+        if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+            return Err(std::io::Error::last_os_error())?;
+        }
+
+        let stat = unsafe { stat.assume_init() };
+
+        // the field widths differ between platforms
+        #[allow(clippy::unnecessary_cast)]
+        let free = stat.f_bavail as u64 * stat.f_frsize as u64;
+
+        Ok(free)
+    }
+
+    #[cfg(windows)]
+    fn free_bytes(&self, path: &Path) -> Result<u64> {
+        use std::{iter, os::windows::ffi::OsStrExt};
+
+        extern "system" {
+            fn GetDiskFreeSpaceExW(
+                directory: *const u16,
+                free_to_caller: *mut u64,
+                total: *mut u64,
+                total_free: *mut u64,
+            ) -> i32;
+        }
+
+        let path = path
+            .as_os_str()
+            .encode_wide()
+            .chain(iter::once(0))
+            .collect::<Vec<_>>();
+
+        let mut free = 0;
+
+        // SAFETY: `path` is nul terminated and the totals we don't want may be
+        // null
+        if unsafe {
+            GetDiskFreeSpaceExW(
+                path.as_ptr(),
+                &mut free,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        } == 0
+        {
+            return Err(std::io::Error::last_os_error())?;
+        }
+
+        Ok(free)
+    }
+}
+
+/// fails when writing `needed` bytes to `path` would leave less than
+/// `min_free`. a drive we can't measure doesn't block saving
+pub fn ensure_free(
+    provider: &dyn SpaceProvider,
+    path: &Path,
+    min_free: u64,
+    needed: u64,
+) -> Result<()> {
+    let Ok(free) = provider.free_bytes(path) else {
+        return Ok(());
+    };
+
+    if free < needed.saturating_add(min_free) {
+        Err(ProgramError::LowDiskSpace { free, min_free })?;
+    }
+
+    Ok(())
+}
+
+/// `bytes` as megabytes with a single decimal
+pub fn show_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Fixed(Option<u64>);
+
+    impl SpaceProvider for Fixed {
+        fn free_bytes(&self, _path: &Path) -> Result<u64> {
+            self.0.ok_or_else(|| anyhow::anyhow!("no drive"))
+        }
+    }
+
+    #[test]
+    fn threshold_includes_the_write() {
+        let path = Path::new("deploy");
+
+        assert!(ensure_free(
+            &Fixed(Some(DEFAULT_MIN_FREE + 100)),
+            path,
+            DEFAULT_MIN_FREE,
+            100
+        )
+        .is_ok());
+        assert!(ensure_free(
+            &Fixed(Some(DEFAULT_MIN_FREE + 99)),
+            path,
+            DEFAULT_MIN_FREE,
+            100
+        )
+        .is_err());
+        assert!(ensure_free(&Fixed(Some(0)), path, 0, 0).is_ok());
+    }
+
+    #[test]
+    fn unknown_space_allows_saving() {
+        assert!(ensure_free(&Fixed(None), Path::new("deploy"), DEFAULT_MIN_FREE, 100).is_ok());
+    }
+
+    #[test]
+    fn reports_space_left() {
+        let err = ensure_free(
+            &Fixed(Some(1024 * 1024)),
+            Path::new("deploy"),
+            DEFAULT_MIN_FREE,
+            0,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "only 1.0 MB free, saving needs at least 5.0 MB left over"
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use bumpalo::Bump;
+
+use crate::{
+    bindings::{BindingsMap, ButtonLocation, CommandMeta, ControllerType, Profile},
+    name_check,
+};
+
+/// one name per line, each prefixed with what it is so a pasted list of
+/// commands and streams can be told apart
+pub fn name_list<'a>(kind: &str, names: impl Iterator<Item = &'a Rc<String>>) -> String {
+    names
+        .map(|name| format!("{kind}: {name}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// the names in a pasted list (one per line, optionally with the prefix
+/// `name_list` adds) that aren't in `existing` yet, and how many lines were
+/// skipped for being empty or already there
+pub fn parse_name_list(
+    kind: &str,
+    text: &str,
+    existing: &BTreeSet<Rc<String>>,
+) -> (Vec<String>, usize) {
+    let mut names: Vec<String> = Vec::new();
+    let mut skipped = 0;
+
+    for line in text.lines() {
+        let line = line.trim();
+        let name = line
+            .strip_prefix(kind)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .unwrap_or(line)
+            .trim();
+
+        let name = name_check::normalize(name);
+
+        if name.is_empty() || name_check::name_taken(existing, &name) || names.contains(&name) {
+            skipped += 1;
+        } else {
+            names.push(name);
+        }
+    }
+
+    (names, skipped)
+}
+
+pub fn markdown_table<const N: usize>(
+    header: [&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) -> String {
+    let mut table = format!("| {} |\n|{}", header.join(" | "), " --- |".repeat(N));
+
+    for row in rows {
+        let cells = row.iter().map(|cell| escape_cell(cell)).collect::<Vec<_>>();
+
+        table.push_str(&format!("\n| {} |", cells.join(" | ")));
+    }
+
+    table
+}
+
+/// every command with how many bindings it has and what it does
+pub fn command_table(
+    commands: &BTreeSet<Rc<String>>,
+    bindings: &BindingsMap,
+    meta: &BTreeMap<Rc<String>, CommandMeta>,
+) -> String {
+    markdown_table(
+        ["command", "bindings", "description"],
+        commands.iter().map(|command| {
+            let count = bindings
+                .command_to_bindings
+                .get(command)
+                .map_or(0, Vec::len);
+            let description = meta
+                .get(command)
+                .and_then(|meta| meta.description.clone())
+                .unwrap_or_default();
+
+            [command.to_string(), count.to_string(), description]
+        }),
+    )
+}
+
+/// every binding as a markdown table for printing, `controllers` are the
+/// slots' names and types. unbound slots and switched off bindings are left
+/// out
+pub fn cheat_sheet(
+    profile: &str,
+    generated: &str,
+    bindings: &BindingsMap,
+    controllers: &[(Rc<String>, ControllerType)],
+    arena: &Bump,
+) -> String {
+    let mut sheet = format!(
+        "# {profile} bindings\n\ngenerated {generated}\n\n\
+        | controller | button | command | when |\n| --- | --- | --- | --- |"
+    );
+
+    for (key @ ((controller, button), modifier), commands) in &bindings.binding_to_commands {
+        let Some((name, kind)) = controllers
+            .get(*controller as usize)
+            .filter(|(_, kind)| kind.bound())
+        else {
+            continue;
+        };
+
+        for (command, when) in commands {
+            if !bindings.is_enabled(command, *key, *when) {
+                continue;
+            }
+
+            sheet.push_str(&format!(
+                "\n| {} | {} | {} | {} |",
+                escape_cell(name),
+                escape_cell(kind.chord_name(button, *modifier, arena)),
+                escape_cell(command),
+                when
+            ));
+        }
+    }
+
+    sheet
+}
+
+/// every binding in every profile, a row each, for pasting into a
+/// spreadsheet. slots that aren't bound in a profile are left out
+pub fn bindings_csv<'a>(
+    profiles: impl Iterator<Item = (Rc<String>, Profile<'a>)>,
+    arena: &Bump,
+) -> String {
+    let mut csv =
+        "profile,controller_slot,controller_name,button,location,run_when,command,enabled"
+            .to_string();
+
+    for (name, profile) in profiles {
+        for (command, bindings) in profile.command_to_bindings.iter() {
+            for binding in bindings {
+                let slot = binding.controller as usize;
+
+                let Some(controller) = profile.controllers.get(slot).filter(|c| c.bound()) else {
+                    continue;
+                };
+
+                let controller_name = match profile.controller_names.get(slot) {
+                    Some(name) if !name.is_empty() => name.to_string(),
+                    _ => slot.to_string(),
+                };
+
+                let location = match binding.button.location {
+                    ButtonLocation::Button => "button",
+                    ButtonLocation::Analog => "analog",
+                    ButtonLocation::Pov => "pov",
+                };
+
+                let row = [
+                    name.as_str(),
+                    &slot.to_string(),
+                    &controller_name,
+                    controller.chord_name(&binding.button, binding.modifier, arena),
+                    location,
+                    binding.during.get_str(),
+                    command,
+                    if binding.enabled { "true" } else { "false" },
+                ]
+                .map(escape_csv);
+
+                csv.push('\n');
+                csv.push_str(&row.join(","));
+            }
+        }
+    }
+
+    csv
+}
+
+/// quotes fields that have commas, quotes or line breaks in them
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefixed_names() {
+        let names = [Rc::new("intake".to_string()), Rc::new("shoot".to_string())];
+
+        assert_eq!(
+            name_list("command", names.iter()),
+            "command: intake\ncommand: shoot"
+        );
+        assert_eq!(name_list("stream", [].iter()), "");
+    }
+
+    #[test]
+    fn pasted_names() {
+        let existing = BTreeSet::from([Rc::new("shoot".to_string())]);
+
+        let (names, skipped) = parse_name_list(
+            "command",
+            "  intake \n\nshoot\ncommand: climb\nintake\n",
+            &existing,
+        );
+
+        assert_eq!(names, vec!["intake".to_string(), "climb".to_string()]);
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn cheat_sheet_names_buttons() {
+        use crate::bindings::{Binding, Button, ButtonLocation, Debounce, RunWhen};
+
+        let arena = Bump::new();
+        let mut bindings = BindingsMap::default();
+
+        let binding = |controller, button| Binding {
+            controller,
+            button: Button {
+                button,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::OnTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        bindings.add_binding(Rc::new("shoot".to_string()), binding(0, 5));
+        bindings.add_binding(Rc::new("intake".to_string()), binding(1, 3));
+        bindings.add_binding(Rc::new("climb".to_string()), binding(2, 1));
+        bindings.add_binding(
+            Rc::new("lift".to_string()),
+            Binding {
+                enabled: false,
+                ..binding(0, 6)
+            },
+        );
+
+        let controllers = [
+            (
+                Rc::new("driver".to_string()),
+                ControllerType::XBox { sensitivity: 0.5 },
+            ),
+            (
+                Rc::new("operator".to_string()),
+                ControllerType::Generic {
+                    buttons: 12,
+                    axises: 4,
+                    sensitivity: 0.5,
+                },
+            ),
+            (Rc::new("2".to_string()), ControllerType::NotBound),
+        ];
+
+        assert_eq!(
+            cheat_sheet(
+                "practice",
+                "2025-03-01T12:00:00Z",
+                &bindings,
+                &controllers,
+                &arena
+            ),
+            "# practice bindings\n\
+            \n\
+            generated 2025-03-01T12:00:00Z\n\
+            \n\
+            | controller | button | command | when |\n\
+            | --- | --- | --- | --- |\n\
+            | driver | left bumper | shoot | on true |\n\
+            | operator | 3 | intake | on true |"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_and_skips_unbound_controllers() {
+        use std::{borrow::Cow, collections::BTreeMap};
+
+        use crate::bindings::{Binding, Button, Debounce, RunWhen};
+
+        let arena = Bump::new();
+
+        let binding = |controller| Binding {
+            controller,
+            button: Button {
+                button: 1,
+                location: ButtonLocation::Button,
+            },
+            during: RunWhen::WhileTrue,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let mut controllers = [ControllerType::NotBound; 5];
+        controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        let mut names: [Rc<String>; 5] = Default::default();
+        names[0] = Rc::new("driver, main".to_string());
+
+        let profile = Profile {
+            command_to_bindings: Cow::Owned(BTreeMap::from([
+                (Rc::new("say \"hi\"".to_string()), vec![binding(0)]),
+                (Rc::new("shoot".to_string()), vec![binding(0), binding(1)]),
+                (
+                    Rc::new("climb".to_string()),
+                    vec![Binding {
+                        enabled: false,
+                        ..binding(0)
+                    }],
+                ),
+            ])),
+            controllers: Cow::Owned(controllers),
+            controller_names: Cow::Owned(names),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            bindings_csv(
+                [(Rc::new("default".to_string()), profile)].into_iter(),
+                &arena
+            ),
+            "profile,controller_slot,controller_name,button,location,run_when,command,enabled\n\
+            default,0,\"driver, main\",a,button,while true,climb,false\n\
+            default,0,\"driver, main\",a,button,while true,\"say \"\"hi\"\"\",true\n\
+            default,0,\"driver, main\",a,button,while true,shoot,true"
+        );
+    }
+
+    #[test]
+    fn table_escapes_pipes() {
+        let rows = [
+            ["shoot".to_string(), "2".to_string()],
+            ["a|b".to_string(), "0".to_string()],
+        ];
+
+        assert_eq!(
+            markdown_table(["command", "bindings"], rows.into_iter()),
+            "| command | bindings |\n| --- | --- |\n| shoot | 2 |\n| a\\|b | 0 |"
+        );
+    }
+
+    #[test]
+    fn command_table_has_descriptions() {
+        use crate::bindings::{Binding, Button, Debounce, RunWhen};
+
+        let shoot = Rc::new("shoot".to_string());
+        let climb = Rc::new("climb".to_string());
+
+        let mut bindings = BindingsMap::default();
+
+        for button in [1, 2] {
+            bindings.add_binding(
+                shoot.clone(),
+                Binding {
+                    controller: 0,
+                    button: Button {
+                        button,
+                        location: ButtonLocation::Button,
+                    },
+                    during: RunWhen::OnTrue,
+                    debounce: Debounce::NONE,
+                    threshold: None,
+                    modifier: None,
+                    enabled: true,
+                },
+            );
+        }
+
+        let meta = BTreeMap::from([(
+            shoot.clone(),
+            CommandMeta {
+                group: Some("shooter".to_string()),
+                description: Some("spins up | fires".to_string()),
+            },
+        )]);
+
+        assert_eq!(
+            command_table(&BTreeSet::from([shoot, climb]), &bindings, &meta),
+            "| command | bindings | description |\n| --- | --- | --- |\n\
+            | climb | 0 |  |\n\
+            | shoot | 2 | spins up \\| fires |"
+        );
+    }
+}
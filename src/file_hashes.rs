@@ -0,0 +1,262 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    ffi::OsString,
+    fs::{copy, read, read_to_string, remove_file, rename, File},
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{recovery::BrokenFile, ProgramError};
+
+/// where the previous version of `path` is kept, next to it with .bak on the
+/// end
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.file_name().unwrap_or_default());
+
+    name.push(suffix);
+
+    path.with_file_name(name)
+}
+
+/// parses `path`, falling back to its backup when the file itself can't be
+/// read or parsed. the flag is true when the backup was used, the error is
+/// the one for the file itself, json it can't parse is a
+/// `ProgramError::Malformed`
+pub fn read_or_backup<T>(path: &Path, parse: impl Fn(&str) -> Result<T>) -> Result<(T, bool)> {
+    let err = match read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| parse(&contents))
+    {
+        Ok(value) => return Ok((value, false)),
+        Err(err) => err,
+    };
+
+    match read_to_string(backup_path(path))
+        .ok()
+        .and_then(|contents| parse(&contents).ok())
+    {
+        Some(value) => Ok((value, true)),
+        None => match err.downcast_ref::<serde_json::Error>() {
+            Some(json) if !json.is_io() => {
+                Err(ProgramError::Malformed(BrokenFile::new(path, json)))?
+            }
+            _ => Err(err),
+        },
+    }
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// what each project file looked like the last time we read or wrote it, so a
+/// save can tell when someone else changed the file in between. timestamps
+/// aren't trusted for this since restored backups and bad clocks move them
+/// backwards
+#[derive(Debug, Default, Clone)]
+pub struct FileHashes {
+    hashes: HashMap<PathBuf, u64>,
+}
+
+impl FileHashes {
+    pub fn record(&mut self, path: &Path, bytes: &[u8]) {
+        self.hashes.insert(path.to_owned(), hash(bytes));
+    }
+
+    /// records the file as it is on disk now, a missing file is forgotten
+    pub fn record_file(&mut self, path: &Path) {
+        match read(path) {
+            Ok(bytes) => self.record(path, &bytes),
+            Err(_) => self.forget(path),
+        }
+    }
+
+    pub fn forget(&mut self, path: &Path) {
+        self.hashes.remove(path);
+    }
+
+    pub fn forget_all(&mut self) {
+        self.hashes.clear();
+    }
+
+    /// true when the file on disk isn't what we last saw. files we never saw
+    /// and files that were deleted can't lose anything by being written
+    pub fn changed_externally(&self, path: &Path) -> bool {
+        let Some(recorded) = self.hashes.get(path) else {
+            return false;
+        };
+
+        match read(path) {
+            Ok(bytes) => hash(&bytes) != *recorded,
+            Err(_) => false,
+        }
+    }
+
+    /// writes `contents` to `path` and remembers it as ours. what was there
+    /// before is kept at `backup_path`
+    pub fn write(&mut self, path: &Path, contents: &[u8]) -> Result<()> {
+        write_file(path, contents)?;
+
+        self.record(path, contents);
+
+        Ok(())
+    }
+
+    /// writes `contents` to `path` and remembers it as ours, see
+    /// `write_file_without_backup`
+    pub fn write_without_backup(&mut self, path: &Path, contents: &[u8]) -> Result<()> {
+        write_file_without_backup(path, contents)?;
+
+        self.record(path, contents);
+
+        Ok(())
+    }
+}
+
+/// writes `contents` to `path`, keeping what was there before at
+/// `backup_path`
+pub fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if path.is_file() {
+        copy(path, backup_path(path)).with_context(|| "failed to back up file")?;
+    }
+
+    write_file_without_backup(path, contents)
+}
+
+/// writes `contents` to a temporary file first that's renamed over `path`, so
+/// a crash half way leaves the old file whole
+pub fn write_file_without_backup(path: &Path, contents: &[u8]) -> Result<()> {
+    let temp = with_suffix(path, ".tmp");
+
+    write_temp(&temp, contents)?;
+
+    rename(&temp, path).with_context(|| "failed to replace file")?;
+
+    Ok(())
+}
+
+/// writes every file to its temporary file and backs up what's there, and
+/// only once all of that worked renames them over. a failure before then
+/// leaves every one of them as it was
+pub fn write_files_together(files: &[(PathBuf, Vec<u8>)]) -> Result<()> {
+    let temps: Vec<_> = files
+        .iter()
+        .map(|(path, _)| with_suffix(path, ".tmp"))
+        .collect();
+
+    let prepared = files
+        .iter()
+        .zip(&temps)
+        .try_for_each(|((path, contents), temp)| {
+            write_temp(temp, contents)?;
+
+            if path.is_file() {
+                copy(path, backup_path(path)).with_context(|| "failed to back up file")?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        });
+
+    if let Err(err) = prepared {
+        for temp in temps {
+            let _ = remove_file(temp);
+        }
+
+        return Err(err);
+    }
+
+    for ((path, _), temp) in files.iter().zip(temps) {
+        rename(&temp, path).with_context(|| "failed to replace file")?;
+    }
+
+    Ok(())
+}
+
+fn write_temp(temp: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = File::create(temp).with_context(|| "failed to create file to save to")?;
+
+    file.write_all(contents)
+        .with_context(|| "failed to save to disk")?;
+
+    file.sync_all().with_context(|| "failed to save to disk")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn notices_writes_behind_our_back() {
+        let temp = temp_dir("hashes");
+        let path = temp.path().join("bindings.json");
+
+        let mut hashes = FileHashes::default();
+
+        write(&path, "{}").unwrap();
+
+        assert!(!hashes.changed_externally(&path));
+
+        hashes.record_file(&path);
+
+        assert!(!hashes.changed_externally(&path));
+
+        write(&path, "{\"theirs\": true}").unwrap();
+
+        assert!(hashes.changed_externally(&path));
+
+        hashes.write(&path, b"{}").unwrap();
+
+        assert!(!hashes.changed_externally(&path));
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!hashes.changed_externally(&path));
+    }
+
+    #[test]
+    fn writes_keep_the_last_version() {
+        let temp = temp_dir("backup");
+        let path = temp.path().join("bindings.json");
+        let backup = backup_path(&path);
+
+        let mut hashes = FileHashes::default();
+
+        hashes.write(&path, b"1").unwrap();
+
+        assert!(!backup.exists());
+
+        hashes.write(&path, b"2").unwrap();
+        hashes.write(&path, b"3").unwrap();
+
+        assert_eq!(read(&path).unwrap(), b"3");
+        assert_eq!(read(&backup).unwrap(), b"2");
+        assert!(!with_suffix(&path, ".tmp").exists());
+
+        let parse = |s: &str| s.parse::<u8>().map_err(anyhow::Error::from);
+
+        assert_eq!(read_or_backup(&path, parse).unwrap(), (3, false));
+
+        // cut off half way through
+        write(&path, "").unwrap();
+
+        assert_eq!(read_or_backup(&path, parse).unwrap(), (2, true));
+
+        write(&backup, "").unwrap();
+
+        assert!(read_or_backup(&path, parse).is_err());
+    }
+}
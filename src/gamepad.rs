@@ -0,0 +1,715 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+use egui::{Align2, Id, Ui, Window};
+use gilrs::{Axis, Button as PadButton, Event, EventType, Gamepad, GamepadId, Gilrs};
+
+use crate::{
+    bindings::{Button, ButtonLocation, ControllerType, DeviceCaps, PButton, CONTROLLER_SLOTS},
+    component::EventStream,
+    global_state::{GlobalEvents, State},
+};
+
+/// how long a listen waits for a press before giving up
+pub const LISTEN_FOR: Duration = Duration::from_secs(5);
+
+// how far an axis has to move before it counts as pressed
+const AXIS_PRESS: f32 = 0.5;
+
+// the driver station numbers xbox buttons in this order, starting from 1
+const XBOX_ORDER: [PadButton; 10] = [
+    PadButton::South,
+    PadButton::East,
+    PadButton::West,
+    PadButton::North,
+    PadButton::LeftTrigger,
+    PadButton::RightTrigger,
+    PadButton::Select,
+    PadButton::Start,
+    PadButton::LeftThumb,
+    PadButton::RightThumb,
+];
+
+// everything but the dpad, which the driver station reports as a pov
+const COUNTED_BUTTONS: [PadButton; 15] = [
+    PadButton::South,
+    PadButton::East,
+    PadButton::North,
+    PadButton::West,
+    PadButton::C,
+    PadButton::Z,
+    PadButton::LeftTrigger,
+    PadButton::LeftTrigger2,
+    PadButton::RightTrigger,
+    PadButton::RightTrigger2,
+    PadButton::Select,
+    PadButton::Start,
+    PadButton::Mode,
+    PadButton::LeftThumb,
+    PadButton::RightThumb,
+];
+
+const COUNTED_AXES: [Axis; 6] = [
+    Axis::LeftStickX,
+    Axis::LeftStickY,
+    Axis::LeftZ,
+    Axis::RightStickX,
+    Axis::RightStickY,
+    Axis::RightZ,
+];
+
+fn pov(angle: i16) -> Button {
+    Button {
+        button: angle,
+        location: ButtonLocation::Pov,
+    }
+}
+
+fn dpad(button: PadButton) -> Option<Button> {
+    match button {
+        PadButton::DPadUp => Some(pov(0)),
+        PadButton::DPadRight => Some(pov(90)),
+        PadButton::DPadDown => Some(pov(180)),
+        PadButton::DPadLeft => Some(pov(270)),
+        _ => None,
+    }
+}
+
+/// the button number the driver station would give a raw button code on a
+/// generic joystick, they count up from the first joystick button
+pub fn generic_button(code: u32) -> Option<i16> {
+    let code = code & 0xffff;
+
+    // linux reports evdev key codes, joystick buttons start at BTN_TRIGGER
+    #[cfg(target_os = "linux")]
+    let number = code.checked_sub(0x120).map(|n| n + 1);
+    // hid usages on the button page already start at 1
+    #[cfg(target_os = "macos")]
+    let number = Some(code);
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let number = Some(code + 1);
+
+    number.and_then(|n| i16::try_from(n).ok())
+}
+
+/// the axis number for a raw axis code, x, y, z, rx, ry, rz like the driver
+/// station
+pub fn generic_axis(code: u32) -> Option<i16> {
+    let code = code & 0xffff;
+
+    #[cfg(target_os = "macos")]
+    let number = code.checked_sub(0x30);
+    #[cfg(not(target_os = "macos"))]
+    let number = Some(code);
+
+    number.and_then(|n| i16::try_from(n).ok())
+}
+
+/// a press or axis move with the raw code it came from, `EventType` without
+/// the parts that don't matter for picking a button
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    Button(PadButton, u32),
+    Axis(Axis, f32, u32),
+}
+
+impl Input {
+    pub fn from_event(event: &EventType) -> Option<Self> {
+        match *event {
+            EventType::ButtonPressed(button, code) => Some(Input::Button(button, code.into_u32())),
+            EventType::AxisChanged(axis, value, code) => {
+                Some(Input::Axis(axis, value, code.into_u32()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// the button `event` presses on a slot set up as `controller`, none for
+/// releases, small stick movements and things the slot doesn't have
+pub fn pressed(controller: &ControllerType, event: &EventType) -> Option<Button> {
+    press(controller, Input::from_event(event)?)
+}
+
+pub fn press(controller: &ControllerType, input: Input) -> Option<Button> {
+    let button = match (controller, input) {
+        (_, Input::Button(button, _)) if dpad(button).is_some() => dpad(button),
+        (_, Input::Axis(Axis::DPadX, value, _)) if value.abs() > AXIS_PRESS => {
+            Some(pov(if value > 0.0 { 90 } else { 270 }))
+        }
+        (_, Input::Axis(Axis::DPadY, value, _)) if value.abs() > AXIS_PRESS => {
+            Some(pov(if value > 0.0 { 0 } else { 180 }))
+        }
+        (ControllerType::XBox { .. }, Input::Button(button, _)) => match button {
+            PadButton::LeftTrigger2 => Some(analog(2)),
+            PadButton::RightTrigger2 => Some(analog(3)),
+            button => XBOX_ORDER
+                .iter()
+                .position(|b| *b == button)
+                .map(|i| Button {
+                    button: i as i16 + 1,
+                    location: ButtonLocation::Button,
+                }),
+        },
+        (ControllerType::XBox { .. }, Input::Axis(axis, value, _)) if value > AXIS_PRESS => {
+            match axis {
+                Axis::LeftZ => Some(analog(2)),
+                Axis::RightZ => Some(analog(3)),
+                _ => None,
+            }
+        }
+        (ControllerType::Generic { .. }, Input::Button(_, code)) => {
+            generic_button(code).map(|button| Button {
+                button,
+                location: ButtonLocation::Button,
+            })
+        }
+        (ControllerType::Generic { .. }, Input::Axis(_, value, code))
+            if value.abs() > AXIS_PRESS =>
+        {
+            generic_axis(code).map(analog)
+        }
+        _ => None,
+    }?;
+
+    controller.valid_binding(button).then_some(button)
+}
+
+fn analog(axis: i16) -> Button {
+    Button {
+        button: axis,
+        location: ButtonLocation::Analog,
+    }
+}
+
+#[derive(Debug)]
+struct Listening {
+    // the listen button that started it, only that one gets the press
+    id: Id,
+    until: Instant,
+    // a gamepad without a slot pressed something, held until the user says
+    // which slot it is
+    unassigned: Option<(GamepadId, EventType, u8)>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    gilrs: Option<Gilrs>,
+    // which controller slot each gamepad is, asked the first time one is used
+    slots: HashMap<GamepadId, u8>,
+    listening: Option<Listening>,
+    // read while listening but not looked at by the listen button yet, the
+    // monitor reads events too and would take them otherwise
+    heard: Vec<Event>,
+}
+
+impl Inner {
+    fn open(&mut self) -> Result<&mut Gilrs, String> {
+        if self.gilrs.is_none() {
+            self.gilrs =
+                Some(Gilrs::new().map_err(|err| format!("couldn't open the gamepads: {err}"))?);
+        }
+
+        Ok(self.gilrs.as_mut().unwrap())
+    }
+
+    /// brings the gamepad states up to date, keeping the events a listen
+    /// still needs
+    fn pump(&mut self) {
+        let Some(gilrs) = &mut self.gilrs else {
+            return;
+        };
+
+        while let Some(event) = gilrs.next_event() {
+            if self.listening.is_some() {
+                self.heard.push(event);
+            }
+        }
+    }
+}
+
+/// the physical gamepads plugged into this computer, only read while
+/// something is listening or the monitor is open. shared by every tab
+/// through the state, so it borrows itself like the event stream does
+#[derive(Debug, Default)]
+pub struct Gamepads {
+    inner: RefCell<Inner>,
+}
+
+impl Gamepads {
+    pub fn slot(&self, gamepad: GamepadId) -> Option<u8> {
+        self.inner.borrow().slots.get(&gamepad).copied()
+    }
+
+    /// puts `gamepad` on `slot`, none takes it off. a slot only has one
+    /// gamepad
+    pub fn assign(&self, gamepad: GamepadId, slot: Option<u8>) {
+        let slots = &mut self.inner.borrow_mut().slots;
+
+        slots.retain(|id, s| *id != gamepad && Some(*s) != slot);
+
+        if let Some(slot) = slot {
+            slots.insert(gamepad, slot);
+        }
+    }
+
+    pub fn is_listening(&self, id: Id) -> bool {
+        self.inner
+            .borrow()
+            .listening
+            .as_ref()
+            .is_some_and(|l| l.id == id)
+    }
+
+    /// starts listening for `id`, dropping whatever was pressed before so an
+    /// old press doesn't get picked up
+    fn listen(&self, id: Id) -> Result<(), String> {
+        let mut inner = self.inner.borrow_mut();
+
+        inner.open()?;
+        inner.pump();
+        inner.heard.clear();
+
+        inner.listening = Some(Listening {
+            id,
+            until: Instant::now() + LISTEN_FOR,
+            unassigned: None,
+        });
+
+        Ok(())
+    }
+
+    /// opens the gamepads if they aren't yet and reads what changed since
+    /// the last update, call it every frame something shows live state
+    pub fn update(&self) -> Result<(), String> {
+        let mut inner = self.inner.borrow_mut();
+
+        inner.open()?;
+        inner.pump();
+
+        Ok(())
+    }
+
+    /// the connected gamepads and their names, as of the last update
+    pub fn connected(&self) -> Vec<(GamepadId, String)> {
+        let inner = self.inner.borrow();
+
+        inner
+            .gilrs
+            .iter()
+            .flat_map(|gilrs| {
+                gilrs
+                    .gamepads()
+                    .map(|(id, gamepad)| (id, gamepad.name().to_string()))
+            })
+            .collect()
+    }
+
+    /// the button and axis counts of the gamepad on each slot, from what its
+    /// mapping has and the highest raw codes it has sent
+    pub fn caps(&self) -> [Option<DeviceCaps>; CONTROLLER_SLOTS] {
+        let inner = self.inner.borrow();
+        let mut caps = [None; CONTROLLER_SLOTS];
+
+        let Some(gilrs) = &inner.gilrs else {
+            return caps;
+        };
+
+        for (id, slot) in &inner.slots {
+            let Some(gamepad) = gilrs.connected_gamepad(*id) else {
+                continue;
+            };
+
+            let mapped_buttons = COUNTED_BUTTONS
+                .iter()
+                .filter(|button| gamepad.button_code(**button).is_some())
+                .count();
+            let mapped_axes = COUNTED_AXES
+                .iter()
+                .filter(|axis| gamepad.axis_code(**axis).is_some())
+                .count();
+
+            let seen_buttons = gamepad
+                .state()
+                .buttons()
+                .filter_map(|(code, _)| generic_button(code.into_u32()))
+                .max()
+                .unwrap_or(0);
+            let seen_axes = gamepad
+                .state()
+                .axes()
+                .filter_map(|(code, _)| generic_axis(code.into_u32()))
+                .map(|axis| axis + 1)
+                .max()
+                .unwrap_or(0);
+
+            let count = |mapped: usize, seen: i16| mapped.max(seen.max(0) as usize).min(32) as u8;
+
+            if let Some(slot) = caps.get_mut(*slot as usize) {
+                *slot = Some(DeviceCaps {
+                    buttons: count(mapped_buttons, seen_buttons),
+                    axises: count(mapped_axes, seen_axes),
+                });
+            }
+        }
+
+        caps
+    }
+
+    /// what the gamepad on `slot` is doing, none when no connected gamepad
+    /// has been put on it
+    pub fn read(&self, slot: u8, controller: &ControllerType) -> Option<Reading> {
+        let inner = self.inner.borrow();
+        let gilrs = inner.gilrs.as_ref()?;
+
+        let (id, _) = inner.slots.iter().find(|(_, s)| **s == slot)?;
+
+        gilrs
+            .connected_gamepad(*id)
+            .map(|gamepad| Reading::new(&gamepad, controller))
+    }
+}
+
+/// a gamepad's buttons and axes in the driver station's numbering for the
+/// slot it's on
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Reading {
+    // buttons held, the pov's angle and triggers past the slot's sensitivity
+    pub pressed: BTreeSet<Button>,
+    pub axes: Vec<(u8, f32)>,
+}
+
+impl Reading {
+    fn new(gamepad: &Gamepad, controller: &ControllerType) -> Self {
+        let mut pressed = BTreeSet::new();
+        let mut axes = Vec::new();
+
+        let held = |button| gamepad.is_pressed(button);
+        let dpad_x = gamepad.value(Axis::DPadX);
+        let dpad_y = gamepad.value(Axis::DPadY);
+
+        let angle = pov_angle(
+            held(PadButton::DPadUp) || dpad_y > AXIS_PRESS,
+            held(PadButton::DPadRight) || dpad_x > AXIS_PRESS,
+            held(PadButton::DPadDown) || dpad_y < -AXIS_PRESS,
+            held(PadButton::DPadLeft) || dpad_x < -AXIS_PRESS,
+        );
+
+        pressed.extend(angle.map(pov));
+
+        match controller {
+            ControllerType::XBox { sensitivity } => {
+                for (i, button) in XBOX_ORDER.iter().enumerate() {
+                    if held(*button) {
+                        pressed.insert(Button {
+                            button: i as i16 + 1,
+                            location: ButtonLocation::Button,
+                        });
+                    }
+                }
+
+                let trigger = |button, axis| {
+                    gamepad
+                        .button_data(button)
+                        .map_or(0.0, |data| data.value())
+                        .max(gamepad.value(axis))
+                };
+
+                // the driver station has y going down, gilrs has it going up
+                axes = vec![
+                    (0, gamepad.value(Axis::LeftStickX)),
+                    (1, -gamepad.value(Axis::LeftStickY)),
+                    (2, trigger(PadButton::LeftTrigger2, Axis::LeftZ)),
+                    (3, trigger(PadButton::RightTrigger2, Axis::RightZ)),
+                    (4, gamepad.value(Axis::RightStickX)),
+                    (5, -gamepad.value(Axis::RightStickY)),
+                ];
+
+                for (axis, value) in &axes[2..4] {
+                    if *value > *sensitivity {
+                        pressed.insert(analog(*axis as i16));
+                    }
+                }
+            }
+            ControllerType::Generic { sensitivity, .. } => {
+                for (code, data) in gamepad.state().buttons() {
+                    if let Some(button) = generic_button(code.into_u32()) {
+                        if data.is_pressed() {
+                            pressed.insert(Button {
+                                button,
+                                location: ButtonLocation::Button,
+                            });
+                        }
+                    }
+                }
+
+                for (code, data) in gamepad.state().axes() {
+                    if let Some(axis) = generic_axis(code.into_u32())
+                        .and_then(|axis| u8::try_from(axis).ok())
+                        .filter(|axis| controller.valid_axis(*axis))
+                    {
+                        axes.push((axis, data.value()));
+
+                        if data.value().abs() > *sensitivity {
+                            pressed.insert(analog(axis.into()));
+                        }
+                    }
+                }
+
+                axes.sort_by_key(|(axis, _)| *axis);
+            }
+            ControllerType::NotBound => {}
+        }
+
+        pressed.retain(|button| controller.valid_binding(*button));
+
+        Self { pressed, axes }
+    }
+}
+
+/// the pov angle for the held directions, none when let go or pushed two
+/// opposite ways
+pub fn pov_angle(up: bool, right: bool, down: bool, left: bool) -> Option<i16> {
+    match (up && !down, right && !left, down && !up, left && !right) {
+        (true, false, _, false) => Some(0),
+        (true, true, _, _) => Some(45),
+        (false, true, false, _) => Some(90),
+        (_, true, true, _) => Some(135),
+        (_, false, true, false) => Some(180),
+        (_, _, true, true) => Some(225),
+        (false, _, false, true) => Some(270),
+        (true, _, _, true) => Some(315),
+        _ => None,
+    }
+}
+
+/// a "listen" button, gives the slot and button of the next press on a real
+/// gamepad once it comes
+pub fn listen_button(
+    id: Id,
+    controller: u8,
+    env: &State,
+    output: &EventStream<GlobalEvents>,
+    ui: &mut Ui,
+) -> Option<PButton> {
+    let gamepads = &env.gamepads;
+
+    if !gamepads.is_listening(id) {
+        if ui
+            .button("listen")
+            .on_hover_text("press a button on the gamepad instead of picking it")
+            .clicked()
+        {
+            if let Err(err) = gamepads.listen(id) {
+                output.add_event(GlobalEvents::DisplayError(err));
+            }
+        }
+
+        return None;
+    }
+
+    let mut inner = gamepads.inner.borrow_mut();
+
+    inner.pump();
+
+    let Inner {
+        gilrs,
+        slots,
+        listening,
+        heard,
+    } = &mut *inner;
+
+    let (Some(gilrs), Some(listen)) = (gilrs.as_mut(), listening.as_mut()) else {
+        return None;
+    };
+
+    if let Some((gamepad, event, slot)) = &mut listen.unassigned {
+        let name = gilrs.gamepad(*gamepad).name().to_string();
+        let mut answer = None;
+
+        Window::new("which controller is this?")
+            .id(id.with("gamepad slot"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("{name} hasn't been given a controller slot yet"));
+
+                for (i, c) in env.controllers.iter().enumerate() {
+                    if c.bound() {
+                        ui.radio_value(slot, i as u8, env.controller_name(i as u8).as_str());
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("confirm").clicked() {
+                        answer = Some(true);
+                    }
+
+                    if ui.button("cancel").clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+
+        return match answer {
+            Some(true) => {
+                let (gamepad, event, slot) = (*gamepad, *event, *slot);
+
+                slots.insert(gamepad, slot);
+                *listening = None;
+
+                match pressed(&env.controllers[slot as usize], &event) {
+                    Some(button) => Some((slot, button)),
+                    None => {
+                        output.add_event(GlobalEvents::DisplayWarning(format!(
+                            "{} doesn't have what was pressed",
+                            env.controller_name(slot)
+                        )));
+
+                        None
+                    }
+                }
+            }
+            Some(false) => {
+                *listening = None;
+                None
+            }
+            None => None,
+        };
+    }
+
+    let left = listen.until.saturating_duration_since(Instant::now());
+
+    let stop = ui
+        .button(format!("press a button… {}s", left.as_secs() + 1))
+        .on_hover_text("click to stop listening")
+        .clicked();
+
+    if stop {
+        *listening = None;
+        return None;
+    }
+
+    for event in std::mem::take(heard) {
+        match slots.get(&event.id) {
+            Some(slot) => {
+                let Some(button) = pressed(&env.controllers[*slot as usize], &event.event) else {
+                    continue;
+                };
+
+                *listening = None;
+
+                return Some((*slot, button));
+            }
+            // only something that would be a press on some controller, so a
+            // drifting stick doesn't ask
+            None if [ControllerType::XBox { sensitivity: 1.0 }, generic_any()]
+                .iter()
+                .any(|c| pressed(c, &event.event).is_some()) =>
+            {
+                listen.unassigned = Some((event.id, event.event, controller));
+
+                ui.ctx().request_repaint();
+
+                return None;
+            }
+            None => {}
+        }
+    }
+
+    if left.is_zero() {
+        *listening = None;
+
+        output.add_event(GlobalEvents::DisplayInfo(format!(
+            "nothing was pressed in {} seconds",
+            LISTEN_FOR.as_secs()
+        )));
+    } else {
+        ui.ctx().request_repaint_after(Duration::from_millis(50));
+    }
+
+    None
+}
+
+// a controller with everything, for telling presses apart from noise before
+// the gamepad has a slot
+fn generic_any() -> ControllerType {
+    ControllerType::Generic {
+        buttons: 32,
+        axises: 12,
+        sensitivity: 1.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn xbox_buttons_follow_the_driver_station() {
+        let xbox = ControllerType::XBox { sensitivity: 1.0 };
+        let button = |button| Input::Button(button, 0);
+
+        assert_eq!(
+            press(&xbox, button(PadButton::South)),
+            Some(Button {
+                button: 1,
+                location: ButtonLocation::Button
+            })
+        );
+        assert_eq!(
+            press(&xbox, button(PadButton::RightThumb)),
+            Some(Button {
+                button: 10,
+                location: ButtonLocation::Button
+            })
+        );
+        assert_eq!(press(&xbox, button(PadButton::DPadLeft)), Some(pov(270)));
+        assert_eq!(
+            press(&xbox, button(PadButton::RightTrigger2)),
+            Some(analog(3))
+        );
+        assert_eq!(press(&xbox, button(PadButton::Mode)), None);
+        assert_eq!(press(&xbox, Input::Axis(Axis::LeftStickX, 1.0, 0)), None);
+        assert_eq!(
+            press(&xbox, Input::Axis(Axis::LeftZ, 0.9, 2)),
+            Some(analog(2))
+        );
+    }
+
+    #[test]
+    fn generic_buttons_come_from_the_code() {
+        let generic = ControllerType::Generic {
+            buttons: 4,
+            axises: 2,
+            sensitivity: 1.0,
+        };
+
+        let first = generic_button(0).or(generic_button(0x120)).unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(
+            press(&generic, Input::Axis(Axis::Unknown, -0.8, 1)),
+            generic_axis(1).map(analog)
+        );
+        assert_eq!(press(&generic, Input::Axis(Axis::Unknown, 0.2, 1)), None);
+        assert_eq!(
+            press(&generic, Input::Axis(Axis::DPadY, 1.0, 17)),
+            Some(pov(0))
+        );
+    }
+
+    #[test]
+    fn pov_from_held_directions() {
+        assert_eq!(pov_angle(true, false, false, false), Some(0));
+        assert_eq!(pov_angle(false, true, true, false), Some(135));
+        assert_eq!(pov_angle(true, false, false, true), Some(315));
+        assert_eq!(pov_angle(true, true, true, false), Some(90));
+        assert_eq!(pov_angle(false, true, false, true), None);
+        assert_eq!(pov_angle(false, false, false, false), None);
+    }
+}
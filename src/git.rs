@@ -0,0 +1,164 @@
+use std::{
+    path::Path,
+    process::{Command, Output},
+};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use anyhow::{Context, Result};
+
+use crate::global_state::Change;
+
+// longest commit subject before the rest of the changes are counted instead
+const SUMMARY_CHANGES: usize = 3;
+
+fn git(dir: &Path, args: &[&str]) -> Result<Output> {
+    let mut c = Command::new("git");
+
+    let command = c.arg("-C").arg(dir).args(args);
+
+    #[cfg(target_os = "windows")]
+    let command = command.creation_flags(0x08000000);
+
+    command.output().with_context(|| "failed to run git")
+}
+
+/// the commit message for a save of `changes`
+pub fn message(changes: &[Change]) -> String {
+    let mut summary = changes
+        .iter()
+        .take(SUMMARY_CHANGES)
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if changes.is_empty() {
+        summary.push_str("save");
+    } else if changes.len() > SUMMARY_CHANGES {
+        summary.push_str(&format!(" and {} more", changes.len() - SUMMARY_CHANGES));
+    }
+
+    format!("bindings_gui: {summary}")
+}
+
+/// commits bindings.json and the profiles in `deploy_dir`. a deploy directory
+/// that isn't in a repository, or a save that didn't change anything, is left
+/// alone without an error
+pub fn commit(deploy_dir: &Path, message: &str) -> Result<()> {
+    if !git(deploy_dir, &["rev-parse", "--is-inside-work-tree"])?
+        .status
+        .success()
+    {
+        return Ok(());
+    }
+
+    // only the profiles themselves, not their backups
+    let added = git(
+        deploy_dir,
+        &["add", "--", "bindings.json", "bindings/*.json"],
+    )?;
+
+    if !added.status.success() {
+        anyhow::bail!(
+            "git add failed: {}",
+            String::from_utf8_lossy(&added.stderr).trim()
+        );
+    }
+
+    if git(
+        deploy_dir,
+        &[
+            "diff",
+            "--cached",
+            "--quiet",
+            "--",
+            "bindings.json",
+            "bindings/*.json",
+        ],
+    )?
+    .status
+    .success()
+    {
+        return Ok(());
+    }
+
+    let committed = git(
+        deploy_dir,
+        &[
+            "commit",
+            "-m",
+            message,
+            "--",
+            "bindings.json",
+            "bindings/*.json",
+        ],
+    )?;
+
+    if !committed.status.success() {
+        anyhow::bail!(
+            "git commit failed: {}",
+            String::from_utf8_lossy(&committed.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::create_dir_all;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn messages_sum_up_the_changes() {
+        assert_eq!(message(&[]), "bindings_gui: save");
+
+        let changes = (0..5)
+            .map(|n| Change::Added(format!("command {n}")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            message(&changes),
+            "bindings_gui: + command 0, + command 1, + command 2 and 2 more"
+        );
+    }
+
+    #[test]
+    fn commits_only_when_there_is_something_to_commit() {
+        let temp = temp_dir("git");
+        let dir = temp.path();
+
+        create_dir_all(dir.join("bindings")).unwrap();
+
+        // not a repository yet
+        commit(dir, "bindings_gui: save").unwrap();
+
+        if !git(dir, &["init", "-q"]).is_ok_and(|o| o.status.success()) {
+            // no git to test with
+            return;
+        }
+
+        git(dir, &["config", "user.name", "test"]).unwrap();
+        git(dir, &["config", "user.email", "test@example.com"]).unwrap();
+
+        std::fs::write(dir.join("bindings.json"), "{}").unwrap();
+        std::fs::write(dir.join("bindings").join("default.json"), "{}").unwrap();
+        std::fs::write(dir.join("bindings").join("default.json.bak"), "").unwrap();
+
+        commit(dir, "bindings_gui: + command shoot").unwrap();
+
+        // nothing changed since
+        commit(dir, "bindings_gui: save").unwrap();
+
+        let log = git(dir, &["log", "--format=%s", "--name-only"]).unwrap();
+        let log = String::from_utf8_lossy(&log.stdout);
+
+        assert_eq!(log.matches("bindings_gui:").count(), 1);
+        assert!(log.contains("+ command shoot"));
+        assert!(log.contains("bindings/default.json"));
+        assert!(!log.contains(".bak"));
+    }
+}
@@ -1,11 +1,17 @@
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, BTreeSet},
-    fs::{create_dir_all, read_to_string, File},
-    io::Write,
-    path::PathBuf,
-    process::{Child, Command},
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    ffi::OsString,
+    fmt::Display,
+    fs::{create_dir_all, read_to_string, remove_dir_all, rename, File},
+    io::{self, Read, Write},
+    mem,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
     rc::Rc,
+    slice,
+    time::{Duration, Instant, SystemTime},
 };
 
 #[cfg(target_os = "windows")]
@@ -13,13 +19,33 @@ use std::os::windows::process::CommandExt;
 
 use anyhow::{Context, Result};
 use bumpalo::Bump;
-use egui::Ui;
-use egui_toast::{Toast, Toasts};
+use egui::{Color32, Ui};
 
 use crate::{
-    bindings::{self, Binding, BindingsMap, ControllerType, Profile, SaveData},
+    audit_log,
+    bindings::{
+        self, Binding, BindingNote, BindingsMap, CommandMeta, ControllerType, DeadBinding,
+        DeviceCaps, DisabledBindings, MergeConflict, PButton, Profile, ProfileBundle, Quarantine,
+        SaveData, SaveMode, SyncBackend, SyncFormat, SyncTarget, CONTROLLER_SLOTS, MAX_COOLDOWN,
+    },
+    codegen::{self, KotlinKeys},
     component::EventStream,
+    conflict_rules::Trigger,
     constants::{Constants, OptionLocation},
+    controller_colors,
+    disk_space::{self, SystemSpace},
+    export,
+    file_hashes::{self, FileHashes},
+    git,
+    layout::{self, Layout},
+    maintenance::{self, Retention},
+    name_check,
+    notifier::Notifier,
+    recovery::BrokenFile,
+    robot_pull::{self, Pulled},
+    sync_log,
+    ui_meta::UiMeta,
+    worker::{Done, Job, Worker, WriteFile},
     Component, ProgramError,
 };
 
@@ -27,507 +53,6200 @@ use crate::{
 pub enum GlobalEvents {
     AddBinding(Binding, Rc<String>),
     RemoveBinding(Binding, Rc<String>),
+    SetIntentional(Binding, bool),
+    // an empty note takes it off
+    SetBindingNote(Binding, Rc<String>, String),
+    // switches a binding off without removing it
+    SetBindingEnabled(Binding, Rc<String>, bool),
+    ClearControllerBindings(u8),
+    MoveControllerBindings { from: u8, to: u8 },
+    SwapButtons(PButton, PButton),
     AddCommand(String),
     RemoveCommand(Rc<String>),
+    SetCommandRequired(Rc<String>, bool),
+    // no cooldown when none
+    SetCommandCooldown(Rc<String>, Option<f32>),
+    SetCommandMeta(Rc<String>, CommandMeta),
+    PurgeCommand(Rc<String>),
     DisplayError(String),
+    DisplayWarning(String),
+    DisplayInfo(String),
     Save,
     RenameCommand(Rc<String>, Rc<String>),
+    PropagateBindings(Rc<String>),
     AddProfile(String),
+    // switches to the copy once it's written when `switch` is set
+    DuplicateProfile { name: String, switch: bool },
     SetProfile(Rc<String>),
+    // switches to a profile started over empty, for one whose file is gone
+    ResetProfile(Rc<String>),
+    RenameProfile(Rc<String>, Rc<String>),
+    ImportProfile(String, Rc<ProfileBundle<'static>>),
+    MergeProfile { from: Rc<String>, into: Rc<String> },
     AddOption(OptionLocation, Constants),
     AddOptionDriver(OptionLocation, Constants),
     RemoveOption(OptionLocation),
     RemoveOptionDriver(OptionLocation),
+    RenameOption(OptionLocation, Rc<String>),
+    // only changes the order constants are shown in
+    PinConstant(OptionLocation, bool),
+    RaiseConstant(OptionLocation),
+    // the address of the selected target, none removes it
+    SetUrl(Option<String>),
+    AddSyncTarget(String),
+    RemoveSyncTarget(String),
+    SetSyncTargetEnabled(String, bool),
+    SetSyncFormat(SyncFormat),
+    SetDisabledBindings(DisabledBindings),
+    SetSaveMode(SaveMode),
+    SetCommitOnSave(bool),
+    // whether sync attempts are also written to sync.log
+    SetSyncLogFile(bool),
+    // the account and directory syncs log in to and copy to on the robot
+    SetSshUser(String),
+    SetRemotePath(String),
+    // only remembers it, the syncing tab fills in the url from it
+    SetTeamNumber(Option<u32>),
+    SetSyncBackend(SyncBackend),
+    SetSyncing(bool),
+    // saves and sends everything to the robot
+    ForceSync,
     SetStream(Rc<String>, u8, u8),
     AddStream(String),
     RenameStream(Rc<String>, Rc<String>),
     RemoveStream(Rc<String>),
+    SetStreamRequired(Rc<String>, bool),
+    KeepQuarantine,
+    DiscardQuarantine,
+    AdjustControllerToDevice(u8),
+    // answers to a save finding files changed outside the app
+    KeepMine,
+    TakeTheirs,
+    // answers to switching profiles with unsaved edits in manual save mode
+    SaveAndSwitch,
+    DiscardAndSwitch,
+    // copies what was downloaded from the robot over the local files
+    UsePulled,
+    MergeTheirs,
+    // answers to profile names found on load that aren't NFC normalized
+    NormalizeProfileNames,
+    KeepProfileNames,
 }
 
-#[derive(Debug)]
-pub struct State {
-    pub deploy_dir: PathBuf,
-    pub url: Option<String>,
-    pub syncing: bool,
-    pub commands: BTreeSet<Rc<String>>,
-    pub bindings: BindingsMap,
-    pub controllers: [ControllerType; 5],
-    pub controller_names: [Rc<String>; 5],
-    pub sync_process: Option<Child>,
-    pub profile: Rc<String>,
-    pub profiles: Vec<Rc<String>>,
-    pub constants: Constants,
-    pub driver_constants: Constants,
-    pub stream_to_axis: BTreeMap<Rc<String>, (u8, u8)>,
-    pub streams: BTreeSet<Rc<String>>,
+/// what an event did to the project, for listing edits that haven't made it
+/// to disk yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Changed(String),
 }
 
-impl Default for State {
-    fn default() -> Self {
-        Self {
-            url: Default::default(),
-            syncing: true,
-            commands: Default::default(),
-            bindings: Default::default(),
-            controllers: Default::default(),
-            controller_names: Default::default(),
-            sync_process: Default::default(),
-            deploy_dir: PathBuf::default(),
-            profile: Rc::new("default".to_string()),
-            profiles: Default::default(),
-            constants: Default::default(),
-            driver_constants: Default::default(),
-            stream_to_axis: Default::default(),
-            streams: Default::default(),
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(what) => write!(f, "+ {what}"),
+            Change::Removed(what) => write!(f, "- {what}"),
+            Change::Changed(what) => write!(f, "~ {what}"),
         }
     }
 }
 
-impl State {
-    pub fn display_tab(
-        &mut self,
-        ui: &mut Ui,
-        tab: &mut Box<dyn Component<OutputEvents = GlobalEvents, Environment = Self>>,
-        toasts: &mut Toasts,
-        arena: &Bump,
-    ) -> Result<()> {
-        let mut events = EventStream::new();
-
-        tab.render(ui, self, &events, arena);
+/// drops adds and removes of the same thing that cancel out, and repeats of
+/// the same change
+pub fn collapse_changes(changes: &[Change]) -> Vec<Change> {
+    let mut collapsed: Vec<Change> = Vec::new();
 
-        let mut update = false;
+    for change in changes {
+        let undone = match change {
+            Change::Added(what) => collapsed
+                .iter()
+                .rposition(|c| *c == Change::Removed(what.clone())),
+            Change::Removed(what) => collapsed
+                .iter()
+                .rposition(|c| *c == Change::Added(what.clone())),
+            Change::Changed(_) => None,
+        };
 
-        for e in events.drain() {
-            update |= self.handle_event(e, arena, toasts); // don't do any because any terminates early
+        match undone {
+            Some(i) => {
+                collapsed.remove(i);
+            }
+            None if collapsed.contains(change) => {}
+            None => collapsed.push(change.clone()),
         }
+    }
 
-        if update {
-            self.write_out(arena)?
-        }
+    collapsed
+}
 
-        Ok(())
+impl GlobalEvents {
+    /// the event's name, for the change log
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GlobalEvents::AddBinding(..) => "AddBinding",
+            GlobalEvents::RemoveBinding(..) => "RemoveBinding",
+            GlobalEvents::SetIntentional(..) => "SetIntentional",
+            GlobalEvents::SetBindingNote(..) => "SetBindingNote",
+            GlobalEvents::SetBindingEnabled(..) => "SetBindingEnabled",
+            GlobalEvents::ClearControllerBindings(..) => "ClearControllerBindings",
+            GlobalEvents::MoveControllerBindings { .. } => "MoveControllerBindings",
+            GlobalEvents::SwapButtons(..) => "SwapButtons",
+            GlobalEvents::AddCommand(..) => "AddCommand",
+            GlobalEvents::RemoveCommand(..) => "RemoveCommand",
+            GlobalEvents::SetCommandRequired(..) => "SetCommandRequired",
+            GlobalEvents::SetCommandCooldown(..) => "SetCommandCooldown",
+            GlobalEvents::SetCommandMeta(..) => "SetCommandMeta",
+            GlobalEvents::PurgeCommand(..) => "PurgeCommand",
+            GlobalEvents::DisplayError(..) => "DisplayError",
+            GlobalEvents::DisplayWarning(..) => "DisplayWarning",
+            GlobalEvents::DisplayInfo(..) => "DisplayInfo",
+            GlobalEvents::Save => "Save",
+            GlobalEvents::RenameCommand(..) => "RenameCommand",
+            GlobalEvents::PropagateBindings(..) => "PropagateBindings",
+            GlobalEvents::AddProfile(..) => "AddProfile",
+            GlobalEvents::DuplicateProfile { .. } => "DuplicateProfile",
+            GlobalEvents::SetProfile(..) => "SetProfile",
+            GlobalEvents::ResetProfile(..) => "ResetProfile",
+            GlobalEvents::RenameProfile(..) => "RenameProfile",
+            GlobalEvents::ImportProfile(..) => "ImportProfile",
+            GlobalEvents::MergeProfile { .. } => "MergeProfile",
+            GlobalEvents::AddOption(..) => "AddOption",
+            GlobalEvents::AddOptionDriver(..) => "AddOptionDriver",
+            GlobalEvents::RemoveOption(..) => "RemoveOption",
+            GlobalEvents::RemoveOptionDriver(..) => "RemoveOptionDriver",
+            GlobalEvents::RenameOption(..) => "RenameOption",
+            GlobalEvents::PinConstant(..) => "PinConstant",
+            GlobalEvents::RaiseConstant(..) => "RaiseConstant",
+            GlobalEvents::SetUrl(..) => "SetUrl",
+            GlobalEvents::AddSyncTarget(..) => "AddSyncTarget",
+            GlobalEvents::RemoveSyncTarget(..) => "RemoveSyncTarget",
+            GlobalEvents::SetSyncTargetEnabled(..) => "SetSyncTargetEnabled",
+            GlobalEvents::SetSyncFormat(..) => "SetSyncFormat",
+            GlobalEvents::SetDisabledBindings(..) => "SetDisabledBindings",
+            GlobalEvents::SetSaveMode(..) => "SetSaveMode",
+            GlobalEvents::SetCommitOnSave(..) => "SetCommitOnSave",
+            GlobalEvents::SetSyncLogFile(..) => "SetSyncLogFile",
+            GlobalEvents::SetSshUser(..) => "SetSshUser",
+            GlobalEvents::SetRemotePath(..) => "SetRemotePath",
+            GlobalEvents::SetTeamNumber(..) => "SetTeamNumber",
+            GlobalEvents::SetSyncBackend(..) => "SetSyncBackend",
+            GlobalEvents::SetSyncing(..) => "SetSyncing",
+            GlobalEvents::ForceSync => "ForceSync",
+            GlobalEvents::SetStream(..) => "SetStream",
+            GlobalEvents::AddStream(..) => "AddStream",
+            GlobalEvents::RenameStream(..) => "RenameStream",
+            GlobalEvents::RemoveStream(..) => "RemoveStream",
+            GlobalEvents::SetStreamRequired(..) => "SetStreamRequired",
+            GlobalEvents::KeepQuarantine => "KeepQuarantine",
+            GlobalEvents::DiscardQuarantine => "DiscardQuarantine",
+            GlobalEvents::AdjustControllerToDevice(..) => "AdjustControllerToDevice",
+            GlobalEvents::KeepMine => "KeepMine",
+            GlobalEvents::TakeTheirs => "TakeTheirs",
+            GlobalEvents::SaveAndSwitch => "SaveAndSwitch",
+            GlobalEvents::DiscardAndSwitch => "DiscardAndSwitch",
+            GlobalEvents::UsePulled => "UsePulled",
+            GlobalEvents::MergeTheirs => "MergeTheirs",
+            GlobalEvents::NormalizeProfileNames => "NormalizeProfileNames",
+            GlobalEvents::KeepProfileNames => "KeepProfileNames",
+        }
     }
 
-    pub fn handle_event(&mut self, event: GlobalEvents, arena: &Bump, toasts: &mut Toasts) -> bool {
-        match event {
+    /// what this event changes, none for events that don't touch the project
+    pub fn describe(&self, env: &State, arena: &Bump) -> Option<Change> {
+        let location = |location: &OptionLocation| {
+            location
+                .iter()
+                .map(|key| key.as_str())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let button = |(controller, button): &PButton| {
+            format!(
+                "{} {}",
+                env.controller_name(*controller),
+                env.controllers[*controller as usize].button_name(button, arena)
+            )
+        };
+
+        Some(match self {
             GlobalEvents::AddBinding(binding, command) => {
-                self.bindings.add_binding(command, binding);
-                true
+                Change::Added(format!("{command} {}", binding.show(command, env, arena)))
             }
             GlobalEvents::RemoveBinding(binding, command) => {
-                self.bindings.remove_binding(&command, binding);
-                true
+                Change::Removed(format!("{command} {}", binding.show(command, env, arena)))
             }
-            GlobalEvents::AddCommand(command) => {
-                self.commands.insert(Rc::new(command));
-                true
+            GlobalEvents::SetIntentional(binding, intentional) => Change::Changed(format!(
+                "{} {}",
+                binding.show_without_note(env, arena),
+                if *intentional {
+                    "on purpose"
+                } else {
+                    "not on purpose"
+                }
+            )),
+            GlobalEvents::SetBindingNote(binding, command, _) => Change::Changed(format!(
+                "note on {command} {}",
+                binding.show_without_note(env, arena)
+            )),
+            GlobalEvents::SetBindingEnabled(binding, command, enabled) => Change::Changed(format!(
+                "{command} {} {}",
+                binding.show(command, env, arena),
+                if *enabled { "enabled" } else { "disabled" }
+            )),
+            GlobalEvents::ClearControllerBindings(controller) => {
+                Change::Changed(format!("cleared {}", env.controller_name(*controller)))
             }
-            GlobalEvents::RemoveCommand(command) => {
-                self.commands.remove(&command);
-                self.bindings.remove_command(&command);
-                true
+            GlobalEvents::MoveControllerBindings { from, to } => Change::Changed(format!(
+                "moved {} to {}",
+                env.controller_name(*from),
+                env.controller_name(*to)
+            )),
+            GlobalEvents::SwapButtons(a, b) => {
+                Change::Changed(format!("swapped {} with {}", button(a), button(b)))
             }
-            GlobalEvents::DisplayError(error) => {
-                toasts.add(Toast {
-                    kind: egui_toast::ToastKind::Error,
-                    text: error.into(),
-                    ..Default::default()
-                });
-                false
+            GlobalEvents::AddCommand(command) => Change::Added(format!("command {command}")),
+            GlobalEvents::RemoveCommand(command) | GlobalEvents::PurgeCommand(command) => {
+                Change::Removed(format!("command {command}"))
             }
-            GlobalEvents::Save => true,
-            GlobalEvents::RenameCommand(old, new) => {
-                if let Err(err) = self.map_profiles(
-                    |profile| {
-                        let bindings = profile.command_to_bindings.to_mut().remove(&old);
-
-                        if let Some(bindings) = bindings {
-                            profile
-                                .command_to_bindings
-                                .to_mut()
-                                .insert(new.clone(), bindings);
-                        }
-                    },
-                    arena,
-                ) {
-                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, toasts);
-                }
-
-                self.commands.remove(&old);
-                self.commands.insert(new);
-                true
+            GlobalEvents::SetCommandRequired(command, _) => {
+                Change::Changed(format!("{command} required"))
             }
-            GlobalEvents::AddProfile(profile) => {
-                self.profiles.push(Rc::new(profile));
-                false
+            GlobalEvents::SetCommandCooldown(command, _) => {
+                Change::Changed(format!("{command} cooldown"))
+            }
+            GlobalEvents::SetCommandMeta(command, _) => {
+                Change::Changed(format!("{command} group and description"))
+            }
+            GlobalEvents::RenameCommand(from, to) => {
+                Change::Changed(format!("renamed command {from} to {to}"))
+            }
+            GlobalEvents::PropagateBindings(command) => {
+                Change::Changed(format!("copied {command} to all profiles"))
+            }
+            GlobalEvents::AddProfile(profile)
+            | GlobalEvents::DuplicateProfile { name: profile, .. }
+            | GlobalEvents::ImportProfile(profile, _) => {
+                Change::Added(format!("profile {profile}"))
             }
             GlobalEvents::SetProfile(profile) => {
-                match self.change_profile(profile) {
-                    Ok(()) => {}
-                    Err(err) => {
-                        self.handle_event(
-                            GlobalEvents::DisplayError(err.to_string()),
-                            arena,
-                            toasts,
-                        );
-                    }
-                };
-                false
+                Change::Changed(format!("switched to profile {profile}"))
             }
-            GlobalEvents::AddOption(key, constant) => {
-                if self.constants.add_option(key, constant) {
-                    self.handle_event(
-                        GlobalEvents::DisplayError("failed to add constants".to_string()),
-                        arena,
-                        toasts,
-                    );
-                    false
-                } else {
-                    true
-                }
+            GlobalEvents::ResetProfile(profile) => {
+                Change::Changed(format!("started profile {profile} over empty"))
             }
-            GlobalEvents::RemoveOption(key) => {
-                self.constants.remove_key(&key);
-
-                if let Err(err) = self.map_profiles(
-                    |profile| {
-                        profile.constants.to_mut().remove_key(&key);
-                    },
-                    arena,
-                ) {
-                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, toasts);
-                }
-
-                true
+            GlobalEvents::RenameProfile(from, to) => {
+                Change::Changed(format!("renamed profile {from} to {to}"))
             }
-            GlobalEvents::RemoveOptionDriver(key) => {
-                self.driver_constants.remove_key(&key);
-
-                true
+            GlobalEvents::MergeProfile { from, into } => {
+                Change::Changed(format!("merged {from} into {into}"))
             }
-            GlobalEvents::AddOptionDriver(key, constant) => {
-                if self.driver_constants.add_option(key, constant) {
-                    self.handle_event(
-                        GlobalEvents::DisplayError("failed to add constant".to_string()),
-                        arena,
-                        toasts,
-                    );
-                    false
-                } else {
-                    true
-                }
+            GlobalEvents::AddOption(at, _) | GlobalEvents::AddOptionDriver(at, _) => {
+                Change::Added(format!("constant {}", location(at)))
             }
-            GlobalEvents::SetStream(stream, controller, axis) => {
-                self.stream_to_axis.insert(stream, (controller, axis));
-                true
+            GlobalEvents::RemoveOption(at) | GlobalEvents::RemoveOptionDriver(at) => {
+                Change::Removed(format!("constant {}", location(at)))
             }
-            GlobalEvents::AddStream(stream) => {
-                self.streams.insert(Rc::new(stream));
-                true
+            GlobalEvents::RenameOption(at, to) => {
+                Change::Changed(format!("renamed constant {} to {to}", location(at)))
+            }
+            GlobalEvents::SetUrl(_) => Change::Changed("robot address".to_string()),
+            GlobalEvents::AddSyncTarget(name) => Change::Added(format!("sync target {name}")),
+            GlobalEvents::RemoveSyncTarget(name) => Change::Removed(format!("sync target {name}")),
+            GlobalEvents::SetSyncTargetEnabled(name, enabled) => Change::Changed(format!(
+                "{} syncing to {name}",
+                if *enabled { "started" } else { "stopped" }
+            )),
+            GlobalEvents::SetSyncFormat(_) => Change::Changed("sync format".to_string()),
+            GlobalEvents::SetDisabledBindings(_) => {
+                Change::Changed("how disabled bindings are saved".to_string())
             }
+            GlobalEvents::SetSaveMode(_) => Change::Changed("save mode".to_string()),
+            GlobalEvents::SetCommitOnSave(_) => {
+                Change::Changed("commit to git on save".to_string())
+            }
+            GlobalEvents::SetSyncLogFile(_) => Change::Changed("sync.log".to_string()),
+            GlobalEvents::SetSshUser(_) => Change::Changed("ssh user".to_string()),
+            GlobalEvents::SetRemotePath(_) => Change::Changed("robot deploy path".to_string()),
+            GlobalEvents::SetTeamNumber(_) => Change::Changed("team number".to_string()),
+            GlobalEvents::SetSyncBackend(_) => Change::Changed("how syncs are sent".to_string()),
+            GlobalEvents::SetSyncing(_) => Change::Changed("syncing".to_string()),
+            GlobalEvents::SetStream(stream, _, _) => Change::Changed(format!("{stream} axis")),
+            GlobalEvents::AddStream(stream) => Change::Added(format!("stream {stream}")),
             GlobalEvents::RenameStream(from, to) => {
-                self.streams.remove(&from);
-                self.streams.insert(to.clone());
+                Change::Changed(format!("renamed stream {from} to {to}"))
+            }
+            GlobalEvents::RemoveStream(stream) => Change::Removed(format!("stream {stream}")),
+            GlobalEvents::SetStreamRequired(stream, _) => {
+                Change::Changed(format!("{stream} required"))
+            }
+            GlobalEvents::KeepQuarantine | GlobalEvents::DiscardQuarantine => {
+                Change::Changed("unrecognized profile data".to_string())
+            }
+            GlobalEvents::AdjustControllerToDevice(controller) => Change::Changed(format!(
+                "{} matched to its device",
+                env.controller_name(*controller)
+            )),
+            GlobalEvents::UsePulled => {
+                Change::Changed("replaced with the robot's files".to_string())
+            }
+            GlobalEvents::NormalizeProfileNames => {
+                Change::Changed("normalized profile names".to_string())
+            }
+            GlobalEvents::DisplayError(_)
+            | GlobalEvents::DisplayWarning(_)
+            | GlobalEvents::DisplayInfo(_)
+            | GlobalEvents::Save
+            | GlobalEvents::ForceSync
+            | GlobalEvents::PinConstant(..)
+            | GlobalEvents::RaiseConstant(_)
+            | GlobalEvents::KeepMine
+            | GlobalEvents::TakeTheirs
+            | GlobalEvents::SaveAndSwitch
+            | GlobalEvents::DiscardAndSwitch
+            | GlobalEvents::MergeTheirs
+            | GlobalEvents::KeepProfileNames => return None,
+        })
+    }
+}
 
-                let binding: Option<(u8, u8)> = self.stream_to_axis.remove(&from);
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncSizes {
+    pub on_disk: u64,
+    pub synced: u64,
+}
 
-                if let Some(binding) = binding {
-                    self.stream_to_axis.insert(to, binding);
-                }
+impl Display for SyncSizes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "synced {:.1} KB ({:.1} KB on disk)",
+            self.synced as f64 / 1000.0,
+            self.on_disk as f64 / 1000.0
+        )
+    }
+}
 
-                true
+/// how the last connection test went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    // with how long the robot took to answer
+    Reachable(Duration),
+    // with what ssh said about it
+    Unreachable(String),
+}
+
+impl Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Reachable(took) => {
+                write!(f, "robot reachable, answered in {} ms", took.as_millis())
             }
-            GlobalEvents::RemoveStream(stream) => {
-                self.streams.remove(&stream);
-                true
+            ConnectionStatus::Unreachable(reason) if reason.is_empty() => {
+                write!(f, "robot unreachable")
             }
+            ConnectionStatus::Unreachable(reason) => write!(f, "robot unreachable: {reason}"),
         }
     }
+}
 
-    pub fn change_profile(&mut self, profile: Rc<String>) -> Result<()> {
-        self.profile = profile.clone();
+/// the account syncs log in to the robot as unless the project says otherwise
+pub const ROBOT_USER: &str = "admin";
 
-        let mut path = self.deploy_dir.to_path_buf();
+/// where the robot looks for deploy files unless the project says otherwise
+pub const ROBOT_DEPLOY_PATH: &str = "/home/lvuser/deploy/";
 
-        path.push("profile");
+pub fn default_ssh_user<'a>() -> Cow<'a, String> {
+    Cow::Owned(ROBOT_USER.to_string())
+}
 
-        let mut file: File = File::create(path).with_context(|| "failed to create profile file")?;
+pub fn default_remote_path<'a>() -> Cow<'a, String> {
+    Cow::Owned(ROBOT_DEPLOY_PATH.to_string())
+}
 
-        file.write_all(profile.as_bytes())?;
+/// the robot's name on the network, found over mdns
+pub fn team_mdns(team: u32) -> String {
+    format!("roborio-{team}-frc.local")
+}
 
-        let profile: Profile<'static> = self.get_profile(profile.as_str())?;
+/// the robot's static ip, none for team numbers too big to fit in one
+pub fn team_ip(team: u32) -> Option<String> {
+    (team / 100 <= 255).then(|| format!("10.{}.{}.2", team / 100, team % 100))
+}
 
-        self.set_fields_from_profile(profile);
+/// whether `url` is empty or one of the addresses worked out from `team`,
+/// anything else was typed in by hand
+pub fn derived_from_team(url: &str, team: Option<u32>) -> bool {
+    url.is_empty()
+        || team.is_some_and(|team| url == team_mdns(team) || Some(url) == team_ip(team).as_deref())
+}
 
-        Ok(())
-    }
+/// `path` as a directory scp copies into, none when there's nothing to copy
+/// to
+pub fn normalize_remote_path(path: &str) -> Option<String> {
+    let path = path.trim();
 
-    pub fn set_fields_from_profile(&mut self, profile: Profile<'_>) {
-        self.bindings = profile.command_to_bindings.into_owned().into();
-        self.controller_names = profile.controller_names.into_owned();
-        self.controllers = profile.controllers.into_owned();
-        self.driver_constants = profile.constants.into_owned();
-        self.stream_to_axis = profile.stream_to_axis.into_owned();
+    if path.is_empty() {
+        None
+    } else if path.ends_with('/') {
+        Some(path.to_string())
+    } else {
+        Some(format!("{path}/"))
     }
+}
 
-    pub fn get_profile(&self, profile: &str) -> Result<Profile<'static>> {
-        Profile::get_from(&self.deploy_dir, profile)
+/// `path` quoted for the robot's shell. a leading ~/ stays outside the quotes
+/// so it still means the home directory
+pub fn quote_remote_path(path: &str) -> String {
+    let (home, rest) = match path.strip_prefix("~/") {
+        Some(rest) => ("~/", rest),
+        None => ("", path),
+    };
+
+    format!("{home}'{}'", rest.replace('\'', r"'\''"))
+}
+
+/// an error when `user` would be read as something other than a user name by
+/// ssh or scp
+pub fn check_ssh_user(user: &str) -> Result<()> {
+    if user.is_empty() {
+        anyhow::bail!("the ssh user can't be empty");
     }
 
-    pub fn write_out(&mut self, arena: &Bump) -> Result<()> {
-        let mut save_file = self.deploy_dir.clone(); // fix the clones in this function
+    if user.starts_with('-') {
+        anyhow::bail!("the ssh user can't start with -");
+    }
 
-        save_file.push("bindings.json");
+    if user.contains(|c: char| c.is_whitespace() || c == '@') {
+        anyhow::bail!("the ssh user can't have spaces or @ in it");
+    }
 
-        let mut profile = self.deploy_dir.clone();
+    Ok(())
+}
 
-        profile.push("bindings");
-        profile.push(bumpalo::format!(in &arena, "{}.json", self.profile).as_str());
+/// the arguments to scp that send `save_file` and everything in
+/// `bindings_dir` to `remote_path` on the robot. local paths are passed
+/// through as they are so spaces and windows separators survive, the remote
+/// one is quoted for the robot's shell
+pub fn build_sync_invocation(
+    save_file: &Path,
+    bindings_dir: &Path,
+    url: &str,
+    user: &str,
+    remote_path: &str,
+    port: Option<u16>,
+) -> Vec<OsString> {
+    let mut args = vec![OsString::from("-r")];
 
-        create_dir_all(save_file.parent().unwrap())?;
+    if let Some(port) = port {
+        args.push("-P".into());
+        args.push(port.to_string().into());
+    }
 
-        let mut file =
-            File::create(&save_file).with_context(|| "failed to create file to save to")?;
+    args.push("--".into());
+    args.push(save_file.into());
+    args.push(bindings_dir.into());
+    args.push(format!("{user}@{url}:{}", quote_remote_path(remote_path)).into());
 
-        file.write_all(
-            serde_json::to_string_pretty(&self.to_savedata())
-                .unwrap()
-                .as_bytes(),
-        )?;
+    args
+}
 
-        create_dir_all(profile.parent().unwrap())?;
+/// how long the remote has to stay the same before it's synced to
+pub const RESYNC_DELAY: Duration = Duration::from_secs(2);
 
-        let mut file =
-            File::create(&profile).with_context(|| "failed to create file to savce to")?;
+/// where gradle projects keep the files deployed to the robot
+pub const DEFAULT_DEPLOY_SUBPATH: &str = "src/main/deploy";
 
-        file.write_all(
-            serde_json::to_string_pretty(&self.to_profile_data())
-                .unwrap()
-                .as_bytes(),
-        )
-        .with_context(|| "failed to save to disk")?;
+/// how long to wait before sending again after each sync that fails in a
+/// row, the last one repeats until it works
+pub const SYNC_RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(5),
+    Duration::from_secs(15),
+    Duration::from_secs(60),
+];
 
-        match &self.url {
-            Some(url) if self.syncing => {
-                if let Some(child) = &mut self.sync_process {
-                    child.kill()?
-                }
+/// how long a connection test waits for the robot before calling it
+/// unreachable
+pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(3);
 
-                let mut c = Command::new("scp");
+// how much of scp's error output goes in the toast, the syncing tab has all
+// of it
+const SYNC_ERROR_LINES: usize = 3;
+const SYNC_ERROR_CHARS: usize = 240;
 
-                let command = c.arg("-r")
-                        .arg(save_file.as_os_str())
-                        .arg(bumpalo::format!(in &arena, "{}/bindings", self.deploy_dir.as_os_str().to_str().unwrap()).into_bump_str())
-                        .arg(bumpalo::format!(in &arena, "admin@{}:/home/lvuser/deploy/", url).into_bump_str());
+/// how often the project files are checked for changes made outside the app,
+/// like a git pull
+pub const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
-                #[cfg(target_os = "windows")]
-                let command = command.creation_flags(0x08000000);
+/// how long edits have to stop for before they're written, so dragging a
+/// slider or typing a name isn't a write and an scp per frame
+pub const SAVE_DELAY: Duration = Duration::from_millis(400);
 
-                self.sync_process = Some(command.spawn()?);
-            }
-            _ => {}
-        }
+/// a sync to one target, the running one and how the last one went
+#[derive(Debug, Default)]
+pub struct TargetSync {
+    pub process: Option<Child>,
+    // the sync running over built in ssh, instead of `process`
+    #[cfg(feature = "native-ssh")]
+    pub native: Option<crate::native_sync::NativeSync>,
+    // files the running built in sync has sent out of all of them
+    pub progress: Option<(usize, usize)>,
+    pub started: Option<Instant>,
+    // when the last sync ended and whether it worked
+    pub last: Option<(SystemTime, bool)>,
+    // what scp printed when the last sync failed
+    pub output: Option<String>,
+}
 
-        Ok(())
-    }
+// how a sync ended, with what went wrong when it failed and scp's exit code
+type Outcome = (Result<(), String>, Option<i32>);
 
-    fn to_profile_data(&self) -> Profile {
-        Profile {
-            command_to_bindings: Cow::Borrowed(&self.bindings.command_to_bindings),
-            controllers: Cow::Borrowed(&self.controllers),
-            controller_names: Cow::Borrowed(&self.controller_names),
-            constants: Cow::Borrowed(&self.driver_constants),
-            stream_to_axis: Cow::Borrowed(&self.stream_to_axis),
+impl TargetSync {
+    pub fn running(&self) -> bool {
+        #[cfg(feature = "native-ssh")]
+        if self.native.is_some() {
+            return true;
         }
-    }
 
-    fn to_savedata(&self) -> SaveData {
-        SaveData {
-            url: Cow::Borrowed(&self.url),
-            commands: Cow::Borrowed(&self.commands),
-            constants: Cow::Borrowed(&self.constants),
-            streams: Cow::Borrowed(&self.streams),
-        }
+        self.process.is_some()
     }
 
-    pub fn map_profiles<F>(&mut self, mut f: F, arena: &Bump) -> Result<()>
-    where
-        F: FnMut(&mut Profile),
-    {
-        for ele in self
-            .profiles
-            .iter()
-            .filter(|ele| ele.as_str() != self.profile.as_str())
-        {
-            let mut profile = self
-                .get_profile(ele.as_str())
-                .with_context(|| "failed to get profile")?;
-
-            f(&mut profile);
-
-            let mut path = self.deploy_dir.clone();
+    /// how the running sync ended once it has, with what went wrong when it
+    /// failed and scp's exit code
+    fn poll(&mut self) -> Result<Option<Outcome>> {
+        #[cfg(feature = "native-ssh")]
+        if let Some(native) = &self.native {
+            use crate::native_sync::Progress;
 
-            path.push("bindings");
-
-            path.push(bumpalo::format!(in arena, "{}.json", ele.as_str()).as_str());
-
-            let mut file =
-                File::create(path).with_context(|| "failed to create file to savce to")?;
+            return Ok(match native.poll() {
+                Some(Progress::Sent(sent, of)) => {
+                    self.progress = Some((sent, of));
+                    None
+                }
+                Some(Progress::Finished(result)) => {
+                    self.native = None;
+                    self.progress = None;
 
-            file.write_all(serde_json::to_string_pretty(&profile).unwrap().as_bytes())
-                .with_context(|| "failed to save to disk")?;
+                    Some((result.map_err(|err| format!("{err:#}")), None))
+                }
+                None => None,
+            });
         }
 
-        let mut p = self.to_profile_data();
+        let Some(child) = &mut self.process else {
+            return Ok(None);
+        };
 
-        f(&mut p);
+        let Some(status) = child
+            .try_wait()
+            .with_context(|| "failed to wait on sync process")?
+        else {
+            return Ok(None);
+        };
 
-        let p = p.get_owned();
+        if status.success() {
+            return Ok(Some((Ok(()), status.code())));
+        }
 
-        self.set_fields_from_profile(p);
+        // it has exited, so this reads what's left in the pipe and stops
+        let mut output = String::new();
 
-        self.write_out(arena)?;
+        if let Some(stderr) = &mut child.stderr {
+            let _ = stderr.read_to_string(&mut output);
+        }
 
-        Ok(())
+        Ok(Some((Err(output), status.code())))
     }
 
-    pub fn enumerate_profiles(&self) -> impl Iterator<Item = Result<Profile>> {
-        self.profiles
-            .iter()
-            .filter(|ele| ele.as_str() != self.profile.as_str())
-            .map(|profile| self.get_profile(profile.as_str()))
-            .chain([Ok(self.to_profile_data())])
-    }
+    /// kills the running sync and waits for it to exit, returns whether one
+    /// was actually interrupted
+    fn stop(&mut self) -> Result<bool> {
+        self.started = None;
 
-    pub fn is_used(&self, command: &Rc<String>) -> Result<bool> {
-        for profile in self.enumerate_profiles() {
-            let profile = profile?;
+        // its thread finishes the file it's on and stops
+        #[cfg(feature = "native-ssh")]
+        if let Some(native) = self.native.take() {
+            native.cancel();
+            self.progress = None;
 
-            if profile.command_to_bindings.contains_key(command) {
-                return Ok(true);
-            }
+            return Ok(true);
         }
 
-        Ok(false)
-    }
-
-    pub fn is_stream_used(&self, stream: &Rc<String>) -> Result<bool> {
-        for profile in self.enumerate_profiles() {
-            let profile = profile?;
+        let Some(mut child) = self.process.take() else {
+            return Ok(false);
+        };
 
-            if profile.stream_to_axis.contains_key(stream) {
-                return Ok(true);
-            }
+        if child.try_wait()?.is_some() {
+            return Ok(false);
         }
 
-        Ok(false)
+        child.kill()?;
+        child.wait()?;
+
+        Ok(true)
     }
+}
 
-    fn from_bindings(
-        bindings: SaveData,
-        profile: Profile,
-        profiles: Vec<Rc<String>>,
-        profile_name: String,
-        path: PathBuf,
-    ) -> Self {
+/// a sync that just ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncEnded {
+    pub target: String,
+    pub url: String,
+    pub success: bool,
+    pub took: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub struct State {
+    pub deploy_dir: PathBuf,
+    // where `deploy_dir` is inside the project
+    pub deploy_subpath: PathBuf,
+    // the robots syncs go to, none until syncing is set up
+    pub targets: Vec<SyncTarget>,
+    // the one whose address is being edited, checked and downloaded from
+    pub selected_target: usize,
+    pub syncing: bool,
+    // a target may not have our files yet, set when one changes and cleared
+    // once a sync goes out
+    pub remote_stale: bool,
+    // when the sync for a changed remote goes out
+    pub resync_at: Option<Instant>,
+    // when the edits not written yet go out, pushed back by every new one
+    pub save_at: Option<Instant>,
+    pub save_mode: SaveMode,
+    // every save is committed when the deploy directory is in a git repo
+    pub commit_on_save: bool,
+    pub team_number: Option<u32>,
+    // edited in manual save mode since the last save
+    pub unsaved: bool,
+    // other profiles changed in manual save mode, written with the next save
+    pub unsaved_profiles: BTreeMap<Rc<String>, Profile<'static>>,
+    pub commands: BTreeSet<Rc<String>>,
+    pub bindings: BindingsMap,
+    pub controllers: [ControllerType; 5],
+    pub controller_names: [Rc<String>; 5],
+    pub controller_colors: [Option<[u8; 3]>; CONTROLLER_SLOTS],
+    // filled in for slots with a gamepad on them, by the controller monitor
+    pub device_caps: [Option<DeviceCaps>; CONTROLLER_SLOTS],
+    // gamepads plugged into this computer and the slots they were given
+    #[cfg(feature = "gamepad")]
+    pub gamepads: crate::gamepad::Gamepads,
+    // how the syncs to each target are going, by target name
+    pub syncs: BTreeMap<String, TargetSync>,
+    // saved while a sync was running, sent once it's done
+    pub sync_pending: bool,
+    pub sync_backend: SyncBackend,
+    // for built in ssh when no key works, only kept until the app closes
+    pub sync_password: Option<String>,
+    pub sync_format: SyncFormat,
+    // bytes a save has to leave free on the drive
+    pub min_free_space: u64,
+    // how many of the old backups, broken files and logs to keep
+    pub retention: Retention,
+    // sizes of the files the running syncs are sending
+    pub sync_sizes: Option<SyncSizes>,
+    // when a failed sync is tried again, and how many have failed in a row
+    pub sync_retry_at: Option<Instant>,
+    pub sync_retries: usize,
+    // every sync attempt since the app opened, oldest first
+    pub sync_log: VecDeque<sync_log::Attempt>,
+    // the attempts are also added to sync.log in the deploy directory
+    pub sync_log_file: bool,
+    pub generation: u64,
+    pub robot_generation: Option<u64>,
+    pub generation_check: Option<Child>,
+    // a running connection test and when it started, kept apart from syncs
+    pub connection_test: Option<(Child, Instant)>,
+    pub connection_status: Option<ConnectionStatus>,
+    // scp downloading the robot's deploy files
+    pub pull_process: Option<Child>,
+    // what it downloaded and how it differs from here, until it's used or
+    // thrown away
+    pub pulled: Option<(Pulled, Vec<Change>)>,
+    pub profile: Rc<String>,
+    pub profiles: Vec<Rc<String>>,
+    pub description: String,
+    pub last_modified: Option<String>,
+    pub intentional: BTreeSet<Binding>,
+    pub binding_notes: BTreeMap<Rc<String>, Vec<BindingNote>>,
+    // descriptions of every profile so the selector can show them
+    pub profile_descriptions: BTreeMap<Rc<String>, String>,
+    pub constants: Constants,
+    pub driver_constants: Constants,
+    pub stream_to_axis: BTreeMap<Rc<String>, (u8, u8)>,
+    pub streams: BTreeSet<Rc<String>>,
+    pub required_commands: BTreeSet<Rc<String>>,
+    pub command_cooldowns: BTreeMap<Rc<String>, f32>,
+    pub command_meta: BTreeMap<Rc<String>, CommandMeta>,
+    pub required_streams: BTreeSet<Rc<String>>,
+    pub kotlin_keys: KotlinKeys,
+    pub disabled_bindings: DisabledBindings,
+    // required items the last readiness check found unbound, cleared on
+    // profile changes
+    pub readiness: Option<Vec<String>>,
+    pub show_readiness: bool,
+    pub quarantine: Quarantine,
+    pub show_quarantine: bool,
+    pub load_warnings: Vec<String>,
+    // a profile that couldn't be switched to because its file is gone
+    pub missing_profile: Option<Rc<String>>,
+    // profiles whose names aren't NFC normalized, only renamed once the user
+    // says so
+    pub unnormalized_profiles: Vec<Rc<String>>,
+    // a profile waiting to be switched to until the unsaved edits are saved
+    // or thrown away
+    pub unsaved_switch: Option<Rc<String>>,
+    // edits since the last write that made it to disk
+    pub pending: Vec<Change>,
+    pub ui_meta: UiMeta,
+    // what the project files held when we last read or wrote them
+    pub file_hashes: FileHashes,
+    // files a save found changed outside the app, saving waits until the user
+    // picks whose version wins
+    pub external_change: Option<Vec<PathBuf>>,
+    // a save went out and the tabs open in the window should follow it
+    pub layout_due: bool,
+    // when the project files were last checked for changes from outside
+    pub disk_checked_at: Option<Instant>,
+    // a profile that couldn't be switched to because its file isn't valid json
+    pub broken_profile: Option<(Rc<String>, BrokenFile)>,
+    // writes and scans the project files off the ui thread
+    pub worker: Worker,
+    // files sent to the worker and not written yet, with how many writes each
+    // is waiting on. what's on disk for them is still the old version
+    pub queued_writes: BTreeMap<PathBuf, usize>,
+    // batches of edits added to the change log this session
+    pub log_written: usize,
+    // how many errors events have put up, so an event that failed isn't
+    // logged as a change
+    pub errors_shown: usize,
+    // how many events changed something without asking for a write, they
+    // write it themselves or it only lasts the session
+    pub changes_applied: usize,
+    // how many times the project was read again from disk, so what was
+    // drafted from the old one can be dropped
+    pub reloads: usize,
+    // profiles other than the active one as they were last read from disk,
+    // forgotten whenever the profile files change
+    pub profile_cache: RefCell<BTreeMap<Rc<String>, Profile<'static>>>,
+    // a save is being written and the sync goes out once it's done
+    pub sync_after_save: bool,
+    // how often each command was bound this session, never saved
+    pub command_usage: BTreeMap<Rc<String>, Usage>,
+    pub usage_clock: usize,
+}
+
+/// ordered by count first so sorting puts the most bound commands last
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Usage {
+    pub count: usize,
+    pub last: usize,
+    // when a binding was last added or removed, for sorting by recent changes
+    pub modified: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
         Self {
-            url: bindings.url.into_owned(),
-            commands: bindings.commands.into_owned(),
-            bindings: profile.command_to_bindings.into_owned().into(),
-            controllers: profile.controllers.into_owned(),
-            controller_names: profile.controller_names.into_owned(),
+            targets: Vec::new(),
+            selected_target: 0,
             syncing: true,
-            sync_process: Default::default(),
-            deploy_dir: path,
-            profile: Rc::new(profile_name),
-            profiles,
-            constants: bindings.constants.into_owned(),
-            driver_constants: profile.constants.into_owned(),
-            stream_to_axis: profile.stream_to_axis.into_owned(),
-            streams: bindings.streams.into_owned(),
+            remote_stale: false,
+            resync_at: None,
+            save_at: None,
+            unsaved: false,
+            unsaved_profiles: BTreeMap::new(),
+            commands: Default::default(),
+            bindings: Default::default(),
+            controllers: Default::default(),
+            controller_names: Default::default(),
+            controller_colors: Default::default(),
+            device_caps: Default::default(),
+            #[cfg(feature = "gamepad")]
+            gamepads: Default::default(),
+            syncs: BTreeMap::new(),
+            sync_pending: false,
+            sync_password: None,
+            sync_format: Default::default(),
+            min_free_space: disk_space::DEFAULT_MIN_FREE,
+            retention: Default::default(),
+            sync_sizes: None,
+            sync_retry_at: None,
+            sync_retries: 0,
+            sync_log: VecDeque::new(),
+            generation: 0,
+            robot_generation: None,
+            generation_check: None,
+            connection_test: None,
+            connection_status: None,
+            pull_process: None,
+            pulled: None,
+            deploy_dir: PathBuf::default(),
+            deploy_subpath: DEFAULT_DEPLOY_SUBPATH.into(),
+            profile: Rc::new("default".to_string()),
+            profiles: Default::default(),
+            description: Default::default(),
+            last_modified: None,
+            intentional: Default::default(),
+            binding_notes: Default::default(),
+            profile_descriptions: Default::default(),
+            constants: Default::default(),
+            driver_constants: Default::default(),
+            stream_to_axis: Default::default(),
+            streams: Default::default(),
+            required_commands: Default::default(),
+            command_cooldowns: Default::default(),
+            command_meta: Default::default(),
+            required_streams: Default::default(),
+            kotlin_keys: Default::default(),
+            disabled_bindings: Default::default(),
+            save_mode: Default::default(),
+            commit_on_save: false,
+            sync_log_file: false,
+            team_number: None,
+            sync_backend: SyncBackend::Scp,
+            readiness: None,
+            show_readiness: false,
+            quarantine: Default::default(),
+            show_quarantine: false,
+            load_warnings: Default::default(),
+            missing_profile: None,
+            unnormalized_profiles: Vec::new(),
+            unsaved_switch: None,
+            pending: Vec::new(),
+            ui_meta: Default::default(),
+            file_hashes: Default::default(),
+            external_change: None,
+            broken_profile: None,
+            worker: Default::default(),
+            queued_writes: BTreeMap::new(),
+            log_written: 0,
+            errors_shown: 0,
+            changes_applied: 0,
+            reloads: 0,
+            profile_cache: Default::default(),
+            sync_after_save: false,
+            disk_checked_at: None,
+            layout_due: false,
+            command_usage: Default::default(),
+            usage_clock: 0,
+        }
+    }
+}
+
+impl State {
+    pub fn display_tab(
+        &mut self,
+        ui: &mut Ui,
+        tab: &mut Box<dyn Component<OutputEvents = GlobalEvents, Environment = Self>>,
+        notifier: &mut dyn Notifier,
+        arena: &Bump,
+    ) -> Result<()> {
+        let mut events = EventStream::new();
+
+        tab.render(ui, self, &events, arena);
+
+        self.process_events(&mut events, arena, notifier)
+    }
+
+    pub fn process_events(
+        &mut self,
+        events: &mut EventStream<GlobalEvents>,
+        arena: &Bump,
+        notifier: &mut dyn Notifier,
+    ) -> Result<()> {
+        let mut update = false;
+        let mut logged = Vec::new();
+        let at = humantime::format_rfc3339_seconds(SystemTime::now()).to_string();
+
+        for e in events.drain() {
+            let change = e.describe(self, arena);
+            let kind = e.kind();
+            let applied = self.changes_applied;
+
+            let write = self.handle_event(e, arena, notifier);
+
+            // events that write their own files say no write is needed, they
+            // count what they changed instead. one that left everything as it
+            // was does neither and isn't logged
+            if let Some(change) = &change {
+                if write || self.changes_applied != applied {
+                    logged.push(audit_log::Entry {
+                        at: at.clone(),
+                        by: audit_log::current_user(),
+                        kind: kind.to_string(),
+                        summary: change.to_string(),
+                    });
+                }
+            }
+
+            if write {
+                update = true; // don't do any because any terminates early
+                self.pending.extend(change);
+            }
+        }
+
+        if !logged.is_empty() && !self.deploy_dir.as_os_str().is_empty() {
+            self.worker.send(Job::Log {
+                deploy_dir: self.deploy_dir.clone(),
+                entries: logged,
+            });
+        }
+
+        if update {
+            self.mark_dirty();
+        }
+
+        self.poll_worker()
+    }
+
+    /// there are edits to write, they go out once nothing has changed for
+    /// `SAVE_DELAY`, or when they're saved by hand in manual save mode
+    pub fn mark_dirty(&mut self) {
+        match self.save_mode {
+            SaveMode::Auto => self.save_at = Some(Instant::now() + SAVE_DELAY),
+            SaveMode::Manual => self.unsaved = true,
+        }
+    }
+
+    pub fn save_due(&self, now: Instant) -> bool {
+        self.save_at.is_some_and(|at| at <= now)
+    }
+
+    pub fn flush_if_due(&mut self, now: Instant, arena: &Bump) -> Result<bool> {
+        if self.save_due(now) {
+            self.flush(arena)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// writes the waiting edits straight away, unsaved ones included, returns
+    /// whether there were any. a failed write isn't tried again, its edits stay
+    /// in `pending`
+    pub fn flush(&mut self, arena: &Bump) -> Result<bool> {
+        if self.save_at.take().is_none() && !self.unsaved {
+            return Ok(false);
+        }
+
+        self.write_out(arena)?;
+
+        Ok(true)
+    }
+
+    pub fn handle_event(
+        &mut self,
+        event: GlobalEvents,
+        arena: &Bump,
+        notifier: &mut dyn Notifier,
+    ) -> bool {
+        match event {
+            GlobalEvents::AddBinding(binding, command) => {
+                self.usage_clock += 1;
+
+                let usage = self.command_usage.entry(command.clone()).or_default();
+                usage.count += 1;
+                usage.last = self.usage_clock;
+                usage.modified = self.usage_clock;
+
+                let clashes = self
+                    .bindings
+                    .binding_to_commands
+                    .get(&binding.key())
+                    .into_iter()
+                    .flatten()
+                    .filter(|(c, when)| *when == binding.during && *c != command)
+                    .map(|(c, _)| c.to_string())
+                    .collect::<Vec<_>>();
+
+                if !clashes.is_empty() && !self.intentional.contains(&binding.trigger()) {
+                    let warning = format!(
+                        "{command} now runs alongside {} {}",
+                        clashes.join(", "),
+                        binding.show(&command, self, arena)
+                    );
+
+                    self.handle_event(GlobalEvents::DisplayWarning(warning), arena, notifier);
+                }
+
+                self.bindings.add_binding(command.clone(), binding);
+
+                let overlaps = self
+                    .bindings
+                    .chord_findings(|trigger| self.trigger_name(trigger, arena))
+                    .into_iter()
+                    .filter(|(bindings, _)| {
+                        bindings
+                            .iter()
+                            .any(|(c, b)| **c == command && b.trigger() == binding.trigger())
+                    })
+                    .map(|(_, finding)| finding.message)
+                    .collect::<Vec<_>>();
+
+                for warning in overlaps {
+                    self.handle_event(GlobalEvents::DisplayWarning(warning), arena, notifier);
+                }
+
+                true
+            }
+            GlobalEvents::RemoveBinding(binding, command) => {
+                if self.bindings.remove_binding(&command, binding) {
+                    self.forget_stale_notes();
+                    self.forget_stale_intentional();
+
+                    self.usage_clock += 1;
+                    self.command_usage.entry(command).or_default().modified = self.usage_clock;
+
+                    return true;
+                }
+
+                self.handle_event(
+                    GlobalEvents::DisplayError(format!(
+                        "{command} was no longer bound {}",
+                        binding.show(&command, self, arena)
+                    )),
+                    arena,
+                    notifier,
+                )
+            }
+            GlobalEvents::SetIntentional(binding, intentional) => {
+                if intentional {
+                    self.intentional.insert(binding);
+                } else {
+                    self.intentional.remove(&binding);
+                }
+
+                true
+            }
+            GlobalEvents::SetBindingNote(binding, command, note) => {
+                let binding = binding.trigger();
+
+                if !self
+                    .bindings
+                    .bindings_for_command(&command)
+                    .any(|b| b.trigger() == binding)
+                {
+                    return self.handle_event(
+                        GlobalEvents::DisplayError(format!(
+                            "{command} isn't bound {}",
+                            binding.show(&command, self, arena)
+                        )),
+                        arena,
+                        notifier,
+                    );
+                }
+
+                let notes = self.binding_notes.entry(command.clone()).or_default();
+                let note = note.trim().to_string();
+
+                match notes.iter_mut().find(|n| n.binding == binding) {
+                    Some(existing) if existing.note == note => return false,
+                    Some(existing) => existing.note = note,
+                    None if note.is_empty() => return false,
+                    None => notes.push(BindingNote { binding, note }),
+                }
+
+                notes.retain(|n| !n.note.is_empty());
+
+                if notes.is_empty() {
+                    self.binding_notes.remove(&command);
+                }
+
+                true
+            }
+            GlobalEvents::SetBindingEnabled(binding, command, enabled) => {
+                let Some(existing) = self
+                    .bindings
+                    .command_to_bindings
+                    .get_mut(&command)
+                    .and_then(|bindings| {
+                        bindings
+                            .iter_mut()
+                            .find(|b| b.trigger() == binding.trigger())
+                    })
+                else {
+                    return self.handle_event(
+                        GlobalEvents::DisplayError(format!(
+                            "{command} isn't bound {}",
+                            binding.show(&command, self, arena)
+                        )),
+                        arena,
+                        notifier,
+                    );
+                };
+
+                if existing.enabled == enabled {
+                    return false;
+                }
+
+                existing.enabled = enabled;
+
+                true
+            }
+            GlobalEvents::ClearControllerBindings(controller) => {
+                let removed = self.bindings.clear_controller(controller);
+
+                self.forget_stale_notes();
+                self.forget_stale_intentional();
+
+                notifier.info(format!(
+                    "cleared {removed} bindings from {}",
+                    self.controller_name(controller)
+                ));
+
+                true
+            }
+            GlobalEvents::MoveControllerBindings { from, to } => {
+                let destination = self.controllers[to as usize];
+
+                let skipped = self.bindings.move_controller(from, to, |b| {
+                    destination.valid_trigger(b.button, b.modifier)
+                });
+
+                // notes go with the bindings that moved, the ones left behind
+                // keep theirs
+                for (command, notes) in &mut self.binding_notes {
+                    for note in notes.iter_mut() {
+                        if note.binding.controller == from
+                            && !skipped
+                                .iter()
+                                .any(|(c, b)| c == command && b.trigger() == note.binding)
+                        {
+                            note.binding.controller = to;
+                        }
+                    }
+                }
+
+                self.forget_stale_notes();
+
+                if !skipped.is_empty() {
+                    let bindings = skipped
+                        .iter()
+                        .map(|(command, binding)| {
+                            format!("{command} {}", binding.show(command, self, arena))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    self.handle_event(
+                        GlobalEvents::DisplayWarning(format!(
+                            "{} doesn't have these, they were left behind: {bindings}",
+                            self.controller_name(to)
+                        )),
+                        arena,
+                        notifier,
+                    );
+                }
+
+                true
+            }
+            GlobalEvents::SwapButtons(a, b) => {
+                if let Some((controller, button)) = [a, b]
+                    .into_iter()
+                    .find(|(controller, button)| !self.valid_binding(*controller, *button, None))
+                {
+                    self.handle_event(
+                        GlobalEvents::DisplayError(format!(
+                            "{} doesn't have {}",
+                            self.controller_name(controller),
+                            self.controllers[controller as usize].button_name(&button, arena)
+                        )),
+                        arena,
+                        notifier,
+                    );
+                    return false;
+                }
+
+                if a == b {
+                    return false;
+                }
+
+                self.bindings.swap_buttons(a, b);
+
+                for note in self.binding_notes.values_mut().flatten() {
+                    note.binding.swap_button(a, b);
+                }
+
+                // conflicts marked on purpose go with the commands
+                self.intentional = mem::take(&mut self.intentional)
+                    .into_iter()
+                    .map(|mut binding| {
+                        binding.swap_button(a, b);
+                        binding
+                    })
+                    .collect();
+
+                true
+            }
+            GlobalEvents::AddCommand(command) => {
+                self.commands
+                    .insert(Rc::new(name_check::normalize(&command)));
+                true
+            }
+            GlobalEvents::RemoveCommand(command) => {
+                self.commands.remove(&command);
+                self.required_commands.remove(&command);
+                self.command_cooldowns.remove(&command);
+                self.command_meta.remove(&command);
+                self.command_usage.remove(&command);
+                self.binding_notes.remove(&command);
+                self.bindings.remove_command(&command);
+                self.forget_stale_intentional();
+                true
+            }
+            GlobalEvents::SetCommandRequired(command, required) => {
+                if required {
+                    self.required_commands.insert(command);
+                } else {
+                    self.required_commands.remove(&command);
+                }
+                true
+            }
+            GlobalEvents::SetCommandCooldown(command, cooldown) => match cooldown {
+                Some(seconds) if !(0.0..=MAX_COOLDOWN).contains(&seconds) => {
+                    self.handle_event(
+                        GlobalEvents::DisplayError(format!(
+                            "a cooldown has to be between 0 and {MAX_COOLDOWN} seconds"
+                        )),
+                        arena,
+                        notifier,
+                    );
+                    false
+                }
+                Some(seconds) if seconds > 0.0 => {
+                    self.command_cooldowns.insert(command, seconds);
+                    true
+                }
+                _ => self.command_cooldowns.remove(&command).is_some(),
+            },
+            GlobalEvents::SetCommandMeta(command, meta) => {
+                if meta.is_empty() {
+                    self.command_meta.remove(&command).is_some()
+                } else {
+                    self.command_meta.insert(command, meta.clone()) != Some(meta)
+                }
+            }
+            GlobalEvents::PurgeCommand(command) => {
+                if let Err(err) = self.map_profiles(
+                    |_, profile| {
+                        profile.command_to_bindings.to_mut().remove(&command);
+
+                        if profile.binding_notes.contains_key(&command) {
+                            profile.binding_notes.to_mut().remove(&command);
+                        }
+                    },
+                    arena,
+                ) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.handle_event(GlobalEvents::RemoveCommand(command), arena, notifier)
+            }
+            GlobalEvents::DisplayError(error) => {
+                self.errors_shown += 1;
+                notifier.error(error);
+                false
+            }
+            GlobalEvents::DisplayWarning(warning) => {
+                notifier.warning(warning);
+                false
+            }
+            GlobalEvents::DisplayInfo(info) => {
+                notifier.info(info);
+                false
+            }
+            GlobalEvents::Save => true,
+            GlobalEvents::SetUrl(url) => {
+                match (url, self.targets.get_mut(self.selected_target)) {
+                    (Some(url), Some(target)) => target.url = url,
+                    // the first target is set up by giving it an address
+                    (Some(url), None) => {
+                        self.targets.push(SyncTarget::new("robot".to_string(), url));
+                        self.selected_target = self.targets.len() - 1;
+                    }
+                    (None, Some(_)) => {
+                        let target = self.targets.remove(self.selected_target);
+                        self.forget_target(&target.name);
+                    }
+                    (None, None) => return false,
+                }
+
+                self.remote_changed();
+                true
+            }
+            GlobalEvents::AddSyncTarget(name) => {
+                let name = name.trim().to_string();
+
+                let problem = if name.is_empty() {
+                    Some("a sync target needs a name".to_string())
+                } else if self.targets.iter().any(|t| t.name == name) {
+                    Some(format!("there's already a sync target called {name}"))
+                } else {
+                    None
+                };
+
+                if let Some(problem) = problem {
+                    self.handle_event(GlobalEvents::DisplayError(problem), arena, notifier);
+                    return false;
+                }
+
+                self.targets.push(SyncTarget::new(name, String::new()));
+                self.selected_target = self.targets.len() - 1;
+                true
+            }
+            GlobalEvents::RemoveSyncTarget(name) => {
+                let Some(at) = self.targets.iter().position(|t| t.name == name) else {
+                    return false;
+                };
+
+                self.targets.remove(at);
+                self.forget_target(&name);
+                true
+            }
+            GlobalEvents::SetSyncTargetEnabled(name, enabled) => {
+                let Some(target) = self.targets.iter_mut().find(|t| t.name == name) else {
+                    return false;
+                };
+
+                target.enabled = enabled;
+
+                // it may have missed syncs while it was off
+                if enabled {
+                    self.remote_changed();
+                }
+
+                true
+            }
+            GlobalEvents::SetSyncFormat(format) => {
+                self.sync_format = format;
+                self.remote_changed();
+                true
+            }
+            GlobalEvents::SetCommitOnSave(commit) => {
+                self.commit_on_save = commit;
+                true
+            }
+            GlobalEvents::SetSyncLogFile(write) => {
+                self.sync_log_file = write;
+                true
+            }
+            GlobalEvents::SetSshUser(user) => {
+                let user = user.trim();
+
+                if let Err(err) = check_ssh_user(user) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                let Some(target) = self.targets.get_mut(self.selected_target) else {
+                    return false;
+                };
+
+                if user == target.user {
+                    return false;
+                }
+
+                target.user = user.to_string();
+                self.remote_changed();
+                true
+            }
+            GlobalEvents::SetSyncBackend(backend) => {
+                if backend == self.sync_backend {
+                    return false;
+                }
+
+                self.sync_backend = backend;
+                self.remote_changed();
+                true
+            }
+            GlobalEvents::SetTeamNumber(team) => {
+                if team == self.team_number {
+                    return false;
+                }
+
+                self.team_number = team;
+                true
+            }
+            GlobalEvents::SetRemotePath(path) => {
+                let Some(path) = normalize_remote_path(&path) else {
+                    self.handle_event(
+                        GlobalEvents::DisplayError(
+                            "the robot deploy path can't be empty".to_string(),
+                        ),
+                        arena,
+                        notifier,
+                    );
+                    return false;
+                };
+
+                let Some(target) = self.targets.get_mut(self.selected_target) else {
+                    return false;
+                };
+
+                if path == target.path {
+                    return false;
+                }
+
+                target.path = path;
+                self.remote_changed();
+                true
+            }
+            GlobalEvents::SetDisabledBindings(disabled) => {
+                if self.disabled_bindings == disabled {
+                    return false;
+                }
+
+                self.disabled_bindings = disabled;
+
+                // every profile is rewritten so they all match the setting
+                match self.map_profiles(|_, _| {}, arena) {
+                    Ok(()) => self.changes_applied += 1,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                    }
+                }
+
+                false
+            }
+            GlobalEvents::SetSaveMode(mode) => {
+                if self.save_mode == mode {
+                    return false;
+                }
+
+                self.save_mode = mode;
+
+                // written now either way, in manual mode the setting itself
+                // would otherwise wait for a save
+                match self.write_out(arena) {
+                    Ok(()) => self.changes_applied += 1,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                    }
+                }
+
+                false
+            }
+            GlobalEvents::SetSyncing(syncing) => {
+                if self.syncing != syncing {
+                    self.changes_applied += 1;
+                }
+
+                self.syncing = syncing;
+
+                if !syncing {
+                    self.cancel_sync_retry();
+                }
+
+                // changes made while it was off never reached the robot
+                if syncing && self.remote_stale {
+                    self.resync_at = Some(Instant::now());
+                }
+
+                false
+            }
+            GlobalEvents::ForceSync => {
+                self.resync_at = None;
+
+                if self.remote_stale && self.sends() {
+                    notifier.info("remote changed — performing full sync".to_string());
+                }
+
+                true
+            }
+            GlobalEvents::RenameCommand(old, new) => {
+                let new = Rc::new(name_check::normalize(&new));
+
+                if let Err(err) = self.map_profiles(
+                    |_, profile| {
+                        let command_to_bindings = profile.command_to_bindings.to_mut();
+
+                        let mut bindings = BindingsMap::from(mem::take(command_to_bindings));
+
+                        bindings.rename_command(&old, new.clone());
+
+                        *command_to_bindings = bindings.command_to_bindings;
+
+                        if profile.binding_notes.contains_key(&old) {
+                            let notes = profile.binding_notes.to_mut();
+                            let moved = notes.remove(&old).unwrap_or_default();
+
+                            notes.insert(new.clone(), moved);
+                        }
+                    },
+                    arena,
+                ) {
+                    // the profiles still have the old name, so everything
+                    // else keeps it too
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                if self.required_commands.remove(&old) {
+                    self.required_commands.insert(new.clone());
+                }
+
+                if let Some(cooldown) = self.command_cooldowns.remove(&old) {
+                    self.command_cooldowns.insert(new.clone(), cooldown);
+                }
+
+                if let Some(meta) = self.command_meta.remove(&old) {
+                    self.command_meta.insert(new.clone(), meta);
+                }
+
+                if let Some(usage) = self.command_usage.remove(&old) {
+                    self.command_usage.insert(new.clone(), usage);
+                }
+
+                self.commands.remove(&old);
+                self.commands.insert(new);
+                true
+            }
+            GlobalEvents::PropagateBindings(command) => {
+                let bindings = self.bindings.command_to_bindings.get(&command).cloned();
+                let notes = self.binding_notes.get(&command).cloned();
+
+                let mut invalid: Vec<Rc<String>> = Vec::new();
+
+                if let Err(err) = self.map_profiles(
+                    |name, profile| match &bindings {
+                        Some(bindings) => {
+                            if bindings.iter().any(|b| {
+                                !profile.controllers[b.controller as usize]
+                                    .valid_trigger(b.button, b.modifier)
+                            }) {
+                                invalid.push(name.clone());
+                            }
+
+                            profile
+                                .command_to_bindings
+                                .to_mut()
+                                .insert(command.clone(), bindings.clone());
+
+                            match &notes {
+                                Some(notes) => {
+                                    profile
+                                        .binding_notes
+                                        .to_mut()
+                                        .insert(command.clone(), notes.clone());
+                                }
+                                None if profile.binding_notes.contains_key(&command) => {
+                                    profile.binding_notes.to_mut().remove(&command);
+                                }
+                                None => {}
+                            }
+                        }
+                        None => {
+                            profile.command_to_bindings.to_mut().remove(&command);
+
+                            if profile.binding_notes.contains_key(&command) {
+                                profile.binding_notes.to_mut().remove(&command);
+                            }
+                        }
+                    },
+                    arena,
+                ) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                } else {
+                    self.changes_applied += 1;
+                }
+
+                if !invalid.is_empty() {
+                    let profiles = invalid
+                        .iter()
+                        .map(|p| p.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    self.handle_event(
+                        GlobalEvents::DisplayWarning(format!(
+                            "copied bindings for {command} aren't valid for the controllers in: {profiles}"
+                        )),
+                        arena,
+                        notifier,
+                    );
+                }
+
+                false
+            }
+            GlobalEvents::AddProfile(profile) => {
+                match self.add_profile(profile) {
+                    Ok(()) => self.changes_applied += 1,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                    }
+                }
+                false
+            }
+            GlobalEvents::DuplicateProfile { name, switch } => match self.duplicate_profile(name) {
+                // by the name it was written under, which is normalized
+                Ok(profile) if switch => {
+                    self.handle_event(GlobalEvents::SetProfile(profile), arena, notifier)
+                }
+                Ok(_) => {
+                    self.changes_applied += 1;
+                    false
+                }
+                Err(err) => {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    false
+                }
+            },
+            GlobalEvents::ImportProfile(name, bundle) => match self.import_profile(name, &bundle) {
+                Ok(warnings) => {
+                    for warning in warnings {
+                        self.handle_event(GlobalEvents::DisplayWarning(warning), arena, notifier);
+                    }
+                    true
+                }
+                Err(err) => {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    false
+                }
+            },
+            GlobalEvents::SetProfile(profile) => {
+                // saving by hand means nothing is written without asking
+                if self.save_mode == SaveMode::Manual && self.unsaved {
+                    self.unsaved_switch = Some(profile);
+                    return false;
+                }
+
+                self.readiness = None;
+
+                // a profile that was never saved has no file yet, and without
+                // one it couldn't be switched back to
+                if !Profile::exists(&self.deploy_dir, &self.profile) {
+                    self.mark_dirty();
+                }
+
+                // the edits waiting to be written belong to the profile being
+                // left, unsaved ones too or switching would throw them away.
+                // they have to be on disk before the next profile is read
+                if let Err(err) = self.flush(arena).and_then(|_| self.finish_io()) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                }
+
+                // an scp that is still running could copy the bindings directory
+                // half way through the switch, so stop it and sync again after
+                let interrupted = match self.stop_sync() {
+                    Ok(interrupted) => interrupted,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                        false
+                    }
+                };
+
+                match self.change_profile(profile.clone()) {
+                    Ok(()) => {
+                        self.changes_applied += 1;
+                        self.broken_profile = None;
+                        self.missing_profile = None;
+
+                        // bindings.json has the resolved constants of the
+                        // active profile, they change with it
+                        return true;
+                    }
+                    // asks what to do about it instead of only showing the error
+                    Err(err) => match BrokenFile::from_error(&err) {
+                        Some(broken) => self.broken_profile = Some((profile, broken)),
+                        None if matches!(
+                            err.downcast_ref(),
+                            Some(ProgramError::MissingProfile(_))
+                        ) =>
+                        {
+                            self.broken_profile = None;
+                            self.missing_profile = Some(profile);
+                        }
+                        None => {
+                            self.handle_event(
+                                GlobalEvents::DisplayError(err.to_string()),
+                                arena,
+                                notifier,
+                            );
+                        }
+                    },
+                };
+                interrupted
+            }
+            GlobalEvents::ResetProfile(profile) => {
+                if let Err(err) = self.write_empty_profile(&profile) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.handle_event(GlobalEvents::SetProfile(profile), arena, notifier)
+            }
+            GlobalEvents::RenameProfile(old, new) => {
+                // the waiting edits go to the file under its old name, which
+                // is then moved along with them
+                match self
+                    .flush(arena)
+                    .and_then(|_| self.rename_profile(old, new))
+                {
+                    Ok(()) => self.changes_applied += 1,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                    }
+                }
+                false
+            }
+            GlobalEvents::MergeProfile { from, into } => match self.merge_profile(&from, &into) {
+                Ok(()) => true,
+                Err(err) => {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    false
+                }
+            },
+            GlobalEvents::AddOption(key, constant) => {
+                if self.constants.add_option(key, constant) {
+                    self.handle_event(
+                        GlobalEvents::DisplayError("failed to add constants".to_string()),
+                        arena,
+                        notifier,
+                    );
+                    false
+                } else {
+                    true
+                }
+            }
+            GlobalEvents::RemoveOption(key) => {
+                if let Err(err) = self.map_profiles(
+                    |_, profile| {
+                        profile.constants.to_mut().remove_key(&key);
+                    },
+                    arena,
+                ) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.constants.remove_key(&key);
+
+                if self.ui_meta.forget(&key) {
+                    self.save_ui_meta(arena, notifier);
+                }
+
+                true
+            }
+            GlobalEvents::RenameOption(key, to) => {
+                // renamed on a copy so nothing changes unless the profiles can
+                // be renamed too
+                let mut constants = self.constants.clone();
+
+                if constants.rename_key(&key, to.clone()) {
+                    self.handle_event(
+                        GlobalEvents::DisplayError(format!("couldn't rename to {to}")),
+                        arena,
+                        notifier,
+                    );
+                    return false;
+                }
+
+                // profiles without an override for it have nothing to rename
+                if let Err(err) = self.map_profiles(
+                    |_, profile| {
+                        profile.constants.to_mut().rename_key(&key, to.clone());
+                    },
+                    arena,
+                ) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.constants = constants;
+
+                if self.ui_meta.rename(&key, to.clone()) {
+                    self.save_ui_meta(arena, notifier);
+                }
+
+                true
+            }
+            GlobalEvents::PinConstant(key, pin) => {
+                self.ui_meta.pin(&key, pin);
+                self.save_ui_meta(arena, notifier);
+                false
+            }
+            GlobalEvents::RaiseConstant(key) => {
+                self.ui_meta.raise(&key);
+                self.save_ui_meta(arena, notifier);
+                false
+            }
+            GlobalEvents::RemoveOptionDriver(key) => {
+                self.driver_constants.remove_key(&key);
+
+                true
+            }
+            GlobalEvents::AddOptionDriver(key, constant) => {
+                if self.driver_constants.add_option(key, constant) {
+                    self.handle_event(
+                        GlobalEvents::DisplayError("failed to add constant".to_string()),
+                        arena,
+                        notifier,
+                    );
+                    false
+                } else {
+                    true
+                }
+            }
+            GlobalEvents::SetStream(stream, controller, axis) => {
+                let error = match self.controllers.get(controller as usize) {
+                    None => Some(format!("there is no controller {controller}")),
+                    Some(c) if !c.bound() => Some(format!(
+                        "{} isn't bound, bind it before giving it streams",
+                        self.controller_name(controller)
+                    )),
+                    Some(c) if !c.valid_axis(axis) => Some(format!(
+                        "{} only has {} axises, there is no axis {axis}",
+                        self.controller_name(controller),
+                        c.num_axises()
+                    )),
+                    Some(_) => None,
+                };
+
+                match error {
+                    Some(error) => {
+                        self.handle_event(GlobalEvents::DisplayError(error), arena, notifier);
+                        false
+                    }
+                    None => {
+                        self.stream_to_axis.insert(stream, (controller, axis));
+                        true
+                    }
+                }
+            }
+            GlobalEvents::AddStream(stream) => {
+                self.streams.insert(Rc::new(name_check::normalize(&stream)));
+                true
+            }
+            GlobalEvents::RenameStream(from, to) => {
+                let to = Rc::new(name_check::normalize(&to));
+
+                self.streams.remove(&from);
+                self.streams.insert(to.clone());
+
+                if self.required_streams.remove(&from) {
+                    self.required_streams.insert(to.clone());
+                }
+
+                let binding: Option<(u8, u8)> = self.stream_to_axis.remove(&from);
+
+                if let Some(binding) = binding {
+                    self.stream_to_axis.insert(to, binding);
+                }
+
+                true
+            }
+            GlobalEvents::RemoveStream(stream) => {
+                self.streams.remove(&stream);
+                self.required_streams.remove(&stream);
+                true
+            }
+            GlobalEvents::SetStreamRequired(stream, required) => {
+                if required {
+                    self.required_streams.insert(stream);
+                } else {
+                    self.required_streams.remove(&stream);
+                }
+                true
+            }
+            GlobalEvents::NormalizeProfileNames => {
+                for from in mem::take(&mut self.unnormalized_profiles) {
+                    let to = Rc::new(name_check::normalize(&from));
+
+                    self.handle_event(GlobalEvents::RenameProfile(from, to), arena, notifier);
+                }
+
+                false
+            }
+            GlobalEvents::KeepProfileNames => {
+                self.unnormalized_profiles.clear();
+                false
+            }
+            GlobalEvents::KeepQuarantine => {
+                if self.show_quarantine {
+                    self.changes_applied += 1;
+                }
+
+                self.show_quarantine = false;
+                false
+            }
+            GlobalEvents::AdjustControllerToDevice(controller) => {
+                let Some(caps) = self.device_caps[controller as usize] else {
+                    return false;
+                };
+
+                let slot = &mut self.controllers[controller as usize];
+
+                *slot = slot.adjusted_to(caps);
+
+                let invalid = self.invalid_bindings(controller);
+
+                if !invalid.is_empty() {
+                    let bindings = invalid
+                        .iter()
+                        .map(|(command, binding)| {
+                            format!("{command} {}", binding.show(command, self, arena))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    self.handle_event(
+                        GlobalEvents::DisplayWarning(format!(
+                            "these bindings are now out of range: {bindings}"
+                        )),
+                        arena,
+                        notifier,
+                    );
+                }
+
+                true
+            }
+            GlobalEvents::DiscardQuarantine => {
+                self.quarantine = Default::default();
+                self.show_quarantine = false;
+                // the report listed it
+                self.readiness = None;
+                true
+            }
+            GlobalEvents::KeepMine => {
+                self.forget_profiles();
+
+                for path in self.external_change.take().unwrap_or_default() {
+                    self.file_hashes.forget(&path);
+                }
+
+                true
+            }
+            GlobalEvents::UsePulled => {
+                match self.apply_pull() {
+                    Ok(()) => self.changes_applied += 1,
+                    Err(err) => {
+                        self.handle_event(
+                            GlobalEvents::DisplayError(err.to_string()),
+                            arena,
+                            notifier,
+                        );
+                    }
+                }
+
+                false
+            }
+            GlobalEvents::SaveAndSwitch => {
+                let Some(profile) = self.unsaved_switch.take() else {
+                    return false;
+                };
+
+                if let Err(err) = self.write_out(arena) {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.handle_event(GlobalEvents::SetProfile(profile), arena, notifier)
+            }
+            GlobalEvents::DiscardAndSwitch => {
+                let Some(profile) = self.unsaved_switch.take() else {
+                    return false;
+                };
+
+                // back to what's on disk, so none of the edits carry over
+                if let Err(err) = self.reload() {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    return false;
+                }
+
+                self.handle_event(GlobalEvents::SetProfile(profile), arena, notifier)
+            }
+            GlobalEvents::TakeTheirs => {
+                self.external_change = None;
+
+                if let Err(err) = self.reload() {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                }
+
+                false
+            }
+            GlobalEvents::MergeTheirs => match self.copy_theirs() {
+                Ok(Some(theirs)) => {
+                    notifier.info(format!(
+                        "their version is saved as {theirs}, merge it into {} from manage profiles",
+                        self.profile
+                    ));
+
+                    self.handle_event(GlobalEvents::KeepMine, arena, notifier)
+                }
+                Ok(None) => {
+                    notifier.info(format!(
+                        "{} didn't change on disk so there's nothing to merge, kept yours",
+                        self.profile
+                    ));
+
+                    self.handle_event(GlobalEvents::KeepMine, arena, notifier)
+                }
+                Err(err) => {
+                    self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+                    false
+                }
+            },
+        }
+    }
+
+    pub fn change_profile(&mut self, profile: Rc<String>) -> Result<()> {
+        // an empty profile in place of a deleted one would be saved over
+        // whatever is left of it, so the current one stays open instead
+        if !Profile::exists(&self.deploy_dir, &profile) {
+            return Err(ProgramError::MissingProfile(profile.to_string()))?;
+        }
+
+        // loaded before anything changes, a broken profile leaves the current
+        // one open
+        let (loaded, from_backup) = Profile::get_from(&self.deploy_dir, profile.as_str())?;
+
+        // switched only once the pointer says so, or the next start would open
+        // a different profile than the one being edited
+        self.write_profile_pointer(&profile)?;
+
+        // the profile being left is only in memory, what's cached of it is
+        // older than what the flush writes
+        self.forget_profiles();
+
+        self.profile = profile.clone();
+
+        let profile_path = self.profile_path(&profile);
+
+        if from_backup {
+            self.note_backup(&profile_path);
+        }
+
+        self.file_hashes.record_file(&profile_path);
+
+        self.set_fields_from_profile(loaded);
+
+        self.show_quarantine = !self.quarantine.is_empty();
+
+        Ok(())
+    }
+
+    pub fn profile_path(&self, profile: &str) -> PathBuf {
+        let mut path = self.deploy_dir.clone();
+
+        path.push("bindings");
+        path.push(format!("{profile}.json"));
+
+        path
+    }
+
+    /// writes an empty profile, so switching to it can tell it apart from
+    /// one whose file was deleted
+    pub fn add_profile(&mut self, profile: String) -> Result<()> {
+        let profile = name_check::normalize(&profile);
+
+        name_check::validate_profile_name(&profile, &self.profiles)?;
+
+        let path = self.profile_path(&profile);
+
+        if path.exists() {
+            return Err(ProgramError::ExistingFileAt(path))?;
+        }
+
+        self.write_empty_profile(&profile)?;
+        self.profiles.push(Rc::new(profile));
+
+        Ok(())
+    }
+
+    fn write_empty_profile(&mut self, profile: &str) -> Result<()> {
+        let path = self.profile_path(profile);
+
+        create_dir_all(path.parent().unwrap())?;
+
+        self.file_hashes.write(
+            &path,
+            Profile::default()
+                .to_json(self.disabled_bindings)
+                .as_bytes(),
+        )?;
+
+        self.forget_profiles();
+
+        Ok(())
+    }
+
+    /// copies the current profile, giving back the name it was saved under
+    pub fn duplicate_profile(&mut self, profile: String) -> Result<Rc<String>> {
+        let profile = name_check::normalize(&profile);
+
+        name_check::validate_profile_name(&profile, &self.profiles)?;
+
+        let path = self.profile_path(&profile);
+
+        if path.exists() {
+            return Err(ProgramError::ExistingFileAt(path))?;
+        }
+
+        create_dir_all(path.parent().unwrap())?;
+
+        let data = self.to_profile_data().to_json(self.disabled_bindings);
+
+        self.file_hashes.write(&path, data.as_bytes())?;
+
+        self.forget_profiles();
+
+        let profile = Rc::new(profile);
+
+        self.profile_descriptions
+            .insert(profile.clone(), self.description.clone());
+        self.profiles.push(profile.clone());
+
+        Ok(profile)
+    }
+
+    /// the active profile and what it needs from the rest of the project
+    pub fn export_bundle(&self) -> ProfileBundle<'_> {
+        ProfileBundle {
+            profile: self.to_profile_data(),
+            commands: self.bindings.command_to_bindings.keys().cloned().collect(),
+            streams: self.stream_to_axis.keys().cloned().collect(),
+            constants: Cow::Borrowed(&self.constants),
+        }
+    }
+
+    pub fn export_profile(&self, path: &Path) -> Result<()> {
+        file_hashes::write_file_without_backup(
+            path,
+            serde_json::to_string_pretty(&self.export_bundle())
+                .unwrap()
+                .as_bytes(),
+        )
+        .with_context(|| "failed to write export file")
+    }
+
+    /// regenerates the kotlin constants for every command and stream, returns
+    /// where they went
+    pub fn write_kotlin_keys(&mut self) -> Result<PathBuf> {
+        codegen::write_kotlin_keys(
+            &self.kotlin_keys,
+            &self.project_root(),
+            &self.commands,
+            &self.streams,
+            &mut self.file_hashes,
+        )
+    }
+
+    /// the active profile's bindings as a markdown table at `path`
+    pub fn export_cheat_sheet(&self, path: &Path, arena: &Bump) -> Result<()> {
+        let controllers = (0..self.controllers.len() as u8)
+            .map(|c| (self.controller_name(c), self.controllers[c as usize]))
+            .collect::<Vec<_>>();
+
+        let sheet = export::cheat_sheet(
+            &self.profile,
+            &humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            &self.bindings,
+            &controllers,
+            arena,
+        );
+
+        file_hashes::write_file_without_backup(path, sheet.as_bytes())
+            .with_context(|| "failed to write cheat sheet")
+    }
+
+    /// every binding across every profile as csv at `path`
+    pub fn export_csv(&self, path: &Path, arena: &Bump) -> Result<()> {
+        let profiles = self
+            .enumerate_profiles()
+            .map(|(name, profile)| profile.map(|profile| (name, profile)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let csv = export::bindings_csv(profiles.into_iter(), arena);
+
+        file_hashes::write_file_without_backup(path, csv.as_bytes())
+            .with_context(|| "failed to write csv file")
+    }
+
+    /// adds the bundle's profile under `name`, creating any commands and streams
+    /// this project doesn't have yet. returns warnings about what changed
+    pub fn import_profile(&mut self, name: String, bundle: &ProfileBundle) -> Result<Vec<String>> {
+        let name = name_check::normalize(&name);
+
+        name_check::validate_profile_name(&name, &self.profiles)?;
+
+        let path = self.profile_path(&name);
+
+        if path.exists() {
+            return Err(ProgramError::ExistingFileAt(path))?;
+        }
+
+        create_dir_all(path.parent().unwrap())?;
+
+        self.file_hashes.write(
+            &path,
+            bundle.profile.to_json(self.disabled_bindings).as_bytes(),
+        )?;
+
+        // only once the profile is written, a failed import leaves nothing behind
+        let mut warnings = Vec::new();
+
+        for command in &bundle.commands {
+            if self.commands.insert(command.clone()) {
+                warnings.push(format!("created command {command} for {name}"));
+            }
+        }
+
+        for stream in &bundle.streams {
+            if self.streams.insert(stream.clone()) {
+                warnings.push(format!("created stream {stream} for {name}"));
+            }
+        }
+
+        if *bundle.constants != self.constants {
+            warnings.push(format!(
+                "{name} was exported from a project with different constants"
+            ));
+        }
+
+        self.forget_profiles();
+
+        let name = Rc::new(name);
+
+        self.profile_descriptions
+            .insert(name.clone(), bundle.profile.description.to_string());
+        self.profiles.push(name);
+
+        Ok(warnings)
+    }
+
+    pub fn rename_profile(&mut self, old: Rc<String>, new: Rc<String>) -> Result<()> {
+        let new = Rc::new(name_check::normalize(&new));
+
+        name_check::validate_profile_name(&new, self.profiles.iter().filter(|p| **p != old))?;
+
+        let from = self.profile_path(&old);
+        let to = self.profile_path(&new);
+
+        if to.exists() {
+            return Err(ProgramError::ExistingFileAt(to))?;
+        }
+
+        // a save still on the worker would put the old file back after the move
+        self.finish_io()?;
+
+        let current = self.profile == old;
+
+        if current {
+            self.write_profile_pointer(&new)?;
+        }
+
+        self.forget_profiles();
+
+        if from.exists() {
+            if let Err(err) = rename(&from, &to) {
+                // the pointer goes back to the file that's still there
+                if current {
+                    self.write_profile_pointer(&old)?;
+                }
+
+                return Err(err).with_context(|| "failed to rename profile file");
+            }
+
+            self.file_hashes.forget(&from);
+            self.file_hashes.record_file(&to);
+        }
+
+        for profile in self.profiles.iter_mut() {
+            if *profile == old {
+                *profile = new.clone();
+            }
+        }
+
+        if let Some(description) = self.profile_descriptions.remove(&old) {
+            self.profile_descriptions.insert(new.clone(), description);
+        }
+
+        if let Some(unsaved) = self.unsaved_profiles.remove(&old) {
+            self.unsaved_profiles.insert(new.clone(), unsaved);
+        }
+
+        if current {
+            self.profile = new;
+        }
+
+        Ok(())
+    }
+
+    /// points the project at `profile` for the next time it's opened
+    fn write_profile_pointer(&self, profile: &str) -> Result<()> {
+        file_hashes::write_file_without_backup(&self.deploy_dir.join("profile"), profile.as_bytes())
+            .with_context(|| "failed to write profile file")
+    }
+
+    /// the profile as it is now, which for the active profile is what's in
+    /// memory rather than on disk
+    pub fn profile_data(&self, profile: &str) -> Result<Profile<'static>> {
+        if profile == self.profile.as_str() {
+            Ok(self.to_profile_data().get_owned())
+        } else {
+            self.get_profile(profile)
+        }
+    }
+
+    pub fn merge_conflicts(&self, from: &str, into: &str) -> Result<Vec<MergeConflict>> {
+        let mut into = self.profile_data(into)?;
+
+        Ok(into.merge_from(&self.profile_data(from)?))
+    }
+
+    pub fn merge_profile(&mut self, from: &str, into: &str) -> Result<()> {
+        let source = self.profile_data(from)?;
+
+        let mut profile = self.profile_data(into)?;
+
+        profile.merge_from(&source);
+
+        if into == self.profile.as_str() {
+            self.set_fields_from_profile(profile);
+        } else if self.save_mode == SaveMode::Manual {
+            self.unsaved_profiles
+                .insert(Rc::new(into.to_string()), profile);
+        } else {
+            let path = self.profile_path(into);
+
+            self.check_unchanged(slice::from_ref(&path))?;
+
+            self.file_hashes
+                .write(&path, profile.to_json(self.disabled_bindings).as_bytes())?;
+
+            self.profile_cache.borrow_mut().remove(&into.to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_fields_from_profile(&mut self, profile: Profile<'_>) {
+        self.bindings = profile.command_to_bindings.into_owned().into();
+        self.controller_names = profile.controller_names.into_owned();
+        self.controller_colors = profile.controller_colors.into_owned();
+        self.controllers = profile.controllers.into_owned();
+        self.driver_constants = profile.constants.into_owned();
+        self.stream_to_axis = profile.stream_to_axis.into_owned();
+        self.quarantine = profile.quarantine.into_owned();
+        self.description = profile.description.into_owned();
+        self.last_modified = profile.last_modified;
+        self.intentional = profile.intentional.into_owned();
+        self.binding_notes = profile.binding_notes.into_owned();
+    }
+
+    /// a profile as it is on disk, read once and then kept in
+    /// `profile_cache`. one with edits waiting for a save by hand is what it
+    /// will be once saved
+    pub fn get_profile(&self, profile: &str) -> Result<Profile<'static>> {
+        if let Some((_, unsaved)) = self
+            .unsaved_profiles
+            .iter()
+            .find(|(name, _)| name.as_str() == profile)
+        {
+            return Ok(unsaved.clone());
+        }
+
+        if let Some(cached) = self.profile_cache.borrow().get(&profile.to_string()) {
+            return Ok(cached.clone());
+        }
+
+        let loaded = Profile::get_from(&self.deploy_dir, profile)?.0;
+
+        // a file the worker is still writing could be read from before the
+        // write, that isn't worth remembering
+        if !self.worker.busy() {
+            self.profile_cache
+                .borrow_mut()
+                .insert(Rc::new(profile.to_string()), loaded.clone());
+        }
+
+        Ok(loaded)
+    }
+
+    /// drops every cached profile, for when the files may not match them
+    fn forget_profiles(&self) {
+        self.profile_cache.borrow_mut().clear();
+    }
+
+    /// left for the toasts when a broken file was loaded from its backup
+    fn note_backup(&mut self, path: &Path) {
+        self.load_warnings.push(format!(
+            "{} was broken, loaded the previous version from its backup",
+            path.display()
+        ));
+    }
+
+    /// hands the project and active profile to the worker to write, the sync
+    /// goes out once they're on disk. with a worker thread a failed write
+    /// comes back from `poll_worker` instead
+    pub fn write_out(&mut self, arena: &Bump) -> Result<()> {
+        let mut save_file = self.deploy_dir.clone(); // fix the clones in this function
+
+        save_file.push("bindings.json");
+
+        let mut profile = self.deploy_dir.clone();
+
+        profile.push("bindings");
+        profile.push(bumpalo::format!(in &arena, "{}.json", self.profile).as_str());
+
+        // the other profiles edited since the last save by hand go with it
+        let others = self
+            .unsaved_profiles
+            .iter()
+            .map(|(name, profile)| WriteFile {
+                path: self.profile_path(name),
+                contents: profile.to_json(self.disabled_bindings).into_bytes(),
+                backup: true,
+            })
+            .collect::<Vec<_>>();
+
+        let mut paths = vec![save_file.clone(), profile.clone()];
+
+        paths.extend(others.iter().map(|file| file.path.clone()));
+
+        self.check_unchanged(&paths)?;
+
+        create_dir_all(profile.parent().unwrap())?;
+
+        let generation = self.generation;
+
+        if self.sends() {
+            // if someone else synced a newer generation ours has to go past theirs
+            self.generation = self
+                .generation
+                .max(self.robot_generation.unwrap_or_default())
+                + 1;
+        }
+
+        let save_data = serde_json::to_string_pretty(&self.to_savedata()).unwrap();
+
+        let previous = self
+            .last_modified
+            .replace(humantime::format_rfc3339_seconds(SystemTime::now()).to_string());
+
+        let profile_data = self.to_profile_data().to_json(self.disabled_bindings);
+
+        // checked before touching anything so a full drive leaves the old files
+        // whole instead of truncated
+        if let Err(err) = disk_space::ensure_free(
+            &SystemSpace,
+            &self.deploy_dir,
+            self.min_free_space,
+            (save_data.len()
+                + profile_data.len()
+                + others.iter().map(|file| file.contents.len()).sum::<usize>()) as u64,
+        ) {
+            // nothing was written, so neither was the new generation
+            self.generation = generation;
+            self.last_modified = previous;
+            return Err(err);
+        }
+
+        let mut files = vec![
+            WriteFile {
+                path: save_file,
+                contents: save_data.into_bytes(),
+                backup: true,
+            },
+            WriteFile {
+                path: profile,
+                contents: profile_data.into_bytes(),
+                backup: true,
+            },
+        ];
+
+        files.extend(others);
+
+        // the layout goes on its own, it shouldn't hold back the bindings
+        self.layout_due = true;
+
+        self.save_at = None;
+        self.unsaved = false;
+        self.unsaved_profiles.clear();
+
+        if self.sends() {
+            self.sync_after_save = true;
+        }
+
+        let commit = self
+            .commit_on_save
+            .then(|| (self.deploy_dir.clone(), git::message(&self.pending)));
+
+        self.queue_save(files, self.pending.len(), commit);
+
+        if self.kotlin_keys.on_save {
+            self.write_kotlin_keys()?;
+        }
+
+        self.poll_worker()
+    }
+
+    /// sends `files` to the worker, remembering them as ours straight away so
+    /// the next save doesn't take them for outside changes
+    fn queue_save(
+        &mut self,
+        files: Vec<WriteFile>,
+        pending: usize,
+        commit: Option<(PathBuf, String)>,
+    ) {
+        for file in &files {
+            self.file_hashes.record(&file.path, &file.contents);
+            *self.queued_writes.entry(file.path.clone()).or_default() += 1;
+        }
+
+        self.worker.send(Job::Save {
+            files,
+            pending,
+            commit,
+        });
+    }
+
+    /// takes in whatever the worker finished since the last frame
+    pub fn poll_worker(&mut self) -> Result<()> {
+        while let Some(done) = self.worker.try_recv() {
+            self.apply_done(done)?;
+        }
+
+        Ok(())
+    }
+
+    /// waits for everything sent to the worker to be done, for reading the
+    /// files back or exiting
+    pub fn finish_io(&mut self) -> Result<()> {
+        while let Some(done) = self.worker.recv() {
+            self.apply_done(done)?;
+        }
+
+        Ok(())
+    }
+
+    /// the worker is done with `paths`, whether the writes worked or not
+    fn written(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            if let Some(queued) = self.queued_writes.get_mut(path) {
+                *queued -= 1;
+
+                if *queued == 0 {
+                    self.queued_writes.remove(path);
+                }
+            }
+        }
+    }
+
+    fn apply_done(&mut self, done: Done) -> Result<()> {
+        if let Done::Saved { paths, .. } | Done::Pulled { paths, .. } = &done {
+            self.written(paths);
+        }
+
+        match done {
+            Done::Saved {
+                paths,
+                pending,
+                result,
+                committed,
+            } => {
+                if let Err(err) = result {
+                    // they're only partly written, what's on disk is what the
+                    // next save compares against
+                    for path in &paths {
+                        self.file_hashes.record_file(path);
+                    }
+
+                    self.sync_after_save = false;
+
+                    return Err(err);
+                }
+
+                self.pending.drain(..pending.min(self.pending.len()));
+
+                // the save itself went through, git is only told about it
+                if let Some(Err(err)) = committed {
+                    self.load_warnings
+                        .push(format!("saved, but couldn't commit to git: {err}"));
+                }
+
+                // a later save still on its way sends everything once it lands
+                if self.sync_after_save && !self.worker.busy() {
+                    self.sync_after_save = false;
+                    self.start_sync()?;
+                }
+            }
+            // the edit went through either way, a log that can't be written
+            // shouldn't stop the next one
+            Done::Logged(result) => match result {
+                Ok(()) => self.log_written += 1,
+                Err(err) => self
+                    .load_warnings
+                    .push(format!("couldn't add to the change log: {err}")),
+            },
+            Done::SyncLogged(result) => {
+                if let Err(err) = result {
+                    self.load_warnings.push(err.to_string());
+                }
+            }
+            Done::Pulled { paths, result } => {
+                if let Err(err) = result {
+                    // none of them were replaced
+                    for path in &paths {
+                        self.file_hashes.record_file(path);
+                    }
+
+                    return Err(err);
+                }
+
+                self.reload()?;
+            }
+            Done::Pruned(failures) => self.load_warnings.extend(failures),
+            Done::LayoutSaved(result) => {
+                if let Err(err) = result {
+                    self.load_warnings.push(err.to_string());
+                }
+            }
+            Done::Described(descriptions) => {
+                self.profile_descriptions = descriptions
+                    .into_iter()
+                    .map(|(name, description)| (Rc::new(name), description))
+                    .filter(|(name, _)| *name != self.profile)
+                    .collect();
+
+                self.profile_descriptions
+                    .insert(self.profile.clone(), self.description.clone());
+            }
+            Done::Checked(checked) => {
+                let (on_disk, changed) = checked?;
+
+                let mut added = false;
+
+                for profile in on_disk {
+                    let profile = Rc::new(name_check::normalize(&profile));
+
+                    if !self.profiles.contains(&profile) {
+                        self.file_hashes.record_file(&self.profile_path(&profile));
+                        self.load_warnings
+                            .push(format!("profile {profile} was added outside the app"));
+                        self.profiles.push(profile);
+                        added = true;
+                    }
+                }
+
+                if added {
+                    self.load_descriptions();
+                }
+
+                if added || !changed.is_empty() {
+                    self.forget_profiles();
+                }
+
+                if !changed.is_empty() && self.external_change.is_none() {
+                    self.external_change = Some(changed);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the target being edited, checked and downloaded from
+    pub fn target(&self) -> Option<&SyncTarget> {
+        self.targets.get(self.selected_target)
+    }
+
+    /// the selected target's address
+    pub fn url(&self) -> Option<&str> {
+        self.target().map(|target| target.url.as_str())
+    }
+
+    /// whether saves go out to any target
+    pub fn sends(&self) -> bool {
+        self.syncing && self.targets.iter().any(|target| target.enabled)
+    }
+
+    /// stops the sync to a target that was removed, and keeps the selection
+    /// on one that's still there
+    fn forget_target(&mut self, name: &str) {
+        if let Some(mut sync) = self.syncs.remove(name) {
+            let _ = sync.stop();
+        }
+
+        self.selected_target = self
+            .selected_target
+            .min(self.targets.len().saturating_sub(1));
+    }
+
+    /// sends the saved files to every enabled target. while a sync is still
+    /// going this one waits for it instead, see `pending_sync_due`. nothing is
+    /// sent while syncing is off or a resync is waiting
+    pub fn start_sync(&mut self) -> Result<()> {
+        self.sync_pending = false;
+
+        if self.sends() {
+            // this one replaces the retry, failing again schedules the next
+            self.sync_retry_at = None;
+
+            // killing scp halfway leaves the robot with some old files and
+            // some new, so saves made while it runs go out together after
+            if self.sync_running() {
+                self.sync_pending = true;
+                return Ok(());
+            }
+        }
+
+        // the pending resync sends this too, once the remote settles
+        if !self.syncing || self.resync_at.is_some() {
+            return Ok(());
+        }
+
+        let targets = self
+            .targets
+            .iter()
+            .filter(|target| target.enabled && !target.url.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        let staging = self.stage_sync()?;
+
+        for target in targets {
+            let mut sync = TargetSync {
+                started: Some(Instant::now()),
+                ..Default::default()
+            };
+
+            match self.sync_backend {
+                SyncBackend::Scp => sync.process = Some(self.spawn_scp(&staging, &target)?),
+                SyncBackend::Native => self.spawn_native(&staging, &target, &mut sync)?,
+            }
+
+            // how the last one went stays until this one ends
+            let old = self.syncs.remove(&target.name).unwrap_or_default();
+
+            self.syncs.insert(
+                target.name,
+                TargetSync {
+                    last: old.last,
+                    output: old.output,
+                    ..sync
+                },
+            );
+        }
+
+        self.remote_stale = false;
+
+        Ok(())
+    }
+
+    /// stops the syncs still going and sends the saved files again
+    pub fn restart_sync(&mut self) -> Result<()> {
+        self.stop_sync()?;
+        self.start_sync()
+    }
+
+    /// whether the saves made during the last sync can go out now that it's
+    /// done
+    pub fn pending_sync_due(&self) -> bool {
+        self.sync_pending && !self.sync_running()
+    }
+
+    fn spawn_scp(&self, staging: &Path, target: &SyncTarget) -> Result<Child> {
+        check_ssh_user(&target.user)?;
+
+        let mut c = Command::new("scp");
+
+        // kept for when it fails, scp only says why on stderr
+        let command = c
+            .args(build_sync_invocation(
+                &staging.join(format!("bindings.{}", self.sync_format.extension())),
+                &staging.join("bindings"),
+                &target.url,
+                &target.user,
+                &target.path,
+                None,
+            ))
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        let command = command.creation_flags(0x08000000);
+
+        Ok(command.spawn()?)
+    }
+
+    /// a target may have none of our files, so everything is sent again. it
+    /// waits `RESYNC_DELAY` so typing a url doesn't sync to every prefix of
+    /// it, and while syncing is off it waits for it to be back on
+    fn remote_changed(&mut self) {
+        self.robot_generation = None;
+        self.remote_stale = true;
+
+        if self.syncing
+            && self
+                .targets
+                .iter()
+                .any(|target| target.enabled && !target.url.is_empty())
+        {
+            self.resync_at = Some(Instant::now() + RESYNC_DELAY);
+        }
+    }
+
+    pub fn resync_due(&self, now: Instant) -> bool {
+        self.resync_at.is_some_and(|at| at <= now)
+    }
+
+    /// writes what gets sent to the robot in the sync format and returns the
+    /// directory to send it from. only the profiles themselves go in it, not
+    /// their backups or half written temp files. it always has a bindings
+    /// directory, scp gives up on the whole transfer when one is missing
+    fn stage_sync(&mut self) -> Result<PathBuf> {
+        let staging =
+            std::env::temp_dir().join(format!("bindings-gui-sync-{}", std::process::id()));
+
+        // profiles that were renamed or removed shouldn't be sent again
+        if staging.exists() {
+            remove_dir_all(&staging)?;
+        }
+
+        create_dir_all(staging.join("bindings"))?;
+
+        let extension = self.sync_format.extension();
+
+        let mut files = vec![(
+            self.deploy_dir.join("bindings.json"),
+            staging.join(format!("bindings.{extension}")),
+        )];
+
+        files.extend(
+            self.profiles
+                .iter()
+                .map(|p| {
+                    (
+                        self.profile_path(p),
+                        staging.join("bindings").join(format!("{p}.{extension}")),
+                    )
+                })
+                .filter(|(from, _)| from.exists()),
+        );
+
+        let mut sizes = SyncSizes::default();
+
+        for (from, to) in files {
+            let json = read_to_string(&from).with_context(|| "failed to read file to sync")?;
+
+            let payload = self.sync_format.encode(&json)?;
+
+            sizes.on_disk += json.len() as u64;
+            sizes.synced += payload.len() as u64;
+
+            File::create(&to)
+                .with_context(|| "failed to create file to sync")?
+                .write_all(&payload)?;
+        }
+
+        // pretty files are sent as they are, there's no saving to show
+        self.sync_sizes = (!self.sync_format.is_pretty()).then_some(sizes);
+
+        Ok(staging)
+    }
+
+    #[cfg(feature = "native-ssh")]
+    fn spawn_native(
+        &self,
+        staging: &Path,
+        target: &SyncTarget,
+        sync: &mut TargetSync,
+    ) -> Result<()> {
+        let save_file = format!("bindings.{}", self.sync_format.extension());
+
+        // read now, the next save can stage over them while this is sending
+        let mut files = vec![(save_file.clone(), std::fs::read(staging.join(&save_file))?)];
+
+        for entry in std::fs::read_dir(staging.join("bindings"))? {
+            let entry = entry?;
+
+            files.push((
+                format!("bindings/{}", entry.file_name().to_string_lossy()),
+                std::fs::read(entry.path())?,
+            ));
+        }
+
+        sync.progress = Some((0, files.len()));
+        sync.native = Some(crate::native_sync::NativeSync::spawn(
+            crate::native_sync::Upload {
+                url: target.url.clone(),
+                user: target.user.clone(),
+                password: self.sync_password.clone(),
+                remote_path: target.path.clone(),
+                files,
+            },
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "native-ssh"))]
+    fn spawn_native(&self, _: &Path, _: &SyncTarget, _: &mut TargetSync) -> Result<()> {
+        anyhow::bail!("this build can't sync without scp, switch back to scp in the syncing tab")
+    }
+
+    /// whether a sync is sending files right now, to any target
+    pub fn sync_running(&self) -> bool {
+        self.syncs.values().any(TargetSync::running)
+    }
+
+    /// the syncs that ended since the last poll, by target
+    pub fn poll_sync(&mut self, now: SystemTime) -> Result<Vec<SyncEnded>> {
+        let mut ended = Vec::new();
+
+        for name in self.syncs.keys().cloned().collect::<Vec<_>>() {
+            let Some((result, exit_code)) = self.syncs.get_mut(&name).unwrap().poll()? else {
+                continue;
+            };
+
+            let success = result.is_ok();
+            let error = result
+                .as_ref()
+                .err()
+                .map(|output| sync_log::error_head(output))
+                .unwrap_or_default();
+            let took = self.sync_finished(&name, result, now);
+            let url = self
+                .targets
+                .iter()
+                .find(|target| target.name == name)
+                .map(|target| target.url.clone())
+                .unwrap_or_default();
+
+            self.log_sync(sync_log::Attempt {
+                at: now,
+                target: name.clone(),
+                url: url.clone(),
+                took,
+                success,
+                exit_code,
+                error,
+            });
+
+            ended.push(SyncEnded {
+                target: name,
+                url,
+                success,
+                took,
+            });
+        }
+
+        Ok(ended)
+    }
+
+    /// keeps `attempt` for the sync log tab, and adds it to sync.log when
+    /// that's on
+    pub fn log_sync(&mut self, attempt: sync_log::Attempt) {
+        if self.sync_log_file && !self.deploy_dir.as_os_str().is_empty() {
+            self.worker.send(Job::SyncLog {
+                deploy_dir: self.deploy_dir.clone(),
+                attempt: attempt.clone(),
+            });
+        }
+
+        self.sync_log.push_back(attempt);
+
+        if self.sync_log.len() > sync_log::KEPT {
+            self.sync_log.pop_front();
+        }
+    }
+
+    /// records how the sync to `target` ended, with how long it took. `result`
+    /// has what went wrong when it failed. a failed one is tried again after
+    /// the next of `SYNC_RETRY_DELAYS`
+    pub fn sync_finished(
+        &mut self,
+        target: &str,
+        result: Result<(), String>,
+        now: SystemTime,
+    ) -> Option<Duration> {
+        let success = result.is_ok();
+        let sync = self.syncs.entry(target.to_string()).or_default();
+
+        sync.process = None;
+        sync.last = Some((now, success));
+        sync.output = result.err().map(|output| output.trim().to_string());
+
+        let took = sync.started.take().map(|started| started.elapsed());
+
+        if success {
+            // another target can still be waiting on its retry
+            if self
+                .syncs
+                .values()
+                .all(|sync| sync.last.is_none_or(|(_, ok)| ok))
+            {
+                self.cancel_sync_retry();
+            }
+        } else if self.sends() && self.sync_retry_at.is_none() {
+            let delay = SYNC_RETRY_DELAYS[self.sync_retries.min(SYNC_RETRY_DELAYS.len() - 1)];
+
+            self.sync_retry_at = Some(Instant::now() + delay);
+            self.sync_retries += 1;
+        }
+
+        took
+    }
+
+    /// the toast for a failed sync, with the start of what scp said
+    pub fn sync_failure(&self, target: &str) -> String {
+        let Some(output) = self
+            .syncs
+            .get(target)
+            .and_then(|sync| sync.output.as_deref())
+            .filter(|o| !o.is_empty())
+        else {
+            return format!("failed to sync to {target}");
+        };
+
+        let mut shown = output
+            .lines()
+            .take(SYNC_ERROR_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if shown.chars().count() > SYNC_ERROR_CHARS {
+            shown = shown.chars().take(SYNC_ERROR_CHARS).collect();
+            shown.push('…');
+        } else if output.lines().count() > SYNC_ERROR_LINES {
+            shown.push_str("\n…");
+        }
+
+        format!("failed to sync to {target}: {shown}")
+    }
+
+    /// "last synced 2 min ago" or "last sync failed", none before the first
+    /// sync to `target` ends
+    pub fn last_sync_text(&self, target: &str, now: SystemTime) -> Option<String> {
+        let (at, success) = self.syncs.get(target)?.last?;
+
+        if !success {
+            return Some("last sync failed".to_string());
+        }
+
+        let seconds = now.duration_since(at).unwrap_or_default().as_secs();
+
+        Some(match seconds {
+            0..60 => "last synced just now".to_string(),
+            60..3600 => format!("last synced {} min ago", seconds / 60),
+            _ => format!("last synced {} h ago", seconds / 3600),
+        })
+    }
+
+    /// forgets the failed syncs, the next failure waits the shortest delay
+    /// again
+    pub fn cancel_sync_retry(&mut self) {
+        self.sync_retry_at = None;
+        self.sync_retries = 0;
+    }
+
+    pub fn sync_retry_due(&self, now: Instant) -> bool {
+        self.sync_retry_at.is_some_and(|at| at <= now)
+    }
+
+    /// gives up on the running syncs and the one waiting on them, returns
+    /// whether any were running
+    pub fn cancel_sync(&mut self) -> Result<bool> {
+        self.sync_sizes = None;
+        self.sync_pending = false;
+        self.cancel_sync_retry();
+
+        self.stop_sync()
+    }
+
+    /// kills the running syncs and waits for them to exit. returns whether
+    /// any were actually interrupted
+    pub fn stop_sync(&mut self) -> Result<bool> {
+        let mut stopped = false;
+
+        for sync in self.syncs.values_mut() {
+            stopped |= sync.stop()?;
+        }
+
+        Ok(stopped)
+    }
+
+    /// asks the robot which generation of bindings.json it has, the answer is
+    /// picked up by `poll_generation_check`
+    pub fn check_robot_generation(&mut self) -> Result<()> {
+        let Some(SyncTarget {
+            url, user, path, ..
+        }) = self.target().cloned()
+        else {
+            return Ok(());
+        };
+
+        check_ssh_user(&user)?;
+
+        if let Some(child) = &mut self.generation_check {
+            child.kill()?
+        }
+
+        let mut c = Command::new("ssh");
+
+        let command = c
+            .arg("-o")
+            .arg("BatchMode=yes")
+            .arg("--")
+            .arg(format!("{user}@{url}"))
+            .arg(format!(
+                "{} -o '\"generation\": *[0-9]*' {}",
+                if self.sync_format.gzip {
+                    "zgrep"
+                } else {
+                    "grep"
+                },
+                quote_remote_path(&format!("{path}bindings.{}", self.sync_format.extension()))
+            ))
+            .stdout(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        let command = command.creation_flags(0x08000000);
+
+        self.generation_check = Some(command.spawn()?);
+
+        Ok(())
+    }
+
+    pub fn poll_generation_check(&mut self) -> Result<()> {
+        let Some(child) = &mut self.generation_check else {
+            return Ok(());
+        };
+
+        let Some(status) = child.try_wait()? else {
+            return Ok(());
+        };
+
+        let mut output = String::new();
+
+        if let Some(stdout) = &mut child.stdout {
+            stdout.read_to_string(&mut output)?;
+        }
+
+        self.generation_check = None;
+
+        self.robot_generation = match status.code() {
+            // grep found the file but no generation, so it was written before they existed
+            Some(1) => Some(0),
+            _ if status.success() => {
+                let digits: String = output.chars().filter(|c| c.is_ascii_digit()).collect();
+
+                Some(
+                    digits
+                        .parse()
+                        .with_context(|| "robot sent an invalid generation")?,
+                )
+            }
+            _ => {
+                self.robot_generation = None;
+                return Err(ProgramError::GenerationCheckFailed)?;
+            }
+        };
+
+        Ok(())
+    }
+
+    /// downloads the robot's deploy files to compare with these ones, picked
+    /// up by `poll_pull`
+    pub fn start_pull(&mut self) -> Result<()> {
+        let Some(SyncTarget {
+            url, user, path, ..
+        }) = self.target().cloned()
+        else {
+            return Ok(());
+        };
+
+        if let Some(child) = &mut self.pull_process {
+            child.kill()?
+        }
+
+        check_ssh_user(&user)?;
+
+        self.discard_pull();
+
+        let dir = robot_pull::pull_dir();
+
+        create_dir_all(&dir)?;
+
+        let mut c = Command::new("scp");
+
+        let command = c
+            .args(robot_pull::build_pull_invocation(&url, &user, &path, &dir))
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        let command = command.creation_flags(0x08000000);
+
+        self.pull_process = Some(command.spawn()?);
+
+        Ok(())
+    }
+
+    /// reads the download once scp is done, returns whether `pulled` was
+    /// just filled in
+    pub fn poll_pull(&mut self) -> Result<bool> {
+        let Some(child) = &mut self.pull_process else {
+            return Ok(false);
+        };
+
+        let Some(status) = child.try_wait()? else {
+            return Ok(false);
+        };
+
+        let mut output = String::new();
+
+        if let Some(stderr) = &mut child.stderr {
+            stderr.read_to_string(&mut output)?;
+        }
+
+        self.pull_process = None;
+
+        if !status.success() {
+            self.discard_pull();
+
+            anyhow::bail!(
+                "failed to download from the robot: {}",
+                output.lines().last().unwrap_or_default()
+            );
+        }
+
+        if let Err(err) = self.read_pulled(robot_pull::pull_dir()) {
+            self.discard_pull();
+
+            return Err(err);
+        }
+
+        Ok(true)
+    }
+
+    /// loads what was downloaded to `dir` and lists how it differs from the
+    /// files here
+    pub fn read_pulled(&mut self, dir: PathBuf) -> Result<()> {
+        self.finish_io()?;
+
+        let pulled = robot_pull::read_pulled(dir)?;
+        let theirs = &pulled.save_data;
+
+        let mut changes = Vec::new();
+
+        for (what, ours, theirs) in [
+            ("command", &self.commands, &*theirs.commands),
+            ("stream", &self.streams, &*theirs.streams),
+        ] {
+            changes.extend(
+                theirs
+                    .difference(ours)
+                    .map(|name| Change::Added(format!("{what} {name}"))),
+            );
+            changes.extend(
+                ours.difference(theirs)
+                    .map(|name| Change::Removed(format!("{what} {name}"))),
+            );
+        }
+
+        for (name, profile) in &pulled.profiles {
+            if !self.profiles.contains(name) {
+                changes.push(Change::Added(format!("profile {name}")));
+                continue;
+            }
+
+            let ours = self.get_profile(name)?;
+
+            // parsed on both sides so formatting doesn't count
+            if serde_json::to_value(&ours)? != serde_json::to_value(profile)? {
+                changes.push(Change::Changed(format!("profile {name}")));
+            }
+        }
+
+        changes.extend(
+            self.profiles
+                .iter()
+                .filter(|name| !pulled.profiles.iter().any(|(theirs, _)| theirs == *name))
+                .map(|name| Change::Changed(format!("profile {name} is only here, it's kept"))),
+        );
+
+        if self.unsaved {
+            changes.push(Change::Removed("edits that haven't been saved".to_string()));
+        }
+
+        if theirs.generation != self.generation {
+            changes.push(Change::Changed(format!(
+                "the robot is at generation {}, this laptop at {}",
+                theirs.generation, self.generation
+            )));
+        }
+
+        self.pulled = Some((pulled, changes));
+
+        Ok(())
+    }
+
+    /// sends the tabs open in the window to the worker to write next to the
+    /// project. it's only the window, a layout that can't be written is
+    /// warned about and nothing else
+    pub fn save_layout(&mut self, layout: &Layout) {
+        self.layout_due = false;
+
+        match layout::to_json(layout) {
+            Ok(contents) => self.worker.send(Job::Layout {
+                path: layout::path(&self.deploy_dir),
+                contents: contents.into_bytes(),
+            }),
+            Err(err) => self.load_warnings.push(err.to_string()),
+        }
+    }
+
+    /// prunes leftover files for at most `OPEN_BUDGET`, the worker deletes
+    /// whatever is left
+    pub fn prune_on_open(&mut self) -> Result<()> {
+        let report = maintenance::prune(
+            &self.deploy_dir,
+            self.retention,
+            SystemTime::now(),
+            Some(Instant::now() + maintenance::OPEN_BUDGET),
+        )?;
+
+        if !report.left.is_empty() {
+            self.worker.send(Job::Prune {
+                deploy_dir: self.deploy_dir.clone(),
+                paths: report.left,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// sends the download to the worker to copy over the local files, they're
+    /// loaded once it's done. they're all serialized before anything is
+    /// written, and each keeps its .bak
+    pub fn apply_pull(&mut self) -> Result<()> {
+        let Some((pulled, _)) = self.pulled.take() else {
+            return Ok(());
+        };
+
+        let disabled = pulled.save_data.disabled_bindings;
+
+        let mut files = vec![(
+            self.deploy_dir.join("bindings.json"),
+            serde_json::to_string_pretty(&pulled.save_data)?,
+        )];
+
+        files.extend(
+            pulled
+                .profiles
+                .iter()
+                .map(|(name, profile)| (self.profile_path(name), profile.to_json(disabled))),
+        );
+
+        disk_space::ensure_free(
+            &SystemSpace,
+            &self.deploy_dir,
+            self.min_free_space,
+            files.iter().map(|(_, data)| data.len() as u64).sum(),
+        )?;
+
+        // remembered as ours straight away, like a save
+        for (path, data) in &files {
+            self.file_hashes.record(path, data.as_bytes());
+            *self.queued_writes.entry(path.clone()).or_default() += 1;
+        }
+
+        self.worker.send(Job::ApplyPull {
+            files: files
+                .into_iter()
+                .map(|(path, data)| (path, data.into_bytes()))
+                .collect(),
+            pull_dir: robot_pull::pull_dir(),
+        });
+
+        Ok(())
+    }
+
+    /// throws away a download, nothing local was touched
+    pub fn discard_pull(&mut self) {
+        self.pulled = None;
+
+        let _ = remove_dir_all(robot_pull::pull_dir());
+    }
+
+    /// logs in to the robot the way syncs do and has it answer, the result
+    /// is picked up by `poll_connection_test`
+    pub fn test_connection(&mut self) -> Result<()> {
+        let Some(SyncTarget { url, user, .. }) = self.target().cloned() else {
+            return Ok(());
+        };
+
+        check_ssh_user(&user)?;
+
+        if let Some((child, _)) = &mut self.connection_test {
+            child.kill()?
+        }
+
+        let mut c = Command::new("ssh");
+
+        let command = c
+            .arg("-o")
+            .arg("BatchMode=yes")
+            .arg("-o")
+            .arg(format!("ConnectTimeout={}", CONNECTION_TIMEOUT.as_secs()))
+            .arg("--")
+            .arg(format!("{user}@{url}"))
+            .arg("echo ok")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        let command = command.creation_flags(0x08000000);
+
+        self.connection_test = Some((command.spawn()?, Instant::now()));
+
+        Ok(())
+    }
+
+    /// how the connection test went once it's over, none while it's still
+    /// going or when there isn't one
+    pub fn poll_connection_test(&mut self) -> Result<Option<ConnectionStatus>> {
+        let Some((child, started)) = &mut self.connection_test else {
+            return Ok(None);
+        };
+
+        let Some(status) = child.try_wait()? else {
+            return Ok(None);
+        };
+
+        let took = started.elapsed();
+
+        let mut answer = String::new();
+        let mut reason = String::new();
+
+        if let Some(stdout) = &mut child.stdout {
+            stdout.read_to_string(&mut answer)?;
+        }
+
+        if let Some(stderr) = &mut child.stderr {
+            stderr.read_to_string(&mut reason)?;
+        }
+
+        self.connection_test = None;
+
+        let result = if status.success() && answer.trim() == "ok" {
+            ConnectionStatus::Reachable(took)
+        } else {
+            ConnectionStatus::Unreachable(reason.lines().last().unwrap_or_default().to_string())
+        };
+
+        self.connection_status = Some(result.clone());
+
+        Ok(Some(result))
+    }
+
+    pub fn generation_status(&self) -> String {
+        match self.robot_generation {
+            None if self.generation_check.is_some() => "checking robot...".to_string(),
+            None => "robot generation unknown".to_string(),
+            Some(robot) if robot < self.generation => {
+                format!("robot is {} syncs behind", self.generation - robot)
+            }
+            Some(robot) if robot > self.generation => {
+                "robot has newer generation — someone else synced".to_string()
+            }
+            Some(_) => "robot is up to date".to_string(),
+        }
+    }
+
+    fn to_profile_data(&self) -> Profile<'_> {
+        Profile {
+            command_to_bindings: Cow::Borrowed(&self.bindings.command_to_bindings),
+            controllers: Cow::Borrowed(&self.controllers),
+            controller_names: Cow::Borrowed(&self.controller_names),
+            controller_colors: Cow::Borrowed(&self.controller_colors),
+            constants: Cow::Borrowed(&self.driver_constants),
+            stream_to_axis: Cow::Borrowed(&self.stream_to_axis),
+            quarantine: Cow::Borrowed(&self.quarantine),
+            description: Cow::Borrowed(&self.description),
+            last_modified: self.last_modified.clone(),
+            intentional: Cow::Borrowed(&self.intentional),
+            binding_notes: Cow::Borrowed(&self.binding_notes),
+            disabled_bindings: Cow::Owned(BTreeMap::new()),
+        }
+    }
+
+    /// the constants the robot ends up with under the active profile, the
+    /// same ones the driver constants tab shows
+    pub fn resolved_constants(&self) -> Constants {
+        self.constants.resolved(&self.driver_constants)
+    }
+
+    fn to_savedata(&self) -> SaveData<'_> {
+        SaveData {
+            commands: Cow::Borrowed(&self.commands),
+            constants: Cow::Borrowed(&self.constants),
+            resolved_constants: Cow::Owned(self.resolved_constants()),
+            streams: Cow::Borrowed(&self.streams),
+            generation: self.generation,
+            sync_format: self.sync_format,
+            min_free_space: self.min_free_space,
+            retention: self.retention,
+            required_commands: Cow::Borrowed(&self.required_commands),
+            command_cooldowns: Cow::Borrowed(&self.command_cooldowns),
+            command_meta: Cow::Borrowed(&self.command_meta),
+            required_streams: Cow::Borrowed(&self.required_streams),
+            kotlin_keys: Cow::Borrowed(&self.kotlin_keys),
+            disabled_bindings: self.disabled_bindings,
+            save_mode: self.save_mode,
+            commit_on_save: self.commit_on_save,
+            sync_log_file: self.sync_log_file,
+            url: None,
+            ssh_user: default_ssh_user(),
+            remote_path: default_remote_path(),
+            targets: Cow::Borrowed(&self.targets),
+            team_number: self.team_number,
+            sync_backend: self.sync_backend,
+        }
+    }
+
+    pub fn map_profiles<F>(&mut self, mut f: F, arena: &Bump) -> Result<()>
+    where
+        F: FnMut(&Rc<String>, &mut Profile),
+    {
+        let others = self
+            .profiles
+            .iter()
+            .filter(|ele| ele.as_str() != self.profile.as_str())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // every file is checked before any is written so a conflict can't leave
+        // the change applied to only some of the profiles
+        let mut targets = others
+            .iter()
+            .map(|ele| self.profile_path(ele))
+            .collect::<Vec<_>>();
+
+        targets.push(self.deploy_dir.join("bindings.json"));
+        targets.push(self.profile_path(&self.profile));
+
+        // the profiles are read back, saves still on their way would be lost
+        self.finish_io()?;
+
+        self.check_unchanged(&targets)?;
+
+        let mut files = Vec::new();
+        let mut edited = Vec::new();
+
+        for (ele, path) in others.iter().zip(targets) {
+            let mut profile = self
+                .get_profile(ele.as_str())
+                .with_context(|| "failed to get profile")?;
+
+            f(ele, &mut profile);
+
+            match self.save_mode {
+                SaveMode::Auto => files.push(WriteFile {
+                    path,
+                    contents: profile.to_json(self.disabled_bindings).into_bytes(),
+                    backup: true,
+                }),
+                SaveMode::Manual => edited.push((ele.clone(), profile)),
+            }
+        }
+
+        // committed with the write_out below. by hand they wait for the save
+        // with the rest, a discard mustn't leave them pointing at commands
+        // bindings.json doesn't have
+        self.queue_save(files, 0, None);
+        self.unsaved_profiles.extend(edited);
+
+        self.forget_profiles();
+
+        let current = self.profile.clone();
+
+        let mut p = self.to_profile_data();
+
+        f(&current, &mut p);
+
+        let p = p.get_owned();
+
+        self.set_fields_from_profile(p);
+
+        // the open profile and bindings.json wait for a save by hand like any
+        // other edit, they'd take the unsaved ones with them
+        match self.save_mode {
+            SaveMode::Auto => self.write_out(arena)?,
+            SaveMode::Manual => self.unsaved = true,
+        }
+
+        Ok(())
+    }
+
+    /// fails without writing anything when one of `paths` changed on disk since
+    /// we last read or wrote it, leaving the files for the user to sort out
+    fn check_unchanged(&mut self, paths: &[PathBuf]) -> Result<()> {
+        let changed = paths
+            .iter()
+            .filter(|path| {
+                !self.queued_writes.contains_key(*path) && self.file_hashes.changed_externally(path)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !changed.is_empty() {
+            self.forget_profiles();
+
+            self.external_change = Some(changed.clone());
+
+            Err(ProgramError::ChangedOnDisk(changed))?;
+        }
+
+        Ok(())
+    }
+
+    /// looks for project files changed outside the app, at most once every
+    /// `DISK_CHECK_INTERVAL` and never while a write is on its way. changed
+    /// ones are put up for the user to pick whose version wins before anything
+    /// overwrites them, profiles that appeared are picked up straight away
+    /// since there's nothing to lose
+    pub fn check_disk(&mut self, now: Instant) -> Result<()> {
+        if self.external_change.is_some()
+            || self.worker.busy()
+            || self
+                .disk_checked_at
+                .is_some_and(|at| now < at + DISK_CHECK_INTERVAL)
+        {
+            return Ok(());
+        }
+
+        self.disk_checked_at = Some(now);
+
+        let paths = [self.deploy_dir.join("bindings.json")]
+            .into_iter()
+            .chain(self.profiles.iter().map(|p| self.profile_path(p)))
+            .collect();
+
+        self.worker.send(Job::CheckDisk {
+            deploy_dir: self.deploy_dir.clone(),
+            known: self.file_hashes.clone(),
+            paths,
+        });
+
+        self.poll_worker()
+    }
+
+    /// the robot project the deploy directory is in
+    pub fn project_root(&self) -> PathBuf {
+        self.deploy_dir
+            .ancestors()
+            .nth(self.deploy_subpath.components().count())
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    /// which project and profile the window has open, starred while there are
+    /// edits that haven't been written or sent yet
+    pub fn window_title(&self) -> String {
+        let project = self.project_root();
+        let project = project
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        let dirty = self.unsaved
+            || self.save_at.is_some()
+            || !self.pending.is_empty()
+            || self.worker.busy();
+
+        format!(
+            "{}Bindings — {project} [{}]",
+            if dirty { "* " } else { "" },
+            self.profile
+        )
+    }
+
+    /// throws away what's in memory for what's on disk, keeping only what
+    /// belongs to this session
+    pub fn reload(&mut self) -> Result<()> {
+        self.finish_io()?;
+
+        let fresh = Self::from_directory(self.project_root(), &self.deploy_subpath)?;
+
+        *self = Self {
+            syncing: self.syncing,
+            remote_stale: self.remote_stale,
+            resync_at: self.resync_at,
+            device_caps: self.device_caps,
+            #[cfg(feature = "gamepad")]
+            gamepads: mem::take(&mut self.gamepads),
+            selected_target: self.selected_target,
+            syncs: mem::take(&mut self.syncs),
+            sync_pending: self.sync_pending,
+            sync_password: self.sync_password.take(),
+            sync_sizes: self.sync_sizes.take(),
+            sync_retry_at: self.sync_retry_at,
+            sync_retries: self.sync_retries,
+            sync_log: mem::take(&mut self.sync_log),
+            robot_generation: self.robot_generation,
+            generation_check: self.generation_check.take(),
+            connection_test: self.connection_test.take(),
+            connection_status: self.connection_status.take(),
+            pull_process: self.pull_process.take(),
+            command_usage: mem::take(&mut self.command_usage),
+            usage_clock: self.usage_clock,
+            layout_due: self.layout_due,
+            worker: mem::take(&mut self.worker),
+            log_written: self.log_written,
+            errors_shown: self.errors_shown,
+            changes_applied: self.changes_applied,
+            reloads: self.reloads + 1,
+            ..fresh
+        };
+
+        Ok(())
+    }
+
+    /// saves the on disk version of the active profile next to it so it can be
+    /// merged by hand, returns its name or none when the profile wasn't what
+    /// changed
+    pub fn copy_theirs(&mut self) -> Result<Option<Rc<String>>> {
+        let path = self.profile_path(&self.profile);
+
+        if !self
+            .external_change
+            .iter()
+            .flatten()
+            .any(|changed| *changed == path)
+        {
+            return Ok(None);
+        }
+
+        let theirs = (1..)
+            .map(|n| match n {
+                1 => format!("{}-theirs", self.profile),
+                n => format!("{}-theirs-{n}", self.profile),
+            })
+            .find(|name| {
+                !name_check::name_taken(&self.profiles, name) && !self.profile_path(name).exists()
+            })
+            .unwrap();
+
+        let data = read_to_string(&path)?;
+        let profile = Profile::from_str_lenient(&data)?;
+
+        let to = self.profile_path(&theirs);
+
+        self.file_hashes.write(&to, data.as_bytes())?;
+
+        self.forget_profiles();
+
+        let theirs = Rc::new(theirs);
+
+        self.profile_descriptions
+            .insert(theirs.clone(), profile.description.into_owned());
+        self.profiles.push(theirs.clone());
+
+        Ok(Some(theirs))
+    }
+
+    pub fn enumerate_profiles(&self) -> impl Iterator<Item = (Rc<String>, Result<Profile<'_>>)> {
+        self.profiles
+            .iter()
+            .filter(|ele| ele.as_str() != self.profile.as_str())
+            .map(|profile| (profile.clone(), self.get_profile(profile.as_str())))
+            .chain([(self.profile.clone(), Ok(self.to_profile_data()))])
+    }
+
+    fn profiles_where(&self, f: impl Fn(&Profile) -> bool) -> Result<Vec<Rc<String>>> {
+        let mut using = Vec::new();
+
+        for (name, profile) in self.enumerate_profiles() {
+            if f(&profile?) {
+                using.push(name);
+            }
+        }
+
+        Ok(using)
+    }
+
+    /// the profiles that still bind `command`
+    pub fn is_used(&self, command: &Rc<String>) -> Result<Vec<Rc<String>>> {
+        self.profiles_where(|profile| profile.command_to_bindings.contains_key(command))
+    }
+
+    /// the profiles that still map `stream` to an axis
+    pub fn is_stream_used(&self, stream: &Rc<String>) -> Result<Vec<Rc<String>>> {
+        self.profiles_where(|profile| profile.stream_to_axis.contains_key(stream))
+    }
+
+    /// every binding, in every profile, that can't fire with that profile's
+    /// controllers
+    pub fn dead_bindings(&self) -> Result<Vec<DeadBinding>> {
+        let mut dead = Vec::new();
+
+        for (name, profile) in self.enumerate_profiles() {
+            dead.extend(profile?.dead_bindings(&name));
+        }
+
+        Ok(dead)
+    }
+
+    /// names the profiles for an error, marking the active one
+    pub fn describe_profiles(&self, profiles: &[Rc<String>]) -> String {
+        profiles
+            .iter()
+            .map(|p| {
+                if *p == self.profile {
+                    format!("{p} (current)")
+                } else {
+                    p.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn from_bindings(
+        bindings: SaveData,
+        profile: Profile,
+        profiles: Vec<Rc<String>>,
+        profile_name: String,
+        path: PathBuf,
+    ) -> Self {
+        Self {
+            targets: bindings.sync_targets(),
+            selected_target: 0,
+            commands: bindings.commands.into_owned(),
+            bindings: profile.command_to_bindings.into_owned().into(),
+            controllers: profile.controllers.into_owned(),
+            controller_names: profile.controller_names.into_owned(),
+            controller_colors: profile.controller_colors.into_owned(),
+            device_caps: Default::default(),
+            #[cfg(feature = "gamepad")]
+            gamepads: Default::default(),
+            syncing: true,
+            remote_stale: false,
+            resync_at: None,
+            save_at: None,
+            unsaved: false,
+            unsaved_profiles: BTreeMap::new(),
+            syncs: BTreeMap::new(),
+            sync_pending: false,
+            sync_password: None,
+            sync_format: bindings.sync_format,
+            min_free_space: bindings.min_free_space,
+            retention: bindings.retention,
+            sync_sizes: None,
+            sync_retry_at: None,
+            sync_retries: 0,
+            sync_log: VecDeque::new(),
+            generation: bindings.generation,
+            robot_generation: None,
+            generation_check: None,
+            connection_test: None,
+            connection_status: None,
+            pull_process: None,
+            pulled: None,
+            deploy_dir: path,
+            deploy_subpath: DEFAULT_DEPLOY_SUBPATH.into(),
+            profile: Rc::new(profile_name),
+            profiles,
+            description: profile.description.into_owned(),
+            last_modified: profile.last_modified,
+            intentional: profile.intentional.into_owned(),
+            binding_notes: profile.binding_notes.into_owned(),
+            profile_descriptions: Default::default(),
+            constants: bindings.constants.into_owned(),
+            driver_constants: profile.constants.into_owned(),
+            stream_to_axis: profile.stream_to_axis.into_owned(),
+            streams: bindings.streams.into_owned(),
+            required_commands: bindings.required_commands.into_owned(),
+            command_cooldowns: bindings.command_cooldowns.into_owned(),
+            command_meta: bindings.command_meta.into_owned(),
+            required_streams: bindings.required_streams.into_owned(),
+            kotlin_keys: bindings.kotlin_keys.into_owned(),
+            disabled_bindings: bindings.disabled_bindings,
+            save_mode: bindings.save_mode,
+            commit_on_save: bindings.commit_on_save,
+            sync_log_file: bindings.sync_log_file,
+            team_number: bindings.team_number,
+            sync_backend: bindings.sync_backend,
+            readiness: None,
+            show_readiness: false,
+            show_quarantine: !profile.quarantine.is_empty(),
+            quarantine: profile.quarantine.into_owned(),
+            load_warnings: Vec::new(),
+            missing_profile: None,
+            unnormalized_profiles: Vec::new(),
+            unsaved_switch: None,
+            pending: Vec::new(),
+            ui_meta: Default::default(),
+            file_hashes: Default::default(),
+            external_change: None,
+            broken_profile: None,
+            worker: Default::default(),
+            queued_writes: BTreeMap::new(),
+            log_written: 0,
+            errors_shown: 0,
+            changes_applied: 0,
+            reloads: 0,
+            profile_cache: Default::default(),
+            sync_after_save: false,
+            disk_checked_at: None,
+            layout_due: false,
+            command_usage: Default::default(),
+            usage_clock: 0,
+        }
+    }
+
+    /// opens the project at `path` with its deploy files in `deploy_subpath`,
+    /// which is `DEFAULT_DEPLOY_SUBPATH` unless the project says otherwise
+    pub fn from_directory(mut path: PathBuf, deploy_subpath: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            return Err(ProgramError::NotDirectory(path))?;
+        }
+
+        if deploy_subpath.as_os_str().is_empty()
+            || deploy_subpath
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(ProgramError::DeployOutsideProject(
+                deploy_subpath.to_owned(),
+            ))?;
+        }
+
+        path.push(deploy_subpath);
+
+        create_dir_all(&path)?;
+
+        path.push("profile");
+
+        if path.is_dir() {
+            // return is here just to convice the borrow checker that this path never
+            // continues executing the function
+            return Err(ProgramError::ExistingDirectoryAt(path))?;
+        }
+
+        // only a missing pointer means a new project, anything else reading it
+        // has to stop here or the profile in use would quietly become default
+        let profile_name = match read_to_string(&path) {
+            Ok(a) => a,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let mut file = File::create_new(&path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+
+                file.write_all("default".as_bytes())?;
+
+                "default".to_string()
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+
+        path.pop();
+
+        let (bindings, bindings_from_backup) = match SaveData::from_directory(&path)? {
+            Some(a) => a,
+            None => {
+                let profile_name = Rc::new(name_check::normalize(&profile_name));
+                return Ok(Self {
+                    deploy_dir: path,
+                    deploy_subpath: deploy_subpath.to_owned(),
+                    profile: profile_name.clone(),
+                    profiles: vec![profile_name],
+                    ..Default::default()
+                });
+            }
+        };
+
+        let mut profiles = Profile::get_profiles(&path)?;
+
+        // a pointer and a file written before names were normalized can differ
+        // in form, the file is what gets loaded and renaming it once the user
+        // agrees fixes both of them
+        let profile_name = profiles
+            .iter()
+            .find(|p| name_check::normalize(p) == name_check::normalize(&profile_name))
+            .map(|p| p.to_string())
+            .unwrap_or(profile_name);
+
+        if !profiles
+            .iter()
+            .map(|s| s.as_str())
+            .any(|s| s == profile_name.as_str())
+        {
+            profiles.push(Rc::new(profile_name.clone()));
+        }
+
+        let (profile, profile_from_backup) = Profile::get_from(&path, &profile_name)?;
+
+        let mut state = Self::from_bindings(bindings, profile, profiles, profile_name, path);
+
+        state.deploy_subpath = deploy_subpath.to_owned();
+
+        if bindings_from_backup {
+            state.note_backup(&state.deploy_dir.join("bindings.json"));
+        }
+
+        if profile_from_backup {
+            state.note_backup(&state.profile_path(&state.profile));
+        }
+
+        match UiMeta::load(&state.project_root()) {
+            Ok(meta) => state.ui_meta = meta,
+            Err(err) => state.load_warnings.push(err.to_string()),
+        }
+
+        state.record_files();
+        state.normalize_names();
+        state.drop_invalid_cooldowns();
+        state.load_descriptions();
+
+        Ok(state)
+    }
+
+    fn save_ui_meta(&mut self, arena: &Bump, notifier: &mut dyn Notifier) {
+        if let Err(err) = self.ui_meta.save(&self.project_root()) {
+            self.handle_event(GlobalEvents::DisplayError(err.to_string()), arena, notifier);
+        }
+    }
+
+    /// remembers every project file as it is now so later saves can tell if
+    /// anything else touched them
+    pub fn record_files(&mut self) {
+        self.file_hashes
+            .record_file(&self.deploy_dir.join("bindings.json"));
+
+        for profile in &self.profiles {
+            self.file_hashes.record_file(&self.profile_path(profile));
+        }
+    }
+
+    /// has the worker read the description out of every profile, a profile
+    /// that fails to load just shows without one
+    pub fn load_descriptions(&mut self) {
+        self.profile_descriptions
+            .insert(self.profile.clone(), self.description.clone());
+
+        let profiles = self
+            .profiles
+            .iter()
+            .filter(|p| **p != self.profile)
+            .map(|p| (p.to_string(), self.profile_path(p)))
+            .collect();
+
+        self.worker.send(Job::Describe(profiles));
+
+        // nothing in a description can fail
+        let _ = self.poll_worker();
+    }
+
+    /// migrates names written before everything was NFC normalized, leaving a
+    /// warning for each one that had to change. profiles are files, they're
+    /// only listed in `unnormalized_profiles` for the user to rename
+    pub fn normalize_names(&mut self) {
+        let mut findings: Vec<String> = Vec::new();
+
+        findings.extend(
+            name_check::normalize_set(&mut self.commands)
+                .iter()
+                .map(|r| r.describe("command")),
+        );
+
+        findings.extend(
+            name_check::normalize_set(&mut self.streams)
+                .iter()
+                .map(|r| r.describe("stream")),
+        );
+
+        // already reported through the commands and streams themselves
+        name_check::normalize_set(&mut self.required_commands);
+        name_check::normalize_keys(&mut self.command_cooldowns, |a, b| *a = a.max(b));
+        name_check::normalize_keys(&mut self.command_meta, |_, _| {});
+        name_check::normalize_set(&mut self.required_streams);
+
+        let unnormalized: Vec<Rc<String>> = self
+            .profiles
+            .iter()
+            .filter(|p| !name_check::is_normalized(p))
+            .cloned()
+            .collect();
+
+        for from in unnormalized {
+            let to = Rc::new(name_check::normalize(&from));
+
+            if self.profiles.contains(&to) {
+                findings.push(format!(
+                    "profile \"{}\" only differs from \"{}\" by unicode normalization, rename or remove one of them",
+                    from.escape_unicode(),
+                    to
+                ));
+                continue;
+            }
+
+            self.unnormalized_profiles.push(from);
+        }
+
+        self.load_warnings.extend(findings);
+    }
+
+    /// cooldowns from a file edited by hand or pulled from another laptop
+    /// are held to the range `SetCommandCooldown` takes. ones outside it are
+    /// dropped with a warning, and the file is saved again without them so
+    /// they don't reach the robot
+    fn drop_invalid_cooldowns(&mut self) {
+        let invalid = self
+            .command_cooldowns
+            .iter()
+            .filter(|(_, seconds)| !(0.0..=MAX_COOLDOWN).contains(*seconds))
+            .map(|(command, seconds)| (command.clone(), *seconds))
+            .collect::<Vec<_>>();
+
+        for (command, seconds) in &invalid {
+            self.command_cooldowns.remove(command);
+            self.load_warnings.push(format!(
+                "the cooldown of {seconds}s for {command} was dropped, it has to be between 0 and {MAX_COOLDOWN} seconds"
+            ));
+        }
+
+        if !invalid.is_empty() {
+            self.mark_dirty();
+        }
+    }
+
+    /// whether `controller` has `binding`, and `modifier` if there is one
+    pub fn valid_binding(
+        &self,
+        controller: u8,
+        binding: bindings::Button,
+        modifier: Option<bindings::Button>,
+    ) -> bool {
+        self.controllers
+            .get(controller as usize)
+            .map(|c| c.valid_trigger(binding, modifier))
+            .unwrap_or(false)
+    }
+
+    /// bindings on `controller` that its current type doesn't have
+    pub fn invalid_bindings(&self, controller: u8) -> Vec<(Rc<String>, Binding)> {
+        self.bindings
+            .command_to_bindings
+            .iter()
+            .flat_map(|(command, bindings)| {
+                bindings
+                    .iter()
+                    .filter(|b| {
+                        b.controller == controller
+                            && !self.valid_binding(b.controller, b.button, b.modifier)
+                    })
+                    .map(|b| (command.clone(), *b))
+            })
+            .collect()
+    }
+
+    pub fn command_description(&self, command: &Rc<String>) -> Option<&str> {
+        self.command_meta
+            .get(command)
+            .and_then(|meta| meta.description.as_deref())
+    }
+
+    pub fn binding_note(&self, command: &String, binding: Binding) -> Option<&str> {
+        let binding = binding.trigger();
+
+        self.binding_notes
+            .get(command)?
+            .iter()
+            .find(|n| n.binding == binding)
+            .map(|n| n.note.as_str())
+    }
+
+    /// drops notes on bindings that are gone
+    fn forget_stale_notes(&mut self) {
+        let bindings = &self.bindings;
+
+        self.binding_notes.retain(|command, notes| {
+            notes.retain(|n| {
+                bindings
+                    .bindings_for_command(command)
+                    .any(|b| b.trigger() == n.binding)
+            });
+
+            !notes.is_empty()
+        });
+    }
+
+    /// drops the conflicts marked on purpose that aren't conflicts anymore, so
+    /// binding a second command there later warns again
+    fn forget_stale_intentional(&mut self) {
+        let bindings = &self.bindings;
+
+        self.intentional.retain(|binding| {
+            bindings
+                .binding_to_commands
+                .get(&binding.key())
+                .into_iter()
+                .flatten()
+                .filter(|(_, when)| *when == binding.during)
+                .count()
+                > 1
+        });
+    }
+
+    /// the `n` commands bound most this session, most used first
+    pub fn frequent_commands(&self, n: usize) -> Vec<Rc<String>> {
+        let mut used = self
+            .command_usage
+            .iter()
+            .filter(|(_, usage)| usage.count > 0)
+            .collect::<Vec<_>>();
+
+        used.sort_by_key(|(_, usage)| std::cmp::Reverse(**usage));
+
+        used.into_iter()
+            .take(n)
+            .map(|(command, _)| command.clone())
+            .collect()
+    }
+
+    /// required commands with no bindings and required streams with no axis in
+    /// the active profile
+    pub fn unbound_required(&self) -> Vec<String> {
+        let commands = self
+            .required_commands
+            .iter()
+            .filter(|c| {
+                self.bindings
+                    .command_to_bindings
+                    .get(*c)
+                    .is_none_or(Vec::is_empty)
+            })
+            .map(|c| format!("command {c} has no bindings"));
+
+        let streams = self
+            .required_streams
+            .iter()
+            .filter(|s| !self.stream_to_axis.contains_key(*s))
+            .map(|s| format!("stream {s} isn't on an axis"));
+
+        commands.chain(streams).collect()
+    }
+
+    /// what's missing before the robot can be driven, with what was set
+    /// aside when the profile was loaded since the robot never sees it
+    pub fn readiness_report(&self) -> Vec<String> {
+        let mut report = self.unbound_required();
+
+        report.extend(
+            self.quarantine
+                .summary()
+                .into_iter()
+                .map(|line| format!("set aside, {line}")),
+        );
+
+        report
+    }
+
+    pub fn first_bound_controller(&self) -> u8 {
+        self.controllers
+            .iter()
+            .position(|c| c.bound())
+            .unwrap_or_default() as u8
+    }
+
+    /// the slot's picked color, or its palette color for the current theme
+    pub fn controller_color(&self, controller: u8, ui: &Ui) -> Color32 {
+        controller_colors::color(
+            self.controller_colors[controller as usize],
+            controller,
+            ui.visuals().dark_mode,
+        )
+    }
+
+    pub fn controller_name(&self, controller: u8) -> Rc<String> {
+        let name = &self.controller_names[controller as usize];
+
+        if name.is_empty() {
+            return Rc::new(controller.to_string());
+        }
+
+        name.clone()
+    }
+
+    /// a controller and the buttons held on it, for the conflict rules'
+    /// findings
+    pub fn trigger_name(&self, trigger: &Trigger, arena: &Bump) -> String {
+        let ((controller, button), modifier) = match trigger {
+            Trigger::Plain(button) => (*button, None),
+            Trigger::Chord { primary, modifiers } => (*primary, modifiers.first().map(|(_, m)| *m)),
+        };
+
+        format!(
+            "{} {}",
+            self.controller_name(controller),
+            self.controllers[controller as usize].chord_name(&button, modifier, arena)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::remove_dir_all;
+
+    use super::*;
+    use crate::{notifier::Collected, test_dir::temp_dir};
+
+    #[test]
+    fn sync_invocation_keeps_paths_whole() {
+        let args = build_sync_invocation(
+            Path::new(r"C:\Users\team 9094\robot\src\main\deploy\bindings.json"),
+            Path::new(r"C:\Users\team 9094\robot\src\main\deploy\bindings"),
+            "10.90.94.2",
+            ROBOT_USER,
+            ROBOT_DEPLOY_PATH,
+            None,
+        );
+
+        assert_eq!(
+            args,
+            [
+                "-r",
+                "--",
+                r"C:\Users\team 9094\robot\src\main\deploy\bindings.json",
+                r"C:\Users\team 9094\robot\src\main\deploy\bindings",
+                "admin@10.90.94.2:'/home/lvuser/deploy/'",
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn refused_saves_keep_the_generation() {
+        let temp = temp_dir("full-disk");
+        let dir = temp.path().to_path_buf();
+        let arena = Bump::new();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            generation: 4,
+            robot_generation: Some(6),
+            targets: vec![SyncTarget::new(
+                "robot".to_string(),
+                "10.90.94.2".to_string(),
+            )],
+            min_free_space: u64::MAX,
+            ..Default::default()
+        };
+
+        assert!(state.write_out(&arena).is_err());
+        assert_eq!(state.generation, 4);
+        assert_eq!(state.last_modified, None);
+    }
+
+    #[test]
+    fn remote_paths_are_quoted() {
+        assert_eq!(
+            quote_remote_path("/home/lvuser/robot deploy/bindings.json"),
+            "'/home/lvuser/robot deploy/bindings.json'"
+        );
+        assert_eq!(
+            quote_remote_path("~/deploy/bindings.json.gz"),
+            "~/'deploy/bindings.json.gz'"
+        );
+        assert_eq!(
+            quote_remote_path("/tmp/it's here; rm -rf ~/"),
+            r"'/tmp/it'\''s here; rm -rf ~/'"
+        );
+    }
+
+    #[test]
+    fn team_numbers_give_robot_addresses() {
+        assert_eq!(team_mdns(9094), "roborio-9094-frc.local");
+        assert_eq!(team_ip(9094).unwrap(), "10.90.94.2");
+        assert_eq!(team_ip(254).unwrap(), "10.2.54.2");
+        assert_eq!(team_ip(7).unwrap(), "10.0.7.2");
+        assert!(team_ip(25600).is_none());
+
+        assert!(derived_from_team("", None));
+        assert!(derived_from_team("10.90.94.2", Some(9094)));
+        assert!(derived_from_team("roborio-9094-frc.local", Some(9094)));
+        assert!(!derived_from_team("roborio-9094-frc.local", Some(254)));
+        assert!(!derived_from_team("robot.lan", Some(9094)));
+        assert!(!derived_from_team("robot.lan", None));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn connection_tests_report_how_they_went() {
+        let mut state = State::default();
+
+        assert_eq!(state.poll_connection_test().unwrap(), None);
+
+        let answering = |script: &str| {
+            let mut child = Command::new("sh")
+                .arg("-c")
+                .arg(script)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap();
+
+            // pipes are left for the poll
+            child.wait().unwrap();
+
+            Some((child, Instant::now()))
+        };
+
+        state.connection_test = answering("echo ok");
+
+        assert!(matches!(
+            state.poll_connection_test().unwrap(),
+            Some(ConnectionStatus::Reachable(_))
+        ));
+        assert!(state.connection_test.is_none());
+
+        state.connection_test = answering(
+            "echo 'Warning: Permanently added' >&2; \
+            echo 'ssh: connect to host robot port 22: Connection timed out' >&2; exit 255",
+        );
+
+        let status = state.poll_connection_test().unwrap().unwrap();
+
+        assert_eq!(
+            status.to_string(),
+            "robot unreachable: ssh: connect to host robot port 22: Connection timed out"
+        );
+        assert_eq!(state.connection_status, Some(status));
+    }
+
+    #[cfg(not(feature = "native-ssh"))]
+    #[test]
+    fn built_in_ssh_needs_the_feature() {
+        let temp = temp_dir("native-ssh");
+        let dir = temp.path().to_path_buf();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            syncing: true,
+            targets: vec![SyncTarget::new(
+                "robot".to_string(),
+                "10.90.94.2".to_string(),
+            )],
+            sync_backend: SyncBackend::Native,
+            ..Default::default()
+        };
+
+        assert!(state.start_sync().is_err());
+        assert!(!state.sync_running());
+    }
+
+    #[test]
+    fn remote_login_is_checked() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+        let mut state = State {
+            targets: vec![SyncTarget::new("robot".to_string(), String::new())],
+            ..Default::default()
+        };
+
+        assert!(!state.handle_event(
+            GlobalEvents::SetSshUser(" ".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.targets[0].user, ROBOT_USER);
+        assert_eq!(state.errors_shown, 1);
+
+        // these would be read by ssh as options or a second host
+        for user in ["-oProxyCommand=touch pwned", "lv user", "admin@evil"] {
+            assert!(!state.handle_event(
+                GlobalEvents::SetSshUser(user.to_string()),
+                &arena,
+                &mut notifier
+            ));
+        }
+        assert_eq!(state.targets[0].user, ROBOT_USER);
+        assert_eq!(state.errors_shown, 4);
+
+        assert!(state.handle_event(
+            GlobalEvents::SetSshUser(" lvuser ".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.targets[0].user, "lvuser");
+
+        assert!(state.handle_event(
+            GlobalEvents::SetRemotePath("/home/lvuser/custom".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.targets[0].path, "/home/lvuser/custom/");
+
+        // the same directory either way
+        assert!(!state.handle_event(
+            GlobalEvents::SetRemotePath("/home/lvuser/custom/".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert!(!state.handle_event(
+            GlobalEvents::SetRemotePath("".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.targets[0].path, "/home/lvuser/custom/");
+        assert_eq!(state.errors_shown, 5);
+    }
+
+    #[test]
+    fn sync_targets_are_added_and_removed() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+        let mut state = State::default();
+
+        // the first address makes the first target
+        assert!(state.handle_event(
+            GlobalEvents::SetUrl(Some("10.90.94.2".to_string())),
+            &arena,
+            &mut notifier
+        ));
+        assert!(state.handle_event(
+            GlobalEvents::AddSyncTarget(" practice ".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.target().unwrap().name, "practice");
+
+        assert!(!state.handle_event(
+            GlobalEvents::AddSyncTarget("robot".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert!(!state.handle_event(
+            GlobalEvents::AddSyncTarget(" ".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.errors_shown, 2);
+
+        // the address goes to the selected one
+        state.handle_event(
+            GlobalEvents::SetUrl(Some("10.90.94.12".to_string())),
+            &arena,
+            &mut notifier,
+        );
+        assert_eq!(state.targets[0].url, "10.90.94.2");
+        assert_eq!(state.url(), Some("10.90.94.12"));
+
+        assert!(state.handle_event(
+            GlobalEvents::SetSyncTargetEnabled("robot".to_string(), false),
+            &arena,
+            &mut notifier
+        ));
+        assert!(!state.targets[0].enabled);
+        assert!(state.sends());
+
+        assert!(state.handle_event(
+            GlobalEvents::RemoveSyncTarget("practice".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        assert_eq!(state.selected_target, 0);
+        assert_eq!(state.url(), Some("10.90.94.2"));
+
+        // a disabled target is kept but nothing is sent to it
+        assert!(!state.sends());
+    }
+
+    #[test]
+    fn sync_invocation_with_port() {
+        let args = build_sync_invocation(
+            Path::new("/home/driver/my robot/bindings.json"),
+            Path::new("/home/driver/my robot/bindings"),
+            "roborio-9094-frc.local",
+            "lvuser",
+            "/home/lvuser/robot deploy/",
+            Some(2222),
+        );
+
+        assert_eq!(
+            args,
+            [
+                "-r",
+                "-P",
+                "2222",
+                "--",
+                "/home/driver/my robot/bindings.json",
+                "/home/driver/my robot/bindings",
+                "lvuser@roborio-9094-frc.local:'/home/lvuser/robot deploy/'",
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn staging_creates_missing_bindings_dir() {
+        let temp = temp_dir("staging");
+        let dir = temp.path().to_path_buf();
+
+        for compact in [false, true] {
+            let mut state = State {
+                deploy_dir: dir.clone(),
+                sync_format: SyncFormat {
+                    compact,
+                    gzip: false,
+                },
+                ..Default::default()
+            };
+
+            let _ = remove_dir_all(dir.join("bindings"));
+
+            std::fs::write(dir.join("bindings.json"), "{}").unwrap();
+
+            let staging = state.stage_sync().unwrap();
+
+            assert!(staging.join("bindings").is_dir());
+        }
+
+        // backups and temp files next to the profiles stay on the laptop
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            profiles: vec![Rc::new("default".to_string())],
+            ..Default::default()
+        };
+
+        create_dir_all(dir.join("bindings")).unwrap();
+
+        for file in ["default.json", "default.json.bak", "default.json.tmp"] {
+            std::fs::write(dir.join("bindings").join(file), "{}").unwrap();
+        }
+
+        let staging = state.stage_sync().unwrap();
+
+        assert!(staging.join("bindings.json").exists());
+        assert_eq!(
+            std::fs::read_dir(staging.join("bindings"))
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect::<Vec<_>>(),
+            vec![OsString::from("default.json")]
+        );
+    }
+
+    #[test]
+    fn rapid_profile_switching() {
+        let temp = temp_dir("switching");
+        let dir = temp.path().to_path_buf();
+
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            ..Default::default()
+        };
+
+        create_dir_all(dir.join("bindings")).unwrap();
+
+        for i in 0..3 {
+            std::fs::write(
+                state.profile_path(&format!("profile{i}")),
+                serde_json::to_string(&Profile::default()).unwrap(),
+            )
+            .unwrap();
+        }
+
+        for i in 0..20 {
+            let profile = Rc::new(format!("profile{}", i % 3));
+
+            state.handle_event(GlobalEvents::SetProfile(profile), &arena, &mut notifier);
+        }
+
+        assert_eq!(read_to_string(dir.join("profile")).unwrap(), "profile1");
+        assert_eq!(state.profile.as_str(), "profile1");
+        assert!(!state.sync_running());
+    }
+
+    #[test]
+    fn missing_profiles_are_not_switched_to() {
+        let temp = temp_dir("missing-profile");
+        let dir = temp.path().to_path_buf();
+
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            ..Default::default()
+        };
+
+        state.handle_event(
+            GlobalEvents::AddCommand("shoot".to_string()),
+            &arena,
+            &mut notifier,
+        );
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        let profile = state.profile.clone();
+
+        // listed when the project was opened, deleted since
+        state.handle_event(
+            GlobalEvents::SetProfile(Rc::new("gone".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.profile, profile);
+        assert_eq!(state.missing_profile.as_deref().unwrap(), "gone");
+        assert!(matches!(state.controllers[0], ControllerType::XBox { .. }));
+
+        // once it's confirmed
+        state.handle_event(
+            GlobalEvents::ResetProfile(Rc::new("gone".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.profile.as_str(), "gone");
+        assert_eq!(state.missing_profile, None);
+        assert!(matches!(state.controllers[0], ControllerType::NotBound));
+        assert!(Profile::exists(&dir, "gone"));
+
+        // the profile that was left had never been saved
+        state.handle_event(
+            GlobalEvents::SetProfile(profile.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.profile, profile);
+        assert_eq!(state.missing_profile, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn switching_stops_running_sync() {
+        let temp = temp_dir("stop-sync");
+        let dir = temp.path().to_path_buf();
+
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            syncs: BTreeMap::from([(
+                "robot".to_string(),
+                TargetSync {
+                    process: Some(Command::new("sleep").arg("30").spawn().unwrap()),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        create_dir_all(dir.join("bindings")).unwrap();
+        std::fs::write(
+            state.profile_path("other"),
+            serde_json::to_string(&Profile::default()).unwrap(),
+        )
+        .unwrap();
+
+        let resync = state.handle_event(
+            GlobalEvents::SetProfile(Rc::new("other".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(resync);
+        assert!(!state.sync_running());
+        assert_eq!(read_to_string(dir.join("profile")).unwrap(), "other");
+    }
+
+    #[test]
+    fn profiles_only_switch_once_the_pointer_is_written() {
+        let temp = temp_dir("pointer");
+        let dir = temp.path().to_path_buf();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            profile: Rc::new("default".to_string()),
+            profiles: vec![Rc::new("default".to_string()), Rc::new("other".to_string())],
+            ..Default::default()
+        };
+
+        create_dir_all(dir.join("bindings")).unwrap();
+
+        for profile in ["default", "other"] {
+            std::fs::write(
+                state.profile_path(profile),
+                serde_json::to_string(&Profile::default()).unwrap(),
+            )
+            .unwrap();
+        }
+
+        // nothing can be written over a directory
+        create_dir_all(dir.join("profile")).unwrap();
+
+        assert!(state.change_profile(Rc::new("other".to_string())).is_err());
+        assert_eq!(state.profile.as_str(), "default");
+
+        assert!(state
+            .rename_profile(
+                Rc::new("default".to_string()),
+                Rc::new("renamed".to_string())
+            )
+            .is_err());
+        assert_eq!(state.profile.as_str(), "default");
+        assert!(state.profile_path("default").exists());
+
+        remove_dir_all(dir.join("profile")).unwrap();
+
+        state.change_profile(Rc::new("other".to_string())).unwrap();
+
+        assert_eq!(state.profile.as_str(), "other");
+        assert_eq!(read_to_string(dir.join("profile")).unwrap(), "other");
+
+        state
+            .rename_profile(Rc::new("other".to_string()), Rc::new("renamed".to_string()))
+            .unwrap();
+
+        assert_eq!(state.profile.as_str(), "renamed");
+        assert_eq!(read_to_string(dir.join("profile")).unwrap(), "renamed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_syncs_keep_what_scp_said() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("printf 'lost connection\\nPermission denied\\nthree\\nfour\\n' >&2; exit 1")
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // the pipe is left for sync_finished, the output is small enough to
+        // not block it
+        child.wait().unwrap();
+
+        let mut state = State {
+            targets: vec![SyncTarget::new(
+                "robot".to_string(),
+                "10.90.94.2".to_string(),
+            )],
+            syncs: BTreeMap::from([(
+                "robot".to_string(),
+                TargetSync {
+                    process: Some(child),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        assert!(state.sync_running());
+
+        let ended = state.poll_sync(SystemTime::now()).unwrap();
+
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0].target, "robot");
+        assert_eq!(ended[0].url, "10.90.94.2");
+        assert!(!ended[0].success);
+        assert!(!state.sync_running());
+
+        assert_eq!(
+            state.syncs["robot"].output.as_deref(),
+            Some("lost connection\nPermission denied\nthree\nfour")
+        );
+
+        let attempt = state.sync_log.back().unwrap();
+
+        assert_eq!(state.sync_log.len(), 1);
+        assert_eq!(attempt.status(), "failed (exit 1)");
+        assert_eq!(attempt.url, "10.90.94.2");
+        assert_eq!(attempt.error.lines().count(), 4);
+        assert_eq!(
+            state.sync_failure("robot"),
+            "failed to sync to robot: lost connection\nPermission denied\nthree\n…"
+        );
+
+        state.syncs.get_mut("robot").unwrap().output = Some("x".repeat(1000));
+
+        assert_eq!(
+            state.sync_failure("robot").chars().count(),
+            "failed to sync to robot: ".len() + SYNC_ERROR_CHARS + 1
+        );
+
+        state.syncs.get_mut("robot").unwrap().output = None;
+
+        assert_eq!(state.sync_failure("robot"), "failed to sync to robot");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saves_during_a_sync_wait_for_it() {
+        let mut state = State {
+            syncing: true,
+            targets: vec![SyncTarget::new(
+                "robot".to_string(),
+                "10.90.94.2".to_string(),
+            )],
+            syncs: BTreeMap::from([(
+                "robot".to_string(),
+                TargetSync {
+                    process: Some(Command::new("sleep").arg("30").spawn().unwrap()),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        // several saves, one sync after
+        for _ in 0..3 {
+            state.start_sync().unwrap();
+        }
+
+        assert!(state.sync_pending);
+        assert!(state.sync_running());
+        assert!(!state.pending_sync_due());
+
+        // it finishes on its own
+        let mut done = Command::new("sh").arg("-c").arg("exit 0").spawn().unwrap();
+
+        done.wait().unwrap();
+
+        let sync = state.syncs.get_mut("robot").unwrap();
+        let mut sleep = sync.process.replace(done).unwrap();
+
+        sleep.kill().unwrap();
+        sleep.wait().unwrap();
+
+        // still running until it's reaped
+        assert!(!state.pending_sync_due());
+
+        state.poll_sync(SystemTime::now()).unwrap();
+
+        assert!(state.pending_sync_due());
+
+        // cancelling drops the one waiting too
+        state.cancel_sync().unwrap();
+
+        assert!(!state.pending_sync_due());
+    }
+
+    #[test]
+    fn sync_attempts_go_to_sync_log_when_asked() {
+        let temp = temp_dir("sync-log");
+        let dir = temp.path().to_path_buf();
+
+        let attempt = sync_log::Attempt {
+            at: SystemTime::now(),
+            target: "robot".to_string(),
+            url: "10.90.94.2".to_string(),
+            took: None,
+            success: true,
+            exit_code: Some(0),
+            error: String::new(),
+        };
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            ..Default::default()
+        };
+
+        state.log_sync(attempt.clone());
+        state.finish_io().unwrap();
+
+        assert!(!sync_log::path(&dir).exists());
+
+        state.sync_log_file = true;
+
+        for _ in 0..sync_log::KEPT {
+            state.log_sync(attempt.clone());
+        }
+
+        state.finish_io().unwrap();
+
+        // the file keeps them all, memory only the newest
+        assert_eq!(state.sync_log.len(), sync_log::KEPT);
+        assert_eq!(
+            read_to_string(sync_log::path(&dir))
+                .unwrap()
+                .lines()
+                .count(),
+            sync_log::KEPT
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cancelling_kills_the_running_sync() {
+        let mut state = State {
+            syncs: BTreeMap::from([(
+                "robot".to_string(),
+                TargetSync {
+                    process: Some(Command::new("sleep").arg("30").spawn().unwrap()),
+                    started: Some(Instant::now()),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        assert!(state.cancel_sync().unwrap());
+        assert!(state.syncs["robot"].process.is_none());
+        assert!(state.syncs["robot"].started.is_none());
+
+        assert!(!state.cancel_sync().unwrap());
+    }
+
+    #[test]
+    fn used_lists_profiles() {
+        let temp = temp_dir("used");
+        let dir = temp.path().to_path_buf();
+
+        let shoot = Rc::new("shoot".to_string());
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            profile: Rc::new("driver".to_string()),
+            profiles: vec![
+                Rc::new("driver".to_string()),
+                Rc::new("demo".to_string()),
+                Rc::new("spare".to_string()),
+            ],
+            ..Default::default()
+        };
+
+        state
+            .bindings
+            .command_to_bindings
+            .insert(shoot.clone(), vec![]);
+
+        create_dir_all(dir.join("bindings")).unwrap();
+        std::fs::write(
+            dir.join("bindings").join("demo.json"),
+            r#"{"command_to_bindings": {"shoot": [
+                {"controller": 0, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let used = state.is_used(&shoot).unwrap();
+
+        assert_eq!(state.describe_profiles(&used), "demo, driver (current)");
+        assert!(state
+            .is_used(&Rc::new("climb".to_string()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn profiles_are_read_once_until_they_change() {
+        let arena = Bump::new();
+        let temp = temp_dir("profile-cache");
+        let dir = temp.path().to_path_buf();
+
+        let shoot = Rc::new("shoot".to_string());
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            profile: Rc::new("driver".to_string()),
+            profiles: vec![Rc::new("driver".to_string()), Rc::new("demo".to_string())],
+            ..Default::default()
+        };
+
+        let demo = dir.join("bindings").join("demo.json");
+
+        create_dir_all(dir.join("bindings")).unwrap();
+        std::fs::write(
+            &demo,
+            r#"{"command_to_bindings": {"shoot": [
+                {"controller": 0, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"}
+            ]}}"#,
+        )
+        .unwrap();
+
+        state.record_files();
+
+        assert_eq!(state.is_used(&shoot).unwrap().len(), 1);
+
+        // gone from disk but still cached, nothing was read the second time
+        std::fs::write(&demo, "{}").unwrap();
+
+        assert_eq!(state.is_used(&shoot).unwrap().len(), 1);
+        assert!(state
+            .profile_cache
+            .borrow()
+            .contains_key(&"demo".to_string()));
+
+        // noticed by the next look at the disk, which drops what was cached
+        state.check_disk(Instant::now()).unwrap();
+
+        assert_eq!(state.external_change, Some(vec![demo.clone()]));
+        assert!(state.profile_cache.borrow().is_empty());
+        assert!(state.is_used(&shoot).unwrap().is_empty());
+
+        // a profile written through map_profiles is read again
+        state.external_change = None;
+        state.record_files();
+
+        state
+            .map_profiles(
+                |_, profile| {
+                    profile.command_to_bindings.to_mut().insert(
+                        shoot.clone(),
+                        vec![Binding {
+                            controller: 0,
+                            button: bindings::Button {
+                                button: 2,
+                                location: bindings::ButtonLocation::Button,
+                            },
+                            during: bindings::RunWhen::OnTrue,
+                            debounce: bindings::Debounce::NONE,
+                            threshold: None,
+                            modifier: None,
+                            enabled: true,
+                        }],
+                    );
+                },
+                &arena,
+            )
+            .unwrap();
+
+        assert!(state.profile_cache.borrow().is_empty());
+        assert_eq!(state.is_used(&shoot).unwrap().len(), 2);
+
+        // and switching profiles starts over
+        state.change_profile(Rc::new("demo".to_string())).unwrap();
+
+        assert!(state.profile_cache.borrow().is_empty());
+    }
+
+    #[test]
+    fn frequent_commands_most_bound_first() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let mut state = State::default();
+
+        for (command, button) in [("shoot", 1), ("intake", 2), ("shoot", 3), ("climb", 4)] {
+            state.handle_event(
+                GlobalEvents::AddBinding(
+                    Binding {
+                        controller: 0,
+                        button: bindings::Button {
+                            button,
+                            location: bindings::ButtonLocation::Button,
+                        },
+                        during: bindings::RunWhen::OnTrue,
+                        debounce: bindings::Debounce::NONE,
+                        threshold: None,
+                        modifier: None,
+                        enabled: true,
+                    },
+                    Rc::new(command.to_string()),
+                ),
+                &arena,
+                &mut notifier,
+            );
+        }
+
+        // climb and intake were both bound once, climb more recently
+        assert_eq!(
+            state.frequent_commands(5),
+            ["shoot", "climb", "intake"].map(|c| Rc::new(c.to_string()))
+        );
+        assert_eq!(state.frequent_commands(1).len(), 1);
+
+        let shoot = Rc::new("shoot".to_string());
+        let binding = state.bindings.bindings_for_command(&shoot).next().unwrap();
+
+        state.handle_event(
+            GlobalEvents::RemoveBinding(binding, shoot.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        // removing counts as a change but not as using the command
+        let usage = state.command_usage[&shoot];
+
+        assert_eq!(usage.count, 2);
+        assert!(usage.modified > state.command_usage[&Rc::new("climb".to_string())].modified);
+    }
+
+    #[test]
+    fn changing_the_remote_schedules_a_resync() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let mut state = State {
+            targets: vec![SyncTarget::new(
+                "robot".to_string(),
+                "10.90.94.2".to_string(),
+            )],
+            robot_generation: Some(4),
+            ..Default::default()
+        };
+
+        assert!(state.handle_event(
+            GlobalEvents::SetUrl(Some("10.90.94.3".to_string())),
+            &arena,
+            &mut notifier,
+        ));
+
+        let now = Instant::now();
+
+        assert!(state.remote_stale);
+        assert_eq!(state.robot_generation, None);
+        assert!(!state.resync_due(now));
+        assert!(state.resync_due(now + RESYNC_DELAY));
+
+        state.handle_event(GlobalEvents::ForceSync, &arena, &mut notifier);
+
+        assert_eq!(state.resync_at, None);
+        assert_eq!(notifier.info.len(), 1);
+
+        // while syncing is off the change waits for it to come back on
+        state.remote_stale = false;
+
+        state.handle_event(GlobalEvents::SetSyncing(false), &arena, &mut notifier);
+        state.handle_event(
+            GlobalEvents::SetSyncFormat(SyncFormat {
+                compact: true,
+                gzip: true,
+            }),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.remote_stale);
+        assert_eq!(state.resync_at, None);
+
+        state.handle_event(GlobalEvents::SetSyncing(true), &arena, &mut notifier);
+
+        assert!(state.resync_due(Instant::now()));
+
+        // sending the same files another way is a change of remote too
+        state.remote_stale = false;
+        state.resync_at = None;
+        state.robot_generation = Some(4);
+
+        assert!(!state.handle_event(
+            GlobalEvents::SetSyncBackend(SyncBackend::Scp),
+            &arena,
+            &mut notifier,
+        ));
+        assert!(!state.remote_stale);
+
+        assert!(state.handle_event(
+            GlobalEvents::SetSyncBackend(SyncBackend::Native),
+            &arena,
+            &mut notifier,
+        ));
+
+        let now = Instant::now();
+
+        assert!(state.remote_stale);
+        assert_eq!(state.robot_generation, None);
+        assert!(!state.resync_due(now));
+        assert!(state.resync_due(now + RESYNC_DELAY));
+    }
+
+    #[test]
+    fn conflicting_binding_warns_unless_intentional() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let binding = Binding {
+            controller: 0,
+            button: bindings::Button::default(),
+            during: bindings::RunWhen::OnTrue,
+            debounce: bindings::Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let mut state = State::default();
+
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        for command in ["shoot", "intake"] {
+            state.handle_event(
+                GlobalEvents::AddBinding(binding, Rc::new(command.to_string())),
+                &arena,
+                &mut notifier,
+            );
         }
+
+        assert_eq!(notifier.warnings.len(), 1);
+
+        state.handle_event(
+            GlobalEvents::SetIntentional(binding, true),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::AddBinding(binding, Rc::new("climb".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(notifier.warnings.len(), 1);
+        assert!(state.to_profile_data().intentional.contains(&binding));
+
+        // still a conflict between intake and climb
+        state.handle_event(
+            GlobalEvents::RemoveCommand(Rc::new("shoot".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.intentional.contains(&binding));
+
+        state.handle_event(
+            GlobalEvents::RemoveBinding(binding, Rc::new("climb".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.intentional.is_empty());
+
+        state.handle_event(
+            GlobalEvents::AddBinding(binding, Rc::new("shoot".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(notifier.warnings.len(), 2);
+
+        state.handle_event(
+            GlobalEvents::SetIntentional(binding, true),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::ClearControllerBindings(0),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.intentional.is_empty());
     }
 
-    pub fn from_directory(mut path: PathBuf) -> Result<Self> {
-        if !path.is_dir() {
-            return Err(ProgramError::NotDirectory(path))?;
+    #[test]
+    fn plain_binding_on_a_chord_modifier_warns() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let button = |button| bindings::Button {
+            button,
+            location: bindings::ButtonLocation::Button,
+        };
+
+        // left bumper alone, then a with left bumper held
+        let bumper = Binding {
+            controller: 0,
+            button: button(5),
+            during: bindings::RunWhen::OnTrue,
+            debounce: bindings::Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+        let chord = Binding {
+            button: button(1),
+            modifier: Some(button(5)),
+            ..bumper
+        };
+
+        let mut state = State::default();
+
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        state.handle_event(
+            GlobalEvents::AddBinding(bumper, Rc::new("intake".to_string())),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::AddBinding(chord, Rc::new("shoot".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(notifier.warnings.len(), 1);
+        assert!(notifier.warnings[0].contains("intake"));
+        assert!(notifier.warnings[0].contains("shoot"));
+
+        // a second chord on a with a different modifier is fine
+        state.handle_event(
+            GlobalEvents::AddBinding(
+                Binding {
+                    modifier: Some(button(6)),
+                    ..chord
+                },
+                Rc::new("climb".to_string()),
+            ),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(notifier.warnings.len(), 1);
+    }
+
+    #[test]
+    fn notes_stay_out_of_binding_equality() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let shoot = Rc::new("shoot".to_string());
+        let fire = Rc::new("fire".to_string());
+
+        let binding = Binding {
+            controller: 0,
+            button: bindings::Button::default(),
+            during: bindings::RunWhen::OnTrue,
+            debounce: bindings::Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let temp = temp_dir("notes");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        assert!(!state.handle_event(
+            GlobalEvents::SetBindingNote(binding, shoot.clone(), "for the amp".to_string()),
+            &arena,
+            &mut notifier,
+        ));
+        assert_eq!(notifier.errors.len(), 1);
+
+        state.handle_event(
+            GlobalEvents::AddBinding(binding, shoot.clone()),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::SetBindingNote(binding, shoot.clone(), " for the amp ".to_string()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.binding_note(&shoot, binding), Some("for the amp"));
+        assert!(binding
+            .show(&shoot, &state, &arena)
+            .ends_with("(for the amp)"));
+        assert!(!binding.show(&fire, &state, &arena).contains("amp"));
+
+        // the same binding again is still a duplicate, note or not
+        state.handle_event(
+            GlobalEvents::AddBinding(binding, shoot.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.bindings.bindings_for_command(&shoot).count(), 1);
+        assert!(state.bindings.has_binding(&shoot, binding));
+
+        state.handle_event(
+            GlobalEvents::RenameCommand(shoot.clone(), fire.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.binding_note(&fire, binding), Some("for the amp"));
+        assert_eq!(state.binding_note(&shoot, binding), None);
+
+        state.handle_event(
+            GlobalEvents::RemoveBinding(binding, fire.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.binding_notes.is_empty());
+    }
+
+    #[test]
+    fn readiness_follows_renames() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let shoot = Rc::new("shoot".to_string());
+        let drive = Rc::new("drive".to_string());
+
+        let mut state = State::default();
+
+        state.commands.insert(shoot.clone());
+        state.streams.insert(drive.clone());
+
+        state.handle_event(
+            GlobalEvents::SetCommandRequired(shoot.clone(), true),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::SetStreamRequired(drive.clone(), true),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.unbound_required().len(), 2);
+
+        state.stream_to_axis.insert(drive.clone(), (0, 1));
+
+        state.handle_event(
+            GlobalEvents::RenameStream(drive, Rc::new("move".to_string())),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(
+            state.unbound_required(),
+            vec!["command shoot has no bindings".to_string()]
+        );
+        assert!(state
+            .required_streams
+            .contains(&Rc::new("move".to_string())));
+
+        state.handle_event(GlobalEvents::RemoveCommand(shoot), &arena, &mut notifier);
+
+        assert!(state.unbound_required().is_empty());
+    }
+
+    #[test]
+    fn cooldowns_follow_their_command() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let shoot = Rc::new("shoot".to_string());
+        let fire = Rc::new("fire".to_string());
+
+        let temp = temp_dir("cooldowns");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        state.commands.insert(shoot.clone());
+
+        for bad in [-1.0, MAX_COOLDOWN + 1.0, f32::NAN] {
+            assert!(!state.handle_event(
+                GlobalEvents::SetCommandCooldown(shoot.clone(), Some(bad)),
+                &arena,
+                &mut notifier,
+            ));
         }
 
-        path.push("src");
-        path.push("main");
-        path.push("deploy");
+        assert_eq!(notifier.errors.len(), 3);
+        assert!(state.command_cooldowns.is_empty());
 
-        create_dir_all(&path)?;
+        state.handle_event(
+            GlobalEvents::SetCommandCooldown(shoot.clone(), Some(0.5)),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(
+            GlobalEvents::RenameCommand(shoot.clone(), fire.clone()),
+            &arena,
+            &mut notifier,
+        );
 
-        path.push("profile");
+        assert_eq!(state.command_cooldowns.get(&fire), Some(&0.5));
+        assert!(!state.command_cooldowns.contains_key(&shoot));
 
-        if path.is_dir() {
-            // return is here just to convice the borrow checker that this path never
-            // continues executing the function
-            return Err(ProgramError::ExistingDirectoryAt(path))?;
+        state.handle_event(GlobalEvents::RemoveCommand(fire), &arena, &mut notifier);
+
+        assert!(state.command_cooldowns.is_empty());
+    }
+
+    #[test]
+    fn command_meta_follows_its_command() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let lift = Rc::new("lift".to_string());
+        let raise = Rc::new("raise".to_string());
+
+        let temp = temp_dir("meta");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        state.commands.insert(lift.clone());
+
+        let meta = CommandMeta {
+            group: Some("arm".to_string()),
+            description: None,
+        };
+
+        assert!(state.handle_event(
+            GlobalEvents::SetCommandMeta(lift.clone(), meta.clone()),
+            &arena,
+            &mut notifier,
+        ));
+        assert!(!state.handle_event(
+            GlobalEvents::SetCommandMeta(lift.clone(), meta.clone()),
+            &arena,
+            &mut notifier,
+        ));
+
+        state.handle_event(
+            GlobalEvents::RenameCommand(lift, raise.clone()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert_eq!(state.command_meta.get(&raise), Some(&meta));
+
+        state.handle_event(
+            GlobalEvents::SetCommandMeta(raise.clone(), CommandMeta::default()),
+            &arena,
+            &mut notifier,
+        );
+
+        assert!(state.command_meta.is_empty());
+
+        state.handle_event(
+            GlobalEvents::SetCommandMeta(raise.clone(), meta),
+            &arena,
+            &mut notifier,
+        );
+        state.handle_event(GlobalEvents::RemoveCommand(raise), &arena, &mut notifier);
+
+        assert!(state.command_meta.is_empty());
+    }
+
+    #[test]
+    fn streams_only_go_on_real_axes() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let drive = Rc::new("drive".to_string());
+
+        let mut state = State::default();
+
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+        assert!(state.handle_event(
+            GlobalEvents::SetStream(drive.clone(), 0, 5),
+            &arena,
+            &mut notifier,
+        ));
+
+        for (controller, axis) in [(0, 6), (1, 0), (200, 0)] {
+            assert!(!state.handle_event(
+                GlobalEvents::SetStream(drive.clone(), controller, axis),
+                &arena,
+                &mut notifier,
+            ));
         }
 
-        let profile_name = match read_to_string(&path) {
-            Ok(a) => a,
-            Err(_err) => {
-                let mut file = File::create_new(&path)?;
+        assert_eq!(notifier.errors.len(), 3);
+        assert!(notifier.errors[0].contains("only has 6 axises"));
+        assert!(notifier.errors[1].contains("isn't bound"));
+        assert!(notifier.errors[2].contains("no controller 200"));
 
-                file.write_all("default".as_bytes())?;
+        assert_eq!(state.stream_to_axis.get(&drive), Some(&(0, 5)));
+    }
 
-                "default".to_string()
-            }
+    #[test]
+    fn cancelling_changes_collapse() {
+        let changes = [
+            Change::Added("shoot a".to_string()),
+            Change::Changed("syncing".to_string()),
+            Change::Removed("shoot a".to_string()),
+            Change::Changed("syncing".to_string()),
+            Change::Removed("command climb".to_string()),
+            Change::Added("command climb".to_string()),
+            Change::Added("command intake".to_string()),
+        ];
+
+        assert_eq!(
+            collapse_changes(&changes),
+            vec![
+                Change::Changed("syncing".to_string()),
+                Change::Added("command intake".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn pending_changes_clear_once_written() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let temp = temp_dir("pending");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            ..Default::default()
         };
 
-        path.pop();
+        let mut events = EventStream::new();
+        events.add_event(GlobalEvents::AddCommand("shoot".to_string()));
+        events.add_event(GlobalEvents::DisplayInfo("hello".to_string()));
 
-        let bindings = match SaveData::from_directory(&path)? {
-            Some(a) => a,
-            None => {
-                let profile_name = Rc::new(profile_name);
-                return Ok(Self {
-                    deploy_dir: path,
-                    profile: profile_name.clone(),
-                    profiles: vec![profile_name],
-                    ..Default::default()
-                });
-            }
+        let _ = state.process_events(&mut events, &arena, &mut notifier);
+
+        assert!(state.save_at.is_some());
+        assert!(!state.flush_if_due(Instant::now(), &arena).unwrap());
+        assert!(state
+            .flush_if_due(Instant::now() + SAVE_DELAY, &arena)
+            .unwrap());
+        assert!(state.pending.is_empty());
+
+        // a write that fails keeps the edit around
+        state.deploy_dir = state.deploy_dir.join("bindings.json");
+        std::fs::write(&state.deploy_dir, "").unwrap();
+
+        events.add_event(GlobalEvents::AddCommand("climb".to_string()));
+
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+
+        assert!(state.flush(&arena).is_err());
+        assert_eq!(
+            state.pending,
+            vec![Change::Added("command climb".to_string())]
+        );
+        assert_eq!(state.save_at, None);
+    }
+
+    #[test]
+    fn changes_are_logged_and_failures_are_not() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let temp = temp_dir("change-log");
+        let dir = temp.path().to_path_buf();
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            profiles: vec![Rc::new("default".to_string()), Rc::new("demo".to_string())],
+            ..Default::default()
         };
 
-        let mut profiles = Profile::get_profiles(&path)?;
+        let mut events = EventStream::new();
+        events.add_event(GlobalEvents::AddCommand("shoot".to_string()));
+        events.add_event(GlobalEvents::DisplayInfo("hello".to_string()));
+        // taken already, so it fails and isn't logged
+        events.add_event(GlobalEvents::RenameProfile(
+            Rc::new("default".to_string()),
+            Rc::new("demo".to_string()),
+        ));
+        // already how things are, so nothing changed
+        events.add_event(GlobalEvents::SetDisabledBindings(state.disabled_bindings));
+        events.add_event(GlobalEvents::SetSyncing(state.syncing));
+        events.add_event(GlobalEvents::KeepQuarantine);
 
-        if !profiles
-            .iter()
-            .map(|s| s.as_str())
-            .any(|s| s == profile_name.as_str())
-        {
-            profiles.push(Rc::new(profile_name.clone()));
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+
+        let logged = audit_log::tail(&dir, 10);
+
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].kind, "AddCommand");
+        assert_eq!(logged[0].summary, "+ command shoot");
+        assert_eq!(state.log_written, 1);
+
+        // a log that can't be written is a warning, the edit still happens
+        remove_dir_all(&dir).unwrap();
+        create_dir_all(audit_log::path(&dir)).unwrap();
+
+        events.add_event(GlobalEvents::AddCommand("climb".to_string()));
+
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+
+        assert!(state.commands.contains(&Rc::new("climb".to_string())));
+        assert_eq!(state.load_warnings.len(), 1);
+    }
+
+    #[test]
+    fn failed_syncs_retry_later_each_time() {
+        let mut state = State {
+            targets: vec![
+                SyncTarget::new("robot".to_string(), "10.90.94.2".to_string()),
+                SyncTarget::new("practice".to_string(), "10.90.94.12".to_string()),
+            ],
+            ..Default::default()
+        };
+        let now = SystemTime::now();
+
+        for delay in [5, 15, 60, 60] {
+            let before = Instant::now();
+
+            // the retry's own sync clears it when it starts
+            state.sync_retry_at = None;
+            state.sync_finished("robot", Err(String::new()), now);
+
+            // the other target failing the same round doesn't wait longer
+            state.sync_finished("practice", Err(String::new()), now);
+
+            let wait = state.sync_retry_at.unwrap() - before;
+
+            assert!(wait >= Duration::from_secs(delay));
+            assert!(wait < Duration::from_secs(delay + 1));
+            assert!(!state.sync_retry_due(Instant::now()));
         }
 
-        let profile = Profile::get_from(&path, &profile_name)?;
+        assert!(state.sync_retry_due(Instant::now() + Duration::from_secs(61)));
 
-        Ok(Self::from_bindings(
-            bindings,
-            profile,
-            profiles,
-            profile_name,
-            path,
-        ))
+        // practice still needs it
+        state.sync_finished("robot", Ok(()), now);
+
+        assert!(state.sync_retry_at.is_some());
+
+        state.sync_finished("practice", Ok(()), now);
+
+        assert_eq!(state.sync_retry_at, None);
+        assert_eq!(state.sync_retries, 0);
+
+        // nothing to retry against once syncing is off
+        state.handle_event(
+            GlobalEvents::SetSyncing(false),
+            &Bump::new(),
+            &mut Collected::default(),
+        );
+        state.sync_finished("robot", Err(String::new()), now);
+
+        assert_eq!(state.sync_retry_at, None);
     }
 
-    pub fn valid_binding(&self, controller: u8, binding: bindings::Button) -> bool {
-        self.controllers
-            .get(controller as usize)
-            .map(|c| c.valid_binding(binding))
-            .unwrap_or(false)
+    #[test]
+    fn finished_syncs_say_when() {
+        let mut state = State::default();
+        let now = SystemTime::now();
+
+        assert_eq!(state.last_sync_text("robot", now), None);
+
+        state.syncs.entry("robot".to_string()).or_default().started = Some(Instant::now());
+
+        assert!(state.sync_finished("robot", Ok(()), now).is_some());
+        assert_eq!(state.syncs["robot"].started, None);
+
+        for (after, text) in [
+            (5, "last synced just now"),
+            (150, "last synced 2 min ago"),
+            (7300, "last synced 2 h ago"),
+        ] {
+            assert_eq!(
+                state
+                    .last_sync_text("robot", now + Duration::from_secs(after))
+                    .as_deref(),
+                Some(text)
+            );
+        }
+
+        assert_eq!(state.sync_finished("robot", Err(String::new()), now), None);
+        assert_eq!(
+            state.last_sync_text("robot", now).as_deref(),
+            Some("last sync failed")
+        );
+
+        // each target has its own
+        assert_eq!(state.last_sync_text("practice", now), None);
     }
 
-    pub fn controller_name(&self, controller: u8) -> Rc<String> {
-        let name = &self.controller_names[controller as usize];
+    #[test]
+    fn saves_on_a_slow_drive_do_not_hold_up_the_frame() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
 
-        if name.is_empty() {
-            return Rc::new(controller.to_string());
+        let temp = temp_dir("slow-drive");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            worker: Worker::spawn_with_delay(Duration::from_millis(200)),
+            ..Default::default()
+        };
+
+        assert!(state.handle_event(
+            GlobalEvents::AddCommand("shoot".to_string()),
+            &arena,
+            &mut notifier
+        ));
+        state
+            .pending
+            .push(Change::Added("command shoot".to_string()));
+
+        let start = Instant::now();
+
+        state.write_out(&arena).unwrap();
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+        assert!(state.worker.busy());
+        assert!(state.window_title().starts_with('*'));
+        assert_eq!(state.pending.len(), 1);
+
+        state.finish_io().unwrap();
+
+        assert!(state.pending.is_empty());
+        assert!(read_to_string(state.deploy_dir.join("bindings.json"))
+            .unwrap()
+            .contains("shoot"));
+
+        // the second save finds the first one's file not written yet, which
+        // isn't a change from outside the app
+        for command in ["climb", "intake"] {
+            state.handle_event(
+                GlobalEvents::AddCommand(command.to_string()),
+                &arena,
+                &mut notifier,
+            );
+            state.write_out(&arena).unwrap();
         }
 
-        name.clone()
+        assert!(state.worker.busy());
+        assert_eq!(state.external_change, None);
+
+        state.finish_io().unwrap();
+
+        assert!(read_to_string(state.deploy_dir.join("bindings.json"))
+            .unwrap()
+            .contains("intake"));
+        assert!(!state
+            .file_hashes
+            .changed_externally(&state.deploy_dir.join("bindings.json")));
+    }
+
+    #[test]
+    fn renaming_waits_for_saves_of_the_profile() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let binding = Binding {
+            controller: 0,
+            button: bindings::Button::default(),
+            during: bindings::RunWhen::OnTrue,
+            debounce: bindings::Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        };
+
+        let temp = temp_dir("rename-while-saving");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            profile: Rc::new("default".to_string()),
+            profiles: vec![Rc::new("default".to_string())],
+            worker: Worker::spawn_with_delay(Duration::from_millis(200)),
+            ..Default::default()
+        };
+
+        state.handle_event(
+            GlobalEvents::AddCommand("shoot".to_string()),
+            &arena,
+            &mut notifier,
+        );
+        state.write_out(&arena).unwrap();
+
+        assert!(state.worker.busy());
+
+        // and one more still waiting on the debounce
+        let mut events = EventStream::new();
+        events.add_event(GlobalEvents::AddBinding(
+            binding,
+            Rc::new("shoot".to_string()),
+        ));
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+
+        assert!(state.save_at.is_some());
+
+        events.add_event(GlobalEvents::RenameProfile(
+            Rc::new("default".to_string()),
+            Rc::new("renamed".to_string()),
+        ));
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+        state.finish_io().unwrap();
+
+        assert!(notifier.errors.is_empty());
+        assert_eq!(state.profile.as_str(), "renamed");
+        assert!(!state.profile_path("default").exists());
+        assert!(read_to_string(state.profile_path("renamed"))
+            .unwrap()
+            .contains("shoot"));
+        assert!(state.queued_writes.is_empty());
+    }
+
+    #[test]
+    fn layout_that_cant_be_written_only_warns() {
+        let arena = Bump::new();
+
+        let temp = temp_dir("layout-warning");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        create_dir_all(layout::path(&state.deploy_dir)).unwrap();
+
+        state.write_out(&arena).unwrap();
+
+        assert!(state.layout_due);
+
+        state.save_layout(&Layout::new(vec![None]));
+        state.finish_io().unwrap();
+
+        assert!(!state.layout_due);
+        assert!(state.deploy_dir.join("bindings.json").exists());
+        assert_eq!(state.load_warnings.len(), 1);
+    }
+
+    #[test]
+    fn manual_save_mode_waits_for_a_save() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+
+        let temp = temp_dir("manual save");
+
+        let mut state = State {
+            deploy_dir: temp.path().to_path_buf(),
+            save_mode: SaveMode::Manual,
+            ..Default::default()
+        };
+
+        let mut events = EventStream::new();
+        events.add_event(GlobalEvents::AddCommand("shoot".to_string()));
+
+        state
+            .process_events(&mut events, &arena, &mut notifier)
+            .unwrap();
+
+        assert!(state.unsaved);
+        assert_eq!(state.save_at, None);
+        assert!(!state
+            .flush_if_due(Instant::now() + SAVE_DELAY, &arena)
+            .unwrap());
+        assert!(!state.deploy_dir.join("bindings.json").exists());
+
+        assert!(state.flush(&arena).unwrap());
+        assert!(!state.unsaved);
+        assert!(state.deploy_dir.join("bindings.json").exists());
+
+        // going back to autosave writes the setting straight away
+        assert!(!state.handle_event(
+            GlobalEvents::SetSaveMode(SaveMode::Auto),
+            &arena,
+            &mut notifier
+        ));
+
+        let saved = serde_json::from_str::<serde_json::Value>(
+            &std::fs::read_to_string(state.deploy_dir.join("bindings.json")).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(saved["save_mode"], "Auto");
+    }
+
+    #[test]
+    fn manual_save_mode_holds_back_other_profiles() {
+        let arena = Bump::new();
+        let mut notifier = Collected::default();
+        let temp = temp_dir("manual-other-profiles");
+        let dir = temp.path().to_path_buf();
+
+        let shoot = Rc::new("shoot".to_string());
+        let fire = Rc::new("fire".to_string());
+
+        let mut state = State {
+            deploy_dir: dir.clone(),
+            save_mode: SaveMode::Manual,
+            profile: Rc::new("driver".to_string()),
+            profiles: vec![Rc::new("driver".to_string()), Rc::new("demo".to_string())],
+            ..Default::default()
+        };
+
+        state.commands.insert(shoot.clone());
+
+        let demo = dir.join("bindings").join("demo.json");
+        let on_disk = r#"{"command_to_bindings": {"shoot": [
+            {"controller": 0, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"}
+        ]}}"#;
+
+        create_dir_all(dir.join("bindings")).unwrap();
+        std::fs::write(&demo, on_disk).unwrap();
+
+        state.record_files();
+
+        state.handle_event(
+            GlobalEvents::RenameCommand(shoot.clone(), fire.clone()),
+            &arena,
+            &mut notifier,
+        );
+        state.finish_io().unwrap();
+
+        // renamed as far as the app can tell, but not on disk yet
+        assert!(state.unsaved);
+        assert_eq!(read_to_string(&demo).unwrap(), on_disk);
+        assert!(state
+            .is_used(&fire)
+            .unwrap()
+            .iter()
+            .any(|p| p.as_str() == "demo"));
+
+        assert!(state.flush(&arena).unwrap());
+        state.finish_io().unwrap();
+
+        assert!(state.unsaved_profiles.is_empty());
+        assert!(read_to_string(&demo).unwrap().contains("fire"));
+    }
+
+    #[test]
+    fn title_names_the_project_and_profile() {
+        let mut state = State {
+            deploy_dir: PathBuf::from("/robots/crescendo/src/main/deploy"),
+            profile: Rc::new("practice".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(state.window_title(), "Bindings — crescendo [practice]");
+
+        state.mark_dirty();
+
+        assert_eq!(state.window_title(), "* Bindings — crescendo [practice]");
     }
 }
@@ -0,0 +1,90 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use egui_dock::DockState;
+use serde_json::Value;
+
+use crate::tabs::TabType;
+
+/// the dock's splits and which tab is in each spot, none for a "new tab"
+pub type Layout = DockState<Option<TabType>>;
+
+pub fn path(deploy_dir: &Path) -> PathBuf {
+    deploy_dir.join(".bindings_gui_layout.json")
+}
+
+/// the layout saved for the project, none when there isn't one or it can't be
+/// read. tabs this build doesn't have come back as new tabs
+pub fn load(deploy_dir: &Path) -> Option<Layout> {
+    let file = read_to_string(path(deploy_dir)).ok()?;
+
+    let mut saved = serde_json::from_str::<Value>(&file).ok()?;
+
+    fill_rects(&mut saved);
+
+    let saved = serde_json::from_value::<DockState<Value>>(saved).ok()?;
+
+    Some(saved.map_tabs(|tab| serde_json::from_value(tab.clone()).ok().flatten()))
+}
+
+/// rects of parts that were never drawn are nan, which json writes as null and
+/// can't read back. they're worked out again every frame so zero will do
+fn fill_rects(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                if (key == "x" || key == "y") && field.is_null() {
+                    *field = 0.0.into();
+                } else {
+                    fill_rects(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(fill_rects),
+        _ => {}
+    }
+}
+
+pub fn to_json(layout: &Layout) -> Result<String> {
+    serde_json::to_string_pretty(layout).with_context(|| "failed to save the tab layout")
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use egui_dock::NodeIndex;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn layouts_round_trip() {
+        let temp = temp_dir("layout");
+        let dir = temp.path();
+
+        assert!(load(dir).is_none());
+
+        let mut layout = Layout::new(vec![Some(TabType::Constants), None]);
+
+        layout
+            .main_surface_mut()
+            .split_right(NodeIndex::root(), 0.5, vec![Some(TabType::Syncing)]);
+
+        write(path(dir), to_json(&layout).unwrap()).unwrap();
+
+        let loaded = load(dir).unwrap();
+
+        assert_eq!(
+            loaded.iter_all_tabs().map(|(_, t)| *t).collect::<Vec<_>>(),
+            layout.iter_all_tabs().map(|(_, t)| *t).collect::<Vec<_>>()
+        );
+
+        write(path(dir), "{\"surfaces\": [").unwrap();
+
+        assert!(load(dir).is_none());
+    }
+}
@@ -0,0 +1,111 @@
+use std::{error::Error, fmt::Display, path::PathBuf};
+
+pub mod accessible;
+pub mod audit_log;
+pub mod bindings;
+pub mod codegen;
+pub mod component;
+pub mod confirm;
+pub mod conflict_rules;
+pub mod constants;
+pub mod controller_colors;
+pub mod disk_space;
+pub mod export;
+pub mod file_hashes;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod git;
+pub mod global_state;
+pub mod layout;
+pub mod maintenance;
+pub mod name_check;
+#[cfg(feature = "native-ssh")]
+pub mod native_sync;
+pub mod notifier;
+pub mod number_input;
+pub mod quick_add;
+pub mod recovery;
+pub mod render_stats;
+pub mod robot_pull;
+pub mod search_selector;
+pub mod settings;
+mod single_linked_list;
+pub mod source_scan;
+pub mod sync_log;
+pub mod tabs;
+#[cfg(test)]
+mod test_dir;
+pub mod ui_meta;
+pub mod worker;
+
+pub use component::Component;
+pub use global_state::State;
+
+#[derive(Debug)]
+pub enum ProgramError {
+    NotDirectory(PathBuf),
+    ExistingDirectoryAt(PathBuf),
+    ExistingFileAt(PathBuf),
+    GenerationCheckFailed,
+    LowDiskSpace { free: u64, min_free: u64 },
+    ChangedOnDisk(Vec<PathBuf>),
+    ProtectedPath(PathBuf),
+    Malformed(recovery::BrokenFile),
+    DeployOutsideProject(PathBuf),
+    MissingProfile(String),
+}
+
+impl Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgramError::NotDirectory(path_buf) => {
+                write!(f, "{} is not a directory", path_buf.display())
+            }
+            ProgramError::ExistingDirectoryAt(path_buf) => {
+                write!(f, "a directory exists at {}, aborting", path_buf.display())
+            }
+            ProgramError::ExistingFileAt(path_buf) => {
+                write!(f, "a file already exists at {}", path_buf.display())
+            }
+            ProgramError::GenerationCheckFailed => {
+                write!(f, "failed to check which generation the robot has")
+            }
+            ProgramError::LowDiskSpace { free, min_free } => {
+                write!(
+                    f,
+                    "only {} free, saving needs at least {} left over",
+                    disk_space::show_megabytes(*free),
+                    disk_space::show_megabytes(*min_free)
+                )
+            }
+            ProgramError::ChangedOnDisk(paths) => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "not saved, {paths} changed outside the app")
+            }
+            ProgramError::ProtectedPath(path_buf) => {
+                write!(
+                    f,
+                    "refusing to delete {}, it isn't a leftover backup or log",
+                    path_buf.display()
+                )
+            }
+            ProgramError::Malformed(broken) => write!(f, "{broken}"),
+            ProgramError::DeployOutsideProject(path_buf) => write!(
+                f,
+                "the deploy directory {} has to be a folder inside the project",
+                path_buf.display()
+            ),
+            ProgramError::MissingProfile(profile) => write!(
+                f,
+                "the file for profile {profile} is gone, it was deleted or moved outside the app"
+            ),
+        }
+    }
+}
+
+impl Error for ProgramError {}
@@ -1,62 +1,198 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 use anyhow::Result;
 use bumpalo::Bump;
-use component::Component;
-use egui::{Align2, Direction, ScrollArea, Ui};
+use egui::{
+    Align2, Color32, Direction, Key, KeyboardShortcut, Modifiers, RichText, ScrollArea, Ui,
+    ViewportCommand,
+};
 use egui_dock::{DockArea, DockState, NodeIndex, Style, SurfaceIndex, TabViewer};
-use egui_toast::{Toast, Toasts};
-use global_state::{GlobalEvents, State};
+use egui_toast::Toasts;
 use once_cell::sync::Lazy;
 use std::collections::BTreeSet;
-use std::error::Error;
-use std::fmt::Display;
-use std::path::PathBuf;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
-mod bindings;
-mod component;
-mod constants;
-mod global_state;
-mod number_input;
-mod search_selector;
-mod single_linked_list;
-mod tabs;
-
-use tabs::*;
+use bindings_gui::{
+    bindings::SaveMode,
+    component::{Component, EventStream},
+    global_state::{
+        collapse_changes, ConnectionStatus, GlobalEvents, State, DEFAULT_DEPLOY_SUBPATH,
+        DISK_CHECK_INTERVAL,
+    },
+    layout,
+    notifier::Notifier,
+    recovery::{open_containing_folder, recovery_panel, BrokenFile, Recovery},
+    render_stats::RenderStats,
+    settings::{self, Settings},
+    tabs::{self, *},
+    worker::Worker,
+};
 
 // for when external event loop support is added
 // mod sync_thread;
 
+#[cfg(test)]
+mod test_dir;
+
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 enum App {
     Initial {
-        error: Option<String>,
+        error: Option<StartError>,
+        settings: Settings,
+        // the last project, opened once we know shift isn't being held
+        reopen: Option<PathBuf>,
+        frames: u8,
+        // where the next project opened keeps its deploy files, empty for
+        // wherever it kept them last time
+        deploy_subpath: String,
     },
 
     Running {
         views: State,
         tree: DockState<Tab>,
         arena: Bump,
-        used_tabs: BTreeSet<TabType>,
+        stats: RenderStats,
+    },
+}
+
+/// why the start screen is showing instead of a project
+#[derive(Debug)]
+enum StartError {
+    Message(String),
+    // the project has a file that isn't valid json, offered ways to recover
+    Broken {
+        project: PathBuf,
+        broken: BrokenFile,
     },
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::Initial { error: None }
+impl StartError {
+    fn from_open(project: PathBuf, err: anyhow::Error) -> Self {
+        match BrokenFile::from_error(&err) {
+            Some(broken) => Self::Broken { project, broken },
+            None => Self::Message(err.to_string()),
+        }
+    }
+}
+
+// how many frames to watch for shift before reopening the last project
+const STARTUP_FRAMES: u8 = 3;
+
+// how often to look for finished saves and scans while the worker has some
+const WORKER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// the project a drop onto the start screen should open, a bindings.json
+/// counts as the project it's in
+fn dropped_project(paths: &[Option<&Path>], settings: &Settings) -> Result<PathBuf, &'static str> {
+    let [Some(path)] = paths else {
+        return Err("drop a single project folder to open it");
+    };
+
+    if path.is_dir() {
+        Ok(path.to_path_buf())
+    } else if path.file_name().is_some_and(|name| name == "bindings.json") {
+        let deploy_dir = path.parent();
+
+        let deploys_from =
+            |dir: &Path, subpath: PathBuf| Some(dir.join(subpath).as_path()) == deploy_dir;
+
+        // the project is the one the deploy directory belongs to, wherever
+        // that project keeps it
+        let mut projects = path.ancestors().skip(1);
+
+        projects
+            .clone()
+            .find(|dir| deploys_from(dir, settings.deploy_subpath(dir)))
+            .or_else(|| projects.find(|dir| deploys_from(dir, DEFAULT_DEPLOY_SUBPATH.into())))
+            .map(Path::to_path_buf)
+            .ok_or("couldn't find the project that bindings.json is in")
+    } else {
+        Err("only project folders and bindings.json can be opened")
     }
 }
 
 impl App {
+    fn new(settings: Result<Settings>, project: Option<PathBuf>) -> Self {
+        let (settings, error) = match settings {
+            Ok(settings) => (settings, None),
+            Err(err) => (
+                Settings::default(),
+                Some(StartError::Message(err.to_string())),
+            ),
+        };
+
+        if let Some(project) = project {
+            return Self::open(project, settings);
+        }
+
+        let reopen = if settings.reopen_last_project {
+            settings.last_project().cloned()
+        } else {
+            None
+        };
+
+        Self::Initial {
+            error,
+            settings,
+            reopen,
+            frames: 0,
+            deploy_subpath: String::new(),
+        }
+    }
+
+    fn open(path: PathBuf, mut settings: Settings) -> Self {
+        match State::from_directory(path.clone(), &settings.deploy_subpath(&path)) {
+            Ok(mut s) => {
+                s.worker = Worker::spawn();
+
+                if let Err(err) = s.prune_on_open() {
+                    s.load_warnings.push(err.to_string());
+                }
+
+                settings.add_recent(path);
+
+                if let Err(err) = settings.save() {
+                    s.load_warnings.push(err.to_string());
+                }
+
+                Self::from_views(s)
+            }
+            Err(err) => Self::Initial {
+                error: Some(StartError::from_open(path, err)),
+                settings,
+                reopen: None,
+                frames: 0,
+                deploy_subpath: String::new(),
+            },
+        }
+    }
+
     fn from_views(view: State) -> Self {
-        Self::Running {
-            views: view,
-            tree: DockState::new(vec![Tab {
+        // a missing or broken layout just starts with the one new tab
+        let tree = match layout::load(&view.deploy_dir) {
+            Some(saved) => saved.map_tabs(|tab| match tab {
+                Some(tab) => Tab {
+                    tab: Some(tab.build(&view)),
+                    name: tab.name(),
+                },
+                None => Tab {
+                    tab: None,
+                    name: "new tab",
+                },
+            }),
+            None => DockState::new(vec![Tab {
                 tab: None,
                 name: "new tab",
             }]),
+        };
+
+        Self::Running {
+            views: view,
+            tree,
             arena: Bump::new(),
-            used_tabs: BTreeSet::new(),
+            stats: RenderStats::default(),
         }
     }
 
@@ -69,10 +205,64 @@ impl eframe::App for App {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         match self {
             App::Initial { .. } => {}
-            App::Running { views, .. } => {
-                if let Some(p) = &mut views.sync_process {
+            App::Running {
+                views, arena, tree, ..
+            } => {
+                // edits not saved by hand in manual mode are saved rather than
+                // lost
+                let flushed = match views.flush(arena) {
+                    Ok(flushed) => flushed,
+                    Err(err) => {
+                        eprintln!("failed to save on exit: {err}");
+                        false
+                    }
+                };
+
+                // written with the saves below, there's nowhere left to show
+                // it failing so it's best effort
+                views.save_layout(&tab_layout(tree));
+
+                if let Err(err) = views.finish_io() {
+                    eprintln!("failed to save on exit: {err}");
+                }
+
+                // nothing is left to send the saves waiting on the running
+                // sync, so it's replaced with one that has them
+                if flushed && views.sync_pending {
+                    if let Err(err) = views.restart_sync() {
+                        eprintln!("failed to sync on exit: {err}");
+                    }
+                }
+
+                // the built in sync's thread goes with the app, so it's
+                // waited on here instead
+                while flushed && views.syncs.values().any(|sync| sync.progress.is_some()) {
+                    if views.poll_sync(SystemTime::now()).is_err() {
+                        break;
+                    }
+
+                    std::thread::sleep(WORKER_POLL_INTERVAL);
+                }
+
+                // the syncs the flush started carry the last edits to the
+                // robots, so they're left to finish
+                if !flushed {
+                    let _ = views.stop_sync();
+                }
+
+                if let Some(p) = &mut views.generation_check {
                     p.kill().unwrap()
                 }
+
+                if let Some((p, _)) = &mut views.connection_test {
+                    let _ = p.kill();
+                }
+
+                if let Some(p) = &mut views.pull_process {
+                    let _ = p.kill();
+                }
+
+                views.discard_pull();
             }
         }
     }
@@ -81,26 +271,157 @@ impl eframe::App for App {
         catppuccin_egui::set_theme(ctx, catppuccin_egui::MOCHA);
 
         if self.initial() {
+            set_title(ctx, "Bindings");
+
+            let mut open = None;
+
             egui::CentralPanel::default().show(ctx, |ui| match self {
-                Self::Initial { error } => {
+                Self::Initial {
+                    error,
+                    settings,
+                    reopen,
+                    frames,
+                    deploy_subpath,
+                } => {
+                    if reopen.is_some() {
+                        if ctx.input(|i| i.modifiers.shift) {
+                            *reopen = None;
+                        } else if *frames >= STARTUP_FRAMES {
+                            open = reopen.take();
+                        } else {
+                            *frames += 1;
+                            ctx.request_repaint();
+                        }
+                    }
+
                     if ui.button("Open Project Directory").clicked() {
                         if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                            match State::from_directory(path) {
-                                Ok(s) => {
-                                    *self = Self::from_views(s);
-                                    return;
+                            open = Some(path);
+                        }
+                    }
+
+                    if ui
+                        .checkbox(
+                            &mut settings.reopen_last_project,
+                            "reopen last project on startup",
+                        )
+                        .changed()
+                    {
+                        if let Err(err) = settings.save() {
+                            *error = Some(StartError::Message(err.to_string()));
+                        }
+                    }
+
+                    if !settings.recent_projects.is_empty() {
+                        ui.separator();
+                        ui.label("recent projects");
+
+                        for project in &settings.recent_projects {
+                            ui.horizontal(|ui| {
+                                if ui.button(project.display().to_string()).clicked() {
+                                    open = Some(project.clone());
+                                }
+
+                                let subpath = match deploy_subpath.trim() {
+                                    "" => settings.deploy_subpath(project),
+                                    edited => PathBuf::from(edited),
+                                };
+
+                                ui.weak(format!(
+                                    "deploys from {}",
+                                    project.join(subpath).display()
+                                ));
+                            });
+                        }
+                    }
+
+                    ui.collapsing("advanced", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("deploy directory in the project");
+
+                            ui.add(
+                                egui::TextEdit::singleline(deploy_subpath)
+                                    .hint_text(DEFAULT_DEPLOY_SUBPATH),
+                            );
+                        });
+
+                        ui.weak(
+                            "left empty, each project uses the one it was last opened with. \
+                            a new one is remembered for the project opened next",
+                        );
+                    });
+
+                    match error {
+                        Some(StartError::Message(err)) => {
+                            ui.label(err.as_str());
+                        }
+                        Some(StartError::Broken { project, broken }) => {
+                            ui.separator();
+                            ui.heading("this project has a broken file");
+
+                            match recovery_panel(ui, broken) {
+                                Some(Recovery::OpenFolder) => {
+                                    if let Err(err) = open_containing_folder(&broken.path) {
+                                        *error = Some(StartError::Message(err.to_string()));
+                                    }
                                 }
-                                Err(err) => *error = Some(err.to_string()),
+                                Some(Recovery::Retry) => open = Some(project.clone()),
+                                Some(Recovery::StartFresh) => match broken.set_aside() {
+                                    Ok(_) => open = Some(project.clone()),
+                                    Err(err) => *error = Some(StartError::Message(err.to_string())),
+                                },
+                                None => {}
                             }
                         }
+                        None => {}
                     }
 
-                    if let Some(err) = error {
-                        ui.label(err.as_str());
+                    let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+
+                    if !dropped.is_empty() {
+                        let paths = dropped
+                            .iter()
+                            .map(|file| file.path.as_deref())
+                            .collect::<Vec<_>>();
+
+                        match dropped_project(&paths, settings) {
+                            Ok(path) => open = Some(path),
+                            Err(err) => *error = Some(StartError::Message(err.to_string())),
+                        }
+                    }
+
+                    if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+                        let rect = ui.max_rect();
+
+                        ui.painter()
+                            .rect_filled(rect, 0.0, Color32::from_black_alpha(160));
+                        ui.painter().text(
+                            rect.center(),
+                            Align2::CENTER_CENTER,
+                            "drop to open",
+                            egui::FontId::proportional(24.0),
+                            Color32::WHITE,
+                        );
                     }
                 }
                 App::Running { .. } => panic!("impossible"),
             });
+
+            if let (
+                Some(path),
+                Self::Initial {
+                    settings,
+                    deploy_subpath,
+                    ..
+                },
+            ) = (open, &mut *self)
+            {
+                if !deploy_subpath.trim().is_empty() {
+                    settings.set_deploy_subpath(&path, Path::new(deploy_subpath.trim()));
+                }
+
+                *self = Self::open(path, mem::take(settings));
+            }
         }
 
         match self {
@@ -109,14 +430,112 @@ impl eframe::App for App {
                 views,
                 tree,
                 arena,
-                used_tabs,
+                stats,
             } => {
+                let frame_start = Instant::now();
+
+                if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::F12)) {
+                    stats.enabled = !stats.enabled;
+                }
+
                 let mut toasts = Toasts::new()
                     .anchor(Align2::LEFT_BOTTOM, (-10.0, -10.0))
                     .direction(Direction::BottomUp);
 
+                for warning in views.load_warnings.drain(..) {
+                    toasts.warning(warning);
+                }
+
                 let mut added_nodes = Vec::new();
 
+                if ctx.input_mut(|i| {
+                    i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::S))
+                }) {
+                    if let Err(err) = views.write_out(arena) {
+                        toasts.error(err.to_string());
+                    }
+                }
+
+                set_title(ctx, &views.window_title());
+
+                egui::TopBottomPanel::bottom("status strip").show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        if views.save_mode == SaveMode::Manual {
+                            let label = if views.unsaved { "save*" } else { "save" };
+
+                            if ui
+                                .add_enabled(views.unsaved, egui::Button::new(label))
+                                .on_hover_text("ctrl+s")
+                                .clicked()
+                            {
+                                if let Err(err) = views.write_out(arena) {
+                                    toasts.error(err.to_string());
+                                }
+                            }
+
+                            ui.separator();
+                        }
+
+                        ui.label(
+                            bumpalo::format!(in &arena, "generation {}", views.generation).as_str(),
+                        );
+
+                        ui.separator();
+
+                        ui.label(views.generation_status());
+
+                        ui.separator();
+
+                        if ui.button("check readiness").clicked() {
+                            views.readiness = Some(views.readiness_report());
+                            views.show_readiness = true;
+                        }
+
+                        let open_report = match &views.readiness {
+                            Some(unbound) if unbound.is_empty() => {
+                                ui.colored_label(Color32::from_rgb(0xa6, 0xe3, 0xa1), "✔ ready");
+                                false
+                            }
+                            Some(unbound) => ui
+                                .link(
+                                    RichText::new(format!("{} unbound", unbound.len()))
+                                        .color(Color32::from_rgb(0xf3, 0x8b, 0xa8)),
+                                )
+                                .clicked(),
+                            None => false,
+                        };
+
+                        if open_report {
+                            views.show_readiness = true;
+                        }
+
+                        ui.separator();
+
+                        if views.url().is_some() && ui.button("check robot").clicked() {
+                            if let Err(err) = views.check_robot_generation() {
+                                toasts.error(err.to_string());
+                            }
+                        }
+
+                        // edits waiting on the save delay aren't stuck yet
+                        if !views.pending.is_empty() && views.save_at.is_none() {
+                            ui.separator();
+
+                            pending_changes(ui, views, arena, &mut toasts);
+                        }
+                    });
+                });
+
+                if ctx.input_mut(|i| {
+                    i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::F))
+                }) {
+                    if let Some((_, Tab { tab: Some(t), .. })) = tree.find_active_focused() {
+                        t.focus_search();
+                    }
+                }
+
+                let used_tabs = used_tabs(tree);
+
                 DockArea::new(tree)
                     .style(Style::from_egui(ctx.style().as_ref()))
                     .show_add_buttons(true)
@@ -128,7 +547,8 @@ impl eframe::App for App {
                             toasts: &mut toasts,
                             arena,
                             added_nodes: &mut added_nodes,
-                            used_tabs,
+                            used_tabs: used_tabs.clone(),
+                            stats,
                         },
                     );
 
@@ -140,6 +560,82 @@ impl eframe::App for App {
                     });
                 }
 
+                if views.show_readiness {
+                    readiness_dialog(ctx, views);
+                }
+
+                if !views.unnormalized_profiles.is_empty() {
+                    let mut events = EventStream::new();
+
+                    unnormalized_profiles_dialog(ctx, views, &events);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
+                if views.show_quarantine {
+                    let mut events = EventStream::new();
+
+                    quarantine_dialog(ctx, views, &events);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
+                if views.missing_profile.is_some() {
+                    let mut events = EventStream::new();
+
+                    missing_profile_dialog(ctx, views, &events);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
+                if views.unsaved_switch.is_some() {
+                    let mut events = EventStream::new();
+
+                    unsaved_switch_dialog(ctx, views, &events);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
+                if views.broken_profile.is_some() {
+                    let mut events = EventStream::new();
+
+                    broken_profile_dialog(ctx, views, &events, &mut toasts);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
+                if views.external_change.is_some() {
+                    let mut events = EventStream::new();
+
+                    external_change_dialog(ctx, views, &events);
+
+                    if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                        toasts.error(err.to_string());
+                    }
+
+                    arena.reset();
+                }
+
                 if tree.main_surface().is_empty() {
                     println!("adding new tab");
                     tree.push_to_first_leaf(Tab {
@@ -148,74 +644,503 @@ impl eframe::App for App {
                     });
                 }
 
-                if let Some(child) = &mut views.sync_process {
-                    match child.try_wait() {
-                        Ok(exit) => {
-                            if let Some(status) = exit {
-                                if !status.success() {
-                                    toasts.add(Toast {
-                                        kind: egui_toast::ToastKind::Error,
-                                        text: "failed to sync".into(),
-                                        ..Default::default()
-                                    });
-                                }
+                match views.poll_sync(SystemTime::now()) {
+                    Ok(ended) => {
+                        for sync in &ended {
+                            println!("exited");
 
-                                println!("exited");
+                            if sync.success {
+                                toasts.success(format!(
+                                    "synced to {} ({}) in {:.1}s",
+                                    sync.target,
+                                    sync.url,
+                                    sync.took.unwrap_or_default().as_secs_f32()
+                                ));
+                            } else {
+                                toasts.error(views.sync_failure(&sync.target));
+                            }
+                        }
 
-                                views.sync_process = None;
+                        if ended.iter().any(|sync| sync.success) {
+                            if let Some(sizes) = views.sync_sizes.take() {
+                                toasts.info(sizes.to_string());
                             }
+
+                            if let Err(err) = views.check_robot_generation() {
+                                toasts.error(err.to_string());
+                            }
+                        }
+                    }
+                    Err(err) => toasts.error(err.to_string()),
+                }
+
+                // the built in sync's progress comes in without waking the
+                // frame
+                if views.syncs.values().any(|sync| sync.progress.is_some()) {
+                    ctx.request_repaint_after(WORKER_POLL_INTERVAL);
+                }
+
+                if let Err(err) = views.poll_generation_check() {
+                    toasts.error(err.to_string());
+                }
+
+                match views.poll_connection_test() {
+                    Ok(Some(status @ ConnectionStatus::Reachable(_))) => {
+                        toasts.success(status.to_string());
+                    }
+                    Ok(Some(status)) => {
+                        toasts.error(status.to_string());
+                    }
+                    Ok(None) => {}
+                    Err(err) => toasts.error(format!("failed to wait on connection test {err}")),
+                }
+
+                match views.poll_pull() {
+                    Ok(true) => {
+                        toasts.info("downloaded from the robot, see the syncing tab".to_string())
+                    }
+                    Ok(false) => {}
+                    Err(err) => toasts.error(err.to_string()),
+                }
+
+                // nothing else wakes the frame when ssh gives up
+                if views.connection_test.is_some() || views.pull_process.is_some() {
+                    ctx.request_repaint_after(WORKER_POLL_INTERVAL);
+                }
+
+                if let Err(err) = views.poll_worker() {
+                    toasts.error(err.to_string());
+                }
+
+                // the worker's results are picked up on the next frame
+                if views.worker.busy() {
+                    ctx.request_repaint_after(WORKER_POLL_INTERVAL);
+                }
+
+                if let Err(err) = views.check_disk(Instant::now()) {
+                    toasts.error(err.to_string());
+                }
+
+                // keeps looking while nothing else is happening
+                ctx.request_repaint_after(DISK_CHECK_INTERVAL);
+
+                if let Err(err) = views.flush_if_due(Instant::now(), arena) {
+                    toasts.error(err.to_string());
+                }
+
+                if let Some(at) = views.save_at {
+                    ctx.request_repaint_after(at.saturating_duration_since(Instant::now()));
+                }
+
+                // saves made while the last sync ran go out together
+                if views.pending_sync_due() {
+                    if let Err(err) = views.finish_io().and_then(|_| views.start_sync()) {
+                        toasts.error(err.to_string());
+                    }
+                }
+
+                if let Some(at) = views.sync_retry_at {
+                    if views.sync_retry_due(Instant::now()) {
+                        if let Err(err) = views.finish_io().and_then(|_| views.start_sync()) {
+                            toasts.error(err.to_string());
                         }
-                        Err(err) => {
-                            toasts.add(Toast { kind: egui_toast::ToastKind::Error, text: bumpalo::format!(in &arena, "failed to wait on sync process {}", err).as_str().into(), ..Default::default() });
+                    } else {
+                        ctx.request_repaint_after(at.saturating_duration_since(Instant::now()));
+                    }
+                }
+
+                if let Some(at) = views.resync_at {
+                    if views.resync_due(Instant::now()) {
+                        let mut events = EventStream::new();
+
+                        events.add_event(GlobalEvents::ForceSync);
+
+                        if let Err(err) = views.process_events(&mut events, arena, &mut toasts) {
+                            toasts.error(err.to_string());
                         }
+
+                        arena.reset();
+                    } else {
+                        ctx.request_repaint_after(at.saturating_duration_since(Instant::now()));
                     }
                 }
 
+                if views.layout_due {
+                    views.save_layout(&tab_layout(tree));
+                }
+
                 toasts.show(ctx);
+
+                if stats.enabled {
+                    stats.frame.record(frame_start.elapsed());
+                    stats.show(ctx, &used_tabs, &mut toasts);
+                }
             }
         }
     }
 }
 
-#[derive(Debug)]
-enum ProgramError {
-    NotDirectory(PathBuf),
-    ExistingDirectoryAt(PathBuf),
+/// only sent when it changes, winit redoes the title on every command
+fn set_title(ctx: &egui::Context, title: &str) {
+    if ctx.input(|i| i.viewport().title.as_deref() != Some(title)) {
+        ctx.send_viewport_cmd(ViewportCommand::Title(title.to_string()));
+    }
 }
 
-impl Display for ProgramError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ProgramError::NotDirectory(path_buf) => {
-                write!(f, "{} is not a directory", path_buf.display())
+/// edits that couldn't be written yet, with ways to retry or drop them
+fn pending_changes(ui: &mut Ui, views: &mut State, arena: &Bump, toasts: &mut Toasts) {
+    let changes = collapse_changes(&views.pending);
+
+    let title = bumpalo::format!(in arena, "{} pending changes", changes.len());
+
+    ui.menu_button(
+        RichText::new(title.as_str()).color(Color32::from_rgb(0xf9, 0xe2, 0xaf)),
+        |ui| {
+            if changes.is_empty() {
+                ui.label("the pending edits cancel each other out");
             }
-            ProgramError::ExistingDirectoryAt(path_buf) => {
-                write!(f, "a directory exists at {}, aborting", path_buf.display())
+
+            ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for change in &changes {
+                    ui.label(change.to_string());
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("flush now").clicked() {
+                    if let Err(err) = views.write_out(arena) {
+                        toasts.error(err.to_string());
+                    }
+
+                    ui.close_menu();
+                }
+
+                // what's on disk is from before every pending edit
+                if ui.button("discard pending").clicked() {
+                    if let Err(err) = views.reload() {
+                        toasts.error(err.to_string());
+                    }
+
+                    ui.close_menu();
+                }
+            });
+        },
+    );
+}
+
+fn readiness_dialog(ctx: &egui::Context, views: &mut State) {
+    let mut open = true;
+
+    egui::Window::new("readiness")
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| match &views.readiness {
+            Some(unbound) if !unbound.is_empty() => {
+                ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for line in unbound {
+                        ui.label(line.as_str());
+                    }
+                });
             }
-        }
+            _ => {
+                ui.colored_label(
+                    Color32::from_rgb(0xa6, 0xe3, 0xa1),
+                    "✔ everything required is bound and nothing is set aside",
+                );
+            }
+        });
+
+    views.show_readiness = open;
+}
+
+fn quarantine_dialog(ctx: &egui::Context, views: &State, output: &EventStream<GlobalEvents>) {
+    egui::Window::new("unrecognized profile data")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(
+                "these parts of the profile don't fit the current layout and were set aside. \
+                kept items are written back out untouched when saving.",
+            );
+
+            ui.separator();
+
+            ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for line in views.quarantine.summary() {
+                    ui.label(line);
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("keep").clicked() {
+                    output.add_event(GlobalEvents::KeepQuarantine);
+                }
+
+                if ui.button("discard").clicked() {
+                    output.add_event(GlobalEvents::DiscardQuarantine);
+                }
+            });
+        });
+}
+
+/// profile files named before names were NFC normalized, which look the same
+/// as the normalized name but aren't found by it
+fn unnormalized_profiles_dialog(
+    ctx: &egui::Context,
+    views: &State,
+    output: &EventStream<GlobalEvents>,
+) {
+    egui::Window::new("profile names to normalize")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(
+                "these profiles were saved with names that aren't NFC normalized. renaming \
+                them changes their files in bindings/.",
+            );
+
+            ui.separator();
+
+            ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for profile in &views.unnormalized_profiles {
+                    ui.label(format!("\"{}\"", profile.escape_unicode()));
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("rename them").clicked() {
+                    output.add_event(GlobalEvents::NormalizeProfileNames);
+                }
+
+                if ui
+                    .button("leave them")
+                    .on_hover_text("asked again the next time the project is opened")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::KeepProfileNames);
+                }
+            });
+        });
+}
+
+fn external_change_dialog(ctx: &egui::Context, views: &State, output: &EventStream<GlobalEvents>) {
+    egui::Window::new("files changed outside the app")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(
+                "these files changed outside the app since they were loaded or last \
+                saved, nothing is written until you pick whose version to keep.",
+            );
+
+            ui.separator();
+
+            ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for path in views.external_change.iter().flatten() {
+                    ui.label(path.display().to_string());
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button("keep mine (overwrite)")
+                    .on_hover_text("overwrite the files with what's open here")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::KeepMine);
+                }
+
+                if ui
+                    .button("reload from disk")
+                    .on_hover_text("reload from disk, unsaved changes here are lost")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::TakeTheirs);
+                }
+
+                if ui
+                    .button("merge")
+                    .on_hover_text("keep mine and save theirs as a profile to merge from")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::MergeTheirs);
+                }
+            });
+        });
+}
+
+/// switching to a profile whose file was deleted from outside the app, which
+/// would otherwise open it empty and save that over it
+fn missing_profile_dialog(
+    ctx: &egui::Context,
+    views: &mut State,
+    output: &EventStream<GlobalEvents>,
+) {
+    let Some(profile) = views.missing_profile.clone() else {
+        return;
+    };
+
+    let mut stay = false;
+
+    egui::Window::new(format!("profile {profile} is gone"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "bindings/{profile}.json was deleted or moved outside the app"
+            ));
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button("start it empty")
+                    .on_hover_text("no controllers or bindings")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::ResetProfile(profile.clone()));
+                }
+
+                stay = ui.button(format!("stay on {}", views.profile)).clicked();
+            });
+        });
+
+    if stay {
+        views.missing_profile = None;
     }
 }
 
-impl Error for ProgramError {}
+/// which tab is where, for saving
+fn tab_layout(tree: &DockState<Tab>) -> layout::Layout {
+    tree.map_tabs(|tab| tab.tab.as_ref().map(|t| t.tab_type()))
+}
+
+/// switching profiles in manual save mode with edits that haven't been saved,
+/// which would otherwise be written or lost without asking
+fn unsaved_switch_dialog(
+    ctx: &egui::Context,
+    views: &mut State,
+    output: &EventStream<GlobalEvents>,
+) {
+    let Some(profile) = views.unsaved_switch.clone() else {
+        return;
+    };
+
+    let mut stay = false;
+
+    egui::Window::new(format!("switch to {profile}?"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{} has edits that haven't been saved",
+                views.profile
+            ));
+
+            ui.horizontal(|ui| {
+                if ui.button("save and switch").clicked() {
+                    output.add_event(GlobalEvents::SaveAndSwitch);
+                }
+
+                if ui
+                    .button("discard and switch")
+                    .on_hover_text("reload from disk, unsaved changes here are lost")
+                    .clicked()
+                {
+                    output.add_event(GlobalEvents::DiscardAndSwitch);
+                }
+
+                stay = ui.button(format!("stay on {}", views.profile)).clicked();
+            });
+        });
+
+    if stay {
+        views.unsaved_switch = None;
+    }
+}
+
+/// switching to a profile whose file isn't valid json, with the same ways out
+/// as the start screen
+fn broken_profile_dialog(
+    ctx: &egui::Context,
+    views: &mut State,
+    output: &EventStream<GlobalEvents>,
+    toasts: &mut Toasts,
+) {
+    let Some((profile, broken)) = views.broken_profile.clone() else {
+        return;
+    };
+
+    let mut open = true;
+
+    egui::Window::new(format!("profile {profile} is broken"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(format!("still on {}", views.profile));
+
+            ui.separator();
+
+            match recovery_panel(ui, &broken) {
+                Some(Recovery::OpenFolder) => {
+                    if let Err(err) = open_containing_folder(&broken.path) {
+                        toasts.error(err.to_string());
+                    }
+                }
+                Some(Recovery::Retry) => {
+                    output.add_event(GlobalEvents::SetProfile(profile.clone()))
+                }
+                Some(Recovery::StartFresh) => match broken.set_aside() {
+                    Ok(_) => output.add_event(GlobalEvents::ResetProfile(profile.clone())),
+                    Err(err) => toasts.error(err.to_string()),
+                },
+                None => {}
+            }
+        });
+
+    if !open {
+        views.broken_profile = None;
+    }
+}
 
 struct Tabs<'a> {
     view: &'a mut State,
     toasts: &'a mut Toasts,
     arena: &'a mut Bump,
     added_nodes: &'a mut Vec<(SurfaceIndex, NodeIndex)>,
-    used_tabs: &'a mut BTreeSet<tabs::TabType>,
+    // only tracks changes within a frame, it is rebuilt from the tree every frame
+    used_tabs: BTreeSet<tabs::TabType>,
+    stats: &'a mut RenderStats,
 }
 
 impl Tabs<'_> {
     fn add_error(&mut self, error: String) {
-        self.toasts.add(Toast {
-            kind: egui_toast::ToastKind::Error,
-            text: error.into(),
-            ..Default::default()
-        });
+        self.toasts.error(error);
     }
 }
 
+/// the tab types that already have a tab open somewhere in the tree
+fn used_tabs(tree: &DockState<Tab>) -> BTreeSet<TabType> {
+    tree.iter_all_tabs()
+        .filter_map(|(_, tab)| tab.tab.as_ref().map(|t| t.tab_type()))
+        .collect()
+}
+
 #[derive(Debug)]
 struct Tab {
     tab: Option<Box<dyn Component<OutputEvents = GlobalEvents, Environment = State>>>,
@@ -244,22 +1169,28 @@ impl TabViewer for Tabs<'_> {
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
         match &mut tab.tab {
             Some(t) => {
+                let start = self.stats.enabled.then(Instant::now);
+
                 match self.view.display_tab(ui, t, self.toasts, self.arena) {
                     Ok(_) => {}
                     Err(err) => {
                         self.add_error(err.to_string());
                     }
                 };
+
+                if let Some(start) = start {
+                    self.stats.record_tab(t.tab_type(), start.elapsed());
+                }
             }
             None => {
                 ScrollArea::vertical().show(ui, |ui| {
                     let mut new_tab: Option<TabType> = None;
 
-                    for i in Lazy::force(&ALL_TABS).difference(self.used_tabs) {
+                    for i in Lazy::force(&ALL_TABS).difference(&self.used_tabs) {
                         if ui.button(i.name()).clicked() {
                             new_tab = Some(*i);
 
-                            let b = i.build();
+                            let b = i.build(self.view);
 
                             tab.tab = Some(b);
                             tab.name = i.name();
@@ -273,6 +1204,10 @@ impl TabViewer for Tabs<'_> {
             }
         }
 
+        if self.stats.enabled {
+            self.stats.record_arena(self.arena.allocated_bytes());
+        }
+
         self.arena.reset();
     }
 
@@ -282,6 +1217,8 @@ impl TabViewer for Tabs<'_> {
 }
 
 fn main() -> Result<(), eframe::Error> {
+    // accesskit is on through the eframe feature, screen readers see the
+    // names given to the icon buttons in accessible.rs
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size((400.0, 300.0)),
         ..eframe::NativeOptions::default()
@@ -299,10 +1236,12 @@ fn main() -> Result<(), eframe::Error> {
     //     &eventloop,
     // );
 
+    let project = settings::project_from_args(std::env::args().skip(1));
+
     eframe::run_native(
         "Bindings",
         native_options,
-        Box::new(|_| Ok(Box::<App>::default())),
+        Box::new(|_| Ok(Box::new(App::new(Settings::load(), project)))),
     )
 }
 
@@ -366,3 +1305,78 @@ fn main() -> Result<(), eframe::Error> {
 //     file.write_all(serde_json::to_string(&savedata).unwrap().as_bytes())
 //         .unwrap();
 // }
+
+#[cfg(test)]
+mod test {
+    use egui_dock::TabIndex;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn closed_tab_returns_to_picker() {
+        let state = State::default();
+
+        let mut tree = DockState::new(vec![
+            Tab {
+                tab: Some(TabType::Streams.build(&state)),
+                name: TabType::Streams.name(),
+            },
+            Tab {
+                tab: None,
+                name: "new tab",
+            },
+        ]);
+
+        assert!(used_tabs(&tree).contains(&TabType::Streams));
+
+        // removing the tab without going through on_close used to leave the
+        // type marked as used forever
+        tree.remove_tab((SurfaceIndex::main(), NodeIndex::root(), TabIndex(0)));
+
+        let used = used_tabs(&tree);
+
+        assert!(ALL_TABS.difference(&used).any(|t| *t == TabType::Streams));
+    }
+
+    #[test]
+    fn dropping_a_project() {
+        let temp = temp_dir("drop");
+        let dir = temp.path().to_path_buf();
+        let deploy = dir.join("src/main/deploy");
+        std::fs::create_dir_all(&deploy).unwrap();
+
+        let bindings = deploy.join("bindings.json");
+        std::fs::write(&bindings, "{}").unwrap();
+
+        let mut settings = Settings::default();
+
+        assert_eq!(dropped_project(&[Some(&dir)], &settings), Ok(dir.clone()));
+        assert_eq!(
+            dropped_project(&[Some(&bindings)], &settings),
+            Ok(dir.clone())
+        );
+
+        assert!(dropped_project(&[Some(&dir), Some(&dir)], &settings).is_err());
+        assert!(dropped_project(&[None], &settings).is_err());
+        assert!(dropped_project(&[Some(&dir.join("README.md"))], &settings).is_err());
+
+        // a project that deploys from somewhere else is found through settings
+        let elsewhere = dir.join("deploy").join("bindings.json");
+        std::fs::create_dir_all(elsewhere.parent().unwrap()).unwrap();
+        std::fs::write(&elsewhere, "{}").unwrap();
+
+        assert!(dropped_project(&[Some(&elsewhere)], &settings).is_err());
+
+        settings.set_deploy_subpath(&dir, Path::new("deploy"));
+
+        assert_eq!(
+            dropped_project(&[Some(&elsewhere)], &settings),
+            Ok(dir.clone())
+        );
+        assert_eq!(
+            dropped_project(&[Some(&bindings)], &settings),
+            Ok(dir.clone())
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    cmp::Reverse,
+    fmt::Display,
+    fs::{canonicalize, read_dir, remove_file},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{audit_log, disk_space, sync_log, ProgramError};
+
+/// how long pruning may hold up opening a project, the worker finishes the
+/// rest
+pub const OPEN_BUDGET: Duration = Duration::from_millis(200);
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// files the app leaves next to the deploy files that nothing needs to load
+/// the project, each kind is kept to the retention on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leftover {
+    // the version before the last save, at `file_hashes::backup_path`. only
+    // the ones whose file is gone are ever pruned
+    Backups,
+    // files that couldn't be read, moved aside when starting fresh
+    Broken,
+    // the change log from before it was rotated, and sync.log
+    Logs,
+}
+
+impl Leftover {
+    pub const ALL: [Leftover; 3] = [Leftover::Backups, Leftover::Broken, Leftover::Logs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Leftover::Backups => "backups",
+            Leftover::Broken => "broken files",
+            Leftover::Logs => "old logs",
+        }
+    }
+
+    /// which kind `path` is, none for the live deploy files and anything else
+    /// the app didn't leave behind
+    pub fn of(path: &Path, deploy_dir: &Path) -> Option<Self> {
+        if path == audit_log::old_path(deploy_dir) || path == sync_log::path(deploy_dir) {
+            return Some(Leftover::Logs);
+        }
+
+        let name = path.file_name()?.to_str()?;
+
+        if name.ends_with(".bak") {
+            Some(Leftover::Backups)
+        } else if name.ends_with(".broken") {
+            Some(Leftover::Broken)
+        } else {
+            None
+        }
+    }
+}
+
+/// how much of each kind of leftover to keep, newest first
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    pub keep: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            keep: 20,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// `.bindings-gui` in the project
+pub fn aux_root(project: &Path) -> PathBuf {
+    project.join(".bindings-gui")
+}
+
+/// the directories leftovers are looked for in, not below them
+fn leftover_dirs(deploy_dir: &Path) -> [PathBuf; 2] {
+    [deploy_dir.to_owned(), deploy_dir.join("bindings")]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    pub size: u64,
+}
+
+/// the leftovers of `kind` in `deploy_dir`, newest first. symlinks and
+/// directories are never counted
+pub fn entries(deploy_dir: &Path, kind: Leftover) -> Vec<Entry> {
+    let mut entries = leftover_dirs(deploy_dir)
+        .iter()
+        .flat_map(|dir| read_dir(dir).into_iter().flatten().flatten())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| Leftover::of(&entry.path(), deploy_dir) == Some(kind))
+        .map(|entry| {
+            let metadata = entry.metadata().ok();
+
+            Entry {
+                modified: metadata
+                    .as_ref()
+                    .and_then(|m| m.modified().ok())
+                    .unwrap_or(SystemTime::UNIX_EPOCH),
+                size: metadata.map_or(0, |m| m.len()),
+                path: entry.path(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort_by_key(|entry| Reverse(entry.modified));
+
+    entries
+}
+
+/// bytes used by the leftovers of `kind`
+pub fn usage(deploy_dir: &Path, kind: Leftover) -> u64 {
+    entries(deploy_dir, kind)
+        .iter()
+        .map(|entry| entry.size)
+        .sum()
+}
+
+/// the entries `retention` says to delete, `entries` has to be newest first
+pub fn expired(entries: &[Entry], retention: Retention, now: SystemTime) -> Vec<PathBuf> {
+    let max_age = DAY * retention.max_age_days as u32;
+
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(i, entry)| {
+            *i >= retention.keep
+                || now
+                    .duration_since(entry.modified)
+                    .is_ok_and(|age| age > max_age)
+        })
+        .map(|(_, entry)| entry.path.clone())
+        .collect()
+}
+
+/// whether `path` is the backup of a deploy file that's still there, the copy
+/// `file_hashes::read_or_backup` recovers it from. only backups left after
+/// their file went away count against the retention
+fn is_live_backup(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".bak"))
+        .is_some_and(|live| path.with_file_name(live).exists())
+}
+
+/// fails unless `path` is a file directly in the deploy directory or its
+/// bindings directory that the app left behind, which the live deploy files
+/// never are
+pub fn ensure_leftover(path: &Path, deploy_dir: &Path) -> Result<()> {
+    let real = |path: &Path| canonicalize(path).unwrap_or_else(|_| path.to_owned());
+
+    let in_deploy = path.parent().is_some_and(|parent| {
+        leftover_dirs(deploy_dir)
+            .iter()
+            .any(|dir| real(parent) == real(dir))
+    });
+
+    let is_file = path.symlink_metadata().is_ok_and(|m| m.is_file());
+
+    if !in_deploy || !is_file || Leftover::of(path, deploy_dir).is_none() {
+        Err(ProgramError::ProtectedPath(path.to_owned()))?;
+    }
+
+    Ok(())
+}
+
+/// deletes a leftover, returning how big it was
+pub fn delete(path: &Path, deploy_dir: &Path) -> Result<u64> {
+    ensure_leftover(path, deploy_dir)?;
+
+    let size = path.symlink_metadata()?.len();
+
+    remove_file(path)?;
+
+    Ok(size)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PruneReport {
+    pub removed: usize,
+    pub freed: u64,
+    // expired but not deleted yet because time ran out
+    pub left: Vec<PathBuf>,
+}
+
+impl Display for PruneReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "removed {} old files, freed {}",
+            self.removed,
+            disk_space::show_megabytes(self.freed)
+        )?;
+
+        if !self.left.is_empty() {
+            write!(f, ", {} left for later", self.left.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// deletes whatever `retention` says to of every kind of leftover, stopping
+/// at `deadline` if there is one
+pub fn prune(
+    deploy_dir: &Path,
+    retention: Retention,
+    now: SystemTime,
+    deadline: Option<Instant>,
+) -> Result<PruneReport> {
+    let mut report = PruneReport::default();
+
+    for kind in Leftover::ALL {
+        let entries = entries(deploy_dir, kind)
+            .into_iter()
+            .filter(|entry| !is_live_backup(&entry.path))
+            .collect::<Vec<_>>();
+
+        for path in expired(&entries, retention, now) {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                report.left.push(path);
+                continue;
+            }
+
+            report.freed += delete(&path, deploy_dir)?;
+            report.removed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{create_dir_all, write};
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    /// a deploy directory with its bindings directory, inside a temporary one
+    /// that leaves room for files outside of it
+    fn deploy(name: &str) -> (TempDir, PathBuf) {
+        let temp = temp_dir(&format!("maintenance-{name}"));
+        let deploy = temp.path().join("deploy");
+
+        create_dir_all(deploy.join("bindings")).unwrap();
+
+        (temp, deploy)
+    }
+
+    fn entry(name: &str, days_old: u64, now: SystemTime) -> Entry {
+        Entry {
+            path: PathBuf::from(name),
+            modified: now - DAY * days_old as u32,
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest() {
+        let now = SystemTime::now();
+
+        let entries = [
+            entry("a", 0, now),
+            entry("b", 1, now),
+            entry("c", 2, now),
+            entry("d", 40, now),
+        ];
+
+        let retention = Retention {
+            keep: 2,
+            max_age_days: 30,
+        };
+
+        assert_eq!(
+            expired(&entries, retention, now),
+            vec![PathBuf::from("c"), PathBuf::from("d")]
+        );
+
+        let retention = Retention {
+            keep: 10,
+            max_age_days: 30,
+        };
+
+        assert_eq!(expired(&entries, retention, now), vec![PathBuf::from("d")]);
+    }
+
+    #[test]
+    fn never_deletes_deploy_files() {
+        let (temp, deploy) = deploy("protected");
+        let outside = temp.path().join("outside.bak");
+
+        for file in [
+            deploy.join("bindings.json"),
+            deploy.join("bindings.json.bak"),
+            deploy.join("profile"),
+            deploy.join("bindings").join("default.json"),
+            deploy.join("bindings").join("default.json.bak"),
+            audit_log::path(&deploy),
+            outside.clone(),
+        ] {
+            write(file, "{}").unwrap();
+        }
+
+        create_dir_all(deploy.join("nested.bak")).unwrap();
+
+        assert!(ensure_leftover(&deploy.join("bindings.json.bak"), &deploy).is_ok());
+        assert!(ensure_leftover(&deploy.join("bindings/default.json.bak"), &deploy).is_ok());
+
+        assert!(ensure_leftover(&deploy, &deploy).is_err());
+        assert!(ensure_leftover(&deploy.join("bindings.json"), &deploy).is_err());
+        assert!(ensure_leftover(&deploy.join("profile"), &deploy).is_err());
+        assert!(ensure_leftover(&deploy.join("bindings").join("default.json"), &deploy).is_err());
+        assert!(ensure_leftover(&audit_log::path(&deploy), &deploy).is_err());
+        assert!(ensure_leftover(&deploy.join("nested.bak"), &deploy).is_err());
+        assert!(ensure_leftover(&outside, &deploy).is_err());
+        assert!(ensure_leftover(
+            &deploy
+                .join("bindings")
+                .join("..")
+                .join("..")
+                .join(outside.file_name().unwrap()),
+            &deploy
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn prune_only_touches_leftovers() {
+        let (_temp, deploy) = deploy("prune");
+
+        write(deploy.join("bindings.json"), "{}").unwrap();
+        write(audit_log::path(&deploy), "{}").unwrap();
+
+        for i in 0..5 {
+            write(deploy.join("bindings").join(format!("{i}.json.bak")), "bak").unwrap();
+        }
+
+        write(deploy.join("bindings.json.broken"), "{").unwrap();
+
+        let retention = Retention {
+            keep: 2,
+            max_age_days: 30,
+        };
+
+        let report = prune(&deploy, retention, SystemTime::now(), None).unwrap();
+
+        assert_eq!(report.removed, 3);
+        assert_eq!(report.freed, 9);
+        assert_eq!(entries(&deploy, Leftover::Backups).len(), 2);
+        assert_eq!(usage(&deploy, Leftover::Broken), 1);
+        assert!(deploy.join("bindings.json").exists());
+        assert!(audit_log::path(&deploy).exists());
+
+        let report = prune(&deploy, retention, SystemTime::now(), Some(Instant::now())).unwrap();
+
+        assert_eq!(report, PruneReport::default());
+    }
+
+    #[test]
+    fn prune_keeps_the_backups_of_live_files() {
+        let (_temp, deploy) = deploy("live-backups");
+
+        write(deploy.join("bindings.json"), "{}").unwrap();
+        write(deploy.join("bindings.json.bak"), "{}").unwrap();
+        write(deploy.join("bindings").join("default.json"), "{}").unwrap();
+        write(deploy.join("bindings").join("default.json.bak"), "{}").unwrap();
+        write(deploy.join("bindings").join("gone.json.bak"), "{}").unwrap();
+
+        let retention = Retention {
+            keep: 1,
+            max_age_days: 30,
+        };
+
+        // far enough ahead that every backup is past the age limit
+        let later = SystemTime::now() + DAY * 60;
+
+        let report = prune(&deploy, retention, later, None).unwrap();
+
+        assert_eq!(report.removed, 1);
+        assert!(deploy.join("bindings.json.bak").exists());
+        assert!(deploy.join("bindings").join("default.json.bak").exists());
+        assert!(!deploy.join("bindings").join("gone.json.bak").exists());
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    error::Error,
+    fmt::Display,
+    rc::Rc,
+};
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+// every name the user can type or paste is stored as NFC so that two names that
+// look identical are also equal byte for byte
+
+pub fn normalize(name: &str) -> String {
+    name.nfc().collect()
+}
+
+pub fn is_normalized(name: &str) -> bool {
+    is_nfc(name)
+}
+
+/// whether `name` is already in `names` once both are normalized
+pub fn name_taken<'a>(names: impl IntoIterator<Item = &'a Rc<String>>, name: &str) -> bool {
+    let name = normalize(name);
+
+    names.into_iter().any(|n| normalize(n) == name)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidName {
+    Empty,
+    SurroundingWhitespace,
+    PathSeparator,
+    ReservedCharacter(char),
+    ReservedName,
+    Taken,
+}
+
+impl Display for InvalidName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidName::Empty => write!(f, "name can't be empty"),
+            InvalidName::SurroundingWhitespace => {
+                write!(f, "name can't start or end with whitespace")
+            }
+            InvalidName::PathSeparator => write!(f, "name can't contain / or \\"),
+            InvalidName::ReservedCharacter(c) => {
+                write!(f, "name can't contain {}", c.escape_default())
+            }
+            InvalidName::ReservedName => write!(f, "name is reserved by the file system"),
+            InvalidName::Taken => write!(f, "a profile with that name already exists"),
+        }
+    }
+}
+
+impl Error for InvalidName {}
+
+const RESERVED_CHARACTERS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+const RESERVED_NAMES: [&str; 22] = [
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// profile names become file names so they have to be valid on every os, and
+/// can't collide with another profile on a case insensitive file system
+pub fn validate_profile_name<'a>(
+    name: &str,
+    existing: impl IntoIterator<Item = &'a Rc<String>>,
+) -> Result<(), InvalidName> {
+    if name.trim().is_empty() {
+        return Err(InvalidName::Empty);
+    }
+
+    if name.trim() != name {
+        return Err(InvalidName::SurroundingWhitespace);
+    }
+
+    if name.contains(['/', '\\']) {
+        return Err(InvalidName::PathSeparator);
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| RESERVED_CHARACTERS.contains(c) || c.is_control())
+    {
+        return Err(InvalidName::ReservedCharacter(c));
+    }
+
+    // windows drops trailing dots, which also rules out . and ..
+    if name.ends_with('.') || RESERVED_NAMES.contains(&name.to_lowercase().as_str()) {
+        return Err(InvalidName::ReservedName);
+    }
+
+    let folded = normalize(name).to_lowercase();
+
+    if existing
+        .into_iter()
+        .any(|n| normalize(n).to_lowercase() == folded)
+    {
+        return Err(InvalidName::Taken);
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Renamed {
+    pub from: Rc<String>,
+    pub to: Rc<String>,
+    /// the normalized name was already present so the two were merged
+    pub merged: bool,
+}
+
+impl Renamed {
+    pub fn describe(&self, kind: &str) -> String {
+        if self.merged {
+            format!(
+                "{kind} \"{}\" was a duplicate of \"{}\" that only differed by unicode normalization, merged them",
+                self.from.escape_unicode(),
+                self.to
+            )
+        } else {
+            format!("{kind} \"{}\" was not NFC normalized, renamed it", self.to)
+        }
+    }
+}
+
+pub fn normalize_set(set: &mut BTreeSet<Rc<String>>) -> Vec<Renamed> {
+    let bad: Vec<Rc<String>> = set.iter().filter(|n| !is_normalized(n)).cloned().collect();
+
+    bad.into_iter()
+        .map(|from| {
+            set.remove(&from);
+
+            let to = Rc::new(normalize(&from));
+
+            let merged = !set.insert(to.clone());
+
+            Renamed { from, to, merged }
+        })
+        .collect()
+}
+
+/// normalizes every key, using `merge` to combine the values when two keys end
+/// up the same
+pub fn normalize_keys<V>(
+    map: &mut BTreeMap<Rc<String>, V>,
+    mut merge: impl FnMut(&mut V, V),
+) -> Vec<Renamed> {
+    let bad: Vec<Rc<String>> = map.keys().filter(|n| !is_normalized(n)).cloned().collect();
+
+    bad.into_iter()
+        .map(|from| {
+            let value = map.remove(&from).unwrap();
+
+            let to = Rc::new(normalize(&from));
+
+            let merged = match map.entry(to.clone()) {
+                Entry::Occupied(mut e) => {
+                    merge(e.get_mut(), value);
+                    true
+                }
+                Entry::Vacant(e) => {
+                    e.insert(value);
+                    false
+                }
+            };
+
+            Renamed { from, to, merged }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PRECOMPOSED: &str = "\u{00C9}levate";
+    const COMBINING: &str = "E\u{0301}levate";
+
+    #[test]
+    fn both_forms_normalize_the_same() {
+        assert_ne!(PRECOMPOSED, COMBINING);
+        assert_eq!(normalize(PRECOMPOSED), normalize(COMBINING));
+        assert!(is_normalized(PRECOMPOSED));
+        assert!(!is_normalized(COMBINING));
+    }
+
+    #[test]
+    fn name_taken_ignores_normalization() {
+        let names = [Rc::new(PRECOMPOSED.to_string())];
+
+        assert!(name_taken(&names, COMBINING));
+        assert!(name_taken(&names, PRECOMPOSED));
+        assert!(!name_taken(&names, "Elevate"));
+    }
+
+    #[test]
+    fn set_merges_duplicates() {
+        let mut set = BTreeSet::from([
+            Rc::new(PRECOMPOSED.to_string()),
+            Rc::new(COMBINING.to_string()),
+        ]);
+
+        let renamed = normalize_set(&mut set);
+
+        assert_eq!(set.len(), 1);
+        assert_eq!(renamed.len(), 1);
+        assert!(renamed[0].merged);
+    }
+
+    #[test]
+    fn keys_are_renamed() {
+        let mut map = BTreeMap::from([(Rc::new(COMBINING.to_string()), vec![1])]);
+
+        let renamed = normalize_keys(&mut map, |a: &mut Vec<i32>, b| a.extend(b));
+
+        assert!(!renamed[0].merged);
+        assert_eq!(map[&Rc::new(PRECOMPOSED.to_string())], vec![1]);
+    }
+
+    #[test]
+    fn keys_merge() {
+        let mut map = BTreeMap::from([
+            (Rc::new(COMBINING.to_string()), vec![1]),
+            (Rc::new(PRECOMPOSED.to_string()), vec![2]),
+        ]);
+
+        normalize_keys(&mut map, |a: &mut Vec<i32>, b| a.extend(b));
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&Rc::new(PRECOMPOSED.to_string())], vec![2, 1]);
+    }
+
+    #[test]
+    fn profile_names() {
+        let existing = [Rc::new("Default".to_string())];
+
+        assert_eq!(
+            validate_profile_name("a/b", &existing),
+            Err(InvalidName::PathSeparator)
+        );
+        assert_eq!(
+            validate_profile_name("..", &existing),
+            Err(InvalidName::ReservedName)
+        );
+        assert_eq!(
+            validate_profile_name("  ", &existing),
+            Err(InvalidName::Empty)
+        );
+        assert_eq!(
+            validate_profile_name("default", &existing),
+            Err(InvalidName::Taken)
+        );
+        assert_eq!(
+            validate_profile_name("practice ", &existing),
+            Err(InvalidName::SurroundingWhitespace)
+        );
+        assert_eq!(
+            validate_profile_name("a:b", &existing),
+            Err(InvalidName::ReservedCharacter(':'))
+        );
+        assert_eq!(
+            validate_profile_name("CON", &existing),
+            Err(InvalidName::ReservedName)
+        );
+        assert_eq!(validate_profile_name("practice", &existing), Ok(()));
+    }
+}
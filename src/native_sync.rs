@@ -0,0 +1,217 @@
+use std::{
+    io::Write,
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+
+/// how long connecting to the robot can take before the sync gives up
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// how long the robot can go quiet in the middle of a sync
+const SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// keys tried from ~/.ssh when the agent doesn't get us in
+const KEY_FILES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+#[derive(Debug)]
+pub enum Progress {
+    // files sent so far out of all of them
+    Sent(usize, usize),
+    Finished(Result<()>),
+}
+
+/// a sync running over ssh on its own thread, sending its progress back
+#[derive(Debug)]
+pub struct NativeSync {
+    progress: Receiver<Progress>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// where to log in and what to send, `files` are paths under the remote
+/// deploy directory with their contents
+#[derive(Debug)]
+pub struct Upload {
+    pub url: String,
+    pub user: String,
+    // none tries the agent and keys only
+    pub password: Option<String>,
+    pub remote_path: String,
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl NativeSync {
+    pub fn spawn(upload: Upload) -> Self {
+        let (sender, progress) = channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let stop = cancelled.clone();
+
+        thread::spawn(move || {
+            let result = send(&upload, &sender, &stop);
+            let _ = sender.send(Progress::Finished(result));
+        });
+
+        Self {
+            progress,
+            cancelled,
+        }
+    }
+
+    /// the newest progress since the last poll
+    pub fn poll(&self) -> Option<Progress> {
+        let mut latest = None;
+
+        while let Ok(progress) = self.progress.try_recv() {
+            let finished = matches!(progress, Progress::Finished(_));
+
+            latest = Some(progress);
+
+            if finished {
+                break;
+            }
+        }
+
+        latest
+    }
+
+    /// stops before the next file, the one being sent is finished first
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// sftp doesn't expand ~, paths without a leading / are already from the
+/// user's home
+fn sftp_path(remote_path: &str) -> &str {
+    remote_path.strip_prefix("~/").unwrap_or(remote_path)
+}
+
+/// an error unless the robot's key is the one ~/.ssh/known_hosts has for it,
+/// the same as ssh with BatchMode on
+fn check_host_key(session: &Session, host: &str) -> Result<()> {
+    let known_hosts_file = dirs::home_dir()
+        .map(|home| home.join(".ssh").join("known_hosts"))
+        .with_context(|| "couldn't find the home directory for known_hosts")?;
+
+    let mut known_hosts = session.known_hosts()?;
+
+    if known_hosts_file.exists() {
+        known_hosts
+            .read_file(&known_hosts_file, KnownHostFileKind::OpenSSH)
+            .with_context(|| format!("failed to read {}", known_hosts_file.display()))?;
+    }
+
+    let (key, _) = session
+        .host_key()
+        .with_context(|| "the robot didn't send a host key")?;
+
+    match known_hosts.check_port(host, 22, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound => {
+            anyhow::bail!("{host} isn't in known_hosts, connect with ssh once to add it")
+        }
+        CheckResult::Mismatch => {
+            anyhow::bail!("{host}'s host key doesn't match known_hosts, it may not be the robot")
+        }
+        CheckResult::Failure => anyhow::bail!("couldn't check {host}'s host key"),
+    }
+}
+
+fn login(session: &Session, user: &str, password: Option<&str>) -> Result<()> {
+    let _ = session.userauth_agent(user);
+
+    if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+        for key in KEY_FILES {
+            if session.authenticated() {
+                break;
+            }
+
+            let key = ssh_dir.join(key);
+
+            if key.exists() {
+                let _ = session.userauth_pubkey_file(user, None, &key, None);
+            }
+        }
+    }
+
+    if !session.authenticated() {
+        if let Some(password) = password {
+            session
+                .userauth_password(user, password)
+                .with_context(|| format!("the robot didn't take the password for {user}"))?;
+        }
+    }
+
+    if !session.authenticated() {
+        anyhow::bail!("no key or password logged in to the robot as {user}");
+    }
+
+    Ok(())
+}
+
+fn send(upload: &Upload, progress: &Sender<Progress>, cancelled: &AtomicBool) -> Result<()> {
+    let address = (upload.url.as_str(), 22)
+        .to_socket_addrs()
+        .with_context(|| format!("couldn't find {}", upload.url))?
+        .next()
+        .with_context(|| format!("couldn't find {}", upload.url))?;
+
+    let tcp = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+        .with_context(|| format!("couldn't connect to {}", upload.url))?;
+
+    let mut session = Session::new()?;
+    session.set_tcp_stream(tcp);
+    session.set_timeout(SESSION_TIMEOUT.as_millis() as u32);
+    session
+        .handshake()
+        .with_context(|| "ssh handshake with the robot failed")?;
+
+    check_host_key(&session, &upload.url)?;
+
+    login(&session, &upload.user, upload.password.as_deref())?;
+
+    let sftp = session
+        .sftp()
+        .with_context(|| "the robot doesn't allow sftp")?;
+
+    // joined by hand, windows would put a \ in
+    let root = sftp_path(&upload.remote_path);
+
+    // already there after the first sync
+    let _ = sftp.mkdir(Path::new(&format!("{root}bindings")), 0o755);
+
+    for (n, (name, contents)) in upload.files.iter().enumerate() {
+        if cancelled.load(Ordering::Relaxed) {
+            anyhow::bail!("cancelled");
+        }
+
+        sftp.create(Path::new(&format!("{root}{name}")))
+            .map_err(std::io::Error::from)
+            .and_then(|mut file| file.write_all(contents))
+            .with_context(|| format!("failed to send {name}"))?;
+
+        let _ = progress.send(Progress::Sent(n + 1, upload.files.len()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn home_relative_paths_are_left_to_sftp() {
+        assert_eq!(sftp_path("~/deploy/"), "deploy/");
+        assert_eq!(sftp_path("/home/lvuser/deploy/"), "/home/lvuser/deploy/");
+    }
+}
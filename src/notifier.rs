@@ -0,0 +1,73 @@
+use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
+
+/// where state reports things to the user, toasts in the gui and a plain list
+/// everywhere else
+pub trait Notifier {
+    fn error(&mut self, message: String);
+    fn warning(&mut self, message: String);
+    fn info(&mut self, message: String);
+    fn success(&mut self, message: String);
+}
+
+impl Notifier for Toasts {
+    fn error(&mut self, message: String) {
+        self.add(Toast {
+            kind: ToastKind::Error,
+            text: message.into(),
+            ..Default::default()
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.add(Toast {
+            kind: ToastKind::Warning,
+            text: message.into(),
+            ..Default::default()
+        });
+    }
+
+    fn info(&mut self, message: String) {
+        self.add(Toast {
+            kind: ToastKind::Info,
+            text: message.into(),
+            options: ToastOptions::default().duration_in_seconds(4.0),
+            ..Default::default()
+        });
+    }
+
+    fn success(&mut self, message: String) {
+        self.add(Toast {
+            kind: ToastKind::Success,
+            text: message.into(),
+            options: ToastOptions::default().duration_in_seconds(4.0),
+            ..Default::default()
+        });
+    }
+}
+
+/// keeps every message, for tests and anything without a window
+#[derive(Debug, Default, Clone)]
+pub struct Collected {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub info: Vec<String>,
+    pub successes: Vec<String>,
+}
+
+impl Notifier for Collected {
+    fn error(&mut self, message: String) {
+        self.errors.push(message);
+    }
+
+    fn warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    fn info(&mut self, message: String) {
+        self.info.push(message);
+    }
+
+    fn success(&mut self, message: String) {
+        self.successes.push(message);
+    }
+}
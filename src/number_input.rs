@@ -1,5 +1,3 @@
-use std::str::FromStr;
-
 use anyhow::{Context, Result};
 use bumpalo::Bump;
 use chumsky::{
@@ -55,7 +53,7 @@ fn to_constructor(con: &dyn Fn(f64) -> Ast<'static>) -> &dyn Fn(f64) -> Ast<'sta
 fn parse<'a>(s: &'a str, arena: &'a Bump) -> Result<&'a Ast<'a>> {
     let res = recursive::recursive::<_, &'a Ast<'a>, extra::Err<Simple<char>>, _, _>(|a| {
         let num = regex("-?\\d+(\\.\\d*)?")
-            .map(|a| Ok::<_, <f64 as FromStr>::Err>(str::parse::<f64>(a)?))
+            .map(str::parse::<f64>)
             .unwrapped()
             .padded();
 
@@ -395,7 +393,7 @@ mod test {
         let text = "2.11";
         let arena = Bump::new();
 
-        let value = match parse(&text, &arena) {
+        let value = match parse(text, &arena) {
             Ok(ast) => match eval(ast) {
                 Some(NumOrUnit::Num(n)) => n,
                 _ => 0.0,
@@ -0,0 +1,324 @@
+use std::{ops::Range, rc::Rc};
+
+use bumpalo::Bump;
+use chumsky::{error::Simple, extra, prelude::any, span::SimpleSpan, IterParser, Parser};
+
+use crate::{
+    bindings::{
+        Binding, Button, ButtonLocation, ControllerType, Debounce, RunWhen, CONTROLLER_SLOTS,
+    },
+    search_selector::valid_result,
+    State,
+};
+
+/// what the quick add box in from bindings parsed to
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickAdd {
+    Ready(Binding, Rc<String>),
+    // more than one command fits what was typed
+    Ambiguous(Binding, Vec<Rc<String>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAddError {
+    // byte range of the text to highlight, empty when something is missing
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+type Word<'a> = (&'a str, Range<usize>);
+
+fn words<'a>(text: &'a str) -> Vec<Word<'a>> {
+    any::<&'a str, extra::Err<Simple<char>>>()
+        .filter(|c: &char| !c.is_whitespace())
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .map_with(|word, e| {
+            let span: SimpleSpan = e.span();
+            (word, span.into_range())
+        })
+        .padded()
+        .repeated()
+        .collect::<Vec<_>>()
+        .padded()
+        .parse(text)
+        .into_output()
+        .unwrap_or_default()
+}
+
+fn phrase(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|(word, _)| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn span(words: &[Word]) -> Range<usize> {
+    match (words.first(), words.last()) {
+        (Some(first), Some(last)) => first.1.start..last.1.end,
+        _ => 0..0,
+    }
+}
+
+/// the longest run of words from the start of `words` that `f` accepts, along
+/// with how many words it took
+fn longest<T>(words: &[Word], f: impl Fn(&str) -> Option<T>) -> Option<(T, usize)> {
+    (1..=words.len())
+        .rev()
+        .find_map(|n| f(&phrase(&words[..n])).map(|t| (t, n)))
+}
+
+fn error<T>(span: Range<usize>, message: impl Into<String>) -> Result<T, QuickAddError> {
+    Err(QuickAddError {
+        span,
+        message: message.into(),
+    })
+}
+
+fn controller(env: &State, phrase: &str) -> Option<u8> {
+    if let Ok(slot) = phrase.parse::<u8>() {
+        return Some(slot).filter(|slot| (*slot as usize) < CONTROLLER_SLOTS);
+    }
+
+    env.controller_names
+        .iter()
+        .position(|name| !name.is_empty() && name.to_lowercase() == phrase)
+        .map(|slot| slot as u8)
+}
+
+fn button(controller: &ControllerType, phrase: &str, arena: &Bump) -> Option<Button> {
+    let named = controller.enumerate_buttons(arena).find(|button| {
+        controller
+            .button_name(button, arena)
+            .to_lowercase()
+            .replace(':', "")
+            == phrase
+    });
+
+    // any controller's buttons can be given by their number instead
+    named.or_else(|| {
+        let button = Button {
+            button: phrase.parse().ok()?,
+            location: ButtonLocation::Button,
+        };
+
+        controller.valid_binding(button).then_some(button)
+    })
+}
+
+fn when(phrase: &str) -> Option<RunWhen> {
+    let short = match phrase {
+        "on" => Some(RunWhen::OnTrue),
+        "while" => Some(RunWhen::WhileTrue),
+        "toggle" => Some(RunWhen::ToggleOnTrue),
+        "change" => Some(RunWhen::OnChange),
+        _ => None,
+    };
+
+    short.or_else(|| RunWhen::enumerate().find(|when| when.get_str() == phrase))
+}
+
+/// parses `<controller> <button> [when] <command>`, the controller is a slot
+/// or its name and `when` defaults to while true
+pub fn parse(text: &str, env: &State, arena: &Bump) -> Result<QuickAdd, QuickAddError> {
+    let words = words(text);
+    let end = text.len()..text.len();
+
+    if words.is_empty() {
+        return error(end, "type <controller> <button> [when] <command>");
+    }
+
+    let Some((slot, used)) = longest(&words, |p| controller(env, p)) else {
+        return error(words[0].1.clone(), "not a controller");
+    };
+
+    let controller_span = span(&words[..used]);
+    let words = &words[used..];
+
+    let controller_type = &env.controllers[slot as usize];
+
+    if !controller_type.bound() {
+        return error(controller_span, "that controller isn't bound");
+    }
+
+    let Some((button, used)) = longest(words, |p| button(controller_type, p, arena)) else {
+        return match words.first() {
+            Some((_, span)) => error(span.clone(), "not a button on that controller"),
+            None => error(end, "missing a button"),
+        };
+    };
+
+    let words = &words[used..];
+
+    let (during, used) = longest(words, when).unwrap_or((RunWhen::WhileTrue, 0));
+
+    let words = &words[used..];
+
+    if words.is_empty() {
+        return error(end, "missing a command");
+    }
+
+    let binding = Binding {
+        controller: slot,
+        button,
+        during,
+        debounce: Debounce::NONE,
+        threshold: None,
+        modifier: None,
+        enabled: true,
+    };
+
+    let query = phrase(words);
+
+    if let Some(command) = env.commands.iter().find(|c| c.to_lowercase() == query) {
+        return Ok(QuickAdd::Ready(binding, command.clone()));
+    }
+
+    let mut matches = env
+        .commands
+        .iter()
+        .filter(|c| valid_result(c, &query))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    match matches.len() {
+        0 => error(span(words), "no command matches"),
+        1 => Ok(QuickAdd::Ready(binding, matches.remove(0))),
+        _ => Ok(QuickAdd::Ambiguous(binding, matches)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixture() -> State {
+        let mut state = State::default();
+
+        state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+        state.controllers[1] = ControllerType::Generic {
+            buttons: 12,
+            axises: 4,
+            sensitivity: 0.5,
+        };
+        state.controller_names[1] = Rc::new("Button Board".to_string());
+
+        for command in ["shoot", "intake_in", "intake_out", "climb"] {
+            state.commands.insert(Rc::new(command.to_string()));
+        }
+
+        state
+    }
+
+    fn binding(controller: u8, button: i16, location: ButtonLocation, during: RunWhen) -> Binding {
+        Binding {
+            controller,
+            button: Button { button, location },
+            during,
+            debounce: Debounce::NONE,
+            threshold: None,
+            modifier: None,
+            enabled: true,
+        }
+    }
+
+    fn ready(binding: Binding, command: &str) -> Result<QuickAdd, QuickAddError> {
+        Ok(QuickAdd::Ready(binding, Rc::new(command.to_string())))
+    }
+
+    #[test]
+    fn xbox_buttons() {
+        let state = fixture();
+        let arena = Bump::new();
+
+        assert_eq!(
+            parse("0 a while shoot", &state, &arena),
+            ready(
+                binding(0, 1, ButtonLocation::Button, RunWhen::WhileTrue),
+                "shoot"
+            )
+        );
+        assert_eq!(
+            parse("0  left bumper on false climb", &state, &arena),
+            ready(
+                binding(0, 5, ButtonLocation::Button, RunWhen::OnFalse),
+                "climb"
+            )
+        );
+        assert_eq!(
+            parse("0 2 toggle shoot", &state, &arena),
+            ready(
+                binding(0, 2, ButtonLocation::Button, RunWhen::ToggleOnTrue),
+                "shoot"
+            )
+        );
+        assert_eq!(
+            parse("0 right trigger sho", &state, &arena),
+            ready(
+                binding(0, 3, ButtonLocation::Analog, RunWhen::WhileTrue),
+                "shoot"
+            )
+        );
+    }
+
+    #[test]
+    fn generic_buttons_by_name() {
+        let state = fixture();
+        let arena = Bump::new();
+
+        assert_eq!(
+            parse("button board 12 on change climb", &state, &arena),
+            ready(
+                binding(1, 12, ButtonLocation::Button, RunWhen::OnChange),
+                "climb"
+            )
+        );
+        assert_eq!(
+            parse("1 axis 3 climb", &state, &arena),
+            ready(
+                binding(1, 3, ButtonLocation::Analog, RunWhen::WhileTrue),
+                "climb"
+            )
+        );
+        assert_eq!(
+            parse("1 pov up right on shoot", &state, &arena),
+            ready(
+                binding(1, 45, ButtonLocation::Pov, RunWhen::OnTrue),
+                "shoot"
+            )
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_word() {
+        let state = fixture();
+        let arena = Bump::new();
+
+        assert_eq!(parse("2 a shoot", &state, &arena).unwrap_err().span, 0..1);
+        assert_eq!(parse("1 13 shoot", &state, &arena).unwrap_err().span, 2..4);
+        assert_eq!(
+            parse("0 a while", &state, &arena).unwrap_err().message,
+            "missing a command"
+        );
+        assert_eq!(parse("0 a fly", &state, &arena).unwrap_err().span, 4..7);
+    }
+
+    #[test]
+    fn ambiguous_command() {
+        let state = fixture();
+        let arena = Bump::new();
+
+        assert_eq!(
+            parse("0 b intake", &state, &arena),
+            Ok(QuickAdd::Ambiguous(
+                binding(0, 2, ButtonLocation::Button, RunWhen::WhileTrue),
+                vec![
+                    Rc::new("intake_in".to_string()),
+                    Rc::new("intake_out".to_string())
+                ]
+            ))
+        );
+    }
+}
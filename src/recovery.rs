@@ -0,0 +1,171 @@
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    fs::rename,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result};
+use egui::{Color32, Ui};
+
+use crate::ProgramError;
+
+/// a project file that isn't valid json (or its backup either), with where
+/// the parse gave up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenFile {
+    pub path: PathBuf,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl BrokenFile {
+    pub fn new(path: &Path, err: &serde_json::Error) -> Self {
+        let at = format!(" at line {} column {}", err.line(), err.column());
+        let message = err.to_string();
+
+        Self {
+            path: path.to_owned(),
+            message: message.strip_suffix(&at).unwrap_or(&message).to_string(),
+            line: err.line(),
+            column: err.column(),
+        }
+    }
+
+    /// the broken file behind `err`, when that's what went wrong
+    pub fn from_error(err: &anyhow::Error) -> Option<Self> {
+        match err.downcast_ref::<ProgramError>()? {
+            ProgramError::Malformed(broken) => Some(broken.clone()),
+            _ => None,
+        }
+    }
+
+    /// where the file is moved to when starting fresh, next to it with .broken
+    /// on the end
+    pub fn set_aside_path(&self) -> PathBuf {
+        let mut name = OsString::from(self.path.file_name().unwrap_or_default());
+
+        name.push(".broken");
+
+        self.path.with_file_name(name)
+    }
+
+    /// moves the file out of the way so the next load starts without it, an
+    /// older .broken copy is replaced
+    pub fn set_aside(&self) -> Result<PathBuf> {
+        let to = self.set_aside_path();
+
+        rename(&self.path, &to).with_context(|| "failed to move the broken file")?;
+
+        Ok(to)
+    }
+}
+
+impl Display for BrokenFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is broken at line {} column {}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// opens the folder `path` is in with the system's file manager
+pub fn open_containing_folder(path: &Path) -> Result<()> {
+    let folder = path.parent().unwrap_or(path);
+
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    Command::new(program)
+        .arg(folder)
+        .spawn()
+        .with_context(|| "failed to open the folder")?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    OpenFolder,
+    Retry,
+    StartFresh,
+}
+
+/// what's wrong with `broken` and the ways out, for the start screen and for
+/// switching to a broken profile
+pub fn recovery_panel(ui: &mut Ui, broken: &BrokenFile) -> Option<Recovery> {
+    let mut picked = None;
+
+    ui.label(broken.path.display().to_string());
+
+    ui.colored_label(
+        Color32::from_rgb(0xf3, 0x8b, 0xa8),
+        format!(
+            "line {}, column {}: {}",
+            broken.line, broken.column, broken.message
+        ),
+    );
+
+    ui.horizontal(|ui| {
+        if ui.button("open containing folder").clicked() {
+            picked = Some(Recovery::OpenFolder);
+        }
+
+        if ui
+            .button("retry")
+            .on_hover_text("load it again, after fixing it by hand")
+            .clicked()
+        {
+            picked = Some(Recovery::Retry);
+        }
+
+        if ui
+            .button("start fresh")
+            .on_hover_text(format!(
+                "rename it to {} and load without it",
+                broken.set_aside_path().display()
+            ))
+            .clicked()
+        {
+            picked = Some(Recovery::StartFresh);
+        }
+    });
+
+    picked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn broken_json_says_where() {
+        let err = serde_json::from_str::<serde_json::Value>("{\n  \"url\" 1\n}").unwrap_err();
+
+        let broken = BrokenFile::new(Path::new("/deploy/bindings.json"), &err);
+
+        assert_eq!(broken.line, 2);
+        assert!(!broken.message.contains("line"));
+        assert_eq!(
+            broken.set_aside_path(),
+            Path::new("/deploy/bindings.json.broken")
+        );
+
+        let err = anyhow::Error::from(ProgramError::Malformed(broken.clone()))
+            .context("failed to get profile");
+
+        assert_eq!(BrokenFile::from_error(&err), Some(broken));
+        assert_eq!(BrokenFile::from_error(&anyhow::anyhow!("other")), None);
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write,
+    time::Duration,
+};
+
+use egui::{Context, Grid, Window};
+
+use crate::{notifier::Notifier, tabs::TabType};
+
+// about a second of frames
+const SAMPLES: usize = 60;
+
+#[derive(Debug, Default, Clone)]
+pub struct Timings {
+    samples: VecDeque<Duration>,
+    worst: Duration,
+}
+
+impl Timings {
+    pub fn record(&mut self, time: Duration) {
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(time);
+        self.worst = self.worst.max(time);
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    pub fn worst(&self) -> Duration {
+        self.worst
+    }
+}
+
+/// timings for the debug overlay, nothing is recorded unless it is open
+#[derive(Debug, Default)]
+pub struct RenderStats {
+    pub enabled: bool,
+    pub tabs: BTreeMap<TabType, Timings>,
+    pub frame: Timings,
+    pub arena_high_water: usize,
+}
+
+impl RenderStats {
+    pub fn record_tab(&mut self, tab: TabType, time: Duration) {
+        self.tabs.entry(tab).or_default().record(time);
+    }
+
+    pub fn record_arena(&mut self, bytes: usize) {
+        self.arena_high_water = self.arena_high_water.max(bytes);
+    }
+
+    pub fn report(&self, open: &BTreeSet<TabType>) -> String {
+        let mut report = String::new();
+
+        for (tab, timings) in self.tabs.iter().filter(|(t, _)| open.contains(t)) {
+            let _ = writeln!(
+                report,
+                "{}: avg {:.2?} worst {:.2?}",
+                tab.name(),
+                timings.average(),
+                timings.worst()
+            );
+        }
+
+        let _ = writeln!(
+            report,
+            "frame: avg {:.2?} worst {:.2?}",
+            self.frame.average(),
+            self.frame.worst()
+        );
+        let _ = write!(report, "arena high water: {} bytes", self.arena_high_water);
+
+        report
+    }
+
+    /// the overlay, the report is copied rather than printed since release
+    /// builds on windows have no console to print to
+    pub fn show(&mut self, ctx: &Context, open: &BTreeSet<TabType>, notifier: &mut dyn Notifier) {
+        let mut enabled = self.enabled;
+
+        Window::new("render stats")
+            .open(&mut enabled)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("render stats grid").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.label("avg");
+                    ui.label("worst");
+                    ui.end_row();
+
+                    for (tab, timings) in self.tabs.iter().filter(|(t, _)| open.contains(t)) {
+                        ui.label(tab.name());
+                        ui.label(format!("{:.2?}", timings.average()));
+                        ui.label(format!("{:.2?}", timings.worst()));
+                        ui.end_row();
+                    }
+
+                    ui.label("frame");
+                    ui.label(format!("{:.2?}", self.frame.average()));
+                    ui.label(format!("{:.2?}", self.frame.worst()));
+                    ui.end_row();
+                });
+
+                ui.label(format!("arena high water: {} bytes", self.arena_high_water));
+
+                ui.horizontal(|ui| {
+                    if ui.button("copy report").clicked() {
+                        ctx.copy_text(self.report(open));
+                        notifier.success("copied render stats".to_string());
+                    }
+
+                    if ui.button("reset").clicked() {
+                        self.tabs.clear();
+                        self.frame = Default::default();
+                        self.arena_high_water = 0;
+                    }
+                });
+            });
+
+        self.enabled = enabled;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rolling_average() {
+        let mut timings = Timings::default();
+
+        timings.record(Duration::from_millis(50));
+
+        for _ in 0..SAMPLES {
+            timings.record(Duration::from_millis(2));
+        }
+
+        // the slow frame fell out of the window but is still the worst
+        assert_eq!(timings.average(), Duration::from_millis(2));
+        assert_eq!(timings.worst(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn report_only_open_tabs() {
+        let mut stats = RenderStats::default();
+
+        stats.record_tab(TabType::Constants, Duration::from_millis(3));
+        stats.record_tab(TabType::Streams, Duration::from_millis(1));
+        stats.record_arena(512);
+
+        let report = stats.report(&BTreeSet::from([TabType::Constants]));
+
+        assert!(report.contains(TabType::Constants.name()));
+        assert!(!report.contains(TabType::Streams.name()));
+        assert!(report.contains("512 bytes"));
+    }
+}
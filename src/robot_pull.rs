@@ -0,0 +1,170 @@
+use std::{
+    ffi::OsString,
+    fs::{read, read_dir, remove_file, write},
+    io::Read,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+
+use crate::{
+    bindings::{Profile, SaveData},
+    global_state,
+};
+
+/// what came down from the robot, parsed and waiting to be copied over the
+/// local files
+#[derive(Debug)]
+pub struct Pulled {
+    // the temporary directory it was downloaded to
+    pub dir: PathBuf,
+    pub save_data: SaveData<'static>,
+    pub profiles: Vec<(Rc<String>, Profile<'static>)>,
+}
+
+/// where a pull downloads to, emptied before each one
+pub fn pull_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("bindings-gui-pull-{}", std::process::id()))
+}
+
+/// the arguments to scp that copy bindings.json and the bindings directory
+/// from the robot's `remote_path` into `to`, whatever format they were synced
+/// in. the path is quoted but the glob after it isn't, the robot expands it
+pub fn build_pull_invocation(url: &str, user: &str, remote_path: &str, to: &Path) -> Vec<OsString> {
+    vec![
+        "-r".into(),
+        "--".into(),
+        format!(
+            "{user}@{url}:{}bindings*",
+            global_state::quote_remote_path(remote_path)
+        )
+        .into(),
+        to.into(),
+    ]
+}
+
+/// unzips the files a gzip sync left on the robot next to where the plain
+/// ones would be, so they load the same way
+fn gunzip_all(dir: &Path) -> Result<()> {
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            gunzip_all(&path)?;
+            continue;
+        }
+
+        let Some(plain) = path
+            .to_str()
+            .and_then(|p| p.strip_suffix(".gz"))
+            .map(PathBuf::from)
+        else {
+            continue;
+        };
+
+        let mut json = String::new();
+
+        GzDecoder::new(read(&path)?.as_slice())
+            .read_to_string(&mut json)
+            .with_context(|| format!("{} isn't gzip", path.display()))?;
+
+        write(plain, json)?;
+        remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// reads what was downloaded into `dir`. nothing outside it is touched, so a
+/// robot with broken files fails here and the project stays as it was
+pub fn read_pulled(dir: PathBuf) -> Result<Pulled> {
+    gunzip_all(&dir)?;
+
+    let (save_data, _) = SaveData::from_directory(&dir)
+        .with_context(|| "the robot's bindings.json didn't load")?
+        .with_context(|| "the robot has no bindings.json")?;
+
+    let profiles = Profile::get_profiles(&dir)?
+        .into_iter()
+        .map(|name| {
+            let (profile, _) = Profile::get_from(&dir, &name)
+                .with_context(|| format!("the robot's profile {name} didn't load"))?;
+
+            Ok((name, profile))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Pulled {
+        dir,
+        save_data,
+        profiles,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::create_dir_all, io::Write};
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn gzipped_syncs_are_read_like_plain_ones() {
+        let temp = temp_dir("pulled");
+        let dir = temp.path().to_path_buf();
+
+        create_dir_all(dir.join("bindings")).unwrap();
+
+        let mut gzipped = GzEncoder::new(Vec::new(), Compression::best());
+        gzipped
+            .write_all(
+                serde_json::to_string(&SaveData::default())
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .unwrap();
+
+        write(dir.join("bindings.json.gz"), gzipped.finish().unwrap()).unwrap();
+        write(
+            dir.join("bindings").join("default.json"),
+            Profile::default().to_json(Default::default()),
+        )
+        .unwrap();
+
+        let pulled = read_pulled(dir.clone()).unwrap();
+
+        assert_eq!(pulled.profiles.len(), 1);
+        assert_eq!(*pulled.profiles[0].0, "default");
+        assert!(dir.join("bindings.json").exists());
+        assert!(!dir.join("bindings.json.gz").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // nothing came down
+        create_dir_all(&dir).unwrap();
+
+        assert!(read_pulled(dir.clone()).is_err());
+    }
+
+    #[test]
+    fn pulls_quote_the_robot_path() {
+        assert_eq!(
+            build_pull_invocation(
+                "10.90.94.2",
+                "lvuser",
+                "/home/lvuser/robot deploy/",
+                Path::new("/tmp/pull")
+            ),
+            [
+                "-r",
+                "--",
+                "lvuser@10.90.94.2:'/home/lvuser/robot deploy/'bindings*",
+                "/tmp/pull",
+            ]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::{hash::Hash, rc::Rc};
 
-use egui::{popup_below_widget, TextEdit, Ui};
+use egui::{popup_below_widget, Button, Key, TextEdit, Ui};
 use once_cell::sync::Lazy;
 
 #[derive(Debug, Default, Clone)]
@@ -35,35 +35,73 @@ impl<K, V> SingleCache<K, V> {
 
 pub type SelectorCache<A> = SingleCache<String, Vec<(Rc<String>, A)>>;
 
-pub(crate) fn valid_result(a: &str, selector: &str) -> bool {
+/// where an option goes in the list, `None` leaves it in the unranked rest
+pub type Rank<'a, A> = &'a dyn Fn(&A) -> Option<usize>;
+
+/// text shown when hovering an option in the list
+pub type Describe<'a, A> = &'a dyn Fn(&A) -> Option<String>;
+
+/// what happened to a search selector this frame
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Selected {
+    /// the selection is different, by clicking, typing down to one option or
+    /// pressing enter
+    pub changed: bool,
+    /// enter was pressed to take the highlighted option, for moving on to the
+    /// next field from the keyboard
+    pub committed: bool,
+}
+
+pub fn valid_result(a: &str, selector: &str) -> bool {
     let on = a.to_lowercase();
-    let mut keywords = selector.split_whitespace().map(|a| a);
+    let mut keywords = selector.split_whitespace();
+
+    keywords.all(|keyword| on.contains(keyword))
+}
 
-    keywords.all(|keyword| on.contains(&keyword))
+/// focuses the text box of the search selector that will be drawn with `id` in
+/// this same `ui`
+pub fn focus_search_selector<I: Hash>(id: I, ui: &Ui) {
+    let id = ui.make_persistent_id(id).with("search text");
+
+    ui.memory_mut(|mem| mem.request_focus(id));
 }
 
-pub(crate) fn search_selector<A, I: Hash>(
+/// `rank` moves the options it gives a place to the top, above a separator,
+/// while nothing has been typed. the first option is highlighted and enter
+/// takes it
+#[allow(clippy::too_many_arguments)]
+pub fn search_selector<A, I: Hash>(
     id: I,
     text: &mut String,
     selection: &mut A,
     options: impl Iterator<Item = (Rc<String>, A)>,
+    rank: Option<Rank<A>>,
+    describe: Option<Describe<A>>,
     cache: &mut SelectorCache<A>,
     width: f32,
     ui: &mut Ui,
-) -> bool
+) -> Selected
 where
     A: Clone,
 {
-    let edit = ui.add(TextEdit::singleline(text).desired_width(width));
+    let id = ui.make_persistent_id(id);
 
-    let mut changed = false;
+    let edit = ui.add(
+        TextEdit::singleline(text)
+            .id(id.with("search text"))
+            .desired_width(width),
+    );
 
-    let id = ui.make_persistent_id(id);
+    let mut selected = Selected::default();
 
     if edit.gained_focus() {
         ui.memory_mut(|mem| mem.open_popup(id));
     }
 
+    // a single line edit gives up focus on enter and tab
+    let (enter, tab) = ui.input(|i| (i.key_pressed(Key::Enter), i.key_pressed(Key::Tab)));
+    let committing = edit.lost_focus() && enter;
 
     popup_below_widget(
         ui,
@@ -73,21 +111,63 @@ where
         |ui| {
             let ntext = Lazy::new(|| text.to_lowercase());
 
-            let vals = cache.get(text, || {
-                options
+            // ranking only applies before anything is typed, then it's just
+            // whatever matches
+            let rank = rank.filter(|_| text.is_empty());
+
+            let vals = cache.get(text, || match rank {
+                Some(rank) => {
+                    let mut vals = options.collect::<Vec<_>>();
+
+                    // stable, so the unranked rest keeps its order
+                    vals.sort_by_key(|(_, value)| rank(value).unwrap_or(usize::MAX));
+                    vals.truncate(10);
+                    vals
+                }
+                None => options
                     .filter(|(name, _value)| valid_result(name.as_str(), &ntext))
                     .take(10)
-                    .collect::<Vec<_>>()
+                    .collect::<Vec<_>>(),
             });
 
             if vals.len() == 1 {
                 *selection = vals[0].1.clone();
-                changed = true;
+                selected.changed = true;
             }
 
-            for (name, value) in vals {
-                if ui.button(name.as_str()).clicked() {
-                    changed = true;
+            if committing {
+                if let Some((name, value)) = vals.first() {
+                    *selection = value.clone();
+                    selected.changed = true;
+                    selected.committed = true;
+
+                    text.clear();
+                    text.push_str(name.as_str());
+                }
+
+                ui.memory_mut(|mem| mem.close_popup());
+                return;
+            }
+
+            let ranked = rank.map_or(0, |rank| {
+                vals.iter()
+                    .take_while(|(_, value)| rank(value).is_some())
+                    .count()
+            });
+
+            for (i, (name, value)) in vals.iter().enumerate() {
+                if i == ranked && ranked != 0 {
+                    ui.separator();
+                }
+
+                let mut button = ui.add(Button::new(name.as_str()).selected(i == 0));
+
+                if let Some(description) = describe.and_then(|describe| describe(value)) {
+                    button = button.on_hover_text(description);
+                }
+
+                if button.clicked() {
+                    selected.changed = true;
                     *selection = value.clone();
                     ui.memory_mut(|mem| mem.close_popup());
                     text.clear();
@@ -98,7 +178,79 @@ where
         },
     );
 
+    if edit.lost_focus() && tab {
+        ui.memory_mut(|mem| mem.close_popup());
+    }
+
     cache.update();
 
-    changed
+    selected
+}
+
+#[cfg(test)]
+mod test {
+    use egui::{Context, Event, Modifiers, RawInput};
+
+    use super::*;
+
+    fn key(key: Key) -> RawInput {
+        RawInput {
+            events: vec![Event::Key {
+                key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Modifiers::NONE,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn enter_takes_the_highlighted_option() {
+        let ctx = Context::default();
+
+        let mut text = "bump".to_string();
+        let mut selection = 0;
+        let mut cache = SelectorCache::default();
+        let mut results = Vec::new();
+
+        let options = ["a", "left bumper", "right bumper"];
+
+        for input in [RawInput::default(), RawInput::default(), key(Key::Enter)] {
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if results.is_empty() {
+                        focus_search_selector("buttons", ui);
+                    }
+
+                    results.push(search_selector(
+                        "buttons",
+                        &mut text,
+                        &mut selection,
+                        options
+                            .iter()
+                            .enumerate()
+                            .map(|(i, name)| (Rc::new(name.to_string()), i)),
+                        None,
+                        None,
+                        &mut cache,
+                        100.0,
+                        ui,
+                    ));
+                });
+            });
+        }
+
+        assert!(!results[1].committed);
+        assert_eq!(
+            results[2],
+            Selected {
+                changed: true,
+                committed: true
+            }
+        );
+        assert_eq!(selection, 1);
+        assert_eq!(text, "left bumper");
+    }
 }
@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{file_hashes, global_state::DEFAULT_DEPLOY_SUBPATH};
+
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// settings for this machine rather than for a project, so they live in the os
+/// config directory instead of the deploy directory
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Settings {
+    #[serde(default)]
+    pub recent_projects: Vec<PathBuf>,
+    #[serde(default)]
+    pub reopen_last_project: bool,
+    // projects that don't keep their deploy files in the default place
+    #[serde(default)]
+    pub deploy_subpaths: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        let mut path = dirs::config_dir()?;
+
+        path.push("bindings-gui");
+        path.push("settings.json");
+
+        Some(path)
+    }
+
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Default::default());
+        };
+
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+
+        let file = read_to_string(path).with_context(|| "failed to read settings")?;
+
+        Ok(serde_json::from_str(&file)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+
+        create_dir_all(path.parent().unwrap())?;
+
+        file_hashes::write_file_without_backup(
+            &path,
+            serde_json::to_string_pretty(self).unwrap().as_bytes(),
+        )
+        .with_context(|| "failed to save settings")
+    }
+
+    pub fn add_recent(&mut self, project: PathBuf) {
+        self.recent_projects.retain(|p| *p != project);
+        self.recent_projects.insert(0, project);
+        self.recent_projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    pub fn last_project(&self) -> Option<&PathBuf> {
+        self.recent_projects.first()
+    }
+
+    /// where `project` keeps its deploy files, relative to it
+    pub fn deploy_subpath(&self, project: &Path) -> PathBuf {
+        self.deploy_subpaths
+            .get(project)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_DEPLOY_SUBPATH.into())
+    }
+
+    /// only projects that differ from the default are remembered
+    pub fn set_deploy_subpath(&mut self, project: &Path, subpath: &Path) {
+        if subpath == Path::new(DEFAULT_DEPLOY_SUBPATH) {
+            self.deploy_subpaths.remove(project);
+        } else {
+            self.deploy_subpaths
+                .insert(project.to_owned(), subpath.to_owned());
+        }
+    }
+}
+
+/// the project passed with `--project <path>`, which always wins over reopening
+/// the last project
+pub fn project_from_args(mut args: impl Iterator<Item = String>) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        if arg == "--project" {
+            return args.next().map(PathBuf::from);
+        }
+
+        if let Some(path) = arg.strip_prefix("--project=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recent_moves_to_front() {
+        let mut settings = Settings::default();
+
+        settings.add_recent("a".into());
+        settings.add_recent("b".into());
+        settings.add_recent("a".into());
+
+        assert_eq!(
+            settings.recent_projects,
+            vec![PathBuf::from("a"), PathBuf::from("b")]
+        );
+    }
+
+    #[test]
+    fn recent_is_capped() {
+        let mut settings = Settings::default();
+
+        for i in 0..20 {
+            settings.add_recent(i.to_string().into());
+        }
+
+        assert_eq!(settings.recent_projects.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(settings.last_project(), Some(&PathBuf::from("19")));
+    }
+
+    #[test]
+    fn deploy_subpath_is_per_project() {
+        let mut settings = Settings::default();
+
+        let robot = Path::new("robot");
+
+        assert_eq!(
+            settings.deploy_subpath(robot),
+            Path::new(DEFAULT_DEPLOY_SUBPATH)
+        );
+
+        settings.set_deploy_subpath(robot, Path::new("robot/deploy"));
+
+        assert_eq!(settings.deploy_subpath(robot), Path::new("robot/deploy"));
+        assert_eq!(
+            settings.deploy_subpath(Path::new("other")),
+            Path::new(DEFAULT_DEPLOY_SUBPATH)
+        );
+
+        settings.set_deploy_subpath(robot, Path::new(DEFAULT_DEPLOY_SUBPATH));
+
+        assert!(settings.deploy_subpaths.is_empty());
+    }
+
+    #[test]
+    fn project_flag() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            project_from_args(args(&["bindings-gui", "--project", "robot"]).into_iter()),
+            Some(PathBuf::from("robot"))
+        );
+        assert_eq!(
+            project_from_args(args(&["bindings-gui", "--project=robot"]).into_iter()),
+            Some(PathBuf::from("robot"))
+        );
+        assert_eq!(project_from_args(args(&["bindings-gui"]).into_iter()), None);
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeSet,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    thread,
+};
+
+/// where robot code lives, relative to the project root
+pub const SOURCE_DIRS: [&str; 2] = ["src/main/java", "src/main/kotlin"];
+
+const EXTENSIONS: [&str; 3] = ["java", "kt", "kts"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Symbol(char),
+}
+
+/// identifiers and punctuation, without comments, strings or whitespace
+fn tokens(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+        } else if rest.starts_with("/*") {
+            rest = rest[2..].find("*/").map_or("", |end| &rest[end + 4..]);
+        } else if c == '"' || c == '\'' {
+            let mut end = 1;
+            let mut escaped = false;
+
+            for (i, s) in rest[1..].char_indices() {
+                end = i + 1 + s.len_utf8();
+
+                match s {
+                    '\\' if !escaped => escaped = true,
+                    s if s == c && !escaped => break,
+                    _ => escaped = false,
+                }
+            }
+
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+
+            tokens.push(Token::Ident(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            if !c.is_whitespace() {
+                tokens.push(Token::Symbol(c));
+            }
+
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    tokens
+}
+
+fn is_command_type(name: &str) -> bool {
+    name.contains("Command") && name != "Commands"
+}
+
+/// skips from an opening bracket at `i` to just past its match
+fn skip_group(tokens: &[Token], mut i: usize, open: char, close: char) -> usize {
+    let mut depth = 0;
+
+    while let Some(token) = tokens.get(i) {
+        match token {
+            Token::Symbol(c) if *c == open => depth += 1,
+            Token::Symbol(c) if *c == close => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    i
+}
+
+/// names of the commands declared in a java or kotlin file: classes that
+/// extend a command and methods, fields and vals that give one back
+pub fn command_names(source: &str) -> Vec<String> {
+    let tokens = tokens(source);
+    let mut names = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let next = |n: usize| tokens.get(i + n).copied();
+
+        match (token, next(1)) {
+            // class Shoot(val shooter: Shooter) : Command() / class Shoot extends Command
+            (Token::Ident("class"), Some(Token::Ident(name))) => {
+                let mut j = i + 2;
+
+                for (open, close) in [('<', '>'), ('(', ')')] {
+                    if tokens.get(j) == Some(&Token::Symbol(open)) {
+                        j = skip_group(&tokens, j, open, close);
+                    }
+                }
+
+                let supertypes = tokens[j.min(tokens.len())..]
+                    .iter()
+                    .take_while(|t| **t != Token::Symbol('{'))
+                    .take_while(|t| **t != Token::Ident("class"));
+
+                let mut extends = false;
+                let mut command = false;
+
+                for t in supertypes {
+                    match t {
+                        Token::Symbol(':') | Token::Ident("extends") => extends = true,
+                        Token::Ident(supertype) if extends && is_command_type(supertype) => {
+                            command = true
+                        }
+                        _ => {}
+                    }
+                }
+
+                if command {
+                    names.push(name.to_string());
+                }
+            }
+            // fun shoot(): Command
+            (Token::Ident("fun"), Some(Token::Ident(name))) => {
+                let j = skip_group(&tokens, i + 2, '(', ')');
+
+                if let (Some(Token::Symbol(':')), Some(Token::Ident(returns))) =
+                    (tokens.get(j), tokens.get(j + 1))
+                {
+                    if is_command_type(returns) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            // val shoot: Command / val shoot = Commands.run(...)
+            (Token::Ident("val" | "var"), Some(Token::Ident(name))) => match (next(2), next(3)) {
+                (Some(Token::Symbol(':')), Some(Token::Ident(t))) if is_command_type(t) => {
+                    names.push(name.to_string());
+                }
+                (Some(Token::Symbol('=')), Some(Token::Ident("Commands"))) => {
+                    names.push(name.to_string());
+                }
+                _ => {}
+            },
+            // public Command shoot() / Command shoot = Commands.run(...)
+            (Token::Ident(t), Some(Token::Ident(name)))
+                if is_command_type(t)
+                    && matches!(next(2), Some(Token::Symbol('(' | '=')))
+                    && i.checked_sub(1).and_then(|p| tokens.get(p))
+                        != Some(&Token::Ident("new")) =>
+            {
+                names.push(name.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    names
+}
+
+// symlinked directories aren't followed, one pointing back up the tree would
+// never end
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(kind) = entry.file_type() else {
+            continue;
+        };
+
+        let path = entry.path();
+
+        if kind.is_dir() {
+            source_files(&path, files);
+        } else if path
+            .extension()
+            .is_some_and(|e| EXTENSIONS.iter().any(|ext| e == *ext))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// what a scan of the robot code found
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Scan {
+    pub names: BTreeSet<String>,
+    // source files that couldn't be read as text, their commands are missing
+    pub skipped: Vec<PathBuf>,
+}
+
+/// every command name declared in the project's robot code
+pub fn scan(project: &Path) -> Scan {
+    let mut files = Vec::new();
+
+    for dir in SOURCE_DIRS {
+        source_files(&project.join(dir), &mut files);
+    }
+
+    let mut scan = Scan::default();
+
+    for file in files {
+        match read_to_string(&file) {
+            Ok(source) => scan.names.extend(command_names(&source)),
+            Err(_) => scan.skipped.push(file),
+        }
+    }
+
+    scan
+}
+
+/// scans on another thread so a big project doesn't freeze the window, the
+/// result arrives once on the receiver
+pub fn scan_in_background(project: PathBuf) -> Receiver<Scan> {
+    let (send, receive) = channel();
+
+    thread::spawn(move || {
+        let _ = send.send(scan(&project));
+    });
+
+    receive
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn kotlin_commands() {
+        let source = r#"
+            class Shoot(private val shooter: Shooter) : Command() {
+                // class Fake : Command()
+                val label = "class Quoted : Command()"
+            }
+
+            class Climb : SequentialCommandGroup(Raise(), Pull())
+
+            class Shooter : SubsystemBase() {
+                fun spinUp(speed: Double): Command = runOnce { }
+                fun stop() { }
+                val intake = Commands.runOnce({ })
+                val eject: Command = Commands.none()
+            }
+        "#;
+
+        assert_eq!(
+            command_names(source),
+            vec!["Shoot", "Climb", "spinUp", "intake", "eject"]
+        );
+    }
+
+    #[test]
+    fn java_commands() {
+        let source = r#"
+            public class Drive extends Command {
+                public Drive() { }
+            }
+
+            public class Arm extends SubsystemBase {
+                public Command raise() { return new Command(); }
+                private final Command lower = Commands.run(() -> {});
+                public static class Inner extends CommandBase { }
+            }
+        "#;
+
+        assert_eq!(
+            command_names(source),
+            vec!["Drive", "raise", "lower", "Inner"]
+        );
+    }
+
+    #[test]
+    fn unreadable_files_and_link_loops_are_skipped() {
+        let temp = temp_dir("source-scan");
+        let project = temp.path();
+        let code = project.join(SOURCE_DIRS[0]);
+
+        std::fs::create_dir_all(&code).unwrap();
+
+        std::fs::write(code.join("Drive.java"), "class Drive extends Command { }").unwrap();
+        std::fs::write(code.join("Binary.java"), [0xff, 0xfe, 0x00]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&code, code.join("loop")).unwrap();
+
+        let found = scan(project);
+
+        assert_eq!(found.names, BTreeSet::from(["Drive".to_string()]));
+        assert_eq!(found.skipped, vec![code.join("Binary.java")]);
+    }
+}
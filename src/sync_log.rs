@@ -0,0 +1,137 @@
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+
+/// attempts kept in memory, sync.log keeps all of them
+pub const KEPT: usize = 500;
+
+/// how much of what scp said is kept with a failed attempt
+pub const ERROR_LINES: usize = 5;
+
+/// one sync to one target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub at: SystemTime,
+    pub target: String,
+    pub url: String,
+    pub took: Option<Duration>,
+    pub success: bool,
+    // none for the built in ssh, which has no process to exit
+    pub exit_code: Option<i32>,
+    // the first lines of what went wrong, empty when it worked
+    pub error: String,
+}
+
+impl Attempt {
+    /// "ok", or "failed" with the exit code when there is one
+    pub fn status(&self) -> String {
+        match (self.success, self.exit_code) {
+            (true, _) => "ok".to_string(),
+            (false, Some(code)) => format!("failed (exit {code})"),
+            (false, None) => "failed".to_string(),
+        }
+    }
+
+    pub fn took_text(&self) -> String {
+        self.took
+            .map(|took| format!("{:.1}s", took.as_secs_f32()))
+            .unwrap_or_default()
+    }
+}
+
+/// one line of sync.log
+impl Display for Attempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({}) {}",
+            humantime::format_rfc3339_seconds(self.at),
+            self.target,
+            self.url,
+            self.status()
+        )?;
+
+        if self.took.is_some() {
+            write!(f, " in {}", self.took_text())?;
+        }
+
+        if !self.error.is_empty() {
+            write!(
+                f,
+                ": {}",
+                self.error.lines().collect::<Vec<_>>().join(" | ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// the start of what a failed sync said, for keeping with its attempt
+pub fn error_head(output: &str) -> String {
+    output
+        .trim()
+        .lines()
+        .take(ERROR_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn path(deploy_dir: &Path) -> PathBuf {
+    deploy_dir.join("sync.log")
+}
+
+/// adds `attempt` to the end of sync.log in `deploy_dir`
+pub fn append(deploy_dir: &Path, attempt: &Attempt) -> Result<()> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path(deploy_dir))
+        .and_then(|mut file| writeln!(file, "{attempt}"))
+        .with_context(|| "failed to write to sync.log")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn attempts_are_one_line_each() {
+        let mut attempt = Attempt {
+            at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000),
+            target: "practice".to_string(),
+            url: "10.90.94.12".to_string(),
+            took: Some(Duration::from_millis(3200)),
+            success: true,
+            exit_code: Some(0),
+            error: String::new(),
+        };
+
+        assert_eq!(
+            attempt.to_string(),
+            "2027-01-15T08:00:00Z practice (10.90.94.12) ok in 3.2s"
+        );
+
+        attempt.success = false;
+        attempt.exit_code = Some(1);
+        attempt.took = None;
+        attempt.error = error_head("\nlost connection\nPermission denied\n");
+
+        assert_eq!(
+            attempt.to_string(),
+            "2027-01-15T08:00:00Z practice (10.90.94.12) failed (exit 1): \
+            lost connection | Permission denied"
+        );
+
+        assert_eq!(
+            error_head(&"line\n".repeat(20)).lines().count(),
+            ERROR_LINES
+        );
+    }
+}
@@ -3,18 +3,25 @@ use std::{
     mem,
     ops::DerefMut,
     rc::Rc,
+    slice,
 };
 
 use bumpalo::Bump;
-use egui::{collapsing_header::CollapsingState, CollapsingHeader, ComboBox, ScrollArea, Ui};
+use egui::{
+    collapsing_header::CollapsingState, CollapsingHeader, Color32, ComboBox, Context, Id, Key,
+    Label, RichText, ScrollArea, Sense, TextEdit, Ui,
+};
 use egui_hooks::UseHookExt;
 
 use crate::{
+    accessible,
     component::EventStream,
+    confirm::ConfirmDelete,
     constants::{Constants, ConstantsType, OptionLocation},
     global_state::{GlobalEvents, State},
     number_input::{angle_input, distance_input, number_input},
     search_selector::SelectorCache,
+    ui_meta::UiMeta,
     Component,
 };
 
@@ -27,9 +34,21 @@ pub struct EditingStates {
     type_caches: Vec<SelectorCache<ConstantsType>>,
 }
 
+/// a key being renamed in place, started by double clicking its name
+#[derive(Debug, Clone)]
+pub struct Renaming {
+    pub path: OptionLocation,
+    pub name: String,
+    pub error: Option<String>,
+    pub focus: bool,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct ConstantsTab {
     pub add: HashMap<OptionLocation, EditingStates>,
+    pub focus_search: bool,
+    pub renaming: Option<Renaming>,
+    pub confirm: ConfirmDelete<GlobalEvents>,
 }
 
 impl Component for ConstantsTab {
@@ -48,27 +67,36 @@ impl Component for ConstantsTab {
 
         ScrollArea::vertical().show(ui, |ui| {
             let constants = &mut env.constants;
+            let meta = &env.ui_meta;
 
             self.add_dialog(Rc::new(Vec::new()), output, arena, ui);
 
             match constants {
                 Constants::Object { map } => {
-                    for (key, value) in map.iter_mut() {
-                        ui.push_id(key, |ui| match value {
+                    let siblings = map.keys().cloned().collect::<Vec<_>>();
+
+                    for key in meta.ordered(&[], map) {
+                        let value = map.get_mut(&key).unwrap();
+
+                        ui.push_id(&key, |ui| match value {
                             Constants::Object { map } => {
                                 modified |= self.show_object(
                                     key.clone(),
                                     map,
                                     Rc::new(Vec::new()),
+                                    &siblings,
+                                    meta,
                                     output,
                                     arena,
                                     ui,
                                 );
                             }
                             _ => {
-                                modified |= Self::show_value(
+                                modified |= self.show_value(
                                     key.clone(),
                                     Rc::new(Vec::new()),
+                                    &siblings,
+                                    meta,
                                     value,
                                     ui,
                                     output,
@@ -85,11 +113,17 @@ impl Component for ConstantsTab {
             }
         });
 
+        self.confirm.show(ui, output);
+
         if modified {
             output.add_event(GlobalEvents::Save);
         }
     }
 
+    fn focus_search(&mut self) {
+        self.focus_search = true;
+    }
+
     fn tab_type(&self) -> super::TabType {
         super::TabType::Constants
     }
@@ -113,7 +147,11 @@ impl ConstantsTab {
 
         ui.horizontal(|ui| {
             ui.label("name:");
-            ui.text_edit_singleline(&mut state.name);
+            let resp = ui.text_edit_singleline(&mut state.name);
+
+            if key.is_empty() && mem::take(&mut self.focus_search) {
+                resp.request_focus();
+            }
 
             ui.label("type:");
             state.t.selector(
@@ -125,7 +163,7 @@ impl ConstantsTab {
                 ui.make_persistent_id(("adding id", &key)),
             );
 
-            if ui.button("add").clicked() {
+            if accessible::labeled_button(ui, "add", "add the option").clicked() {
                 if state.name.is_empty() {
                     output.add_event(GlobalEvents::DisplayError(
                         "no name provided for constant".to_string(),
@@ -144,11 +182,159 @@ impl ConstantsTab {
         });
     }
 
+    /// the name of the key at `path`, turned into a text box by double
+    /// clicking it. Enter or clicking away renames, Escape puts it back.
+    /// returns the new name once the rename is sent
+    fn key_label(
+        &mut self,
+        name: &Rc<String>,
+        path: &OptionLocation,
+        siblings: &[Rc<String>],
+        output: &EventStream<GlobalEvents>,
+        ui: &mut Ui,
+    ) -> Option<Rc<String>> {
+        let Some(renaming) = self.renaming.as_mut().filter(|r| r.path == *path) else {
+            if ui
+                .add(Label::new(name.as_str()).sense(Sense::click()))
+                .on_hover_text("double click to rename")
+                .double_clicked()
+            {
+                self.renaming = Some(Renaming {
+                    path: path.clone(),
+                    name: name.to_string(),
+                    error: None,
+                    focus: true,
+                });
+            }
+
+            return None;
+        };
+
+        let resp = ui.add(TextEdit::singleline(&mut renaming.name).desired_width(100.0));
+
+        if mem::take(&mut renaming.focus) {
+            resp.request_focus();
+        }
+
+        if let Some(error) = &renaming.error {
+            ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), error);
+        }
+
+        if ui.input(|i| i.key_pressed(Key::Escape)) {
+            self.renaming = None;
+            return None;
+        }
+
+        if !resp.lost_focus() {
+            return None;
+        }
+
+        let to = renaming.name.trim();
+
+        if to == name.as_str() {
+            self.renaming = None;
+            return None;
+        }
+
+        if to.is_empty() {
+            renaming.error = Some("a constant needs a name".to_string());
+            renaming.focus = true;
+            return None;
+        }
+
+        if siblings.iter().any(|s| s.as_str() == to) {
+            renaming.error = Some(format!("{to} already exists here"));
+            renaming.focus = true;
+            return None;
+        }
+
+        let to = Rc::new(to.to_string());
+
+        self.renaming = None;
+
+        let mut new_path = path.clone();
+        *Rc::make_mut(&mut new_path).last_mut().unwrap() = to.clone();
+
+        // add dialogs belong to the object they're in, so they follow it
+        self.add = mem::take(&mut self.add)
+            .into_iter()
+            .map(|(key, state)| match key.strip_prefix(path.as_slice()) {
+                Some(rest) => (Rc::new([new_path.as_slice(), rest].concat()), state),
+                None => (key, state),
+            })
+            .collect();
+
+        output.add_event(GlobalEvents::RenameOption(path.clone(), to.clone()));
+
+        Some(to)
+    }
+
+    /// pinning puts a key above the alphabetical rest of its object
+    fn pin_buttons(
+        path: &OptionLocation,
+        meta: &UiMeta,
+        output: &EventStream<GlobalEvents>,
+        ui: &mut Ui,
+    ) {
+        let pinned = meta.is_pinned(path);
+
+        let (label, name) = if pinned {
+            (RichText::new("📌").strong(), "unpin")
+        } else {
+            (RichText::new("📌").weak(), "pin to the top")
+        };
+
+        if accessible::labeled_button(ui, label, name).clicked() {
+            output.add_event(GlobalEvents::PinConstant(path.clone(), !pinned));
+        }
+
+        let first = path
+            .split_last()
+            .is_some_and(|(name, object)| meta.pinned(object).first() == Some(name));
+
+        if pinned && !first && accessible::labeled_button(ui, "⬆", "move up").clicked() {
+            output.add_event(GlobalEvents::RaiseConstant(path.clone()));
+        }
+    }
+
+    fn header_id(path: &[Rc<String>]) -> Id {
+        Id::new(("object header", path))
+    }
+
+    /// keeps what was expanded under `from` expanded under `to`
+    fn move_expansion(
+        ctx: &Context,
+        from: &[Rc<String>],
+        to: &[Rc<String>],
+        constants: &BTreeMap<Rc<String>, Constants>,
+    ) {
+        if let Some(state) = CollapsingState::load(ctx, Self::header_id(from)) {
+            let mut moved =
+                CollapsingState::load_with_default_open(ctx, Self::header_id(to), false);
+            moved.set_open(state.is_open());
+            moved.store(ctx);
+        }
+
+        for (key, value) in constants {
+            if let Constants::Object { map } = value {
+                Self::move_expansion(
+                    ctx,
+                    &[from, slice::from_ref(key)].concat(),
+                    &[to, slice::from_ref(key)].concat(),
+                    map,
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn show_object(
         &mut self,
         name: Rc<String>,
         constants: &mut BTreeMap<Rc<String>, Constants>,
         mut key_path: OptionLocation,
+        siblings: &[Rc<String>],
+        meta: &UiMeta,
         output: &EventStream<GlobalEvents>,
         arena: &Bump,
         ui: &mut Ui,
@@ -159,59 +345,108 @@ impl ConstantsTab {
 
         k.push(name.clone());
 
-        CollapsingState::load_with_default_open(
-            ui.ctx(),
-            ui.make_persistent_id("object header"),
-            false,
-        )
-        .show_header(ui, |ui| {
-            ui.label(name.as_str());
-            if constants.is_empty() && ui.button("X").clicked() {
-                output.add_event(GlobalEvents::RemoveOption(key_path.clone()));
-            }
-        })
-        .body(|ui| {
-            self.add_dialog(key_path.clone(), output, arena, ui);
-
-            for (key, value) in constants {
-                ui.push_id(key, |ui| match value {
-                    Constants::Object { map } => {
-                        modified |=
-                            self.show_object(key.clone(), map, key_path.clone(), output, arena, ui);
-                    }
-                    _ => {
-                        modified |= Self::show_value(
-                            key.clone(),
-                            key_path.clone(),
-                            value,
-                            ui,
+        let mut renamed = None;
+
+        let (toggle, _, _) =
+            CollapsingState::load_with_default_open(ui.ctx(), Self::header_id(&key_path), false)
+                .show_header(ui, |ui| {
+                    renamed = self.key_label(&name, &key_path, siblings, output, ui);
+
+                    Self::pin_buttons(&key_path, meta, output, ui);
+
+                    if constants.is_empty()
+                        && accessible::danger_button(ui, "X", &format!("remove {name}")).clicked()
+                    {
+                        self.confirm.ask(
+                            key_path
+                                .iter()
+                                .map(|k| k.as_str())
+                                .collect::<Vec<_>>()
+                                .join("."),
+                            GlobalEvents::RemoveOption(key_path.clone()),
                             output,
-                            arena,
-                        )
+                        );
+                    }
+                })
+                .body(|ui| {
+                    self.add_dialog(key_path.clone(), output, arena, ui);
+
+                    let siblings = constants.keys().cloned().collect::<Vec<_>>();
+
+                    for key in meta.ordered(&key_path, constants) {
+                        let value = constants.get_mut(&key).unwrap();
+
+                        ui.push_id(&key, |ui| match value {
+                            Constants::Object { map } => {
+                                modified |= self.show_object(
+                                    key.clone(),
+                                    map,
+                                    key_path.clone(),
+                                    &siblings,
+                                    meta,
+                                    output,
+                                    arena,
+                                    ui,
+                                );
+                            }
+                            _ => {
+                                modified |= self.show_value(
+                                    key.clone(),
+                                    key_path.clone(),
+                                    &siblings,
+                                    meta,
+                                    value,
+                                    ui,
+                                    output,
+                                    arena,
+                                )
+                            }
+                        });
                     }
                 });
-            }
-        });
+
+        accessible::name_toggle(&toggle, &name);
+
+        if let Some(to) = renamed {
+            let mut new_path = key_path.clone();
+            *Rc::make_mut(&mut new_path).last_mut().unwrap() = to;
+
+            Self::move_expansion(ui.ctx(), &key_path, &new_path, constants);
+        }
 
         modified
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn show_value(
+        &mut self,
         name: Rc<String>,
         mut key_path: OptionLocation,
+        siblings: &[Rc<String>],
+        meta: &UiMeta,
         constant: &mut Constants,
         ui: &mut Ui,
         output: &EventStream<GlobalEvents>,
         arena: &Bump,
     ) -> bool {
+        Rc::make_mut(&mut key_path).push(name.clone());
+
         ui.horizontal(|ui| {
-            ui.label(bumpalo::format!(in &arena, "{} = ", name).as_str());
+            self.key_label(&name, &key_path, siblings, output, ui);
+            Self::pin_buttons(&key_path, meta, output, ui);
+            ui.label("= ");
             let ret = Self::modify_value(arena, constant, ui);
 
-            if ui.button("X").clicked() {
-                let k = Rc::make_mut(&mut key_path);
-                k.push(name);
-                output.add_event(GlobalEvents::RemoveOption(dbg!(key_path)));
+            if accessible::danger_button(ui, "X", &format!("remove {name}")).clicked() {
+                self.confirm.ask(
+                    key_path
+                        .iter()
+                        .map(|k| k.as_str())
+                        .collect::<Vec<_>>()
+                        .join("."),
+                    GlobalEvents::RemoveOption(key_path),
+                    output,
+                );
             }
 
             ret
@@ -244,23 +479,31 @@ impl ConstantsTab {
             Constants::List(items, constants_type) => {
                 let mut update = false;
 
-                CollapsingHeader::new("").show(ui, |ui| {
-                    let mut id = 0;
+                let title = bumpalo::format!(in &arena, "{} items", items.len());
 
-                    items.retain_mut(|i| {
-                        ui.horizontal(|ui| {
-                            update |= ui.push_id(id, |ui| Self::modify_value(arena, i, ui)).inner;
-                            id += 1;
+                CollapsingHeader::new(title.as_str())
+                    .id_salt("items")
+                    .show(ui, |ui| {
+                        let mut id = 0;
 
-                            !ui.button("X").clicked()
-                        })
-                        .inner
-                    });
+                        items.retain_mut(|i| {
+                            ui.horizontal(|ui| {
+                                update |=
+                                    ui.push_id(id, |ui| Self::modify_value(arena, i, ui)).inner;
+                                id += 1;
 
-                    if ui.button("add").clicked() {
-                        items.push(Constants::default_for_type(constants_type));
-                    }
-                });
+                                !accessible::danger_button(ui, "X", &format!("remove item {id}"))
+                                    .clicked()
+                            })
+                            .inner
+                        });
+
+                        if accessible::labeled_button(ui, "add", "add an item to the list")
+                            .clicked()
+                        {
+                            items.push(Constants::default_for_type(constants_type));
+                        }
+                    });
 
                 update
             }
@@ -0,0 +1,382 @@
+use std::{collections::BTreeSet, rc::Rc};
+
+use bumpalo::Bump;
+use egui::{
+    pos2, vec2, Align2, Color32, FontId, Pos2, Rect, ScrollArea, Sense, Stroke, StrokeKind, Ui,
+};
+
+use crate::{
+    bindings::{
+        Binding, BindingKey, Button, ButtonLocation, ControllerType, Debounce, RunWhen, POVS,
+    },
+    component::{Component, EventStream},
+    controller_colors,
+    global_state::{GlobalEvents, State},
+    search_selector::{search_selector, SelectorCache},
+};
+
+use super::from_bindings::{EditingStates, FromBindings};
+
+const BOX: egui::Vec2 = vec2(120.0, 50.0);
+
+// where each xbox button sits on the diagram, roughly how the pad looks from
+// above
+const XBOX_LAYOUT: [(ButtonLocation, i16, [f32; 2]); 12] = [
+    (ButtonLocation::Analog, 2, [120.0, 40.0]),
+    (ButtonLocation::Analog, 3, [780.0, 40.0]),
+    (ButtonLocation::Button, 5, [120.0, 100.0]),
+    (ButtonLocation::Button, 6, [780.0, 100.0]),
+    (ButtonLocation::Button, 9, [120.0, 170.0]),
+    (ButtonLocation::Button, 7, [370.0, 170.0]),
+    (ButtonLocation::Button, 8, [530.0, 170.0]),
+    (ButtonLocation::Button, 4, [780.0, 170.0]),
+    (ButtonLocation::Button, 3, [660.0, 230.0]),
+    (ButtonLocation::Button, 2, [900.0, 230.0]),
+    (ButtonLocation::Button, 1, [780.0, 290.0]),
+    (ButtonLocation::Button, 10, [620.0, 420.0]),
+];
+
+// the pov as a 3x3 pad, let go in the middle
+const POV_CENTER: [f32; 2] = [330.0, 400.0];
+
+fn pov_offset(angle: i16) -> [f32; 2] {
+    match angle {
+        0 => [0.0, -60.0],
+        45 => [130.0, -60.0],
+        90 => [130.0, 0.0],
+        135 => [130.0, 60.0],
+        180 => [0.0, 60.0],
+        225 => [-130.0, 60.0],
+        270 => [-130.0, 0.0],
+        315 => [-130.0, -60.0],
+        _ => [0.0, 0.0],
+    }
+}
+
+/// the center of every button drawn for `controller`, relative to the top
+/// left of the diagram
+pub fn layout(controller: &ControllerType) -> Vec<(Button, Pos2)> {
+    let button = |location, button| Button { button, location };
+
+    let pov = |top: f32| {
+        POVS.iter().map(move |(angle, _)| {
+            let [x, y] = pov_offset(*angle);
+
+            (
+                button(ButtonLocation::Pov, *angle),
+                pos2(POV_CENTER[0] + x, top + y),
+            )
+        })
+    };
+
+    match controller {
+        ControllerType::XBox { .. } => XBOX_LAYOUT
+            .iter()
+            .map(|(location, b, [x, y])| (button(*location, *b), pos2(*x, *y)))
+            .chain(pov(POV_CENTER[1]))
+            .collect(),
+        ControllerType::Generic {
+            buttons, axises, ..
+        } => {
+            let per_row = 8;
+            let cell = |i: usize, top: f32| {
+                pos2(
+                    80.0 + (i % per_row) as f32 * (BOX.x + 10.0),
+                    top + (i / per_row) as f32 * (BOX.y + 10.0),
+                )
+            };
+
+            let rows = |n: usize| n.div_ceil(per_row) as f32 * (BOX.y + 10.0);
+
+            let axes_top = 40.0 + rows(*buttons as usize);
+            let pov_top = axes_top + rows(*axises as usize) + 60.0;
+
+            (1..=*buttons as i16)
+                .enumerate()
+                .map(|(i, b)| (button(ButtonLocation::Button, b), cell(i, 40.0)))
+                .chain(
+                    (0..*axises as i16)
+                        .enumerate()
+                        .map(|(i, a)| (button(ButtonLocation::Analog, a), cell(i, axes_top))),
+                )
+                .chain(pov(pov_top))
+                .collect()
+        }
+        ControllerType::NotBound => Vec::new(),
+    }
+}
+
+/// a drawing of one controller with what every button runs, for checking the
+/// whole pad at a glance
+#[derive(Debug, Default, Clone)]
+pub struct ControllerMap {
+    pub controller: u8,
+    pub controller_filter: String,
+    pub controller_cache: SelectorCache<u8>,
+    // the button clicked on the diagram, its add widgets show below
+    pub picked: Option<Button>,
+    pub editing: Option<EditingStates>,
+    pub filtered_commands: SelectorCache<Rc<String>>,
+}
+
+impl ControllerMap {
+    pub fn new(env: &State) -> Self {
+        Self {
+            controller: env.first_bound_controller(),
+            ..Default::default()
+        }
+    }
+
+    /// the lines under a button, chords say what's held with it
+    fn bound_lines(&self, button: Button, env: &State, arena: &Bump) -> Vec<String> {
+        let controller = &env.controllers[self.controller as usize];
+
+        env.bindings
+            .binding_to_commands
+            .range(((self.controller, button), None)..)
+            .take_while(|(((c, b), _), _)| *c == self.controller && *b == button)
+            .flat_map(|(key, commands)| {
+                commands.iter().map(move |(command, when)| {
+                    let off = if env.bindings.is_enabled(command, *key, *when) {
+                        ""
+                    } else {
+                        " (off)"
+                    };
+
+                    match key.1 {
+                        Some(modifier) => format!(
+                            "{} + {command} {when}{off}",
+                            controller.button_name(&modifier, arena)
+                        ),
+                        None => format!("{command} {when}{off}"),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// true when something on `button` conflicts without being marked on
+    /// purpose or can't be on the controller
+    fn has_problem(
+        &self,
+        button: Button,
+        conflicts: &[(BindingKey, RunWhen)],
+        env: &State,
+    ) -> bool {
+        conflicts.iter().any(|(((c, b), modifier), when)| {
+            let binding = Binding {
+                controller: *c,
+                button: *b,
+                during: *when,
+                debounce: Debounce::NONE,
+                threshold: None,
+                modifier: *modifier,
+                enabled: true,
+            };
+
+            *c == self.controller && *b == button && !env.intentional.contains(&binding)
+        }) || env
+            .bindings
+            .binding_to_commands
+            .keys()
+            .filter(|((c, b), _)| *c == self.controller && *b == button)
+            .any(|((c, b), modifier)| !env.valid_binding(*c, *b, *modifier))
+    }
+
+    fn diagram(&mut self, env: &State, ui: &mut Ui, arena: &Bump) {
+        let controller = env.controllers[self.controller as usize];
+        let buttons = layout(&controller);
+
+        let size = buttons.iter().fold(vec2(0.0, 0.0), |size, (_, center)| {
+            size.max(center.to_vec2() + BOX / 2.0)
+        }) + vec2(20.0, 20.0);
+
+        let (area, _) = ui.allocate_exact_size(size, Sense::hover());
+
+        let conflicts = env.bindings.conflicts();
+        let visuals = ui.visuals().clone();
+        let red = Color32::from_rgb(0xf3, 0x8b, 0xa8);
+        let outline = env.controller_color(self.controller, ui);
+
+        for (button, center) in buttons {
+            let rect = Rect::from_center_size(area.min + center.to_vec2(), BOX);
+
+            let response = ui
+                .interact(rect, ui.id().with(("map button", button)), Sense::click())
+                .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+            let lines = self.bound_lines(button, env, arena);
+            let problem = self.has_problem(button, &conflicts, env);
+
+            let fill = if self.picked == Some(button) {
+                visuals.selection.bg_fill
+            } else if response.hovered() {
+                visuals.widgets.hovered.bg_fill
+            } else {
+                visuals.widgets.inactive.bg_fill
+            };
+
+            let stroke = if problem {
+                Stroke::new(2.0, red)
+            } else {
+                Stroke::new(1.0, outline)
+            };
+
+            let painter = ui.painter().with_clip_rect(rect);
+
+            painter.rect(rect, 6.0, fill, stroke, StrokeKind::Inside);
+
+            painter.text(
+                rect.center_top() + vec2(0.0, 4.0),
+                Align2::CENTER_TOP,
+                controller.button_name(&button, arena),
+                FontId::proportional(13.0),
+                visuals.strong_text_color(),
+            );
+
+            let text = match lines.len() {
+                0 => String::new(),
+                1 | 2 => lines.join("\n"),
+                n => format!("{}\n+{} more", lines[0], n - 1),
+            };
+
+            painter.text(
+                rect.center_top() + vec2(0.0, 20.0),
+                Align2::CENTER_TOP,
+                text,
+                FontId::proportional(10.0),
+                if problem { red } else { visuals.text_color() },
+            );
+
+            let response = if lines.is_empty() {
+                response
+            } else {
+                response.on_hover_text(lines.join("\n"))
+            };
+
+            if response.clicked() {
+                self.picked = (self.picked != Some(button)).then_some(button);
+                self.editing = Some(EditingStates::new(env));
+            }
+        }
+    }
+}
+
+impl Component for ControllerMap {
+    type OutputEvents = GlobalEvents;
+
+    type Environment = State;
+
+    fn render(
+        &mut self,
+        ui: &mut Ui,
+        env: &mut Self::Environment,
+        output: &EventStream<Self::OutputEvents>,
+        arena: &Bump,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("controller");
+
+            controller_colors::chip(ui, env.controller_color(self.controller, ui));
+
+            if search_selector(
+                ui.make_persistent_id("controller map selector"),
+                &mut self.controller_filter,
+                &mut self.controller,
+                env.controllers.iter().enumerate().flat_map(|(id, c)| {
+                    c.bound().then(|| (env.controller_name(id as u8), id as u8))
+                }),
+                None,
+                None,
+                &mut self.controller_cache,
+                100.0,
+                ui,
+            )
+            .changed
+            {
+                self.picked = None;
+            }
+
+            ui.separator();
+
+            if ui
+                .button("export cheat sheet")
+                .on_hover_text("every binding in this profile as a printable table")
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("markdown", &["md"])
+                    .set_file_name(format!("{} bindings.md", env.profile))
+                    .save_file()
+                {
+                    if let Err(err) = env.export_cheat_sheet(&path, arena) {
+                        output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                    }
+                }
+            }
+        });
+
+        let controller = env.controllers[self.controller as usize];
+
+        if !controller.bound() {
+            ui.label("pick a controller that's set up in manage controllers");
+            return;
+        }
+
+        // bound to buttons that aren't on the pad, there's nowhere to draw them
+        let drawn = layout(&controller)
+            .into_iter()
+            .map(|(button, _)| button)
+            .collect::<BTreeSet<_>>();
+
+        let missing = env
+            .bindings
+            .binding_to_commands
+            .iter()
+            .filter(|(((c, b), _), _)| *c == self.controller && !drawn.contains(b))
+            .flat_map(|(((_, b), _), commands)| {
+                commands
+                    .iter()
+                    .map(move |(command, when)| format!("{command} {when} on {}", b.button))
+            })
+            .collect::<Vec<_>>();
+
+        ScrollArea::both().show(ui, |ui| {
+            self.diagram(env, ui, arena);
+
+            if !missing.is_empty() {
+                ui.colored_label(
+                    Color32::from_rgb(0xf3, 0x8b, 0xa8),
+                    format!(
+                        "bound to buttons this controller doesn't have: {}",
+                        missing.join(", ")
+                    ),
+                );
+            }
+
+            if let Some(button) = self.picked {
+                ui.separator();
+
+                ui.strong(format!(
+                    "{} {}",
+                    env.controller_name(self.controller),
+                    controller.button_name(&button, arena)
+                ));
+
+                FromBindings::add_widgets(
+                    &mut self.filtered_commands,
+                    ui,
+                    env,
+                    output,
+                    self.editing.get_or_insert_with(|| EditingStates::new(env)),
+                    ((self.controller, button), None),
+                    arena,
+                );
+            }
+        });
+    }
+
+    fn tab_type(&self) -> super::TabType {
+        super::TabType::ControllerMap
+    }
+}
@@ -0,0 +1,193 @@
+use bumpalo::Bump;
+use egui::{Color32, ComboBox, Grid, ProgressBar, RichText, ScrollArea, Ui};
+
+use crate::{
+    bindings::Button,
+    component::{Component, EventStream},
+    controller_colors,
+    gamepad::Reading,
+    global_state::{GlobalEvents, State},
+};
+
+const GREEN: Color32 = Color32::from_rgb(0xa6, 0xe3, 0xa1);
+
+/// live buttons and axes of the gamepads plugged in, for checking a
+/// controller is on the right slot before deploying. only reads them while
+/// it's drawn
+#[derive(Debug, Default, Clone)]
+pub struct ControllerMonitor {}
+
+impl ControllerMonitor {
+    fn assignments(&self, env: &State, ui: &mut Ui) {
+        let connected = env.gamepads.connected();
+
+        if connected.is_empty() {
+            ui.label("no gamepads plugged in");
+            return;
+        }
+
+        for (id, name) in connected {
+            ui.horizontal(|ui| {
+                ui.label(name);
+
+                let mut slot = env.gamepads.slot(id);
+
+                ComboBox::from_id_salt(("monitor slot", id))
+                    .selected_text(
+                        slot.map_or("no slot".into(), |s| env.controller_name(s).to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut slot, None, "no slot");
+
+                        for (i, c) in env.controllers.iter().enumerate() {
+                            if c.bound() {
+                                ui.selectable_value(
+                                    &mut slot,
+                                    Some(i as u8),
+                                    env.controller_name(i as u8).as_str(),
+                                );
+                            }
+                        }
+                    });
+
+                if slot != env.gamepads.slot(id) {
+                    env.gamepads.assign(id, slot);
+                }
+            });
+        }
+    }
+
+    /// the commands a press of `button` runs right now, chords only count
+    /// while their modifier is held too
+    fn triggered(&self, slot: u8, button: Button, reading: &Reading, env: &State) -> Vec<String> {
+        env.bindings
+            .binding_to_commands
+            .range(((slot, button), None)..)
+            .take_while(|(((c, b), _), _)| *c == slot && *b == button)
+            .filter(|((_, modifier), _)| modifier.is_none_or(|m| reading.pressed.contains(&m)))
+            .flat_map(|(key, commands)| {
+                commands
+                    .iter()
+                    .filter(|(command, when)| env.bindings.is_enabled(command, *key, *when))
+                    .map(|(command, when)| format!("{command} {when}"))
+            })
+            .collect()
+    }
+
+    fn slot(&self, slot: u8, reading: &Reading, env: &State, ui: &mut Ui, arena: &Bump) {
+        let controller = env.controllers[slot as usize];
+
+        Grid::new(("monitor buttons", slot))
+            .striped(true)
+            .show(ui, |ui| {
+                for button in controller.enumerate_buttons(arena) {
+                    let held = reading.pressed.contains(&button);
+                    let name = controller.button_name(&button, arena);
+
+                    ui.label(if held {
+                        RichText::new(name).color(GREEN).strong()
+                    } else {
+                        RichText::new(name)
+                    });
+
+                    if held {
+                        let commands = self.triggered(slot, button, reading, env);
+
+                        if commands.is_empty() {
+                            ui.weak("nothing bound");
+                        } else {
+                            ui.colored_label(GREEN, commands.join(", "));
+                        }
+                    } else {
+                        ui.label("");
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(8.0);
+
+        Grid::new(("monitor axes", slot)).show(ui, |ui| {
+            for (axis, value) in &reading.axes {
+                ui.label(controller.axis_name(*axis, arena));
+
+                ui.add(
+                    ProgressBar::new((value + 1.0) / 2.0)
+                        .desired_width(200.0)
+                        .text(format!("{value:.2}")),
+                );
+
+                let streams = env
+                    .stream_to_axis
+                    .iter()
+                    .filter(|(_, (c, a))| *c == slot && a == axis)
+                    .map(|(stream, _)| stream.as_str())
+                    .collect::<Vec<_>>();
+
+                ui.weak(streams.join(", "));
+
+                ui.end_row();
+            }
+        });
+    }
+}
+
+impl Component for ControllerMonitor {
+    type OutputEvents = GlobalEvents;
+
+    type Environment = State;
+
+    fn render(
+        &mut self,
+        ui: &mut Ui,
+        env: &mut Self::Environment,
+        _output: &EventStream<Self::OutputEvents>,
+        arena: &Bump,
+    ) {
+        if let Err(err) = env.gamepads.update() {
+            ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), err);
+            return;
+        }
+
+        // picks up gamepads plugged in, pulled out or moved to another slot,
+        // the controllers tab compares its counts against these
+        env.device_caps = env.gamepads.caps();
+
+        // only drawn while the tab is showing, so this stops once it's closed
+        // or behind another tab
+        ui.ctx().request_repaint();
+
+        ScrollArea::vertical().show(ui, |ui| {
+            ui.heading("gamepads");
+
+            self.assignments(env, ui);
+
+            for (slot, controller) in env.controllers.iter().enumerate() {
+                let slot = slot as u8;
+
+                if !controller.bound() {
+                    continue;
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    controller_colors::chip(ui, env.controller_color(slot, ui));
+                    ui.heading(env.controller_name(slot).as_str());
+                });
+
+                match env.gamepads.read(slot, controller) {
+                    Some(reading) => self.slot(slot, &reading, env, ui, arena),
+                    None => {
+                        ui.weak("no gamepad on this slot, give it one above");
+                    }
+                }
+            }
+        });
+    }
+
+    fn tab_type(&self) -> super::TabType {
+        super::TabType::ControllerMonitor
+    }
+}
@@ -1,11 +1,12 @@
 use std::{collections::BTreeMap, rc::Rc};
 
 use bumpalo::Bump;
-use egui::{collapsing_header::CollapsingState, ScrollArea, Ui};
+use egui::{collapsing_header::CollapsingState, Color32, RichText, ScrollArea, Ui};
 
 use crate::{
+    accessible,
     component::EventStream,
-    constants::Constants,
+    constants::{self, Constants, Source},
     global_state::{GlobalEvents, State},
     single_linked_list::SingleLinkedList,
     Component,
@@ -30,6 +31,13 @@ impl Component for DriverConstantsTab {
     ) {
         let mut modified = false;
 
+        if ui.button("copy resolved constants").clicked() {
+            match serde_json::to_string_pretty(&env.resolved_constants()) {
+                Ok(json) => ui.ctx().copy_text(json),
+                Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+            }
+        }
+
         ScrollArea::vertical().show(ui, |ui| {
             let constants = &mut env.driver_constants;
 
@@ -103,7 +111,7 @@ impl DriverConstantsTab {
     ) -> bool {
         let mut modified = false;
 
-        CollapsingState::load_with_default_open(
+        let (toggle, _, _) = CollapsingState::load_with_default_open(
             ui.ctx(),
             ui.make_persistent_id("object header"),
             false,
@@ -150,6 +158,8 @@ impl DriverConstantsTab {
             }
         });
 
+        accessible::name_toggle(&toggle, &name);
+
         modified
     }
 
@@ -162,19 +172,79 @@ impl DriverConstantsTab {
         ui: &mut Ui,
         arena: &Bump,
     ) -> bool {
-        ui.horizontal(|ui| match constant {
+        ui.horizontal(|ui| {
+            Self::provenance(constant.as_deref(), default, ui, arena);
+
+            Self::edit_value(name, key_path, constant, default, output, ui, arena)
+        })
+        .inner
+    }
+
+    /// says whether the robot gets the default or an override, hovering shows
+    /// everything that was considered
+    fn provenance(constant: Option<&Constants>, default: &Constants, ui: &mut Ui, arena: &Bump) {
+        let resolved = constants::resolve(default, [(Source::ThisProfile, constant)]);
+
+        let color = match resolved.source {
+            Source::Default => ui.visuals().weak_text_color(),
+            Source::ThisProfile => Color32::from_rgb(0xa6, 0xe3, 0xa1),
+        };
+
+        let mut chain = bumpalo::collections::String::new_in(arena);
+
+        for (source, value) in &resolved.chain {
+            let line = match value {
+                Some(value) => bumpalo::format!(in arena, "{}: {}\n", source, value),
+                None => bumpalo::format!(in arena, "{}: not set\n", source),
+            };
+
+            chain.push_str(&line);
+        }
+
+        ui.label(
+            RichText::new(resolved.source.to_string())
+                .small()
+                .color(color),
+        )
+        .on_hover_text(chain.trim_end());
+    }
+
+    fn edit_value(
+        name: Rc<String>,
+        key_path: &SingleLinkedList<Rc<String>>,
+        constant: Option<&mut Constants>,
+        default: &Constants,
+        output: &EventStream<GlobalEvents>,
+        ui: &mut Ui,
+        arena: &Bump,
+    ) -> bool {
+        match constant {
             Some(c) => {
                 ui.label(bumpalo::format!(in &arena, "{} = ", name).as_str());
                 let ret = crate::tabs::constants::ConstantsTab::modify_value(arena, c, ui);
 
-                if ui.button("reset").clicked() {
+                if accessible::labeled_button(
+                    ui,
+                    "reset",
+                    bumpalo::format!(in &arena, "use the project value for {}", name).as_str(),
+                )
+                .clicked()
+                {
                     output.add_event(GlobalEvents::RemoveOptionDriver(Rc::new(key_path.to_vec())));
                 }
 
                 ret
             }
             None => {
-                if ui.button("change value").clicked() {
+                ui.label(bumpalo::format!(in &arena, "{} = {}", name, default).as_str());
+
+                if accessible::labeled_button(
+                    ui,
+                    "change value",
+                    bumpalo::format!(in &arena, "set {} for this profile", name).as_str(),
+                )
+                .clicked()
+                {
                     output.add_event(GlobalEvents::AddOptionDriver(
                         Rc::new(key_path.to_vec()),
                         default.clone(),
@@ -183,7 +253,6 @@ impl DriverConstantsTab {
 
                 true
             }
-        })
-        .inner
+        }
     }
 }
@@ -1,16 +1,27 @@
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     rc::Rc,
 };
 
 use bumpalo::Bump;
-use egui::{Color32, ScrollArea, Ui};
+use egui::{
+    popup_below_widget, text::LayoutJob, Color32, Key, PopupCloseBehavior, RichText, ScrollArea,
+    Stroke, TextEdit, TextFormat, TextStyle, Ui,
+};
 
 use crate::{
-    bindings::{Binding, Button, PButton, RunWhen},
+    accessible,
+    bindings::{
+        Binding, BindingKey, Button, ButtonLocation, Debounce, PButton, RunWhen, Threshold,
+        DEFAULT_THRESHOLD,
+    },
     component::{Component, EventStream},
+    confirm::ConfirmDelete,
+    controller_colors,
     global_state::GlobalEvents,
-    search_selector::{search_selector, SelectorCache},
+    number_input::number_input,
+    quick_add::{self, QuickAdd, QuickAddError},
+    search_selector::{focus_search_selector, search_selector, valid_result, SelectorCache},
     State,
 };
 
@@ -19,6 +30,10 @@ pub struct EditingStates {
     command: Rc<String>,
     filter: String,
     when: RunWhen,
+    debounce: f64,
+    debounce_text: String,
+    threshold: f64,
+    threshold_text: String,
 }
 
 impl Default for EditingStates {
@@ -26,22 +41,114 @@ impl Default for EditingStates {
         Self {
             command: Rc::new("".to_string()),
             when: RunWhen::WhileTrue,
+            debounce: 0.0,
+            debounce_text: "0".to_string(),
+            threshold: DEFAULT_THRESHOLD,
+            threshold_text: DEFAULT_THRESHOLD.to_string(),
             filter: "".to_string(),
         }
     }
 }
 
+impl EditingStates {
+    pub fn new(env: &State) -> Self {
+        match env.commands.first() {
+            Some(command) => Self {
+                command: command.clone(),
+                filter: command.to_string(),
+                ..Default::default()
+            },
+            None => Default::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct FromBindings {
-    pub editing_states: HashMap<PButton, EditingStates>,
+    pub editing_states: HashMap<BindingKey, EditingStates>,
     pub button: Button,
+    pub modifier: Option<Button>,
     pub controller: u8,
-    pub bindings: BTreeSet<PButton>,
+    pub bindings: BTreeSet<BindingKey>,
     pub button_filter: String,
     pub button_filter_cache: SelectorCache<Button>,
+    pub modifier_filter: String,
+    pub modifier_filter_cache: SelectorCache<Button>,
     pub filtered_commands: SelectorCache<Rc<String>>,
     pub controller_filter: String,
     pub controller_cache: SelectorCache<u8>,
+    pub focus_search: bool,
+    pub quick_add: String,
+    // notes being typed, sent once the field loses focus
+    pub note_edits: HashMap<(Rc<String>, Binding), String>,
+    pub confirm: ConfirmDelete<GlobalEvents>,
+    // command chips ticked for "remove selected"
+    pub selected: HashSet<(BindingKey, Rc<String>, RunWhen)>,
+    // hides binding rows whose controller and button names don't match
+    pub filter: String,
+    // the two buttons picked in the swap dialog, none while it's closed
+    pub swap: Option<[ButtonPicker; 2]>,
+}
+
+/// a controller and one of its buttons, picked with the usual selectors
+#[derive(Debug, Default, Clone)]
+pub struct ButtonPicker {
+    pub controller: u8,
+    pub controller_filter: String,
+    pub controller_cache: SelectorCache<u8>,
+    pub button: Button,
+    pub button_filter: String,
+    pub button_cache: SelectorCache<Button>,
+}
+
+impl ButtonPicker {
+    pub fn new(controller: u8) -> Self {
+        Self {
+            controller,
+            ..Default::default()
+        }
+    }
+
+    pub fn picked(&self) -> PButton {
+        (self.controller, self.button)
+    }
+
+    pub fn show(
+        &mut self,
+        id: impl std::hash::Hash + Copy,
+        env: &State,
+        ui: &mut Ui,
+        arena: &Bump,
+    ) {
+        controller_colors::chip(ui, env.controller_color(self.controller, ui));
+
+        search_selector(
+            ui.make_persistent_id(("picker controller", id)),
+            &mut self.controller_filter,
+            &mut self.controller,
+            env.controllers.iter().enumerate().flat_map(|(id, c)| {
+                if c.bound() {
+                    Some((env.controller_name(id as u8), id as u8))
+                } else {
+                    None
+                }
+            }),
+            None,
+            None,
+            &mut self.controller_cache,
+            100.0,
+            ui,
+        );
+
+        env.controllers[self.controller as usize].show_button_selector(
+            ui.make_persistent_id(("picker button", id)),
+            &mut self.button_filter,
+            &mut self.button_cache,
+            &mut self.button,
+            ui,
+            arena,
+        );
+    }
 }
 
 impl Component for FromBindings {
@@ -57,9 +164,19 @@ impl Component for FromBindings {
         arena: &Bump,
     ) {
         ScrollArea::vertical().show(ui, |ui| {
+            self.quick_add(ui, env, output, arena);
+
+            ui.separator();
+
             ui.horizontal(|ui| {
                 ui.label("controller");
 
+                controller_colors::chip(ui, env.controller_color(self.controller, ui));
+
+                if std::mem::take(&mut self.focus_search) {
+                    focus_search_selector(ui.make_persistent_id("controller_selector"), ui);
+                }
+
                 search_selector(
                     ui.make_persistent_id("controller_selector"),
                     &mut self.controller_filter,
@@ -71,6 +188,8 @@ impl Component for FromBindings {
                             None
                         }
                     }),
+                    None,
+                    None,
                     &mut self.controller_cache,
                     100.0,
                     ui,
@@ -87,20 +206,128 @@ impl Component for FromBindings {
                     arena,
                 );
 
+                #[cfg(feature = "gamepad")]
+                if let Some((controller, button)) = crate::gamepad::listen_button(
+                    ui.make_persistent_id("bindings listen"),
+                    self.controller,
+                    env,
+                    output,
+                    ui,
+                ) {
+                    self.controller = controller;
+                    self.controller_filter = env.controller_name(controller).to_string();
+                    self.button = button;
+                    self.button_filter = env.controllers[controller as usize]
+                        .button_name(&button, arena)
+                        .to_string();
+                }
+
+                env.controllers[self.controller as usize].show_modifier_selector(
+                    ui.make_persistent_id("bindings modifier selector"),
+                    &mut self.modifier_filter,
+                    &mut self.modifier_filter_cache,
+                    &mut self.modifier,
+                    ui,
+                    arena,
+                );
+
                 if ui.button("add button").clicked()
-                    && env.valid_binding(self.controller, self.button)
+                    && env.valid_binding(self.controller, self.button, self.modifier)
+                {
+                    self.bindings
+                        .insert(((self.controller, self.button), self.modifier));
+                }
+
+                if ui
+                    .button("export csv")
+                    .on_hover_text("every binding in every profile, for spreadsheets")
+                    .clicked()
                 {
-                    self.bindings.insert((self.controller, self.button));
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("csv", &["csv"])
+                        .set_file_name("bindings.csv")
+                        .save_file()
+                    {
+                        if let Err(err) = env.export_csv(&path, arena) {
+                            output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                        }
+                    }
+                }
+
+                if ui.button("swap buttons").clicked() {
+                    self.swap = match self.swap {
+                        Some(_) => None,
+                        None => Some([
+                            ButtonPicker::new(self.controller),
+                            ButtonPicker::new(self.controller),
+                        ]),
+                    };
                 }
             });
 
+            self.swap_dialog(ui, env, output, arena);
+
             ui.separator();
 
             self.bindings.retain(|b| !env.bindings.has_button(*b));
 
+            let invalid = self.invalid_pending(env).count();
+
+            ui.horizontal(|ui| {
+                ui.label("filter");
+                ui.text_edit_singleline(&mut self.filter);
+
+                if invalid > 0
+                    && ui
+                        .button(
+                            bumpalo::format!(in &arena, "remove invalid ({})", invalid).as_str(),
+                        )
+                        .on_hover_text("added buttons the controllers no longer have")
+                        .clicked()
+                {
+                    self.remove_invalid_pending(env);
+                }
+            });
+
+            let filter = self.filter.to_lowercase();
+
+            let conflicts = env.bindings.conflicts();
+
+            let overlaps = env
+                .bindings
+                .chord_findings(|trigger| env.trigger_name(trigger, arena));
+
+            self.selected.retain(|(key, command, when)| {
+                env.bindings
+                    .binding_to_commands
+                    .get(key)
+                    .is_some_and(|commands| commands.contains(&(command.clone(), *when)))
+            });
+
             egui::Grid::new("from_bindings_grid").show(ui, |ui| {
-                for (controller, button) in &self.bindings {
-                    Self::display_binding(*controller, *button, env, ui, arena);
+                for key in &self.bindings {
+                    if !Self::matches(*key, &filter, env, arena) {
+                        continue;
+                    }
+
+                    Self::display_binding(*key, false, env, ui, arena);
+
+                    let ((controller, button), modifier) = *key;
+
+                    // there's nothing to bind it to until the controller has
+                    // the button again
+                    if !env.valid_binding(controller, button, modifier) {
+                        ui.weak(
+                            bumpalo::format!(in &arena,
+                                "{} doesn't have this anymore",
+                                env.controller_name(controller)
+                            )
+                            .as_str(),
+                        );
+
+                        ui.end_row();
+                        continue;
+                    }
 
                     Self::add_widgets(
                         &mut self.filtered_commands,
@@ -108,34 +335,204 @@ impl Component for FromBindings {
                         env,
                         output,
                         self.editing_states
-                            .entry((*controller, *button))
-                            .or_default(),
-                        (*controller, *button),
+                            .entry(*key)
+                            .or_insert_with(|| EditingStates::new(env)),
+                        *key,
+                        arena,
                     );
 
                     ui.end_row();
                 }
 
-                for ((controller, button), commands) in &env.bindings.binding_to_commands {
-                    Self::display_binding(*controller, *button, env, ui, arena);
+                for (key, commands) in &env.bindings.binding_to_commands {
+                    if !Self::matches(*key, &filter, env, arena) {
+                        continue;
+                    }
+
+                    let ((controller, button), modifier) = key;
+
+                    let binding = |during| Binding {
+                        controller: *controller,
+                        button: *button,
+                        during,
+                        debounce: Debounce::NONE,
+                        threshold: None,
+                        modifier: *modifier,
+                        enabled: true,
+                    };
+
+                    let conflicted = conflicts
+                        .iter()
+                        .filter(|(b, _)| b == key)
+                        .map(|(_, when)| *when)
+                        .collect::<Vec<_>>();
+
+                    let unintended = |when: &RunWhen| {
+                        conflicted.contains(when) && !env.intentional.contains(&binding(*when))
+                    };
+
+                    Self::display_binding(*key, conflicted.iter().any(unintended), env, ui, arena);
 
                     ui.horizontal(|ui| {
                         for (command, when) in commands {
-                            ui.label(bumpalo::format!(in &arena, "{} {}", command, when).as_str());
+                            let text = bumpalo::format!(in &arena, "{} {}", command, when);
+
+                            let mut text = RichText::new(text.as_str());
+
+                            let enabled = env.bindings.is_enabled(command, *key, *when);
+
+                            if !enabled {
+                                text = text.strikethrough().weak();
+                            } else if unintended(when) {
+                                text = text.color(Color32::from_rgb(0xf3, 0x8b, 0xa8));
+                            }
+
+                            let chip = (*key, command.clone(), *when);
+                            let mut selected = self.selected.contains(&chip);
+
+                            let label = ui.checkbox(&mut selected, text);
 
-                            let keep = !ui.button("X").clicked();
+                            if label.changed() {
+                                if selected {
+                                    self.selected.insert(chip);
+                                } else {
+                                    self.selected.remove(&chip);
+                                }
+                            }
+
+                            if let Some(description) = env.command_description(command) {
+                                label.on_hover_text(description);
+                            }
+
+                            let mut on = enabled;
+
+                            if ui
+                                .checkbox(&mut on, "on")
+                                .on_hover_text("off keeps the binding but the robot won't run it")
+                                .changed()
+                            {
+                                output.add_event(GlobalEvents::SetBindingEnabled(
+                                    binding(*when),
+                                    command.clone(),
+                                    on,
+                                ));
+                            }
+
+                            let name = bumpalo::format!(in &arena,
+                                "remove binding {} → {}",
+                                env.controllers[*controller as usize]
+                                    .chord_name(button, *modifier, arena),
+                                command
+                            );
+
+                            let note = (command.clone(), binding(*when));
+
+                            let mut text =
+                                self.note_edits.get(&note).cloned().unwrap_or_else(|| {
+                                    env.binding_note(command, note.1).unwrap_or("").to_string()
+                                });
+
+                            let resp = TextEdit::singleline(&mut text)
+                                .hint_text("note")
+                                .desired_width(80.0)
+                                .show(ui)
+                                .response;
+
+                            if resp.lost_focus() {
+                                self.note_edits.remove(&note);
+
+                                if text.trim() != env.binding_note(command, note.1).unwrap_or("") {
+                                    output.add_event(GlobalEvents::SetBindingNote(
+                                        note.1,
+                                        command.clone(),
+                                        text,
+                                    ));
+                                }
+                            } else if resp.has_focus() {
+                                self.note_edits.insert(note, text);
+                            }
+
+                            if accessible::danger_button(ui, "X", name.as_str()).clicked() {
+                                self.confirm.ask(
+                                    format!(
+                                        "{command} {}",
+                                        binding(*when).show(command, env, arena)
+                                    ),
+                                    GlobalEvents::RemoveBinding(binding(*when), command.clone()),
+                                    output,
+                                );
+                            }
+                        }
 
-                            if !keep {
+                        let selected = commands
+                            .iter()
+                            .filter(|(command, when)| {
+                                self.selected.contains(&(*key, command.clone(), *when))
+                            })
+                            .collect::<Vec<_>>();
+
+                        if !selected.is_empty()
+                            && ui
+                                .button(
+                                    bumpalo::format!(in &arena,
+                                        "remove selected ({})",
+                                        selected.len()
+                                    )
+                                    .as_str(),
+                                )
+                                .clicked()
+                        {
+                            // all in one frame so they're written out once
+                            for (command, when) in selected {
                                 output.add_event(GlobalEvents::RemoveBinding(
-                                    Binding {
-                                        controller: *controller,
-                                        button: *button,
-                                        during: *when,
-                                    },
+                                    binding(*when),
                                     command.clone(),
                                 ));
                             }
                         }
+
+                        if commands.len() > 1 && ui.button("remove all").clicked() {
+                            self.confirm.ask_all(
+                                format!(
+                                    "all {} bindings on {}",
+                                    commands.len(),
+                                    env.controllers[*controller as usize]
+                                        .chord_name(button, *modifier, arena)
+                                ),
+                                commands
+                                    .iter()
+                                    .map(|(command, when)| {
+                                        GlobalEvents::RemoveBinding(binding(*when), command.clone())
+                                    })
+                                    .collect(),
+                                output,
+                            );
+                        }
+
+                        for when in &conflicted {
+                            let mut intentional = env.intentional.contains(&binding(*when));
+
+                            if ui
+                                .checkbox(
+                                    &mut intentional,
+                                    bumpalo::format!(in &arena, "{} on purpose", when).as_str(),
+                                )
+                                .changed()
+                            {
+                                output.add_event(GlobalEvents::SetIntentional(
+                                    binding(*when),
+                                    intentional,
+                                ));
+                            }
+                        }
+
+                        for (_, finding) in overlaps
+                            .iter()
+                            .filter(|(bindings, _)| bindings.iter().any(|(_, b)| b.key() == *key))
+                        {
+                            ui.colored_label(Color32::from_rgb(0xf9, 0xe2, 0xaf), "overlaps")
+                                .on_hover_text(&finding.message);
+                        }
                     });
 
                     Self::add_widgets(
@@ -144,15 +541,22 @@ impl Component for FromBindings {
                         env,
                         output,
                         self.editing_states
-                            .entry((*controller, *button))
-                            .or_default(),
-                        (*controller, *button),
+                            .entry(*key)
+                            .or_insert_with(|| EditingStates::new(env)),
+                        *key,
+                        arena,
                     );
 
                     ui.end_row();
                 }
             });
         });
+
+        self.confirm.show(ui, output);
+    }
+
+    fn focus_search(&mut self) {
+        self.focus_search = true;
     }
 
     fn tab_type(&self) -> super::TabType {
@@ -161,28 +565,240 @@ impl Component for FromBindings {
 }
 
 impl FromBindings {
-    fn display_binding(controller: u8, button: Button, env: &State, ui: &mut Ui, arena: &Bump) {
+    pub fn new(env: &State) -> Self {
+        Self {
+            controller: env.first_bound_controller(),
+            ..Default::default()
+        }
+    }
+
+    /// added buttons that aren't on their controller anymore, e.g. after it
+    /// was unbound or given fewer buttons in manage controllers
+    pub fn invalid_pending<'a>(&'a self, env: &'a State) -> impl Iterator<Item = BindingKey> + 'a {
+        self.bindings
+            .iter()
+            .copied()
+            .filter(|((controller, button), modifier)| {
+                !env.valid_binding(*controller, *button, *modifier)
+            })
+    }
+
+    pub fn remove_invalid_pending(&mut self, env: &State) {
+        self.bindings.retain(|((controller, button), modifier)| {
+            env.valid_binding(*controller, *button, *modifier)
+        });
+    }
+
+    fn quick_add(
+        &mut self,
+        ui: &mut Ui,
+        env: &State,
+        output: &EventStream<GlobalEvents>,
+        arena: &Bump,
+    ) {
+        let red = Color32::from_rgb(0xf3, 0x8b, 0xa8);
+
+        ui.horizontal(|ui| {
+            // the layouter parses whatever is in the box to underline the
+            // bad part, the rest of the frame reuses that
+            let mut parsed: Option<(String, Result<QuickAdd, QuickAddError>)> = None;
+
+            let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+                let format = TextFormat::simple(
+                    TextStyle::Body.resolve(ui.style()),
+                    ui.visuals().text_color(),
+                );
+
+                let mut job = LayoutJob::default();
+                job.wrap.max_width = wrap_width;
+
+                let result = quick_add::parse(text, env, arena);
+
+                match &result {
+                    Err(err) if !err.span.is_empty() => {
+                        job.append(&text[..err.span.start], 0.0, format.clone());
+                        job.append(
+                            &text[err.span.clone()],
+                            0.0,
+                            TextFormat {
+                                color: red,
+                                underline: Stroke::new(1.0, red),
+                                ..format.clone()
+                            },
+                        );
+                        job.append(&text[err.span.end..], 0.0, format);
+                    }
+                    _ => job.append(text, 0.0, format),
+                }
+
+                parsed = Some((text.to_string(), result));
+
+                ui.fonts(|f| f.layout_job(job))
+            };
+
+            ui.label("quick add");
+
+            let edit = ui.add(
+                TextEdit::singleline(&mut self.quick_add)
+                    .hint_text("0 a while shoot")
+                    .desired_width(300.0)
+                    .layouter(&mut layouter),
+            );
+
+            let parsed = match parsed.take() {
+                Some((text, parsed)) if text == self.quick_add => parsed,
+                _ => quick_add::parse(&self.quick_add, env, arena),
+            };
+
+            let mut add = None;
+
+            match &parsed {
+                _ if self.quick_add.trim().is_empty() => {}
+                Ok(QuickAdd::Ready(binding, command)) => {
+                    ui.label(
+                        bumpalo::format!(in arena, "{} {}", command, binding.show(command, env, arena))
+                            .as_str(),
+                    );
+
+                    if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                        add = Some((*binding, command.clone()));
+                    }
+                }
+                Ok(QuickAdd::Ambiguous(binding, commands)) => {
+                    ui.label(
+                        bumpalo::format!(in arena,
+                            "{} commands match, pick one",
+                            commands.len()
+                        )
+                        .as_str(),
+                    );
+
+                    let id = ui.make_persistent_id("quick add commands");
+
+                    if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                        ui.memory_mut(|mem| mem.open_popup(id));
+                    }
+
+                    popup_below_widget(
+                        ui,
+                        id,
+                        &edit,
+                        PopupCloseBehavior::CloseOnClickOutside,
+                        |ui| {
+                            for command in commands {
+                                if ui.button(command.as_str()).clicked() {
+                                    add = Some((*binding, command.clone()));
+                                    ui.memory_mut(|mem| mem.close_popup());
+                                }
+                            }
+                        },
+                    );
+                }
+                Err(err) => {
+                    ui.colored_label(red, err.message.as_str());
+                }
+            }
+
+            if let Some((binding, command)) = add {
+                if env.bindings.has_binding(&command, binding) {
+                    output.add_event(GlobalEvents::DisplayError(
+                        "binding already exists".to_string(),
+                    ));
+                } else {
+                    output.add_event(GlobalEvents::AddBinding(binding, command));
+                    self.quick_add.clear();
+                }
+
+                edit.request_focus();
+            }
+        });
+    }
+
+    fn swap_dialog(
+        &mut self,
+        ui: &mut Ui,
+        env: &State,
+        output: &EventStream<GlobalEvents>,
+        arena: &Bump,
+    ) {
+        let Some([a, b]) = &mut self.swap else {
+            return;
+        };
+
+        let mut close = false;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("swap");
+                a.show("swap a", env, ui, arena);
+                ui.label("with");
+                b.show("swap b", env, ui, arena);
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("swap").clicked() {
+                    output.add_event(GlobalEvents::SwapButtons(a.picked(), b.picked()));
+                }
+
+                close = ui.button("close").clicked();
+            });
+        });
+
+        if close {
+            self.swap = None;
+        }
+    }
+
+    fn matches(
+        ((controller, button), modifier): BindingKey,
+        filter: &str,
+        env: &State,
+        arena: &Bump,
+    ) -> bool {
+        let text = bumpalo::format!(in arena,
+            "{} {}",
+            env.controller_name(controller),
+            env.controllers[controller as usize].chord_name(&button, modifier, arena)
+        );
+
+        valid_result(&text, filter)
+    }
+
+    fn display_binding(
+        ((controller, button), modifier): BindingKey,
+        conflicted: bool,
+        env: &State,
+        ui: &mut Ui,
+        arena: &Bump,
+    ) {
         let text = bumpalo::format!(in &arena,
             "{} {} has bindings",
             env.controller_name(controller),
-            env.controllers[controller as usize].button_name(&button, arena)
+            env.controllers[controller as usize].chord_name(&button, modifier, arena)
         );
 
-        if env.valid_binding(controller, button) {
-            ui.label(text.as_str())
-        } else {
-            ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), text.as_str())
-        };
+        ui.horizontal(|ui| {
+            controller_colors::chip(ui, env.controller_color(controller, ui));
+
+            if env.valid_binding(controller, button, modifier) && !conflicted {
+                ui.label(text.as_str());
+            } else {
+                ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), text.as_str());
+            }
+        });
     }
 
-    fn add_widgets(
+    pub fn add_widgets(
         cache: &mut SelectorCache<Rc<String>>,
         ui: &mut Ui,
         env: &State,
         output: &EventStream<GlobalEvents>,
         state: &mut EditingStates,
-        binding: PButton,
+        binding: BindingKey,
+        arena: &Bump,
     ) {
+        let frequent = env.frequent_commands(5);
+
         ui.horizontal(|ui| {
             ui.label("command");
 
@@ -191,6 +807,8 @@ impl FromBindings {
                 &mut state.filter,
                 &mut state.command,
                 env.commands.iter().map(|a| (a.clone(), a.clone())),
+                Some(&|command| frequent.iter().position(|c| c == command)),
+                Some(&|command| env.command_description(command).map(str::to_string)),
                 cache,
                 200.0,
                 ui,
@@ -201,7 +819,21 @@ impl FromBindings {
 
             when_run.selection_ui(ui, binding);
 
-            if ui.button("add").clicked() {
+            let ((controller, button), modifier) = binding;
+
+            let analog = button.location == ButtonLocation::Analog;
+
+            if analog {
+                ui.label("past");
+
+                number_input(&mut state.threshold_text, &mut state.threshold, arena, ui);
+            }
+
+            ui.label("debounce (s)");
+
+            number_input(&mut state.debounce_text, &mut state.debounce, arena, ui);
+
+            if accessible::labeled_button(ui, "add", "bind the command to this button").clicked() {
                 if !env.commands.contains(&state.command) {
                     output.add_event(GlobalEvents::DisplayError(
                         "not a valid command (maybe try adding it in manage commands)".to_string(),
@@ -210,9 +842,13 @@ impl FromBindings {
                 }
 
                 let binding = Binding {
-                    controller: binding.0,
-                    button: binding.1,
+                    controller,
+                    button,
                     during: *when_run,
+                    debounce: Debounce::from_seconds(state.debounce),
+                    threshold: analog.then(|| Threshold::from_value(state.threshold)),
+                    modifier,
+                    enabled: true,
                 };
 
                 if env.bindings.has_binding(&state.command, binding) {
@@ -1,13 +1,20 @@
 use bumpalo::Bump;
-use egui::{Color32, Grid, ScrollArea, Ui};
+use egui::{Align, CollapsingHeader, Color32, Grid, RichText, ScrollArea, Ui};
 
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
 
 use crate::{
-    bindings::{Binding, Button, RunWhen},
-    component::Component,
+    accessible,
+    bindings::{Binding, Button, ButtonLocation, Debounce, RunWhen, Threshold, DEFAULT_THRESHOLD},
+    component::{Component, EventStream},
+    confirm::ConfirmDelete,
+    controller_colors,
     global_state::GlobalEvents,
-    search_selector::{search_selector, SelectorCache},
+    number_input::number_input,
+    search_selector::{focus_search_selector, search_selector, valid_result, SelectorCache},
     State,
 };
 
@@ -17,7 +24,14 @@ pub struct BindingEditingState {
     button: Button,
     filter: String,
     cache: SelectorCache<Button>,
+    modifier: Option<Button>,
+    modifier_filter: String,
+    modifier_cache: SelectorCache<Button>,
     when: RunWhen,
+    debounce: f64,
+    debounce_text: String,
+    threshold: f64,
+    threshold_text: String,
 
     controller_filter: String,
     controller_cache: SelectorCache<u8>,
@@ -29,20 +43,360 @@ impl Default for BindingEditingState {
             controller: Default::default(),
             button: Button {
                 button: 1,
-                location: crate::bindings::ButtonLocation::Button,
+                location: ButtonLocation::Button,
             },
             when: RunWhen::WhileTrue,
+            debounce: 0.0,
+            debounce_text: "0".to_string(),
+            threshold: DEFAULT_THRESHOLD,
+            threshold_text: DEFAULT_THRESHOLD.to_string(),
             filter: Default::default(),
             cache: Default::default(),
+            modifier: None,
+            modifier_filter: Default::default(),
+            modifier_cache: Default::default(),
             controller_filter: Default::default(),
             controller_cache: Default::default(),
         }
     }
 }
 
+impl BindingEditingState {
+    fn new(env: &State) -> Self {
+        Self {
+            controller: env.first_bound_controller(),
+            ..Default::default()
+        }
+    }
+}
+
+/// the order rows are shown in, nothing about it is saved
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CommandSort {
+    #[default]
+    Alphabetical,
+    // unbound commands first
+    FewestBindings,
+    RecentlyModified,
+}
+
+impl CommandSort {
+    fn label(self) -> &'static str {
+        match self {
+            CommandSort::Alphabetical => "name",
+            CommandSort::FewestBindings => "fewest bindings",
+            CommandSort::RecentlyModified => "recently changed",
+        }
+    }
+
+    /// `commands` start out alphabetical, ties keep that order
+    fn sort(self, commands: &mut [&Rc<String>], env: &State) {
+        match self {
+            CommandSort::Alphabetical => {}
+            CommandSort::FewestBindings => {
+                commands.sort_by_key(|command| env.bindings.bindings_for_command(command).count())
+            }
+            CommandSort::RecentlyModified => commands.sort_by_key(|command| {
+                std::cmp::Reverse(env.command_usage.get(*command).map(|u| u.modified))
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct FromCommands {
     pub editing_states: HashMap<Rc<String>, BindingEditingState>,
+    pub filter: String,
+    pub focus_search: bool,
+    pub only_unbound: bool,
+    // a command picked from the unbound list, scrolled to on the next frame
+    pub jump_to: Option<Rc<String>>,
+    // the row after the one just bound, its button selector takes focus so
+    // the next binding can be typed straight away
+    pub focus_row: Option<Rc<String>>,
+    // the controller last bound to, carried over to the focused row
+    pub last_controller: Option<u8>,
+    pub confirm: ConfirmDelete<GlobalEvents>,
+    pub sort: CommandSort,
+}
+
+impl FromCommands {
+    pub fn new(env: &State) -> Self {
+        Self {
+            editing_states: env
+                .commands
+                .iter()
+                .map(|command| (command.clone(), BindingEditingState::new(env)))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// true when a binding was added from the row
+    fn command_row(
+        &mut self,
+        command: &Rc<String>,
+        jumping: bool,
+        env: &State,
+        output: &EventStream<GlobalEvents>,
+        ui: &mut Ui,
+        arena: &Bump,
+    ) -> bool {
+        let mut added = false;
+
+        if jumping {
+            ui.scroll_to_cursor(Some(Align::Center));
+        }
+
+        ui.horizontal(|ui| {
+            let count = env.bindings.bindings_for_command(command).count();
+
+            let label = ui.label(
+                bumpalo::format!(in arena,
+                    "{} has {} {}",
+                    command.as_str(),
+                    count,
+                    if count == 1 { "binding" } else { "bindings" }
+                )
+                .as_str(),
+            );
+
+            if let Some(description) = env.command_description(command) {
+                label.on_hover_text(description);
+            }
+
+            if let Some(cooldown) = env.command_cooldowns.get(command) {
+                ui.weak(bumpalo::format!(in arena, "cooldown {} s", cooldown).as_str());
+            }
+
+            if ui.button("copy to all profiles").clicked() {
+                output.add_event(GlobalEvents::PropagateBindings(command.clone()));
+            }
+
+            for binding in env.bindings.bindings_for_command(command) {
+                controller_colors::chip(ui, env.controller_color(binding.controller, ui));
+
+                let mut on = binding.enabled;
+
+                if ui
+                    .checkbox(&mut on, "")
+                    .on_hover_text("off keeps the binding but the robot won't run it")
+                    .changed()
+                {
+                    output.add_event(GlobalEvents::SetBindingEnabled(
+                        binding,
+                        command.clone(),
+                        on,
+                    ));
+                }
+
+                if !binding.enabled {
+                    ui.label(
+                        RichText::new(binding.show_without_note(env, arena))
+                            .strikethrough()
+                            .weak(),
+                    );
+                } else if !env.valid_binding(binding.controller, binding.button, binding.modifier) {
+                    ui.colored_label(
+                        Color32::from_rgb(0xf3, 0x8b, 0xa8),
+                        binding.show_without_note(env, arena),
+                    );
+                } else {
+                    ui.label(binding.show_without_note(env, arena));
+                }
+
+                if let Some(note) = env.binding_note(command, binding) {
+                    ui.weak(note);
+                }
+
+                let name = bumpalo::format!(in arena,
+                    "remove binding {} → {}",
+                    env.controllers[binding.controller as usize].chord_name(
+                        &binding.button,
+                        binding.modifier,
+                        arena
+                    ),
+                    command
+                );
+
+                if accessible::danger_button(ui, "X", name.as_str()).clicked() {
+                    self.confirm.ask(
+                        format!("{command} {}", binding.show(command, env, arena)),
+                        GlobalEvents::RemoveBinding(binding, command.clone()),
+                        output,
+                    );
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let edit_state = self
+                .editing_states
+                .entry(command.clone())
+                .or_insert_with(|| BindingEditingState::new(env));
+
+            let focusing = self.focus_row.as_ref() == Some(command);
+
+            if focusing {
+                self.focus_row = None;
+
+                if let Some(controller) = self.last_controller {
+                    edit_state.controller = controller;
+                }
+
+                ui.scroll_to_cursor(Some(Align::Center));
+
+                focus_search_selector(ui.make_persistent_id(("from commands button", command)), ui);
+            }
+
+            ui.label("controller");
+
+            controller_colors::chip(ui, env.controller_color(edit_state.controller, ui));
+
+            if jumping {
+                focus_search_selector(
+                    ui.make_persistent_id(("from commands controller", command)),
+                    ui,
+                );
+            }
+
+            search_selector(
+                ui.make_persistent_id(("from commands controller", command)),
+                &mut edit_state.controller_filter,
+                &mut edit_state.controller,
+                env.controllers.iter().enumerate().flat_map(|(id, c)| {
+                    if c.bound() {
+                        Some((env.controller_name(id as u8), id as u8))
+                    } else {
+                        None
+                    }
+                }),
+                None,
+                None,
+                &mut edit_state.controller_cache,
+                100.0,
+                ui,
+            );
+
+            ui.label("button");
+
+            let button = env.controllers[edit_state.controller as usize].show_button_selector(
+                ui.make_persistent_id(("from commands button", command)),
+                &mut edit_state.filter,
+                &mut edit_state.cache,
+                &mut edit_state.button,
+                ui,
+                arena,
+            );
+
+            #[cfg(feature = "gamepad")]
+            if let Some((controller, button)) = crate::gamepad::listen_button(
+                ui.make_persistent_id(("from commands listen", command)),
+                edit_state.controller,
+                env,
+                output,
+                ui,
+            ) {
+                edit_state.controller = controller;
+                edit_state.controller_filter = env.controller_name(controller).to_string();
+                edit_state.button = button;
+                edit_state.filter = env.controllers[controller as usize]
+                    .button_name(&button, arena)
+                    .to_string();
+            }
+
+            env.controllers[edit_state.controller as usize].show_modifier_selector(
+                ui.make_persistent_id(("from commands modifier", command)),
+                &mut edit_state.modifier_filter,
+                &mut edit_state.modifier_cache,
+                &mut edit_state.modifier,
+                ui,
+                arena,
+            );
+
+            let run_when = &mut edit_state.when;
+
+            let when = run_when.selection_ui(ui, command);
+
+            // enter on the button goes straight to when it runs
+            if button.committed {
+                when.request_focus();
+            }
+
+            let analog = edit_state.button.location == ButtonLocation::Analog;
+
+            if analog {
+                ui.label("past");
+
+                number_input(
+                    &mut edit_state.threshold_text,
+                    &mut edit_state.threshold,
+                    arena,
+                    ui,
+                );
+            }
+
+            ui.label("debounce (s)");
+
+            number_input(
+                &mut edit_state.debounce_text,
+                &mut edit_state.debounce,
+                arena,
+                ui,
+            );
+
+            let binding = Binding {
+                controller: edit_state.controller,
+                button: edit_state.button,
+                during: edit_state.when,
+                debounce: Debounce::from_seconds(edit_state.debounce),
+                threshold: analog.then(|| Threshold::from_value(edit_state.threshold)),
+                modifier: edit_state.modifier,
+                enabled: true,
+            };
+
+            if accessible::labeled_button(
+                ui,
+                "add",
+                bumpalo::format!(in arena, "bind {}", command).as_str(),
+            )
+            .clicked()
+                && env.valid_binding(
+                    edit_state.controller,
+                    edit_state.button,
+                    edit_state.modifier,
+                )
+            {
+                output.add_event(GlobalEvents::AddBinding(binding, command.clone()));
+
+                self.last_controller = Some(edit_state.controller);
+                added = true;
+            }
+        });
+
+        if let Some(edit_state) = self.editing_states.get(command) {
+            let controller = &env.controllers[edit_state.controller as usize];
+
+            if controller.bound() {
+                let free = env
+                    .bindings
+                    .free_buttons(edit_state.controller, controller, arena)
+                    .iter()
+                    .map(|button| controller.button_name(button, arena))
+                    .collect::<Vec<_>>();
+
+                ui.horizontal_wrapped(|ui| {
+                    if free.is_empty() {
+                        ui.weak("free: nothing, every button is taken");
+                    } else {
+                        ui.weak(format!("free: {}", free.join(", ")));
+                    }
+                });
+            }
+        }
+
+        added
+    }
 }
 
 impl Component for FromCommands {
@@ -57,92 +411,120 @@ impl Component for FromCommands {
         output: &crate::component::EventStream<Self::OutputEvents>,
         arena: &Bump,
     ) {
+        ui.horizontal(|ui| {
+            ui.label("filter");
+
+            let resp = ui.text_edit_singleline(&mut self.filter);
+
+            if std::mem::take(&mut self.focus_search) {
+                resp.request_focus();
+            }
+
+            ui.checkbox(&mut self.only_unbound, "only show unbound");
+
+            ui.separator();
+
+            ui.label("sort by");
+
+            for sort in [
+                CommandSort::Alphabetical,
+                CommandSort::FewestBindings,
+                CommandSort::RecentlyModified,
+            ] {
+                ui.selectable_value(&mut self.sort, sort, sort.label());
+            }
+        });
+
+        let unbound = env.bindings.unbound(&env.commands).collect::<Vec<_>>();
+
+        if !unbound.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(
+                    Color32::from_rgb(0xf9, 0xe2, 0xaf),
+                    bumpalo::format!(in arena, "{} unbound commands:", unbound.len()).as_str(),
+                );
+
+                for command in &unbound {
+                    if ui.link(command.as_str()).clicked() {
+                        self.jump_to = Some((*command).clone());
+                    }
+                }
+            });
+        }
+
+        let filter = self.filter.to_lowercase();
+
+        let jump_to = self.jump_to.take();
+
+        let mut groups: BTreeMap<Option<&str>, Vec<&Rc<String>>> = BTreeMap::new();
+
+        for command in &env.commands {
+            if jump_to.as_ref() == Some(command)
+                || valid_result(command, &filter)
+                    && (!self.only_unbound || unbound.contains(&command))
+            {
+                let group = env
+                    .command_meta
+                    .get(command)
+                    .and_then(|meta| meta.group.as_deref());
+
+                groups.entry(group).or_default().push(command);
+            }
+        }
+
+        for commands in groups.values_mut() {
+            self.sort.sort(commands, env);
+        }
+
+        // the order rows are drawn in, for finding the one after a binding
+        let order = groups.values().flatten().copied().collect::<Vec<_>>();
+
         ScrollArea::vertical().show(ui, |ui| {
             // TODO ADD POV BINDING
 
-            Grid::new("from_commands_grid").show(ui, |ui| {
-                for command in &env.commands {
-                    ui.horizontal(|ui| {
-                        ui.label(
-                            bumpalo::format!(in arena, "{} has bindings", command.as_str())
-                                .as_str(),
-                        );
-
-                        for binding in env.bindings.bindings_for_command(command) {
-                            if !env.controllers[binding.controller as usize]
-                                .valid_binding(binding.button)
-                            {
-                                ui.colored_label(
-                                    Color32::from_rgb(0xf3, 0x8b, 0xa8),
-                                    binding.show(env, arena),
-                                );
-                            } else {
-                                ui.label(binding.show(env, arena));
+            for (group, commands) in &groups {
+                let mut grid = |ui: &mut Ui| {
+                    Grid::new(("from_commands_grid", group)).show(ui, |ui| {
+                        for command in commands {
+                            if self.command_row(
+                                command,
+                                jump_to.as_ref() == Some(*command),
+                                env,
+                                output,
+                                ui,
+                                arena,
+                            ) {
+                                self.focus_row = order
+                                    .iter()
+                                    .skip_while(|c| *c != command)
+                                    .nth(1)
+                                    .map(|c| (*c).clone());
                             }
 
-                            if ui.button("X").clicked() {
-                                output.add_event(GlobalEvents::RemoveBinding(
-                                    binding,
-                                    command.clone(),
-                                ));
-                            }
+                            ui.end_row();
                         }
                     });
+                };
 
-                    ui.horizontal(|ui| {
-                        let edit_state = self.editing_states.entry(command.clone()).or_default();
-
-                        ui.label("controller");
-
-                        search_selector(
-                            ui.make_persistent_id(("from commands controller", command)),
-                            &mut edit_state.controller_filter,
-                            &mut edit_state.controller,
-                            env.controllers.iter().enumerate().flat_map(|(id, c)| {
-                                if c.bound() {
-                                    Some((env.controller_name(id as u8), id as u8))
-                                } else {
-                                    None
-                                }
-                            }),
-                            &mut edit_state.controller_cache,
-                            100.0,
-                            ui,
-                        );
-
-                        ui.label("button");
-
-                        env.controllers[edit_state.controller as usize].show_button_selector(
-                            ui.make_persistent_id(("from commands button", command)),
-                            &mut edit_state.filter,
-                            &mut edit_state.cache,
-                            &mut edit_state.button,
-                            ui,
-                            arena,
-                        );
-
-                        let run_when = &mut edit_state.when;
-
-                        run_when.selection_ui(ui, command);
-
-                        let binding = Binding {
-                            controller: edit_state.controller,
-                            button: edit_state.button,
-                            during: edit_state.when,
-                        };
-
-                        if ui.button("add").clicked()
-                            && (env.controllers[edit_state.controller as usize]
-                                .valid_binding(edit_state.button))
-                        {
-                            output.add_event(GlobalEvents::AddBinding(binding, command.clone()));
-                        }
-                    });
+                match group {
+                    Some(group) => {
+                        let jumping = commands.iter().any(|c| jump_to.as_ref() == Some(*c));
 
-                    ui.end_row();
+                        CollapsingHeader::new(*group)
+                            .default_open(true)
+                            .open(jumping.then_some(true))
+                            .show(ui, grid);
+                    }
+                    None => grid(ui),
                 }
-            });
+            }
         });
+
+        self.confirm.show(ui, output);
+    }
+
+    fn focus_search(&mut self) {
+        self.focus_search = true;
     }
 
     fn tab_type(&self) -> super::TabType {
@@ -0,0 +1,72 @@
+use egui::{Grid, ScrollArea, TextEdit};
+
+use crate::{
+    audit_log::{self, Entry},
+    global_state::{GlobalEvents, State},
+    Component,
+};
+
+/// how far back the history goes
+const SHOWN: usize = 300;
+
+/// the change log for the project, newest first. only reads it again when
+/// something new was logged
+#[derive(Debug, Default, Clone)]
+pub struct HistoryTab {
+    pub filter: String,
+    pub entries: Vec<Entry>,
+    // `State::log_written` when the log was last read, none before the first
+    pub read_at: Option<usize>,
+}
+
+impl Component for HistoryTab {
+    type OutputEvents = GlobalEvents;
+
+    type Environment = State;
+
+    fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut Self::Environment,
+        _output: &crate::component::EventStream<Self::OutputEvents>,
+        _arena: &bumpalo::Bump,
+    ) {
+        if self.read_at != Some(env.log_written) {
+            self.entries = audit_log::tail(&env.deploy_dir, SHOWN);
+            self.read_at = Some(env.log_written);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("filter");
+            ui.add(TextEdit::singleline(&mut self.filter).hint_text("who, what or when"));
+
+            if ui.button("refresh").clicked() {
+                self.read_at = None;
+            }
+        });
+
+        if self.entries.is_empty() {
+            ui.label("nothing has been changed yet");
+            return;
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("history grid").striped(true).show(ui, |ui| {
+                for entry in self.entries.iter().rev() {
+                    if !self.filter.is_empty() && !entry.matches(&self.filter) {
+                        continue;
+                    }
+
+                    ui.weak(&entry.at);
+                    ui.label(entry.by.as_deref().unwrap_or(""));
+                    ui.label(&entry.summary).on_hover_text(&entry.kind);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn tab_type(&self) -> super::TabType {
+        super::TabType::History
+    }
+}
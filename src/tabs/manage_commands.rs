@@ -1,14 +1,48 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::Duration,
+};
 
 use bumpalo::Bump;
-use egui::{ScrollArea, TextEdit, Ui};
+use egui::{Button, ScrollArea, TextEdit, Ui};
 
-use crate::{component::Component, global_state::GlobalEvents, State};
+use crate::{
+    accessible,
+    bindings::CommandMeta,
+    component::{Component, EventStream},
+    confirm::ConfirmDelete,
+    export,
+    global_state::GlobalEvents,
+    name_check,
+    number_input::number_input,
+    search_selector::valid_result,
+    source_scan, State,
+};
 
-#[derive(Debug, Clone)]
-pub(crate) struct ManageTab {
+#[derive(Debug)]
+pub struct ManageTab {
     pub adding: String,
     pub rename: HashMap<Rc<String>, String>,
+    pub cooldowns: HashMap<Rc<String>, String>,
+    pub groups: HashMap<Rc<String>, String>,
+    pub descriptions: HashMap<Rc<String>, String>,
+    // the project the drafts above were filled from, they're dropped once it
+    // is read again from disk, its commands change or an entry is refused.
+    // plain saves keep them, those happen while the next field is being typed
+    pub drafted_from: Option<Drafted>,
+    // text in the paste box for adding lots of commands at once
+    pub pasted: String,
+    // a scan of the robot code that hasn't finished yet
+    pub source_scan: Option<Receiver<source_scan::Scan>>,
+    // names the last scan found, ticked ones get added
+    pub discovered: Vec<(String, bool)>,
+    pub filter: String,
+    pub focus_search: bool,
+    // a delete that was refused, with the profiles that still use the command
+    pub blocked_delete: Option<(Rc<String>, String)>,
+    pub confirm: ConfirmDelete<GlobalEvents>,
 }
 
 impl Default for ManageTab {
@@ -16,6 +50,123 @@ impl Default for ManageTab {
         Self {
             adding: "".to_string(),
             rename: HashMap::new(),
+            cooldowns: HashMap::new(),
+            groups: HashMap::new(),
+            descriptions: HashMap::new(),
+            drafted_from: None,
+            pasted: String::new(),
+            source_scan: None,
+            discovered: Vec::new(),
+            filter: "".to_string(),
+            focus_search: false,
+            blocked_delete: None,
+            confirm: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Drafted {
+    reloads: usize,
+    errors_shown: usize,
+    commands: BTreeSet<Rc<String>>,
+}
+
+impl ManageTab {
+    /// drops the text drafts when they might no longer match what they were
+    /// filled from
+    pub fn forget_stale_drafts(&mut self, env: &State) {
+        let current = self.drafted_from.as_ref().is_some_and(|drafted| {
+            drafted.reloads == env.reloads
+                && drafted.errors_shown == env.errors_shown
+                && drafted.commands == env.commands
+        });
+
+        if current {
+            return;
+        }
+
+        self.rename.clear();
+        self.cooldowns.clear();
+        self.groups.clear();
+        self.descriptions.clear();
+
+        self.drafted_from = Some(Drafted {
+            reloads: env.reloads,
+            errors_shown: env.errors_shown,
+            commands: env.commands.clone(),
+        });
+    }
+
+    fn import_from_source(&mut self, ui: &mut Ui, env: &State, output: &EventStream<GlobalEvents>) {
+        if let Some(scan) = &self.source_scan {
+            match scan.try_recv() {
+                Ok(scan) => {
+                    if !scan.skipped.is_empty() {
+                        output.add_event(GlobalEvents::DisplayWarning(format!(
+                            "{} source files couldn't be read and were skipped: {}",
+                            scan.skipped.len(),
+                            scan.skipped
+                                .iter()
+                                .map(|file| file.display().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    }
+
+                    self.discovered = scan
+                        .names
+                        .into_iter()
+                        .filter(|name| !name_check::name_taken(&env.commands, name))
+                        .map(|name| (name, true))
+                        .collect();
+
+                    if self.discovered.is_empty() {
+                        output.add_event(GlobalEvents::DisplayInfo(
+                            "no new commands in the robot code".to_string(),
+                        ));
+                    }
+
+                    self.source_scan = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    ui.ctx().request_repaint_after(Duration::from_millis(100));
+                }
+                Err(TryRecvError::Disconnected) => self.source_scan = None,
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let scanning = self.source_scan.is_some();
+
+            if ui
+                .add_enabled(!scanning, Button::new("scan robot code"))
+                .on_hover_text(source_scan::SOURCE_DIRS.join(" and "))
+                .clicked()
+            {
+                self.discovered.clear();
+                self.source_scan = Some(source_scan::scan_in_background(env.project_root()));
+            }
+
+            if scanning {
+                ui.spinner();
+            }
+        });
+
+        if self.discovered.is_empty() {
+            return;
+        }
+
+        for (name, add) in &mut self.discovered {
+            ui.checkbox(add, name.as_str());
+        }
+
+        if ui.button("add ticked commands").clicked() {
+            for (name, add) in self.discovered.drain(..) {
+                if add {
+                    output.add_event(GlobalEvents::AddCommand(name));
+                }
+            }
         }
     }
 }
@@ -30,8 +181,10 @@ impl Component for ManageTab {
         ui: &mut Ui,
         env: &mut Self::Environment,
         output: &crate::component::EventStream<Self::OutputEvents>,
-        _arena: &Bump,
+        arena: &Bump,
     ) {
+        self.forget_stale_drafts(env);
+
         ScrollArea::vertical().show(ui, |ui| {
             let mut update = false;
             let adding = &mut self.adding;
@@ -39,15 +192,112 @@ impl Component for ManageTab {
             ui.horizontal(|ui| {
                 ui.text_edit_singleline(adding);
 
-                if ui.button("add").clicked() && !adding.is_empty() {
+                if accessible::labeled_button(ui, "add", "add command").clicked()
+                    && !adding.is_empty()
+                {
                     output.add_event(GlobalEvents::AddCommand(std::mem::take(adding)));
                     update = true;
                 }
             });
 
+            ui.collapsing("add many", |ui| {
+                ui.add(
+                    TextEdit::multiline(&mut self.pasted)
+                        .hint_text("one command per line")
+                        .desired_rows(4),
+                );
+
+                ui.horizontal(|ui| {
+                    if ui.button("add all").clicked() {
+                        add_names(&std::mem::take(&mut self.pasted), env, output);
+                    }
+
+                    if ui.button("import from file").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("text", &["txt"])
+                            .pick_file()
+                        {
+                            match std::fs::read_to_string(path) {
+                                Ok(text) => add_names(&text, env, output),
+                                Err(err) => {
+                                    output.add_event(GlobalEvents::DisplayError(err.to_string()))
+                                }
+                            }
+                        }
+                    }
+                });
+            });
+
+            ui.collapsing("import from robot code", |ui| {
+                self.import_from_source(ui, env, output);
+            });
+
+            ui.horizontal(|ui| {
+                let any = !env.commands.is_empty();
+
+                if ui
+                    .add_enabled(any, Button::new("copy all command names"))
+                    .on_disabled_hover_text("there are no commands to copy")
+                    .clicked()
+                {
+                    ui.ctx()
+                        .copy_text(export::name_list("command", env.commands.iter()));
+                }
+
+                if ui
+                    .add_enabled(any, Button::new("copy as markdown table"))
+                    .on_disabled_hover_text("there are no commands to copy")
+                    .clicked()
+                {
+                    ui.ctx().copy_text(export::command_table(
+                        &env.commands,
+                        &env.bindings,
+                        &env.command_meta,
+                    ));
+                }
+            });
+
+            if let Some((command, profiles)) = &self.blocked_delete {
+                let mut close = false;
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("{command} is still used in: {profiles}"));
+
+                    if ui
+                        .button("delete anyway and strip from all profiles")
+                        .clicked()
+                    {
+                        output.add_event(GlobalEvents::PurgeCommand(command.clone()));
+                        close = true;
+                    }
+
+                    close |= ui.button("cancel").clicked();
+                });
+
+                if close {
+                    self.blocked_delete = None;
+                }
+            }
+
             ui.separator();
 
+            ui.horizontal(|ui| {
+                ui.label("filter");
+
+                let resp = ui.text_edit_singleline(&mut self.filter);
+
+                if std::mem::take(&mut self.focus_search) {
+                    resp.request_focus();
+                }
+            });
+
+            let filter = self.filter.to_lowercase();
+
             for command in &env.commands {
+                if !valid_result(command, &filter) {
+                    continue;
+                }
+
                 ui.horizontal(|ui| {
                     let rename = self
                         .rename
@@ -61,7 +311,7 @@ impl Component for ManageTab {
                     );
 
                     if resp.lost_focus() {
-                        if env.commands.contains(rename) {
+                        if name_check::name_taken(&env.commands, rename) {
                             output.add_event(GlobalEvents::DisplayError(
                                 "command of that name already exists".to_string(),
                             ));
@@ -73,31 +323,129 @@ impl Component for ManageTab {
                         }
                     }
 
-                    if ui.button("X").clicked() {
-                        let is_used = match env.is_used(command) {
-                            Ok(a) => a,
-                            Err(a) => {
-                                output.add_event(GlobalEvents::DisplayError(a.to_string()));
-                                true
+                    let mut required = env.required_commands.contains(command);
+
+                    if ui.checkbox(&mut required, "required").changed() {
+                        output
+                            .add_event(GlobalEvents::SetCommandRequired(command.clone(), required));
+                    }
+
+                    let current = env.command_cooldowns.get(command).copied().unwrap_or(0.0);
+                    let mut cooldown = current as f64;
+
+                    ui.label("cooldown (s)");
+
+                    let text = self
+                        .cooldowns
+                        .entry(command.clone())
+                        .or_insert_with(|| current.to_string());
+
+                    if number_input(text, &mut cooldown, arena, ui) && cooldown as f32 != current {
+                        output.add_event(GlobalEvents::SetCommandCooldown(
+                            command.clone(),
+                            Some(cooldown as f32),
+                        ));
+                    }
+
+                    let meta = env.command_meta.get(command).cloned().unwrap_or_default();
+
+                    let group = self
+                        .groups
+                        .entry(command.clone())
+                        .or_insert_with(|| meta.group.clone().unwrap_or_default());
+
+                    if ui
+                        .add(
+                            TextEdit::singleline(group)
+                                .hint_text("group")
+                                .desired_width(80.0),
+                        )
+                        .lost_focus()
+                    {
+                        output.add_event(GlobalEvents::SetCommandMeta(
+                            command.clone(),
+                            CommandMeta {
+                                group: non_empty(group),
+                                ..meta.clone()
+                            },
+                        ));
+                    }
+
+                    let description = self
+                        .descriptions
+                        .entry(command.clone())
+                        .or_insert_with(|| meta.description.clone().unwrap_or_default());
+
+                    if ui
+                        .add(TextEdit::singleline(description).hint_text("description"))
+                        .lost_focus()
+                    {
+                        output.add_event(GlobalEvents::SetCommandMeta(
+                            command.clone(),
+                            CommandMeta {
+                                description: non_empty(description),
+                                ..meta
+                            },
+                        ));
+                    }
+
+                    if accessible::danger_button(ui, "X", &format!("remove command {command}"))
+                        .clicked()
+                    {
+                        match env.is_used(command) {
+                            Ok(profiles) if profiles.is_empty() => {
+                                self.confirm.ask(
+                                    format!("command {command}"),
+                                    GlobalEvents::RemoveCommand(command.clone()),
+                                    output,
+                                );
                             }
-                        };
+                            Ok(profiles) => {
+                                let profiles = env.describe_profiles(&profiles);
 
-                        let valid_remove = !is_used;
+                                output.add_event(GlobalEvents::DisplayError(format!(
+                                    "can't delete a command that is still used in: {profiles}"
+                                )));
 
-                        if valid_remove {
-                            output.add_event(GlobalEvents::RemoveCommand(command.clone()));
-                        } else {
-                            output.add_event(GlobalEvents::DisplayError(
-                                "can't delete a command that is still used".to_string(),
-                            ));
+                                self.blocked_delete = Some((command.clone(), profiles));
+                            }
+                            Err(err) => {
+                                output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                            }
                         }
                     }
                 });
             }
         });
+
+        self.confirm.show(ui, output);
+    }
+
+    fn focus_search(&mut self) {
+        self.focus_search = true;
     }
 
     fn tab_type(&self) -> super::TabType {
         super::TabType::ManageCommands
     }
 }
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn add_names(text: &str, env: &State, output: &EventStream<GlobalEvents>) {
+    let (names, skipped) = export::parse_name_list("command", text, &env.commands);
+
+    for name in names {
+        output.add_event(GlobalEvents::AddCommand(name));
+    }
+
+    if skipped > 0 {
+        output.add_event(GlobalEvents::DisplayInfo(format!(
+            "skipped {skipped} empty or duplicate lines"
+        )));
+    }
+}
@@ -1,16 +1,107 @@
 use std::rc::Rc;
 
 use bumpalo::Bump;
-use egui::{DragValue, ScrollArea, TextEdit};
+use egui::{Color32, ComboBox, DragValue, ScrollArea, TextEdit};
 
 use crate::{
+    accessible,
     bindings::ControllerType,
     component::Component,
+    controller_colors,
     global_state::{GlobalEvents, State},
 };
 
 #[derive(Debug, Default, Clone)]
-pub struct ManageControllers {}
+pub struct ManageControllers {
+    // slot being cleared and what has been typed to confirm it
+    pub confirm_clear: Option<(u8, String)>,
+    pub move_from: u8,
+    pub move_to: u8,
+}
+
+impl ManageControllers {
+    fn move_bindings(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("move bindings from");
+
+            slot_combo("move from", &mut self.move_from, env, ui);
+
+            ui.label("to");
+
+            slot_combo("move to", &mut self.move_to, env, ui);
+
+            if ui
+                .add_enabled(
+                    self.move_from != self.move_to
+                        && env.controllers[self.move_to as usize].bound()
+                        && env.bindings.has_controller(self.move_from),
+                    egui::Button::new("move"),
+                )
+                .clicked()
+            {
+                output.add_event(GlobalEvents::MoveControllerBindings {
+                    from: self.move_from,
+                    to: self.move_to,
+                });
+            }
+        });
+    }
+
+    fn confirm_clear(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: u8,
+        output: &crate::component::EventStream<GlobalEvents>,
+        arena: &Bump,
+    ) {
+        let Some((slot, typed)) = &mut self.confirm_clear else {
+            return;
+        };
+
+        if *slot != id {
+            return;
+        }
+
+        let mut done = false;
+
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                Color32::from_rgb(0xf3, 0x8b, 0xa8),
+                bumpalo::format!(in &arena,
+                    "type {} to remove every binding on this slot",
+                    id
+                )
+                .as_str(),
+            );
+
+            ui.add(TextEdit::singleline(typed).desired_width(30.0));
+
+            if ui
+                .add_enabled(
+                    typed.trim() == bumpalo::format!(in &arena, "{}", id).as_str(),
+                    egui::Button::new("clear"),
+                )
+                .clicked()
+            {
+                output.add_event(GlobalEvents::ClearControllerBindings(id));
+                done = true;
+            }
+
+            if ui.button("cancel").clicked() {
+                done = true;
+            }
+        });
+
+        if done {
+            self.confirm_clear = None;
+        }
+    }
+}
 
 impl Component for ManageControllers {
     type OutputEvents = GlobalEvents;
@@ -47,6 +138,27 @@ impl Component for ManageControllers {
                         if before != s.as_str() {
                             output.add_event(GlobalEvents::Save);
                         }
+
+                        let [r, g, b, _] = controller_colors::color(
+                            env.controller_colors[id],
+                            id as u8,
+                            ui.visuals().dark_mode,
+                        )
+                        .to_array();
+
+                        let mut rgb = [r, g, b];
+
+                        if ui.color_edit_button_srgb(&mut rgb).changed() {
+                            env.controller_colors[id] = Some(rgb);
+                            output.add_event(GlobalEvents::Save);
+                        }
+
+                        if env.controller_colors[id].is_some()
+                            && ui.button("default color").clicked()
+                        {
+                            env.controller_colors[id] = None;
+                            output.add_event(GlobalEvents::Save);
+                        }
                     }
 
                     match controller {
@@ -93,7 +205,35 @@ impl Component for ManageControllers {
                                 output.add_event(GlobalEvents::Save);
                             }
 
-                            if ui.button("remove").clicked() {
+                            // the counts come from the gamepad preview, so without it
+                            // there's nothing to compare against
+                            #[cfg(feature = "gamepad")]
+                            if let Some(caps) = env.device_caps[id] {
+                                let mismatches = controller.caps_mismatches(caps);
+
+                                for mismatch in &mismatches {
+                                    ui.colored_label(
+                                        Color32::from_rgb(0xf9, 0xe2, 0xaf),
+                                        bumpalo::format!(in &arena, "{}", mismatch).as_str(),
+                                    );
+                                }
+
+                                if !mismatches.is_empty()
+                                    && ui.button("adjust to reported counts").clicked()
+                                {
+                                    output.add_event(GlobalEvents::AdjustControllerToDevice(
+                                        id as u8,
+                                    ));
+                                }
+                            }
+
+                            if accessible::danger_button(
+                                ui,
+                                "remove",
+                                &format!("unbind controller {id}"),
+                            )
+                            .clicked()
+                            {
                                 *controller = ControllerType::NotBound;
                                 output.add_event(GlobalEvents::Save);
                             }
@@ -105,14 +245,32 @@ impl Component for ManageControllers {
 
                             ui.add(DragValue::new(sensitivity).range(0..=1).speed(0.1));
 
-                            if ui.button("remove").clicked() {
+                            if accessible::danger_button(
+                                ui,
+                                "remove",
+                                &format!("unbind controller {id}"),
+                            )
+                            .clicked()
+                            {
                                 *controller = ControllerType::NotBound;
                                 output.add_event(GlobalEvents::Save);
                             }
                         }
                     };
+
+                    if env.bindings.has_controller(id as u8)
+                        && ui.button("clear all bindings").clicked()
+                    {
+                        self.confirm_clear = Some((id as u8, String::new()));
+                    }
                 });
+
+                self.confirm_clear(ui, id as u8, output, arena);
             }
+
+            ui.separator();
+
+            self.move_bindings(ui, env, output);
         });
     }
 
@@ -120,3 +278,13 @@ impl Component for ManageControllers {
         super::TabType::ManageControllers
     }
 }
+
+fn slot_combo(id: &str, slot: &mut u8, env: &State, ui: &mut egui::Ui) {
+    ComboBox::from_id_salt(id)
+        .selected_text(env.controller_name(*slot).as_str())
+        .show_ui(ui, |ui| {
+            for id in 0..env.controllers.len() as u8 {
+                ui.selectable_value(slot, id, env.controller_name(id).as_str());
+            }
+        });
+}
@@ -1,14 +1,18 @@
 use std::{collections::HashMap, rc::Rc};
 
 use bumpalo::Bump;
-use egui::{ScrollArea, TextEdit, Ui};
+use egui::{Button, ScrollArea, TextEdit, Ui};
 
-use crate::{component::Component, global_state::GlobalEvents, State};
+use crate::{
+    accessible, component::Component, confirm::ConfirmDelete, export, global_state::GlobalEvents,
+    name_check, State,
+};
 
 #[derive(Debug, Clone)]
-pub(crate) struct ManageStreamsTab {
+pub struct ManageStreamsTab {
     pub adding: String,
     pub rename: HashMap<Rc<String>, String>,
+    pub confirm: ConfirmDelete<GlobalEvents>,
 }
 
 impl Default for ManageStreamsTab {
@@ -16,6 +20,7 @@ impl Default for ManageStreamsTab {
         Self {
             adding: "".to_string(),
             rename: HashMap::new(),
+            confirm: Default::default(),
         }
     }
 }
@@ -39,12 +44,47 @@ impl Component for ManageStreamsTab {
             ui.horizontal(|ui| {
                 ui.text_edit_singleline(adding);
 
-                if ui.button("add").clicked() && !adding.is_empty() {
+                if accessible::labeled_button(ui, "add", "add stream").clicked()
+                    && !adding.is_empty()
+                {
                     output.add_event(GlobalEvents::AddStream(std::mem::take(adding)));
                     update = true;
                 }
             });
 
+            ui.horizontal(|ui| {
+                let any = !env.streams.is_empty();
+
+                if ui
+                    .add_enabled(any, Button::new("copy all streams"))
+                    .on_disabled_hover_text("there are no streams to copy")
+                    .clicked()
+                {
+                    ui.ctx()
+                        .copy_text(export::name_list("stream", env.streams.iter()));
+                }
+
+                if ui
+                    .add_enabled(any, Button::new("copy as markdown table"))
+                    .on_disabled_hover_text("there are no streams to copy")
+                    .clicked()
+                {
+                    ui.ctx().copy_text(export::markdown_table(
+                        ["stream", "axis"],
+                        env.streams.iter().map(|s| {
+                            let axis = match env.stream_to_axis.get(s) {
+                                Some((controller, axis)) => {
+                                    format!("controller {controller} axis {axis}")
+                                }
+                                None => "unbound".to_string(),
+                            };
+
+                            [s.to_string(), axis]
+                        }),
+                    ));
+                }
+            });
+
             ui.separator();
 
             for stream in &env.streams {
@@ -61,7 +101,7 @@ impl Component for ManageStreamsTab {
                     );
 
                     if resp.lost_focus() {
-                        if env.streams.contains(rename) {
+                        if name_check::name_taken(&env.streams, rename) {
                             output.add_event(GlobalEvents::DisplayError(
                                 "stream of that name already exists".to_string(),
                             ));
@@ -73,28 +113,39 @@ impl Component for ManageStreamsTab {
                         }
                     }
 
-                    if ui.button("X").clicked() {
-                        let is_used = match env.is_stream_used(stream) {
-                            Ok(a) => a,
+                    let mut required = env.required_streams.contains(stream);
+
+                    if ui.checkbox(&mut required, "required").changed() {
+                        output.add_event(GlobalEvents::SetStreamRequired(stream.clone(), required));
+                    }
+
+                    if accessible::danger_button(ui, "X", &format!("remove stream {stream}"))
+                        .clicked()
+                    {
+                        match env.is_stream_used(stream) {
+                            Ok(profiles) if profiles.is_empty() => {
+                                self.confirm.ask(
+                                    format!("stream {stream}"),
+                                    GlobalEvents::RemoveStream(stream.clone()),
+                                    output,
+                                );
+                            }
+                            Ok(profiles) => {
+                                output.add_event(GlobalEvents::DisplayError(format!(
+                                    "can't delete a stream that is still used in: {}",
+                                    env.describe_profiles(&profiles)
+                                )));
+                            }
                             Err(err) => {
                                 output.add_event(GlobalEvents::DisplayError(err.to_string()));
-                                true
                             }
-                        };
-
-                        let valid_remove = !is_used;
-
-                        if valid_remove {
-                            output.add_event(GlobalEvents::RemoveStream(stream.clone()));
-                        } else {
-                            output.add_event(GlobalEvents::DisplayError(
-                                "can't delete a stream that is still used".to_string(),
-                            ));
                         }
                     }
                 });
             }
         });
+
+        self.confirm.show(ui, output);
     }
 
     fn tab_type(&self) -> super::TabType {
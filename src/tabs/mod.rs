@@ -1,15 +1,22 @@
 use std::collections::BTreeSet;
 
 use constants::ConstantsTab;
+use controller_map::ControllerMap;
+#[cfg(feature = "gamepad")]
+use controller_monitor::ControllerMonitor;
 use driver_constants::DriverConstantsTab;
 use from_bindings::FromBindings;
 use from_commands::FromCommands;
+use history::HistoryTab;
 use manage_commands::ManageTab;
 use manage_controllers::ManageControllers;
 use manage_streams::ManageStreamsTab;
 use once_cell::sync::Lazy;
 use profiles::ProfilesTab;
+use serde::{Deserialize, Serialize};
+use settings::SettingsTab;
 use streams::StreamsTab;
+use sync_log::SyncLogTab;
 use syncing::SyncingTab;
 
 use crate::{
@@ -18,42 +25,60 @@ use crate::{
 };
 
 pub mod constants;
+pub mod controller_map;
+#[cfg(feature = "gamepad")]
+pub mod controller_monitor;
 pub mod driver_constants;
 pub mod from_bindings;
 pub mod from_commands;
+pub mod history;
 pub mod manage_commands;
 pub mod manage_controllers;
 pub mod manage_streams;
 pub mod password_lock;
 pub mod profiles;
+pub mod settings;
 pub mod streams;
+pub mod sync_log;
 pub mod syncing;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub(crate) enum TabType {
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
+pub enum TabType {
     Constants,
+    ControllerMap,
+    #[cfg(feature = "gamepad")]
+    ControllerMonitor,
     DriverConstants,
     FromBindings,
     FromCommands,
+    History,
     ManageCommands,
     ManageControllers,
     ManageSteams,
     Profiles,
+    Settings,
     Streams,
+    SyncLog,
     Syncing,
 }
 
 pub static ALL_TABS: Lazy<BTreeSet<TabType>> = Lazy::new(|| {
     BTreeSet::from_iter([
         TabType::Constants,
+        TabType::ControllerMap,
+        #[cfg(feature = "gamepad")]
+        TabType::ControllerMonitor,
         TabType::DriverConstants,
         TabType::FromBindings,
         TabType::FromCommands,
+        TabType::History,
         TabType::ManageCommands,
         TabType::ManageControllers,
         TabType::ManageSteams,
         TabType::Profiles,
+        TabType::Settings,
         TabType::Streams,
+        TabType::SyncLog,
         TabType::Syncing,
     ])
 });
@@ -62,30 +87,223 @@ impl TabType {
     pub fn name(&self) -> &'static str {
         match self {
             TabType::Constants => "constants",
+            TabType::ControllerMap => "controller map",
+            #[cfg(feature = "gamepad")]
+            TabType::ControllerMonitor => "controller monitor",
             TabType::DriverConstants => "driver constants",
             TabType::FromBindings => "from bindings",
             TabType::FromCommands => "from commands",
+            TabType::History => "history",
             TabType::ManageCommands => "manage commands",
             TabType::ManageControllers => "manage controllers",
             TabType::ManageSteams => "manage streams",
             TabType::Profiles => "manage profiles",
+            TabType::Settings => "settings",
             TabType::Streams => "streams",
+            TabType::SyncLog => "sync log",
             TabType::Syncing => "syncing",
         }
     }
 
-    pub fn build(&self) -> Box<dyn Component<OutputEvents = GlobalEvents, Environment = State>> {
+    pub fn build(
+        &self,
+        env: &State,
+    ) -> Box<dyn Component<OutputEvents = GlobalEvents, Environment = State>> {
         match self {
             TabType::Constants => Box::new(ConstantsTab::default().lock()),
+            TabType::ControllerMap => Box::new(ControllerMap::new(env)),
+            #[cfg(feature = "gamepad")]
+            TabType::ControllerMonitor => Box::new(ControllerMonitor::default()),
             TabType::DriverConstants => Box::new(DriverConstantsTab::default()),
-            TabType::FromBindings => Box::new(FromBindings::default()),
-            TabType::FromCommands => Box::new(FromCommands::default()),
+            TabType::FromBindings => Box::new(FromBindings::new(env)),
+            TabType::FromCommands => Box::new(FromCommands::new(env)),
+            TabType::History => Box::new(HistoryTab::default()),
             TabType::ManageCommands => Box::new(ManageTab::default().lock()),
             TabType::ManageControllers => Box::new(ManageControllers::default()),
             TabType::ManageSteams => Box::new(ManageStreamsTab::default().lock()),
-            TabType::Profiles => Box::new(ProfilesTab::default()),
+            TabType::Profiles => Box::new(ProfilesTab::new(env)),
+            TabType::Settings => Box::new(SettingsTab::default().lock()),
             TabType::Streams => Box::new(StreamsTab::default()),
+            TabType::SyncLog => Box::new(SyncLogTab::default()),
             TabType::Syncing => Box::new(SyncingTab::default().lock()),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::bindings::{self, ControllerType};
+
+    fn fixture() -> State {
+        let mut state = State {
+            profile: Rc::new("practice".to_string()),
+            ..Default::default()
+        };
+
+        state.controllers[2] = ControllerType::XBox { sensitivity: 0.5 };
+        state.profiles = vec![Rc::new("default".to_string()), state.profile.clone()];
+        state.commands.insert(Rc::new("intake".to_string()));
+        state.commands.insert(Rc::new("shoot".to_string()));
+
+        state
+    }
+
+    #[test]
+    fn builds_every_tab() {
+        let state = fixture();
+
+        for tab in ALL_TABS.iter() {
+            assert_eq!(tab.build(&state).tab_type(), *tab);
+        }
+    }
+
+    #[test]
+    fn pending_buttons_follow_the_controllers() {
+        let mut state = fixture();
+
+        let button = |button| bindings::Button {
+            button,
+            location: bindings::ButtonLocation::Button,
+        };
+
+        let mut tab = FromBindings::new(&state);
+
+        tab.bindings.insert(((2, button(1)), None));
+        tab.bindings.insert(((2, button(11)), None));
+        tab.bindings.insert(((3, button(1)), None));
+
+        let generic = ControllerType::Generic {
+            buttons: 12,
+            axises: 4,
+            sensitivity: 0.5,
+        };
+
+        state.controllers[2] = generic;
+        state.controllers[3] = generic;
+
+        assert_eq!(tab.invalid_pending(&state).count(), 0);
+
+        state.controllers[2] = ControllerType::NotBound;
+        state.controllers[3] = ControllerType::Generic {
+            buttons: 0,
+            axises: 4,
+            sensitivity: 0.5,
+        };
+
+        assert_eq!(tab.invalid_pending(&state).count(), 3);
+
+        // xbox only goes up to 10
+        state.controllers[2] = ControllerType::XBox { sensitivity: 0.5 };
+
+        tab.remove_invalid_pending(&state);
+
+        assert_eq!(
+            tab.bindings.into_iter().collect::<Vec<_>>(),
+            [((2, button(1)), None)]
+        );
+    }
+
+    #[test]
+    fn command_drafts_are_dropped_when_the_project_changes() {
+        let mut state = fixture();
+        let mut tab = ManageTab::default();
+
+        let drafted = |tab: &mut ManageTab, state: &State| {
+            tab.forget_stale_drafts(state);
+            tab.cooldowns
+                .entry(Rc::new("shoot".to_string()))
+                .or_insert_with(|| "0.5".to_string());
+
+            tab.forget_stale_drafts(state);
+            !tab.cooldowns.is_empty()
+        };
+
+        assert!(drafted(&mut tab, &state));
+
+        // a save on its own keeps what is being typed
+        state.generation += 1;
+        tab.forget_stale_drafts(&state);
+        assert!(!tab.cooldowns.is_empty());
+
+        state.reloads += 1;
+        tab.forget_stale_drafts(&state);
+        assert!(tab.cooldowns.is_empty());
+
+        assert!(drafted(&mut tab, &state));
+        state.errors_shown += 1;
+        tab.forget_stale_drafts(&state);
+        assert!(tab.cooldowns.is_empty());
+
+        assert!(drafted(&mut tab, &state));
+        state.commands.insert(Rc::new("climb".to_string()));
+        tab.forget_stale_drafts(&state);
+        assert!(tab.cooldowns.is_empty());
+    }
+
+    #[test]
+    fn renames_of_missing_profiles_are_forgotten() {
+        let mut state = fixture();
+        let mut tab = ProfilesTab::new(&state);
+
+        for profile in &state.profiles {
+            tab.rename.insert(profile.clone(), format!("{profile} 2"));
+        }
+
+        state.profiles = vec![Rc::new("default".to_string()), Rc::new("match".to_string())];
+        tab.forget_renames(&state);
+
+        assert_eq!(
+            tab.rename.keys().map(|p| p.as_str()).collect::<Vec<_>>(),
+            ["default"]
+        );
+    }
+
+    #[test]
+    fn controller_map_draws_every_button_once() {
+        for controller in [
+            ControllerType::XBox { sensitivity: 0.5 },
+            ControllerType::Generic {
+                buttons: 12,
+                axises: 4,
+                sensitivity: 0.5,
+            },
+        ] {
+            let layout = controller_map::layout(&controller);
+            let arena = bumpalo::Bump::new();
+
+            let drawn = layout
+                .iter()
+                .map(|(button, _)| *button)
+                .collect::<BTreeSet<_>>();
+
+            assert_eq!(drawn.len(), layout.len());
+            assert_eq!(drawn, controller.enumerate_buttons(&arena).collect());
+        }
+    }
+
+    #[test]
+    fn initial_selections() {
+        let state = fixture();
+
+        assert_eq!(FromBindings::new(&state).controller, 2);
+        assert_eq!(
+            ProfilesTab::new(&state).profile_selection.as_str(),
+            "practice"
+        );
+        assert_eq!(
+            FromCommands::new(&state).editing_states.len(),
+            state.commands.len()
+        );
+    }
+
+    #[test]
+    fn initial_selections_without_controllers() {
+        let state = State::default();
+
+        assert_eq!(FromBindings::new(&state).controller, 0);
+        assert!(FromCommands::new(&state).editing_states.is_empty());
+    }
+}
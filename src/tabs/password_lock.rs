@@ -7,6 +7,7 @@ pub struct PasswordLock<A> {
     component: A,
     locked: bool,
     password_typed: String,
+    focus_search: bool,
 }
 
 impl<A> PasswordLock<A> {
@@ -15,6 +16,7 @@ impl<A> PasswordLock<A> {
             component: a,
             locked: true,
             password_typed: "".to_string(),
+            focus_search: false,
         }
     }
 }
@@ -38,15 +40,17 @@ where
             ui.horizontal(|ui| {
                 ui.label("password: ");
 
-                if ui
-                    .add(
-                        TextEdit::singleline(&mut self.password_typed)
-                            .password(true)
-                            .desired_width(100.0),
-                    )
-                    .lost_focus()
-                    && self.password_typed == "theyWillNeverKnow!"
-                {
+                let resp = ui.add(
+                    TextEdit::singleline(&mut self.password_typed)
+                        .password(true)
+                        .desired_width(100.0),
+                );
+
+                if std::mem::take(&mut self.focus_search) {
+                    resp.request_focus();
+                }
+
+                if resp.lost_focus() && self.password_typed == "theyWillNeverKnow!" {
                     self.password_typed.clear();
                     self.locked = false;
                 }
@@ -59,6 +63,14 @@ where
         }
     }
 
+    fn focus_search(&mut self) {
+        if self.locked {
+            self.focus_search = true;
+        } else {
+            self.component.focus_search();
+        }
+    }
+
     fn tab_type(&self) -> super::TabType {
         self.component.tab_type()
     }
@@ -1,17 +1,208 @@
-use std::{mem, rc::Rc};
+use std::{collections::HashMap, mem, rc::Rc};
+
+use egui::{ComboBox, ScrollArea, TextEdit};
 
 use crate::{
+    accessible,
+    bindings::ProfileBundle,
     global_state::{GlobalEvents, State},
+    name_check,
     search_selector::{search_selector, SelectorCache},
     Component,
 };
 
 #[derive(Debug, Default, Clone)]
-pub(crate) struct ProfilesTab {
+pub struct ProfilesTab {
     pub name: String,
     pub filter: String,
     pub profile_selection: Rc<String>,
     pub filter_cache: SelectorCache<Rc<String>>,
+    pub rename: HashMap<Rc<String>, String>,
+    pub duplicate_name: String,
+    pub switch_to_duplicate: bool,
+    pub merge_from: Rc<String>,
+    pub merge_into: Rc<String>,
+    // conflicts found by the last preview, apply is only offered after one
+    pub merge_report: Option<Vec<String>>,
+    pub import_name: String,
+    // bindings from the last check, `None` until it has been run
+    pub dead_bindings: Option<Vec<String>>,
+}
+
+impl ProfilesTab {
+    pub fn new(env: &State) -> Self {
+        Self {
+            profile_selection: env.profile.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// drops the rename drafts of profiles that are gone, renamed or deleted
+    pub fn forget_renames(&mut self, env: &State) {
+        self.rename
+            .retain(|profile, _| env.profiles.contains(profile));
+    }
+
+    fn share(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button("export profile").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("profile", &["json"])
+                    .set_file_name(format!("{}.json", env.profile))
+                    .save_file()
+                {
+                    if let Err(err) = env.export_profile(&path) {
+                        output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                    }
+                }
+            }
+
+            ui.separator();
+
+            ui.label("import as: ");
+            ui.text_edit_singleline(&mut self.import_name);
+
+            if ui
+                .add_enabled(
+                    !self.import_name.is_empty(),
+                    egui::Button::new("import profile"),
+                )
+                .clicked()
+            {
+                if let Err(err) =
+                    name_check::validate_profile_name(&self.import_name, &env.profiles)
+                {
+                    output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                    return;
+                }
+
+                let Some(path) = rfd::FileDialog::new()
+                    .add_filter("profile", &["json"])
+                    .pick_file()
+                else {
+                    return;
+                };
+
+                match std::fs::read_to_string(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|s| ProfileBundle::from_str_lenient(&s))
+                {
+                    Ok(bundle) => output.add_event(GlobalEvents::ImportProfile(
+                        mem::take(&mut self.import_name),
+                        Rc::new(bundle),
+                    )),
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+            }
+        });
+    }
+
+    fn merge(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        let (from, into) = (self.merge_from.clone(), self.merge_into.clone());
+
+        ui.horizontal(|ui| {
+            ui.label("merge");
+
+            profile_combo("merge from", &mut self.merge_from, &env.profiles, ui);
+
+            ui.label("into");
+
+            profile_combo("merge into", &mut self.merge_into, &env.profiles, ui);
+
+            let valid = !self.merge_from.is_empty()
+                && !self.merge_into.is_empty()
+                && self.merge_from != self.merge_into;
+
+            if ui
+                .add_enabled(valid, egui::Button::new("preview"))
+                .clicked()
+            {
+                match env.merge_conflicts(&self.merge_from, &self.merge_into) {
+                    Ok(conflicts) => {
+                        self.merge_report = Some(conflicts.iter().map(|c| c.to_string()).collect())
+                    }
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+            }
+        });
+
+        // a report for a different pair of profiles doesn't say anything
+        if from != self.merge_from || into != self.merge_into {
+            self.merge_report = None;
+        }
+
+        if let Some(report) = &self.merge_report {
+            if report.is_empty() {
+                ui.label("no conflicts");
+            } else {
+                ui.label("conflicts, these keep the destination's value:");
+
+                ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                    for conflict in report {
+                        ui.label(conflict.as_str());
+                    }
+                });
+            }
+
+            if ui.button("apply merge").clicked() {
+                output.add_event(GlobalEvents::MergeProfile {
+                    from: self.merge_from.clone(),
+                    into: self.merge_into.clone(),
+                });
+
+                self.merge_report = None;
+            }
+        }
+    }
+
+    fn check_bindings(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui.button("check bindings").clicked() {
+                match env.dead_bindings() {
+                    Ok(dead) => {
+                        self.dead_bindings = Some(dead.iter().map(|d| d.to_string()).collect())
+                    }
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+            }
+
+            match &self.dead_bindings {
+                Some(dead) if dead.is_empty() => {
+                    ui.label("every binding is on a bound controller");
+                }
+                Some(dead) => {
+                    ui.label(format!("{} bindings can't fire:", dead.len()));
+                }
+                None => {}
+            }
+        });
+
+        if let Some(dead) = self.dead_bindings.as_ref().filter(|d| !d.is_empty()) {
+            ScrollArea::vertical()
+                .id_salt("dead bindings")
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for binding in dead {
+                        ui.label(binding.as_str());
+                    }
+                });
+        }
+    }
 }
 
 impl Component for ProfilesTab {
@@ -24,16 +215,34 @@ impl Component for ProfilesTab {
         ui: &mut egui::Ui,
         env: &mut Self::Environment,
         output: &crate::component::EventStream<Self::OutputEvents>,
-        _arena: &bumpalo::Bump,
+        arena: &bumpalo::Bump,
     ) {
         ui.horizontal(|ui| {
             ui.label("new profile: ");
             ui.text_edit_singleline(&mut self.name);
-            if ui.button("add").clicked() {
+            if accessible::labeled_button(ui, "add", "add profile").clicked() {
                 output.add_event(GlobalEvents::AddProfile(mem::take(&mut self.name)));
             }
         });
 
+        ui.horizontal(|ui| {
+            ui.label("duplicate current as: ");
+            ui.text_edit_singleline(&mut self.duplicate_name);
+            ui.checkbox(&mut self.switch_to_duplicate, "switch to it");
+            if ui.button("duplicate").clicked() && !self.duplicate_name.is_empty() {
+                let name = mem::take(&mut self.duplicate_name);
+
+                if let Err(err) = name_check::validate_profile_name(&name, &env.profiles) {
+                    output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                } else {
+                    output.add_event(GlobalEvents::DuplicateProfile {
+                        name,
+                        switch: self.switch_to_duplicate,
+                    });
+                }
+            }
+        });
+
         ui.horizontal(|ui| {
             ui.label("active_profile: ");
 
@@ -45,17 +254,114 @@ impl Component for ProfilesTab {
                 ui.make_persistent_id("profiles selector"),
                 &mut self.filter,
                 &mut self.profile_selection,
-                env.profiles.iter().map(|s| (s.clone(), s.clone())),
+                env.profiles.iter().map(|s| {
+                    let label = match env.profile_descriptions.get(s) {
+                        Some(description) if !description.is_empty() => {
+                            Rc::new(format!("{s} - {description}"))
+                        }
+                        _ => s.clone(),
+                    };
+
+                    (label, s.clone())
+                }),
+                None,
+                None,
                 &mut self.filter_cache,
                 300.0,
                 ui,
-            ) {
+            )
+            .changed
+            {
                 output.add_event(GlobalEvents::SetProfile(self.profile_selection.clone()));
             };
         });
+
+        ui.horizontal(|ui| {
+            ui.label("description: ");
+
+            let resp = ui.text_edit_singleline(&mut env.description);
+
+            if resp.lost_focus()
+                && env.profile_descriptions.get(&env.profile) != Some(&env.description)
+            {
+                env.profile_descriptions
+                    .insert(env.profile.clone(), env.description.clone());
+                output.add_event(GlobalEvents::Save);
+            }
+
+            if let Some(last_modified) = &env.last_modified {
+                ui.weak(bumpalo::format!(in arena, "last modified {}", last_modified).as_str());
+            }
+        });
+
+        ui.separator();
+
+        self.forget_renames(env);
+
+        for profile in &env.profiles {
+            let rename = self
+                .rename
+                .entry(profile.clone())
+                .or_insert_with(|| profile.to_string());
+
+            let resp = ui
+                .horizontal(|ui| {
+                    let resp = ui.add(
+                        TextEdit::singleline(rename)
+                            .frame(false)
+                            .desired_width(100.0),
+                    );
+
+                    if let Some(description) = env.profile_descriptions.get(profile) {
+                        ui.weak(description.as_str());
+                    }
+
+                    resp
+                })
+                .inner;
+
+            if resp.lost_focus() && rename.as_str() != profile.as_str() {
+                if let Err(err) = name_check::validate_profile_name(
+                    rename,
+                    env.profiles.iter().filter(|p| *p != profile),
+                ) {
+                    output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                } else {
+                    output.add_event(GlobalEvents::RenameProfile(
+                        profile.clone(),
+                        Rc::new(mem::take(rename)),
+                    ));
+
+                    // the draft starts again from whatever name it ends up with
+                    self.rename.remove(profile);
+                }
+            }
+        }
+
+        ui.separator();
+
+        self.merge(ui, env, output);
+
+        ui.separator();
+
+        self.share(ui, env, output);
+
+        ui.separator();
+
+        self.check_bindings(ui, env, output);
     }
 
     fn tab_type(&self) -> super::TabType {
         super::TabType::Profiles
     }
 }
+
+fn profile_combo(id: &str, selection: &mut Rc<String>, profiles: &[Rc<String>], ui: &mut egui::Ui) {
+    ComboBox::from_id_salt(id)
+        .selected_text(selection.as_str())
+        .show_ui(ui, |ui| {
+            for profile in profiles {
+                ui.selectable_value(selection, profile.clone(), profile.as_str());
+            }
+        });
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use egui::DragValue;
+
+use crate::{
+    disk_space::{self, SpaceProvider, SystemSpace},
+    global_state::{GlobalEvents, State},
+    maintenance::{self, Leftover},
+    Component,
+};
+
+// asking the os every frame is pointless, free space doesn't move that fast
+const SPACE_REFRESH: Duration = Duration::from_secs(10);
+
+/// where the project's files live and how much of what the app leaves next
+/// to them is kept
+#[derive(Debug, Default, Clone)]
+pub struct SettingsTab {
+    pub free_space: Option<(Instant, Option<u64>)>,
+    pub leftover_usage: Option<(Instant, Vec<(Leftover, u64)>)>,
+    pub last_prune: Option<String>,
+}
+
+impl SettingsTab {
+    fn disk(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        if self
+            .free_space
+            .is_none_or(|(checked, _)| checked.elapsed() > SPACE_REFRESH)
+        {
+            self.free_space = Some((Instant::now(), SystemSpace.free_bytes(&env.deploy_dir).ok()));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("project: ");
+            ui.label(env.deploy_dir.display().to_string());
+        });
+
+        ui.horizontal(|ui| {
+            match self.free_space {
+                Some((_, Some(free))) => {
+                    ui.label(format!("{} free", disk_space::show_megabytes(free)))
+                }
+                _ => ui.weak("free space unknown"),
+            };
+
+            ui.separator();
+
+            ui.label("keep at least");
+
+            let mut megabytes = env.min_free_space / (1024 * 1024);
+
+            if ui
+                .add(DragValue::new(&mut megabytes).suffix(" MB"))
+                .changed()
+            {
+                env.min_free_space = megabytes * 1024 * 1024;
+                output.add_event(GlobalEvents::Save);
+            }
+
+            ui.label("free when saving");
+        });
+    }
+
+    fn maintenance(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        if self
+            .leftover_usage
+            .as_ref()
+            .is_none_or(|(checked, _)| checked.elapsed() > SPACE_REFRESH)
+        {
+            let usage = Leftover::ALL
+                .iter()
+                .map(|kind| (*kind, maintenance::usage(&env.deploy_dir, *kind)))
+                .collect();
+
+            self.leftover_usage = Some((Instant::now(), usage));
+        }
+
+        ui.horizontal(|ui| {
+            if let Some((_, usage)) = &self.leftover_usage {
+                for (kind, bytes) in usage {
+                    ui.label(format!(
+                        "{}: {}",
+                        kind.name(),
+                        disk_space::show_megabytes(*bytes)
+                    ));
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let before = env.retention;
+
+            ui.label("keep the newest");
+            ui.add(DragValue::new(&mut env.retention.keep).range(1..=1000));
+            ui.label("of each, none older than");
+            ui.add(
+                DragValue::new(&mut env.retention.max_age_days)
+                    .range(1..=3650)
+                    .suffix(" days"),
+            );
+
+            if before != env.retention {
+                output.add_event(GlobalEvents::Save);
+            }
+
+            if ui.button("clean now").clicked() {
+                match maintenance::prune(&env.deploy_dir, env.retention, SystemTime::now(), None) {
+                    Ok(report) => self.last_prune = Some(report.to_string()),
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+
+                self.leftover_usage = None;
+            }
+        });
+
+        if let Some(report) = &self.last_prune {
+            ui.weak(report);
+        }
+    }
+}
+
+impl Component for SettingsTab {
+    type OutputEvents = GlobalEvents;
+
+    type Environment = State;
+
+    fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut Self::Environment,
+        output: &crate::component::EventStream<Self::OutputEvents>,
+        _arena: &bumpalo::Bump,
+    ) {
+        self.disk(ui, env, output);
+
+        ui.separator();
+
+        self.maintenance(ui, env, output);
+    }
+
+    fn tab_type(&self) -> super::TabType {
+        super::TabType::Settings
+    }
+}
@@ -3,6 +3,7 @@ use std::{collections::BTreeMap, rc::Rc};
 use egui::{Grid, ScrollArea};
 
 use crate::{
+    accessible, controller_colors,
     global_state::{GlobalEvents, State},
     search_selector::{search_selector, SelectorCache},
     Component,
@@ -19,6 +20,15 @@ pub struct EditingStates {
     axis_cache: SelectorCache<u8>,
 }
 
+impl EditingStates {
+    fn new(env: &State) -> Self {
+        Self {
+            controller: env.first_bound_controller(),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct StreamsTab {
     pub edit_state: BTreeMap<Rc<String>, EditingStates>,
@@ -45,18 +55,32 @@ impl Component for StreamsTab {
                         Some((controller, axis)) => {
                             let axis =
                                 env.controllers[*controller as usize].axis_name(*axis, arena);
+                            let color = env.controller_color(*controller, ui);
                             let controller = env.controller_name(*controller);
 
-                            ui.label(
-                                bumpalo::format!(in &arena, "{} on {}", controller, axis).as_str(),
-                            )
+                            ui.horizontal(|ui| {
+                                controller_colors::chip(ui, color);
+                                ui.label(
+                                    bumpalo::format!(in &arena, "{} on {}", controller, axis)
+                                        .as_str(),
+                                );
+                            })
+                            .response
                         }
                         None => ui.label("Not Bound"),
                     };
 
-                    let edit_state = self.edit_state.entry(ele.clone()).or_default();
+                    let edit_state = self
+                        .edit_state
+                        .entry(ele.clone())
+                        .or_insert_with(|| EditingStates::new(env));
 
                     ui.horizontal(|ui| {
+                        controller_colors::chip(
+                            ui,
+                            env.controller_color(edit_state.controller, ui),
+                        );
+
                         search_selector(
                             ui.make_persistent_id(("streams controller selector", ele)),
                             &mut edit_state.controller_filter,
@@ -68,6 +92,8 @@ impl Component for StreamsTab {
                                     None
                                 }
                             }),
+                            None,
+                            None,
                             &mut edit_state.controller_cache,
                             100.0,
                             ui,
@@ -83,12 +109,20 @@ impl Component for StreamsTab {
                             controller.enumerate_axises().map(|axis| {
                                 (Rc::new(controller.axis_name(axis, arena).to_string()), axis)
                             }),
+                            None,
+                            None,
                             &mut edit_state.axis_cache,
                             100.0,
                             ui,
                         );
 
-                        if ui.button("change").clicked() {
+                        if accessible::labeled_button(
+                            ui,
+                            "change",
+                            bumpalo::format!(in &arena, "map {} to this axis", ele).as_str(),
+                        )
+                        .clicked()
+                        {
                             output.add_event(GlobalEvents::SetStream(
                                 ele.clone(),
                                 edit_state.controller,
@@ -0,0 +1,81 @@
+use egui::{Color32, Grid, RichText, ScrollArea};
+
+use crate::{
+    global_state::{GlobalEvents, State},
+    sync_log, Component,
+};
+
+/// every sync since the app opened, newest first, for telling a flaky radio
+/// from a wrong address
+#[derive(Debug, Default, Clone)]
+pub struct SyncLogTab {
+    pub failures_only: bool,
+}
+
+impl Component for SyncLogTab {
+    type OutputEvents = GlobalEvents;
+
+    type Environment = State;
+
+    fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut Self::Environment,
+        output: &crate::component::EventStream<Self::OutputEvents>,
+        _arena: &bumpalo::Bump,
+    ) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.failures_only, "failures only");
+
+            if ui
+                .add_enabled(!env.sync_log.is_empty(), egui::Button::new("clear"))
+                .on_hover_text("sync.log is left as it is")
+                .clicked()
+            {
+                env.sync_log.clear();
+            }
+
+            let mut write = env.sync_log_file;
+
+            if ui
+                .checkbox(&mut write, "also write to sync.log")
+                .on_hover_text(sync_log::path(&env.deploy_dir).display().to_string())
+                .changed()
+            {
+                output.add_event(GlobalEvents::SetSyncLogFile(write));
+            }
+        });
+
+        if env.sync_log.is_empty() {
+            ui.label("nothing has been synced yet");
+            return;
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("sync log grid").striped(true).show(ui, |ui| {
+                for attempt in env.sync_log.iter().rev() {
+                    if self.failures_only && attempt.success {
+                        continue;
+                    }
+
+                    ui.weak(humantime::format_rfc3339_seconds(attempt.at).to_string());
+                    ui.label(&attempt.target).on_hover_text(&attempt.url);
+
+                    if attempt.success {
+                        ui.label(attempt.status());
+                    } else {
+                        ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), attempt.status());
+                    }
+
+                    ui.label(attempt.took_text());
+                    ui.label(RichText::new(&attempt.error).monospace());
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn tab_type(&self) -> super::TabType {
+        super::TabType::SyncLog
+    }
+}
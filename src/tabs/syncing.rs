@@ -1,12 +1,495 @@
-use egui::TextEdit;
+use std::time::{Duration, Instant, SystemTime};
+
+use egui::{Color32, RichText, ScrollArea, TextEdit};
 
 use crate::{
-    global_state::{GlobalEvents, State},
+    bindings::{DisabledBindings, SaveMode, SyncBackend},
+    codegen::KotlinKeys,
+    global_state::{self, GlobalEvents, State, TargetSync},
+    number_input::number_input,
     Component,
 };
 
 #[derive(Debug, Default, Clone)]
-pub struct SyncingTab {}
+pub struct SyncingTab {
+    // the keys path being typed, saved once the box loses focus
+    pub keys_path: Option<String>,
+    // sync now may replace a sync that's still running
+    pub replace_sync: bool,
+    // the ssh user and robot path being typed, saved once their box loses
+    // focus
+    pub ssh_user: Option<String>,
+    pub remote_path: Option<String>,
+    pub team_text: Option<String>,
+    // an address from the team number waiting to replace one typed by hand
+    pub replace_url: Option<String>,
+    // the name of the next sync target
+    pub new_target: String,
+    // the target the drafts above were typed for
+    pub drafts_for: Option<String>,
+}
+
+impl SyncingTab {
+    /// switches the url to `url` when it came from the team number, otherwise
+    /// asks first
+    fn use_address(
+        &mut self,
+        url: String,
+        env: &State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        if global_state::derived_from_team(env.url().unwrap_or(""), env.team_number) {
+            self.replace_url = None;
+            output.add_event(GlobalEvents::SetUrl(Some(url)));
+        } else {
+            self.replace_url = Some(url);
+        }
+    }
+
+    fn team_number(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+        arena: &bumpalo::Bump,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("team number: ")
+                .on_hover_text("fills in the robot's address, 0 to forget it");
+
+            let text = self.team_text.get_or_insert_with(|| {
+                env.team_number
+                    .map(|team| team.to_string())
+                    .unwrap_or_default()
+            });
+            let mut team = env.team_number.map_or(0, i64::from);
+
+            if number_input(text, &mut team, arena, ui) {
+                let team = u32::try_from(team).ok().filter(|team| *team > 0);
+
+                if team != env.team_number {
+                    if let Some(team) = team {
+                        self.use_address(global_state::team_mdns(team), env, output);
+                    }
+
+                    output.add_event(GlobalEvents::SetTeamNumber(team));
+                }
+            }
+
+            let Some(team) = env.team_number else {
+                return;
+            };
+
+            let url = env.url().unwrap_or("");
+
+            for (address, hover) in [
+                (
+                    Some(global_state::team_mdns(team)),
+                    "found by name, works over usb and the radio",
+                ),
+                (
+                    global_state::team_ip(team),
+                    "the static ip, for when names don't resolve",
+                ),
+            ] {
+                let Some(address) = address else {
+                    continue;
+                };
+
+                if ui
+                    .add_enabled(address != url, egui::Button::new(&address))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    self.use_address(address, env, output);
+                }
+            }
+        });
+
+        if let Some(replacement) = self.replace_url.clone() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "replace {} with {replacement}?",
+                    env.url().unwrap_or("")
+                ));
+
+                if ui.button("replace").clicked() {
+                    self.replace_url = None;
+                    output.add_event(GlobalEvents::SetUrl(Some(replacement)));
+                }
+
+                if ui.button("keep mine").clicked() {
+                    self.replace_url = None;
+                }
+            });
+        }
+    }
+
+    fn remote_login(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        let Some(target) = env.target().cloned() else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("ssh user: ");
+
+            let user = self.ssh_user.get_or_insert_with(|| target.user.clone());
+            let empty = user.trim().is_empty();
+
+            let resp = ui.add(TextEdit::singleline(user).desired_width(100.0));
+
+            // an empty one stays in the box to be fixed instead of saved
+            if resp.lost_focus() && !empty {
+                let user = self.ssh_user.take().unwrap_or_default();
+
+                if user.trim() != target.user {
+                    output.add_event(GlobalEvents::SetSshUser(user));
+                }
+            }
+
+            if empty {
+                ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), "can't be empty");
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("robot path: ");
+
+            let path = self.remote_path.get_or_insert_with(|| target.path.clone());
+            let empty = path.trim().is_empty();
+
+            let resp = ui.add(
+                TextEdit::singleline(path)
+                    .hint_text(global_state::ROBOT_DEPLOY_PATH)
+                    .desired_width(250.0),
+            );
+
+            if resp.lost_focus() && !empty {
+                let path = self.remote_path.take().unwrap_or_default();
+
+                if global_state::normalize_remote_path(&path).as_ref() != Some(&target.path) {
+                    output.add_event(GlobalEvents::SetRemotePath(path));
+                }
+            }
+
+            if empty {
+                ui.colored_label(Color32::from_rgb(0xf3, 0x8b, 0xa8), "can't be empty");
+            }
+        });
+    }
+
+    /// every robot a save goes to, with how its last sync went
+    fn targets(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        let now = SystemTime::now();
+        let mut select = None;
+
+        for (at, target) in env.targets.iter().enumerate() {
+            let sync = env.syncs.get(&target.name);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .radio(at == env.selected_target, &target.name)
+                    .on_hover_text("edit this one")
+                    .clicked()
+                {
+                    select = Some(at);
+                }
+
+                let mut enabled = target.enabled;
+
+                if ui
+                    .checkbox(&mut enabled, "sync")
+                    .on_hover_text("send saves here")
+                    .changed()
+                {
+                    output.add_event(GlobalEvents::SetSyncTargetEnabled(
+                        target.name.clone(),
+                        enabled,
+                    ));
+                }
+
+                if target.url.is_empty() {
+                    ui.weak("no address");
+                } else {
+                    ui.weak(&target.url);
+                }
+
+                if let Some((sent, of)) = sync.and_then(|sync| sync.progress) {
+                    ui.add(
+                        egui::ProgressBar::new(sent as f32 / of.max(1) as f32)
+                            .desired_width(150.0)
+                            .text(format!("sent {sent} of {of} files")),
+                    );
+                } else if sync.is_some_and(TargetSync::running) {
+                    ui.spinner();
+                } else if let Some(last) = env.last_sync_text(&target.name, now) {
+                    ui.label(last);
+                }
+
+                if ui.small_button("remove").clicked() {
+                    output.add_event(GlobalEvents::RemoveSyncTarget(target.name.clone()));
+                }
+            });
+
+            if let Some(said) = sync
+                .and_then(|sync| sync.output.as_deref())
+                .filter(|o| !o.is_empty())
+            {
+                egui::CollapsingHeader::new(format!("what {} said", target.name))
+                    .id_salt(("sync output", &target.name))
+                    .show(ui, |ui| {
+                        ScrollArea::vertical()
+                            .id_salt(("sync output scroll", &target.name))
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                ui.label(RichText::new(said).monospace());
+                            });
+                    });
+            }
+        }
+
+        if let Some(at) = select {
+            env.selected_target = at;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.new_target)
+                    .hint_text("practice bot")
+                    .desired_width(150.0),
+            );
+
+            if ui
+                .add_enabled(
+                    !self.new_target.trim().is_empty(),
+                    egui::Button::new("add target"),
+                )
+                .on_hover_text("another robot every save is sent to")
+                .clicked()
+            {
+                output.add_event(GlobalEvents::AddSyncTarget(std::mem::take(
+                    &mut self.new_target,
+                )));
+            }
+        });
+    }
+
+    fn sync_backend(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("send with");
+
+            let mut backend = env.sync_backend;
+
+            ui.radio_value(&mut backend, SyncBackend::Scp, "scp")
+                .on_hover_text("the scp installed on this computer");
+            ui.add_enabled_ui(cfg!(feature = "native-ssh"), |ui| {
+                ui.radio_value(&mut backend, SyncBackend::Native, "built in ssh")
+                    .on_hover_text("for computers without scp, shows how far along it is")
+                    .on_disabled_hover_text("this build was made without native-ssh");
+            });
+
+            if backend != env.sync_backend {
+                output.add_event(GlobalEvents::SetSyncBackend(backend));
+            }
+        });
+
+        if env.sync_backend != SyncBackend::Native {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("password: ");
+
+            let mut password = env.sync_password.clone().unwrap_or_default();
+
+            if ui
+                .add(TextEdit::singleline(&mut password).password(true))
+                .on_hover_text("only tried when no ssh key gets in, forgotten when the app closes")
+                .changed()
+            {
+                env.sync_password = Some(password);
+            }
+        });
+    }
+
+    fn pull(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    env.pull_process.is_none(),
+                    egui::Button::new("download from robot"),
+                )
+                .on_hover_text("compares the robot's files with these before replacing anything")
+                .clicked()
+            {
+                if let Err(err) = env.start_pull() {
+                    output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                }
+            }
+
+            if env.pull_process.is_some() {
+                ui.spinner();
+                ui.weak("downloading...");
+            }
+        });
+
+        let Some((_, changes)) = &env.pulled else {
+            return;
+        };
+
+        let mut discard = false;
+
+        ui.group(|ui| {
+            if changes.is_empty() {
+                ui.label("the robot has the same files as this laptop");
+            } else {
+                ui.label("replacing the files here with the robot's would change:");
+
+                ScrollArea::vertical()
+                    .id_salt("pulled changes")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for change in changes {
+                            ui.label(change.to_string());
+                        }
+                    });
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("overwrite local files").clicked() {
+                    output.add_event(GlobalEvents::UsePulled);
+                }
+
+                discard = ui.button("discard").clicked();
+            });
+        });
+
+        if discard {
+            env.discard_pull();
+        }
+    }
+
+    fn sync_controls(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        let running = env.sync_running();
+
+        if !running {
+            self.replace_sync = false;
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    env.syncing && (!running || self.replace_sync),
+                    egui::Button::new("sync now"),
+                )
+                .on_hover_text("send what's saved to the robot without changing anything")
+                .clicked()
+            {
+                env.cancel_sync_retry();
+
+                // a save still being written would go out half done
+                if let Err(err) = env.finish_io().and_then(|_| env.restart_sync()) {
+                    output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                }
+            }
+
+            if running {
+                ui.checkbox(&mut self.replace_sync, "replace the running one");
+            }
+
+            if env.sync_pending {
+                ui.weak("newer saves go out after this one");
+            }
+
+            if ui
+                .add_enabled(running, egui::Button::new("cancel sync"))
+                .clicked()
+            {
+                match env.cancel_sync() {
+                    Ok(true) => output.add_event(GlobalEvents::DisplayInfo(
+                        "sync cancelled, the robot may have only some of the files".to_string(),
+                    )),
+                    Ok(false) => {}
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+            }
+        });
+    }
+
+    /// the kotlin file that lets robot code use constants instead of
+    /// repeating every command and stream name
+    fn kotlin_keys(
+        &mut self,
+        ui: &mut egui::Ui,
+        env: &mut State,
+        output: &crate::component::EventStream<GlobalEvents>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("kotlin keys: ");
+
+            let path = self
+                .keys_path
+                .get_or_insert_with(|| env.kotlin_keys.path.clone().unwrap_or_default());
+
+            let resp = ui.add(
+                TextEdit::singleline(path)
+                    .hint_text(KotlinKeys::default_path())
+                    .desired_width(250.0),
+            );
+
+            if resp.lost_focus() {
+                let path = self.keys_path.take().filter(|p| !p.trim().is_empty());
+
+                if path != env.kotlin_keys.path {
+                    env.kotlin_keys.path = path;
+                    output.add_event(GlobalEvents::Save);
+                }
+            }
+
+            if ui
+                .checkbox(&mut env.kotlin_keys.on_save, "regenerate on save")
+                .changed()
+            {
+                output.add_event(GlobalEvents::Save);
+            }
+
+            if ui.button("generate now").clicked() {
+                match env.write_kotlin_keys() {
+                    Ok(path) => output.add_event(GlobalEvents::DisplayInfo(format!(
+                        "wrote {}",
+                        path.display()
+                    ))),
+                    Err(err) => output.add_event(GlobalEvents::DisplayError(err.to_string())),
+                }
+            }
+        });
+    }
+}
 
 impl Component for SyncingTab {
     type OutputEvents = GlobalEvents;
@@ -20,30 +503,155 @@ impl Component for SyncingTab {
         output: &crate::component::EventStream<Self::OutputEvents>,
         arena: &bumpalo::Bump,
     ) {
-        match &mut env.url {
+        self.kotlin_keys(ui, env, output);
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("disabled bindings");
+
+            let mut disabled = env.disabled_bindings;
+
+            ui.radio_value(&mut disabled, DisabledBindings::Omit, "left out")
+                .on_hover_text("kept apart from the rest, robot code never sees them");
+            ui.radio_value(&mut disabled, DisabledBindings::Flag, "marked")
+                .on_hover_text("written with \"enabled\": false, robot code has to skip them");
+
+            if disabled != env.disabled_bindings {
+                output.add_event(GlobalEvents::SetDisabledBindings(disabled));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("saving");
+
+            let mut mode = env.save_mode;
+
+            ui.radio_value(&mut mode, SaveMode::Auto, "automatic")
+                .on_hover_text("written and synced shortly after every change");
+            ui.radio_value(&mut mode, SaveMode::Manual, "manual")
+                .on_hover_text("only written when saved, with ctrl+s or the save button");
+
+            if mode != env.save_mode {
+                output.add_event(GlobalEvents::SetSaveMode(mode));
+            }
+        });
+
+        let mut commit = env.commit_on_save;
+
+        if ui
+            .checkbox(&mut commit, "commit to git on save")
+            .on_hover_text(
+                "every save becomes a commit of bindings.json and the profiles, \
+                when the deploy directory is in a git repository",
+            )
+            .changed()
+        {
+            output.add_event(GlobalEvents::SetCommitOnSave(commit));
+        }
+
+        ui.separator();
+
+        self.team_number(ui, env, output, arena);
+
+        let selected = env.target().map(|target| target.name.clone());
+
+        // what was typed for one target isn't saved to another
+        if selected != self.drafts_for {
+            self.ssh_user = None;
+            self.remote_path = None;
+            self.replace_url = None;
+            self.drafts_for = selected;
+        }
+
+        match env.url().map(str::to_string) {
             Some(url) => {
-                let before = bumpalo::collections::String::from_str_in(url, arena);
+                self.targets(ui, env, output);
+
+                ui.separator();
+
+                let mut edited = url.clone();
 
                 ui.horizontal(|ui| {
                     ui.label("url: ");
-                    TextEdit::singleline(url).show(ui);
+                    TextEdit::singleline(&mut edited).show(ui);
                 });
 
-                if before.as_str() != url {
-                    output.add_event(GlobalEvents::Save);
+                if edited != url {
+                    output.add_event(GlobalEvents::SetUrl(Some(edited)));
                 }
 
+                self.remote_login(ui, env, output);
+
+                ui.horizontal(|ui| {
+                    let mut format = env.sync_format;
+
+                    ui.checkbox(&mut format.compact, "compact json");
+                    ui.checkbox(&mut format.gzip, "gzip");
+
+                    if format != env.sync_format {
+                        output.add_event(GlobalEvents::SetSyncFormat(format));
+                    }
+                });
+
                 if env.syncing {
                     if ui.button("disable syncing").clicked() {
-                        env.syncing = false;
+                        output.add_event(GlobalEvents::SetSyncing(false));
                     }
                 } else if ui.button("enable syncing").clicked() {
-                    env.syncing = true;
+                    output.add_event(GlobalEvents::SetSyncing(true));
+                }
+
+                self.sync_backend(ui, env, output);
+
+                self.sync_controls(ui, env, output);
+
+                self.pull(ui, env, output);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            env.connection_test.is_none(),
+                            egui::Button::new("test connection"),
+                        )
+                        .on_hover_text("logs in to the robot without sending anything")
+                        .clicked()
+                    {
+                        if let Err(err) = env.test_connection() {
+                            output.add_event(GlobalEvents::DisplayError(err.to_string()));
+                        }
+                    }
+
+                    if env.connection_test.is_some() {
+                        ui.spinner();
+                        ui.weak("testing...");
+                    } else if let Some(status) = &env.connection_status {
+                        ui.label(status.to_string());
+                    }
+                });
+
+                if let Some(at) = env.sync_retry_at {
+                    ui.horizontal(|ui| {
+                        let left = at.saturating_duration_since(Instant::now());
+
+                        ui.label(format!("retrying in {}s…", left.as_secs() + 1));
+                        ui.ctx().request_repaint_after(Duration::from_secs(1));
+
+                        if ui.button("cancel").clicked() {
+                            env.cancel_sync_retry();
+                        }
+                    });
+                }
+
+                if env.resync_at.is_some() {
+                    ui.weak("remote changed, syncing everything shortly");
+                } else if env.remote_stale {
+                    ui.weak("remote changed, everything is sent once syncing is on");
                 }
             }
             None => {
                 if ui.button("setup syncing").clicked() {
-                    env.url = Some("".to_string());
+                    output.add_event(GlobalEvents::SetUrl(Some("".to_string())));
                 }
             }
         }
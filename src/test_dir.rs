@@ -0,0 +1,10 @@
+use tempfile::TempDir;
+
+/// an empty directory for a test, named after it. it's deleted when dropped,
+/// so a test that fails part way doesn't leave it behind
+pub fn temp_dir(name: &str) -> TempDir {
+    tempfile::Builder::new()
+        .prefix(&format!("bindings-gui-{name}-"))
+        .tempdir()
+        .unwrap()
+}
@@ -0,0 +1,222 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{file_hashes, maintenance};
+
+/// how the project is shown rather than what it holds, kept in `.bindings-gui`
+/// so none of it is deployed to the robot
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct UiMeta {
+    #[serde(default)]
+    pub pinned_constants: Vec<PinnedKeys>,
+}
+
+/// keys shown first in a constants object, in this order. the rest follow
+/// alphabetically
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct PinnedKeys {
+    pub object: Vec<Rc<String>>,
+    pub keys: Vec<Rc<String>>,
+}
+
+impl UiMeta {
+    fn path(project: &Path) -> PathBuf {
+        maintenance::aux_root(project).join("ui.json")
+    }
+
+    pub fn load(project: &Path) -> Result<Self> {
+        let path = Self::path(project);
+
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+
+        let file = read_to_string(path).with_context(|| "failed to read display settings")?;
+
+        Ok(serde_json::from_str(&file)?)
+    }
+
+    pub fn save(&self, project: &Path) -> Result<()> {
+        let path = Self::path(project);
+
+        create_dir_all(path.parent().unwrap())?;
+
+        file_hashes::write_file_without_backup(
+            &path,
+            serde_json::to_string_pretty(self).unwrap().as_bytes(),
+        )
+        .with_context(|| "failed to save display settings")
+    }
+
+    pub fn pinned(&self, object: &[Rc<String>]) -> &[Rc<String>] {
+        self.pinned_constants
+            .iter()
+            .find(|p| p.object == object)
+            .map_or(&[], |p| &p.keys)
+    }
+
+    pub fn is_pinned(&self, key: &[Rc<String>]) -> bool {
+        let Some((name, object)) = key.split_last() else {
+            return false;
+        };
+
+        self.pinned(object).contains(name)
+    }
+
+    /// the keys of `map` in display order, pinned ones first
+    pub fn ordered<V>(
+        &self,
+        object: &[Rc<String>],
+        map: &BTreeMap<Rc<String>, V>,
+    ) -> Vec<Rc<String>> {
+        let pinned = self.pinned(object);
+
+        pinned
+            .iter()
+            .filter(|key| map.contains_key(*key))
+            .chain(map.keys().filter(|key| !pinned.contains(key)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn pin(&mut self, key: &[Rc<String>], pin: bool) {
+        let Some((name, object)) = key.split_last() else {
+            return;
+        };
+
+        match self
+            .pinned_constants
+            .iter_mut()
+            .find(|p| p.object == object)
+        {
+            Some(pinned) if pin && !pinned.keys.contains(name) => pinned.keys.push(name.clone()),
+            Some(_) if pin => {}
+            Some(pinned) => pinned.keys.retain(|k| k != name),
+            None if pin => self.pinned_constants.push(PinnedKeys {
+                object: object.to_vec(),
+                keys: vec![name.clone()],
+            }),
+            None => {}
+        }
+
+        self.pinned_constants.retain(|p| !p.keys.is_empty());
+    }
+
+    /// moves a pinned key one place up
+    pub fn raise(&mut self, key: &[Rc<String>]) {
+        let Some((name, object)) = key.split_last() else {
+            return;
+        };
+
+        if let Some(pinned) = self
+            .pinned_constants
+            .iter_mut()
+            .find(|p| p.object == object)
+        {
+            if let Some(i) = pinned
+                .keys
+                .iter()
+                .position(|k| k == name)
+                .filter(|i| *i > 0)
+            {
+                pinned.keys.swap(i, i - 1);
+            }
+        }
+    }
+
+    /// drops `key` and everything in it, true when something was pinned there
+    pub fn forget(&mut self, key: &[Rc<String>]) -> bool {
+        let before = self.pinned_constants.clone();
+
+        self.pin(key, false);
+        self.pinned_constants.retain(|p| !p.object.starts_with(key));
+
+        before != self.pinned_constants
+    }
+
+    /// true when something was pinned in or at `key`
+    pub fn rename(&mut self, key: &[Rc<String>], to: Rc<String>) -> bool {
+        let Some((name, object)) = key.split_last() else {
+            return false;
+        };
+
+        let before = self.pinned_constants.clone();
+
+        for pinned in &mut self.pinned_constants {
+            if pinned.object == object {
+                for k in &mut pinned.keys {
+                    if k == name {
+                        *k = to.clone();
+                    }
+                }
+            }
+
+            if pinned.object.starts_with(key) {
+                pinned.object[object.len()] = to.clone();
+            }
+        }
+
+        before != self.pinned_constants
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(keys: &[&str]) -> Vec<Rc<String>> {
+        keys.iter().map(|k| Rc::new(k.to_string())).collect()
+    }
+
+    #[test]
+    fn pinned_keys_come_first() {
+        let mut meta = UiMeta::default();
+
+        let map = BTreeMap::from(
+            ["feedforward", "kD", "kI", "kP", "max"].map(|k| (Rc::new(k.to_string()), ())),
+        );
+
+        meta.pin(&path(&["arm", "kI"]), true);
+        meta.pin(&path(&["arm", "kP"]), true);
+        meta.raise(&path(&["arm", "kP"]));
+        meta.pin(&path(&["arm", "gone"]), true);
+
+        assert_eq!(
+            meta.ordered(&path(&["arm"]), &map),
+            path(&["kP", "kI", "feedforward", "kD", "max"])
+        );
+
+        assert_eq!(
+            meta.ordered(&path(&["drive"]), &map),
+            path(&["feedforward", "kD", "kI", "kP", "max"])
+        );
+    }
+
+    #[test]
+    fn renames_and_removals_are_followed() {
+        let mut meta = UiMeta::default();
+
+        meta.pin(&path(&["arm", "kP"]), true);
+        meta.pin(&path(&["arm", "wrist", "kP"]), true);
+
+        assert!(meta.rename(&path(&["arm", "kP"]), Rc::new("p".to_string())));
+        assert!(meta.rename(&path(&["arm"]), Rc::new("lift".to_string())));
+        assert!(!meta.rename(&path(&["drive"]), Rc::new("base".to_string())));
+
+        assert_eq!(meta.pinned(&path(&["arm"])), &[] as &[Rc<String>]);
+        assert!(meta.is_pinned(&path(&["lift", "wrist", "kP"])));
+
+        // the arm itself was never pinned, only what's in it
+        assert!(meta.forget(&path(&["lift", "wrist"])));
+
+        assert!(!meta.is_pinned(&path(&["lift", "wrist", "kP"])));
+        assert!(meta.is_pinned(&path(&["lift", "p"])));
+    }
+}
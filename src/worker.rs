@@ -0,0 +1,400 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    audit_log::{self, Entry},
+    bindings::Profile,
+    file_hashes::{self, FileHashes},
+    git, maintenance,
+    sync_log::{self, Attempt},
+};
+
+/// a file for the worker to write, `backup` keeps what was there before at
+/// its .bak
+#[derive(Debug)]
+pub struct WriteFile {
+    pub path: PathBuf,
+    pub contents: Vec<u8>,
+    pub backup: bool,
+}
+
+#[derive(Debug)]
+pub enum Job {
+    /// writes the files in order, stopping at the first one that fails.
+    /// `pending` is how many of the pending changes it carries, `commit` is
+    /// the deploy directory and message to commit them to git with
+    Save {
+        files: Vec<WriteFile>,
+        pending: usize,
+        commit: Option<(PathBuf, String)>,
+    },
+    /// adds edits to the end of the change log
+    Log {
+        deploy_dir: PathBuf,
+        entries: Vec<Entry>,
+    },
+    /// adds a sync attempt to the end of sync.log
+    SyncLog {
+        deploy_dir: PathBuf,
+        attempt: Attempt,
+    },
+    /// replaces the local files with the ones from a pull, all of them or
+    /// none, then empties the directory it was downloaded to
+    ApplyPull {
+        files: Vec<(PathBuf, Vec<u8>)>,
+        pull_dir: PathBuf,
+    },
+    /// writes the tab layout, apart from the saves so it can't hold them back
+    Layout { path: PathBuf, contents: Vec<u8> },
+    /// deletes leftovers pruning didn't get to when the project was opened
+    Prune {
+        deploy_dir: PathBuf,
+        paths: Vec<PathBuf>,
+    },
+    /// reads the description out of each profile file, by name
+    Describe(Vec<(String, PathBuf)>),
+    /// lists the profiles in the deploy directory and which of `paths` don't
+    /// match what `known` last saw
+    CheckDisk {
+        deploy_dir: PathBuf,
+        known: FileHashes,
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug)]
+pub enum Done {
+    Saved {
+        paths: Vec<PathBuf>,
+        pending: usize,
+        result: Result<()>,
+        // none when it wasn't committed or the write failed
+        committed: Option<Result<()>>,
+    },
+    Logged(Result<()>),
+    SyncLogged(Result<()>),
+    Pulled {
+        paths: Vec<PathBuf>,
+        result: Result<()>,
+    },
+    LayoutSaved(Result<()>),
+    // what couldn't be deleted
+    Pruned(Vec<String>),
+    // profiles that failed to load are left out
+    Described(Vec<(String, String)>),
+    Checked(Result<(Vec<String>, Vec<PathBuf>)>),
+}
+
+impl Job {
+    fn run(self) -> Done {
+        match self {
+            Job::Save {
+                files,
+                pending,
+                commit,
+            } => {
+                let paths = files.iter().map(|f| f.path.clone()).collect();
+
+                let result = files.iter().try_for_each(|file| {
+                    if file.backup {
+                        file_hashes::write_file(&file.path, &file.contents)
+                    } else {
+                        file_hashes::write_file_without_backup(&file.path, &file.contents)
+                    }
+                });
+
+                let committed = commit
+                    .filter(|_| result.is_ok())
+                    .map(|(deploy_dir, message)| git::commit(&deploy_dir, &message));
+
+                Done::Saved {
+                    paths,
+                    pending,
+                    result,
+                    committed,
+                }
+            }
+            Job::Log {
+                deploy_dir,
+                entries,
+            } => Done::Logged(audit_log::append(
+                &deploy_dir,
+                &entries,
+                audit_log::MAX_LOG_SIZE,
+            )),
+            Job::SyncLog {
+                deploy_dir,
+                attempt,
+            } => Done::SyncLogged(sync_log::append(&deploy_dir, &attempt)),
+            Job::ApplyPull { files, pull_dir } => {
+                let paths = files.iter().map(|(path, _)| path.clone()).collect();
+
+                let result = files
+                    .iter()
+                    .filter_map(|(path, _)| path.parent())
+                    .try_for_each(std::fs::create_dir_all)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|()| file_hashes::write_files_together(&files));
+
+                if result.is_ok() {
+                    let _ = std::fs::remove_dir_all(pull_dir);
+                }
+
+                Done::Pulled { paths, result }
+            }
+            Job::Layout { path, contents } => Done::LayoutSaved(
+                file_hashes::write_file_without_backup(&path, &contents)
+                    .with_context(|| "failed to save the tab layout"),
+            ),
+            Job::Prune { deploy_dir, paths } => Done::Pruned(
+                paths
+                    .into_iter()
+                    .filter_map(|path| {
+                        let err = maintenance::delete(&path, &deploy_dir).err()?;
+
+                        Some(format!("failed to prune {}: {err}", path.display()))
+                    })
+                    .collect(),
+            ),
+            Job::Describe(profiles) => Done::Described(
+                profiles
+                    .into_iter()
+                    .filter_map(|(name, path)| {
+                        let data = std::fs::read_to_string(path).ok()?;
+                        let profile = Profile::from_str_lenient(&data).ok()?;
+
+                        Some((name, profile.description.into_owned()))
+                    })
+                    .collect(),
+            ),
+            Job::CheckDisk {
+                deploy_dir,
+                known,
+                paths,
+            } => Done::Checked(Profile::get_profiles(&deploy_dir).map(|profiles| {
+                (
+                    profiles.iter().map(|p| p.to_string()).collect(),
+                    paths
+                        .into_iter()
+                        .filter(|path| known.changed_externally(path))
+                        .collect(),
+                )
+            })),
+        }
+    }
+}
+
+/// does the project's disk work off the ui thread, one job at a time in the
+/// order they were sent. the default one has no thread and does each job as
+/// it's sent, for tests and tools that want the files written before going on
+#[derive(Debug)]
+pub struct Worker {
+    // none runs jobs in place
+    jobs: Option<Sender<Job>>,
+    // the thread holds the only sender, so this disconnects if it dies
+    done: Receiver<Done>,
+    // jobs done in place, waiting to be received like the thread's
+    ran_here: VecDeque<Done>,
+    // sent and not received back yet
+    queued: usize,
+}
+
+impl Default for Worker {
+    fn default() -> Self {
+        let (_, done) = channel();
+
+        Self {
+            jobs: None,
+            done,
+            ran_here: VecDeque::new(),
+            queued: 0,
+        }
+    }
+}
+
+impl Worker {
+    pub fn spawn() -> Self {
+        Self::spawn_with_delay(Duration::ZERO)
+    }
+
+    /// a worker that sleeps before every job, standing in for a slow drive
+    pub(crate) fn spawn_with_delay(delay: Duration) -> Self {
+        let (jobs, receiver) = channel::<Job>();
+        let (done, done_rx) = channel();
+        let worker = Self {
+            jobs: Some(jobs),
+            done: done_rx,
+            ..Default::default()
+        };
+
+        thread::spawn(move || {
+            for job in receiver {
+                thread::sleep(delay);
+
+                if done.send(job.run()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        worker
+    }
+
+    pub fn send(&mut self, job: Job) {
+        self.queued += 1;
+
+        // a worker thread that died takes no more jobs, they're done here
+        // instead of lost
+        let job = match &self.jobs {
+            Some(jobs) => match jobs.send(job) {
+                Ok(()) => return,
+                Err(err) => err.0,
+            },
+            None => job,
+        };
+
+        self.ran_here.push_back(job.run());
+    }
+
+    /// a finished job if there is one, without waiting
+    pub fn try_recv(&mut self) -> Option<Done> {
+        let done = match self.done.try_recv() {
+            Ok(done) => done,
+            Err(TryRecvError::Empty) => self.ran_here.pop_front()?,
+            Err(TryRecvError::Disconnected) => self.ran_here.pop_front().or_else(|| {
+                self.thread_died();
+                None
+            })?,
+        };
+
+        self.queued -= 1;
+
+        Some(done)
+    }
+
+    /// waits for the next job to finish, none once nothing is left
+    pub fn recv(&mut self) -> Option<Done> {
+        if self.queued == 0 {
+            return None;
+        }
+
+        if let Some(done) = self.try_recv() {
+            return Some(done);
+        }
+
+        match self.done.recv() {
+            Ok(done) => {
+                self.queued -= 1;
+
+                Some(done)
+            }
+            Err(_) => {
+                self.thread_died();
+                None
+            }
+        }
+    }
+
+    /// the jobs the thread had left are gone with it, the ones sent from
+    /// here on are done in place
+    fn thread_died(&mut self) {
+        if self.jobs.take().is_some() {
+            self.queued = self.ran_here.len();
+        }
+    }
+
+    pub fn busy(&self) -> bool {
+        self.queued > 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::read_to_string, time::Instant};
+
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn slow_saves_do_not_block_the_sender() {
+        let temp = temp_dir("worker");
+        let dir = temp.path();
+
+        let mut worker = Worker::spawn_with_delay(Duration::from_millis(200));
+
+        let start = Instant::now();
+
+        worker.send(Job::Save {
+            files: vec![WriteFile {
+                path: dir.join("bindings.json"),
+                contents: b"{}".to_vec(),
+                backup: true,
+            }],
+            pending: 1,
+            commit: None,
+        });
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+        assert!(worker.busy());
+        assert!(worker.try_recv().is_none());
+
+        match worker.recv() {
+            Some(Done::Saved {
+                result, pending, ..
+            }) => {
+                result.unwrap();
+                assert_eq!(pending, 1);
+            }
+            other => panic!("{other:?}"),
+        }
+
+        assert!(!worker.busy());
+        assert!(worker.recv().is_none());
+        assert_eq!(read_to_string(dir.join("bindings.json")).unwrap(), "{}");
+    }
+
+    #[test]
+    fn a_dead_thread_does_not_hang_recv() {
+        let temp = temp_dir("worker-dead");
+        let (jobs, receiver) = channel::<Job>();
+        let (done, done_rx) = channel::<Done>();
+
+        let thread = thread::spawn(move || {
+            let _done = done;
+            let _job = receiver.recv();
+
+            panic!("the disk went away");
+        });
+
+        let mut worker = Worker {
+            jobs: Some(jobs),
+            done: done_rx,
+            ..Default::default()
+        };
+
+        worker.send(Job::Layout {
+            path: temp.path().join("layout.json"),
+            contents: b"{}".to_vec(),
+        });
+
+        assert!(thread.join().is_err());
+        assert!(worker.recv().is_none());
+        assert!(!worker.busy());
+
+        // and it goes on without the thread
+        worker.send(Job::Layout {
+            path: temp.path().join("layout.json"),
+            contents: b"{}".to_vec(),
+        });
+
+        assert!(matches!(worker.recv(), Some(Done::LayoutSaved(Ok(())))));
+        assert!(!worker.busy());
+    }
+}
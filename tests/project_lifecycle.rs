@@ -0,0 +1,1246 @@
+use std::{
+    borrow::Cow,
+    fs::{create_dir_all, read_to_string, remove_dir_all, write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Instant,
+};
+
+use bindings_gui::{
+    bindings::{
+        Binding, Button, ButtonLocation, ControllerType, Debounce, Profile, ProfileBundle, RunWhen,
+        SaveMode, SyncBackend,
+    },
+    constants::Constants,
+    global_state::{Change, GlobalEvents, DEFAULT_DEPLOY_SUBPATH, DISK_CHECK_INTERVAL},
+    notifier::Collected,
+    recovery::BrokenFile,
+    State,
+};
+use bumpalo::Bump;
+use tempfile::TempDir;
+
+#[path = "../src/test_dir.rs"]
+mod test_dir;
+
+struct Project {
+    root: PathBuf,
+    // deleted along with the project
+    _temp: TempDir,
+    arena: Bump,
+    notifier: Collected,
+}
+
+impl Project {
+    fn new(name: &str) -> Self {
+        let temp = test_dir::temp_dir(&format!("lifecycle-{name}"));
+
+        Self {
+            root: temp.path().to_path_buf(),
+            _temp: temp,
+            arena: Bump::new(),
+            notifier: Collected::default(),
+        }
+    }
+
+    fn open(&self) -> State {
+        State::from_directory(self.root.clone(), Path::new(DEFAULT_DEPLOY_SUBPATH)).unwrap()
+    }
+
+    fn deploy(&self) -> PathBuf {
+        self.root.join("src").join("main").join("deploy")
+    }
+
+    /// handles the events the way a frame does, writing out when any of them
+    /// asks for it
+    fn apply(&mut self, state: &mut State, events: impl IntoIterator<Item = GlobalEvents>) {
+        let mut update = false;
+
+        for event in events {
+            update |= state.handle_event(event, &self.arena, &mut self.notifier);
+        }
+
+        if update {
+            state.write_out(&self.arena).unwrap();
+        }
+
+        assert!(
+            self.notifier.errors.is_empty(),
+            "{:?}",
+            self.notifier.errors
+        );
+
+        self.arena.reset();
+    }
+}
+
+fn name(s: &str) -> Rc<String> {
+    Rc::new(s.to_string())
+}
+
+fn binding(controller: u8, button: i16) -> Binding {
+    Binding {
+        controller,
+        button: Button {
+            button,
+            location: ButtonLocation::Button,
+        },
+        during: RunWhen::OnTrue,
+        debounce: Debounce::NONE,
+        threshold: None,
+        modifier: None,
+        enabled: true,
+    }
+}
+
+fn assert_same(a: &State, b: &State) {
+    assert_eq!(a.commands, b.commands);
+    assert_eq!(a.streams, b.streams);
+    assert_eq!(
+        a.bindings.command_to_bindings,
+        b.bindings.command_to_bindings
+    );
+    assert_eq!(a.stream_to_axis, b.stream_to_axis);
+    assert_eq!(a.controllers, b.controllers);
+    assert_eq!(a.controller_names, b.controller_names);
+    assert_eq!(a.constants, b.constants);
+    assert_eq!(a.driver_constants, b.driver_constants);
+    assert_eq!(a.profile, b.profile);
+    assert_eq!(a.targets, b.targets);
+}
+
+#[test]
+fn edits_survive_reload() {
+    let mut project = Project::new("reload");
+
+    let mut state = project.open();
+
+    assert_eq!(state.profile.as_str(), "default");
+
+    state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+    state.controllers[1] = ControllerType::Generic {
+        buttons: 12,
+        axises: 4,
+        sensitivity: 0.25,
+    };
+    state.controller_names[0] = name("driver");
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddCommand("intake".to_string()),
+            GlobalEvents::AddStream("drive".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+            GlobalEvents::AddBinding(binding(1, 12), name("intake")),
+            GlobalEvents::SetStream(name("drive"), 0, 1),
+            GlobalEvents::AddOption(Rc::new(vec![name("speed")]), Constants::Float(0.5)),
+            GlobalEvents::Save,
+        ],
+    );
+
+    let reloaded = project.open();
+
+    assert_same(&state, &reloaded);
+    assert_eq!(reloaded.bindings.command_to_bindings.len(), 2);
+}
+
+#[test]
+fn profiles_keep_their_own_bindings() {
+    let mut project = Project::new("profiles");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+        ],
+    );
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddProfile("demo".to_string()),
+            GlobalEvents::SetProfile(name("demo")),
+        ],
+    );
+
+    assert_eq!(state.profile.as_str(), "demo");
+    assert!(state.bindings.command_to_bindings.is_empty());
+
+    project.apply(
+        &mut state,
+        [GlobalEvents::AddBinding(binding(0, 2), name("shoot"))],
+    );
+
+    let reloaded = project.open();
+
+    assert_same(&state, &reloaded);
+    assert_eq!(reloaded.profiles.len(), 2);
+
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("default"))]);
+
+    assert_eq!(
+        state.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1)]
+    );
+}
+
+#[test]
+fn profile_pointers_are_normalized_on_load() {
+    let mut project = Project::new("pointer-form");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::DuplicateProfile {
+                name: "caf\u{e9}".to_string(),
+                switch: true,
+            },
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+        ],
+    );
+
+    // the same name with a combining accent, as an older version wrote it
+    write(project.deploy().join("profile"), "cafe\u{301}").unwrap();
+
+    let reloaded = project.open();
+
+    assert_eq!(reloaded.profile.as_str(), "caf\u{e9}");
+    assert_eq!(reloaded.profiles.len(), 2);
+    assert_eq!(
+        reloaded.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1)]
+    );
+}
+
+#[test]
+fn unnormalized_profiles_are_only_renamed_when_asked() {
+    let mut project = Project::new("profile-form");
+
+    let mut state = project.open();
+
+    project.apply(&mut state, [GlobalEvents::AddCommand("shoot".to_string())]);
+
+    // "e" and a combining accent, as an older version wrote it
+    let old = project.deploy().join("bindings").join("cafe\u{301}.json");
+    let new = project.deploy().join("bindings").join("caf\u{e9}.json");
+
+    create_dir_all(old.parent().unwrap()).unwrap();
+    write(&old, "{}").unwrap();
+
+    let mut state = project.open();
+
+    assert_eq!(state.unnormalized_profiles, vec![name("cafe\u{301}")]);
+    assert!(old.exists());
+
+    project.apply(&mut state, [GlobalEvents::NormalizeProfileNames]);
+
+    assert!(state.unnormalized_profiles.is_empty());
+    assert!(state.profiles.contains(&name("caf\u{e9}")));
+    assert!(!old.exists());
+    assert!(new.exists());
+}
+
+#[test]
+fn unsaved_profiles_can_be_switched_back_to() {
+    let mut project = Project::new("unsaved-profile");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddProfile("demo".to_string()),
+            GlobalEvents::SetProfile(name("demo")),
+            GlobalEvents::SetProfile(name("default")),
+        ],
+    );
+
+    assert_eq!(state.profile.as_str(), "default");
+    assert_eq!(state.missing_profile, None);
+}
+
+#[test]
+fn duplicates_are_switched_to_once_written() {
+    let mut project = Project::new("duplicate-switch");
+
+    let mut state = project.open();
+
+    // "e" and a combining accent, saved as the single "é"
+    project.apply(
+        &mut state,
+        [GlobalEvents::DuplicateProfile {
+            name: "cafe\u{301}".to_string(),
+            switch: true,
+        }],
+    );
+
+    assert_eq!(state.profile.as_str(), "caf\u{e9}");
+    assert_eq!(state.missing_profile, None);
+
+    // a name that's taken is never switched to
+    state.handle_event(
+        GlobalEvents::DuplicateProfile {
+            name: "default".to_string(),
+            switch: true,
+        },
+        &project.arena,
+        &mut project.notifier,
+    );
+
+    assert_eq!(state.profile.as_str(), "caf\u{e9}");
+    assert_eq!(project.notifier.errors.len(), 1);
+}
+
+#[test]
+fn rename_reaches_every_profile() {
+    let mut project = Project::new("rename");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+            GlobalEvents::RenameCommand(name("shoot"), name("fire")),
+        ],
+    );
+
+    assert!(state.commands.contains(&name("fire")));
+    assert!(!state.commands.contains(&name("shoot")));
+
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("demo"))]);
+
+    assert_eq!(
+        state.bindings.command_to_bindings[&name("fire")],
+        vec![binding(0, 1)]
+    );
+    assert!(!state
+        .bindings
+        .command_to_bindings
+        .contains_key(&name("shoot")));
+}
+
+#[test]
+fn manual_saves_are_not_taken_by_other_edits() {
+    let mut project = Project::new("manual-rename");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+            GlobalEvents::SetSaveMode(SaveMode::Manual),
+        ],
+    );
+    state.finish_io().unwrap();
+
+    let save_file = project.deploy().join("bindings.json");
+    let profile_file = state.profile_path("default");
+    let saved = read_to_string(&save_file).unwrap();
+    let saved_profile = read_to_string(&profile_file).unwrap();
+
+    // an edit waiting for a save, then one that rewrites every profile
+    for event in [
+        GlobalEvents::AddCommand("climb".to_string()),
+        GlobalEvents::RenameCommand(name("shoot"), name("fire")),
+    ] {
+        if state.handle_event(event, &project.arena, &mut project.notifier) {
+            state.mark_dirty();
+        }
+    }
+    state.finish_io().unwrap();
+
+    assert!(state.unsaved);
+    assert!(state.commands.contains(&name("fire")));
+    assert_eq!(read_to_string(&save_file).unwrap(), saved);
+    assert_eq!(read_to_string(&profile_file).unwrap(), saved_profile);
+
+    // switching asks what to do with them first
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("demo"))]);
+
+    assert_eq!(state.profile.as_str(), "default");
+    assert_eq!(state.unsaved_switch, Some(name("demo")));
+    assert_eq!(read_to_string(&save_file).unwrap(), saved);
+
+    project.apply(&mut state, [GlobalEvents::DiscardAndSwitch]);
+
+    assert_eq!(state.profile.as_str(), "demo");
+    assert_eq!(state.unsaved_switch, None);
+    assert!(!state.commands.contains(&name("climb")));
+    assert_eq!(read_to_string(&save_file).unwrap(), saved);
+
+    state.handle_event(
+        GlobalEvents::AddCommand("climb".to_string()),
+        &project.arena,
+        &mut project.notifier,
+    );
+    state.mark_dirty();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::SetProfile(name("default")),
+            GlobalEvents::SaveAndSwitch,
+        ],
+    );
+    state.finish_io().unwrap();
+
+    assert_eq!(state.profile.as_str(), "default");
+    assert!(!state.unsaved);
+    assert!(project.open().commands.contains(&name("climb")));
+}
+
+#[test]
+fn renamed_constants_keep_driver_overrides() {
+    let mut project = Project::new("rename-option");
+
+    let mut state = project.open();
+
+    let path = |keys: &[&str]| Rc::new(keys.iter().map(|k| name(k)).collect::<Vec<_>>());
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddOption(
+                path(&["shooter"]),
+                Constants::Object {
+                    map: Default::default(),
+                },
+            ),
+            GlobalEvents::AddOption(
+                path(&["shooter", "speed"]),
+                Constants::Driver {
+                    default: Box::new(Constants::Float(1.0)),
+                },
+            ),
+            GlobalEvents::AddOption(path(&["shooter", "angle"]), Constants::Float(30.0)),
+            GlobalEvents::AddOptionDriver(path(&["shooter", "speed"]), Constants::Float(0.8)),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+            GlobalEvents::RenameOption(path(&["shooter", "speed"]), name("rpm")),
+        ],
+    );
+
+    let taken = state.handle_event(
+        GlobalEvents::RenameOption(path(&["shooter", "angle"]), name("rpm")),
+        &project.arena,
+        &mut project.notifier,
+    );
+
+    assert!(!taken);
+    assert_eq!(project.notifier.errors.len(), 1);
+
+    project.notifier.errors.clear();
+
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("demo"))]);
+
+    let Constants::Object { map } = &state.constants else {
+        panic!("constants should be an object");
+    };
+    let Constants::Object { map: shooter } = &map[&name("shooter")] else {
+        panic!("shooter should be an object");
+    };
+
+    assert_eq!(
+        shooter.keys().cloned().collect::<Vec<_>>(),
+        vec![name("angle"), name("rpm")]
+    );
+
+    let Constants::Object { map } = &state.driver_constants else {
+        panic!("driver constants should be an object");
+    };
+
+    assert_eq!(
+        map[&name("shooter")],
+        Constants::Object {
+            map: [(name("rpm"), Constants::Float(0.8))].into_iter().collect()
+        }
+    );
+}
+
+#[test]
+fn renames_stop_when_a_profile_changed_on_disk() {
+    let mut project = Project::new("rename-conflict");
+
+    let mut state = project.open();
+
+    let speed = Rc::new(vec![name("speed")]);
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddOption(speed.clone(), Constants::Float(1.0)),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+        ],
+    );
+    state.finish_io().unwrap();
+
+    // edited by hand while the app had it open
+    write(project.deploy().join("bindings").join("demo.json"), "{}").unwrap();
+
+    for event in [
+        GlobalEvents::RenameCommand(name("shoot"), name("fire")),
+        GlobalEvents::RenameOption(speed.clone(), name("rpm")),
+        GlobalEvents::RemoveOption(speed.clone()),
+    ] {
+        assert!(!state.handle_event(event, &project.arena, &mut project.notifier));
+    }
+
+    assert_eq!(project.notifier.errors.len(), 3);
+    assert!(state.commands.contains(&name("shoot")));
+    assert!(!state.commands.contains(&name("fire")));
+    assert_eq!(state.constants.get(&speed), Some(&Constants::Float(1.0)));
+}
+
+#[test]
+fn deployed_constants_follow_the_active_profile() {
+    let mut project = Project::new("resolved-constants");
+
+    let mut state = project.open();
+
+    let speed = Rc::new(vec![name("speed")]);
+    let deployed = |project: &Project| {
+        let saved: serde_json::Value =
+            serde_json::from_str(&read_to_string(project.deploy().join("bindings.json")).unwrap())
+                .unwrap();
+
+        saved["resolved_constants"]["map"]["speed"].clone()
+    };
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddOption(
+                speed.clone(),
+                Constants::Driver {
+                    default: Box::new(Constants::Float(1.0)),
+                },
+            ),
+            GlobalEvents::AddOptionDriver(speed, Constants::Float(0.8)),
+        ],
+    );
+
+    assert_eq!(deployed(&project), 0.8);
+
+    project.apply(&mut state, [GlobalEvents::ResetProfile(name("empty"))]);
+
+    assert_eq!(deployed(&project), 1.0);
+
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("default"))]);
+
+    assert_eq!(deployed(&project), 0.8);
+    assert_eq!(
+        state.resolved_constants().get(&[name("speed")]),
+        Some(&Constants::Float(0.8))
+    );
+}
+
+#[test]
+fn corrupt_profile_is_quarantined() {
+    let mut project = Project::new("corrupt");
+
+    let deploy = project.deploy();
+
+    create_dir_all(deploy.join("bindings")).unwrap();
+    write(
+        deploy.join("bindings.json"),
+        r#"{"url": null, "commands": ["shoot"], "constants": null, "streams": []}"#,
+    )
+    .unwrap();
+    write(
+        deploy.join("bindings").join("default.json"),
+        r#"{
+            "command_to_bindings": {
+                "shoot": [
+                    {"controller": 0, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"},
+                    {"controller": 9, "button": {"button": 1, "location": "Button"}, "during": "OnTrue"}
+                ]
+            },
+            "stream_to_axis": {},
+            "controllers": ["NotBound", "NotBound", "NotBound", "NotBound", "NotBound"],
+            "controller_names": ["", "", "", "", ""],
+            "constants": null,
+            "from_the_future": true
+        }"#,
+    )
+    .unwrap();
+
+    let mut state = project.open();
+
+    assert!(state.show_quarantine);
+    assert_eq!(
+        state.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1)]
+    );
+
+    project.apply(
+        &mut state,
+        [GlobalEvents::KeepQuarantine, GlobalEvents::Save],
+    );
+
+    let reloaded = project.open();
+
+    assert_same(&state, &reloaded);
+    assert_eq!(reloaded.quarantine, state.quarantine);
+    assert!(!reloaded.quarantine.is_empty());
+}
+
+#[test]
+fn cooldowns_out_of_range_are_dropped_on_load() {
+    let mut project = Project::new("cooldowns");
+
+    create_dir_all(project.deploy()).unwrap();
+    write(
+        project.deploy().join("bindings.json"),
+        r#"{
+            "url": null, "commands": ["intake", "shoot", "climb"], "constants": null, "streams": [],
+            "command_cooldowns": {"intake": -1.0, "shoot": 0.5, "climb": 600.0}
+        }"#,
+    )
+    .unwrap();
+
+    let mut state = project.open();
+
+    assert_eq!(
+        state.command_cooldowns.iter().collect::<Vec<_>>(),
+        [(&name("shoot"), &0.5)]
+    );
+    assert_eq!(state.load_warnings.len(), 2);
+    assert!(state.load_warnings[0].contains("climb"));
+
+    // and the file the robot gets no longer has them either
+    project.apply(&mut state, [GlobalEvents::Save]);
+
+    let saved = read_to_string(project.deploy().join("bindings.json")).unwrap();
+
+    assert!(!saved.contains("600"));
+    assert!(project.open().load_warnings.is_empty());
+}
+
+#[test]
+fn purge_strips_command_from_every_profile() {
+    let mut project = Project::new("purge");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+        ],
+    );
+
+    assert_eq!(state.is_used(&name("shoot")).unwrap().len(), 2);
+
+    project.apply(&mut state, [GlobalEvents::PurgeCommand(name("shoot"))]);
+
+    assert!(!state.commands.contains(&name("shoot")));
+    assert!(state.is_used(&name("shoot")).unwrap().is_empty());
+    assert_same(&state, &project.open());
+}
+
+#[test]
+fn export_then_import_is_lossless() {
+    let mut project = Project::new("export");
+
+    let mut state = project.open();
+
+    state.description = "left station driver".to_string();
+    state.controllers[0] = ControllerType::XBox { sensitivity: 0.5 };
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddStream("drive".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+            GlobalEvents::SetStream(name("drive"), 0, 1),
+        ],
+    );
+
+    let export = project.root.join("export.json");
+
+    state.export_profile(&export).unwrap();
+
+    let bundle =
+        ProfileBundle::from_str_lenient(&std::fs::read_to_string(&export).unwrap()).unwrap();
+
+    project.apply(
+        &mut state,
+        [GlobalEvents::ImportProfile(
+            "copy".to_string(),
+            Rc::new(bundle),
+        )],
+    );
+
+    let original = state.profile_data("default").unwrap();
+    let copy = state.profile_data("copy").unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&original).unwrap(),
+        serde_json::to_value(&copy).unwrap()
+    );
+}
+
+#[test]
+fn a_failed_import_adds_nothing() {
+    let mut project = Project::new("failed-import");
+
+    let mut state = project.open();
+
+    project.apply(&mut state, [GlobalEvents::AddCommand("climb".to_string())]);
+
+    let bundle = ProfileBundle {
+        profile: Profile::default(),
+        commands: [name("shoot")].into(),
+        streams: [name("drive")].into(),
+        constants: Cow::Owned(state.constants.clone()),
+    };
+
+    // a directory where the profile's temporary file goes stops it being written
+    create_dir_all(project.deploy().join("bindings").join("copy.json.tmp")).unwrap();
+
+    assert!(!state.handle_event(
+        GlobalEvents::ImportProfile("copy".to_string(), Rc::new(bundle)),
+        &project.arena,
+        &mut project.notifier,
+    ));
+
+    assert_eq!(project.notifier.errors.len(), 1);
+    assert!(!state.commands.contains(&name("shoot")));
+    assert!(!state.streams.contains(&name("drive")));
+    assert!(!state.profiles.contains(&name("copy")));
+}
+
+#[test]
+fn outside_edits_are_not_overwritten() {
+    let mut project = Project::new("outside");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+        ],
+    );
+
+    let profile = project.deploy().join("bindings").join("default.json");
+
+    let mut theirs = project.open();
+
+    project.apply(
+        &mut theirs,
+        [GlobalEvents::AddBinding(binding(0, 2), name("shoot"))],
+    );
+
+    let on_disk = read_to_string(&profile).unwrap();
+
+    state.handle_event(
+        GlobalEvents::AddBinding(binding(0, 3), name("shoot")),
+        &project.arena,
+        &mut project.notifier,
+    );
+
+    assert!(state.write_out(&project.arena).is_err());
+    assert_eq!(read_to_string(&profile).unwrap(), on_disk);
+    assert_eq!(state.external_change, Some(vec![profile.clone()]));
+
+    project.apply(&mut state, [GlobalEvents::TakeTheirs]);
+
+    assert!(state.external_change.is_none());
+    assert_eq!(
+        state.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1), binding(0, 2)]
+    );
+
+    project.apply(
+        &mut state,
+        [GlobalEvents::AddBinding(binding(0, 3), name("shoot"))],
+    );
+
+    assert_eq!(
+        project.open().bindings.command_to_bindings[&name("shoot")].len(),
+        3
+    );
+}
+
+#[test]
+fn keep_mine_overwrites_after_asking() {
+    let mut project = Project::new("keep-mine");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+        ],
+    );
+
+    let demo = project.deploy().join("bindings").join("demo.json");
+
+    write(&demo, "{}").unwrap();
+
+    // renames touch every profile, none of them are written when one changed
+    assert!(state
+        .map_profiles(
+            |_, profile| profile.description = "renamed".into(),
+            &project.arena
+        )
+        .is_err());
+    assert_eq!(read_to_string(&demo).unwrap(), "{}");
+
+    project.apply(&mut state, [GlobalEvents::MergeTheirs]);
+
+    assert_eq!(project.notifier.info.len(), 1);
+    assert!(state.external_change.is_none());
+    assert!(!state.profiles.contains(&name("default-theirs")));
+
+    project.apply(&mut state, [GlobalEvents::Save]);
+
+    let profile = project.deploy().join("bindings").join("default.json");
+
+    write(&profile, "{}").unwrap();
+
+    assert!(state.write_out(&project.arena).is_err());
+
+    project.apply(&mut state, [GlobalEvents::MergeTheirs]);
+
+    assert!(state.profiles.contains(&name("default-theirs")));
+    assert_ne!(read_to_string(&profile).unwrap(), "{}");
+    assert_eq!(
+        read_to_string(
+            project
+                .deploy()
+                .join("bindings")
+                .join("default-theirs.json")
+        )
+        .unwrap(),
+        "{}"
+    );
+}
+
+#[test]
+fn truncated_files_load_from_their_backup() {
+    let mut project = Project::new("truncated");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+        ],
+    );
+    project.apply(&mut state, [GlobalEvents::AddCommand("climb".to_string())]);
+
+    let bindings = project.deploy().join("bindings.json");
+    let profile = project.deploy().join("bindings").join("default.json");
+
+    // a save cut off by a power loss
+    for path in [&bindings, &profile] {
+        let whole = read_to_string(path).unwrap();
+
+        write(path, &whole[..whole.len() / 2]).unwrap();
+    }
+
+    let recovered = project.open();
+
+    assert_eq!(recovered.load_warnings.len(), 2);
+    assert!(recovered.commands.contains(&name("shoot")));
+    assert!(!recovered.commands.contains(&name("climb")));
+    assert_eq!(
+        recovered.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1)]
+    );
+}
+
+#[test]
+fn broken_files_offer_recovery() {
+    let mut project = Project::new("broken");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::DuplicateProfile {
+                name: "demo".to_string(),
+                switch: false,
+            },
+        ],
+    );
+
+    let demo = project.deploy().join("bindings").join("demo.json");
+
+    write(&demo, "{\n  \"command_to_bindings\": {\n").unwrap();
+
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("demo"))]);
+
+    // still on the profile it was on
+    assert_eq!(state.profile.as_str(), "default");
+
+    let (profile, broken) = state.broken_profile.clone().unwrap();
+
+    assert_eq!(profile.as_str(), "demo");
+    assert_eq!(broken.path, demo);
+    assert_eq!(broken.line, 3);
+
+    broken.set_aside().unwrap();
+
+    // with the broken file set aside there is nothing left to open
+    project.apply(&mut state, [GlobalEvents::SetProfile(name("demo"))]);
+
+    assert!(state.broken_profile.is_none());
+    assert_eq!(state.missing_profile.as_deref().unwrap(), "demo");
+
+    project.apply(&mut state, [GlobalEvents::ResetProfile(name("demo"))]);
+
+    assert_eq!(state.profile.as_str(), "demo");
+    assert!(state.broken_profile.is_none());
+    assert!(project
+        .deploy()
+        .join("bindings")
+        .join("demo.json.broken")
+        .exists());
+
+    let bindings = project.deploy().join("bindings.json");
+
+    // the switches wrote it again, without its backup there is nothing to
+    // fall back on
+    std::fs::remove_file(project.deploy().join("bindings.json.bak")).unwrap();
+    write(&bindings, "{\"url\": ").unwrap();
+
+    let err =
+        State::from_directory(project.root.clone(), Path::new(DEFAULT_DEPLOY_SUBPATH)).unwrap_err();
+
+    assert_eq!(BrokenFile::from_error(&err).unwrap().path, bindings);
+}
+
+#[test]
+fn pulled_changes_are_noticed_before_saving() {
+    let mut project = Project::new("pulled");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::AddCommand("shoot".to_string()),
+            GlobalEvents::AddBinding(binding(0, 1), name("shoot")),
+        ],
+    );
+
+    let now = Instant::now();
+
+    state.check_disk(now).unwrap();
+
+    assert!(state.external_change.is_none());
+
+    // a teammate's bindings come in with a git pull
+    let mut theirs = project.open();
+
+    project.apply(
+        &mut theirs,
+        [
+            GlobalEvents::AddBinding(binding(0, 2), name("shoot")),
+            GlobalEvents::DuplicateProfile {
+                name: "practice".to_string(),
+                switch: false,
+            },
+        ],
+    );
+
+    state.check_disk(now).unwrap();
+
+    assert!(state.external_change.is_none());
+
+    state.check_disk(now + DISK_CHECK_INTERVAL).unwrap();
+
+    assert!(state.profiles.contains(&name("practice")));
+    assert!(state
+        .external_change
+        .as_ref()
+        .unwrap()
+        .contains(&project.deploy().join("bindings").join("default.json")));
+
+    project.apply(&mut state, [GlobalEvents::TakeTheirs]);
+
+    assert!(state.external_change.is_none());
+    assert_eq!(
+        state.bindings.command_to_bindings[&name("shoot")],
+        vec![binding(0, 1), binding(0, 2)]
+    );
+}
+
+#[test]
+fn deploy_files_can_live_elsewhere() {
+    let mut project = Project::new("elsewhere");
+
+    let subpath = Path::new("robot/deploy");
+
+    let mut state = State::from_directory(project.root.clone(), subpath).unwrap();
+
+    assert_eq!(state.deploy_dir, project.root.join("robot").join("deploy"));
+    assert_eq!(state.project_root(), project.root);
+
+    project.apply(&mut state, [GlobalEvents::AddCommand("shoot".to_string())]);
+
+    assert!(project.root.join("robot/deploy/bindings.json").exists());
+    assert!(!project.deploy().exists());
+
+    let reloaded = State::from_directory(project.root.clone(), subpath).unwrap();
+
+    assert_same(&state, &reloaded);
+
+    for outside in ["", "../deploy", "/deploy"] {
+        assert!(State::from_directory(project.root.clone(), Path::new(outside)).is_err());
+    }
+}
+
+#[test]
+fn read_only_profile_pointer_is_kept() {
+    let project = Project::new("readonly-pointer");
+
+    create_dir_all(project.deploy()).unwrap();
+
+    let pointer = project.deploy().join("profile");
+
+    write(&pointer, "comp").unwrap();
+
+    let mut permissions = std::fs::metadata(&pointer).unwrap().permissions();
+    permissions.set_readonly(true);
+    std::fs::set_permissions(&pointer, permissions).unwrap();
+
+    let state = project.open();
+
+    assert_eq!(state.profile.as_str(), "comp");
+    assert_eq!(read_to_string(&pointer).unwrap(), "comp");
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_profile_pointer_is_an_error() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let project = Project::new("unreadable-pointer");
+
+    create_dir_all(project.deploy()).unwrap();
+
+    let pointer = project.deploy().join("profile");
+
+    write(&pointer, "comp").unwrap();
+
+    std::fs::set_permissions(&pointer, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    // root reads it anyway, nothing to check there
+    if read_to_string(&pointer).is_err() {
+        let err = State::from_directory(project.root.clone(), Path::new(DEFAULT_DEPLOY_SUBPATH))
+            .unwrap_err();
+
+        assert!(format!("{err:#}").contains(&pointer.display().to_string()));
+    }
+
+    std::fs::set_permissions(&pointer, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    assert_eq!(read_to_string(&pointer).unwrap(), "comp");
+}
+
+#[test]
+fn profile_directory_is_an_error() {
+    let project = Project::new("pointer-directory");
+
+    let pointer = project.deploy().join("profile");
+
+    create_dir_all(pointer.join("inside")).unwrap();
+
+    assert!(
+        State::from_directory(project.root.clone(), Path::new(DEFAULT_DEPLOY_SUBPATH)).is_err()
+    );
+
+    assert!(pointer.join("inside").is_dir());
+}
+
+#[test]
+fn saves_can_be_committed_to_git() {
+    let mut project = Project::new("git-commit");
+
+    let root = project.root.clone();
+
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(&root)
+            .args(args)
+            .output()
+    };
+
+    if !git(&["init", "-q"]).is_ok_and(|o| o.status.success()) {
+        // no git to test with
+        return;
+    }
+
+    git(&["config", "user.name", "test"]).unwrap();
+    git(&["config", "user.email", "test@example.com"]).unwrap();
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::SetCommitOnSave(true),
+            GlobalEvents::AddCommand("shoot".to_string()),
+        ],
+    );
+
+    state.finish_io().unwrap();
+
+    assert!(state.load_warnings.is_empty(), "{:?}", state.load_warnings);
+
+    let log = git(&["log", "--format=%s", "--name-only"]).unwrap();
+    let log = String::from_utf8_lossy(&log.stdout);
+
+    assert!(log.starts_with("bindings_gui: "));
+    assert!(log.contains("src/main/deploy/bindings.json"));
+    assert!(log.contains("src/main/deploy/bindings/default.json"));
+    assert!(!log.contains(".bak"));
+
+    // stays on once reloaded
+    assert!(project.open().commit_on_save);
+}
+
+#[test]
+fn robot_login_survives_a_reload() {
+    let mut project = Project::new("robot-login");
+
+    let mut state = project.open();
+
+    project.apply(
+        &mut state,
+        [
+            GlobalEvents::SetTeamNumber(Some(9094)),
+            GlobalEvents::SetUrl(Some("10.90.94.2".to_string())),
+            GlobalEvents::SetSshUser("lvuser".to_string()),
+            GlobalEvents::SetRemotePath("~/deploy".to_string()),
+            GlobalEvents::SetSyncBackend(SyncBackend::Native),
+            GlobalEvents::AddSyncTarget("practice".to_string()),
+            GlobalEvents::SetUrl(Some("10.90.94.12".to_string())),
+            GlobalEvents::SetSyncTargetEnabled("practice".to_string(), false),
+        ],
+    );
+
+    state.finish_io().unwrap();
+
+    let reloaded = project.open();
+
+    assert_eq!(reloaded.team_number, Some(9094));
+    assert_eq!(reloaded.sync_backend, SyncBackend::Native);
+    assert_eq!(reloaded.targets.len(), 2);
+
+    let robot = &reloaded.targets[0];
+
+    assert_eq!(robot.name, "robot");
+    assert_eq!(robot.url, "10.90.94.2");
+    assert_eq!(robot.user, "lvuser");
+    assert_eq!(robot.path, "~/deploy/");
+    assert!(robot.enabled);
+
+    let practice = &reloaded.targets[1];
+
+    assert_eq!(practice.url, "10.90.94.12");
+    assert_eq!(practice.user, "admin");
+    assert!(!practice.enabled);
+}
+
+#[test]
+fn robot_files_replace_local_ones_once_confirmed() {
+    let mut laptop = Project::new("pull-laptop");
+    let mut robot = Project::new("pull-robot");
+
+    let mut ours = laptop.open();
+    laptop.apply(&mut ours, [GlobalEvents::AddCommand("shoot".to_string())]);
+    ours.finish_io().unwrap();
+
+    let mut theirs = robot.open();
+    robot.apply(&mut theirs, [GlobalEvents::AddCommand("climb".to_string())]);
+    theirs.finish_io().unwrap();
+
+    // a download without bindings.json changes nothing
+    assert!(ours.read_pulled(robot.root.clone()).is_err());
+    assert!(ours.pulled.is_none());
+
+    ours.read_pulled(robot.deploy()).unwrap();
+
+    let (_, changes) = ours.pulled.as_ref().unwrap();
+
+    assert!(changes.contains(&Change::Added("command climb".to_string())));
+    assert!(changes.contains(&Change::Removed("command shoot".to_string())));
+
+    // nothing is replaced until it's confirmed
+    assert!(laptop.open().commands.contains(&name("shoot")));
+
+    // a profile that can't be written leaves every file as it was
+    let save_file = laptop.deploy().join("bindings.json");
+    let before = read_to_string(&save_file).unwrap();
+    let blocked = PathBuf::from(format!(
+        "{}.tmp",
+        ours.profile_path(&theirs.profile).display()
+    ));
+
+    create_dir_all(&blocked).unwrap();
+
+    laptop.apply(&mut ours, [GlobalEvents::UsePulled]);
+
+    assert!(ours.finish_io().is_err());
+    assert_eq!(read_to_string(&save_file).unwrap(), before);
+    assert!(!laptop.deploy().join("bindings.json.tmp").exists());
+    assert!(laptop.open().commands.contains(&name("shoot")));
+
+    remove_dir_all(&blocked).unwrap();
+    ours.read_pulled(robot.deploy()).unwrap();
+
+    laptop.apply(&mut ours, [GlobalEvents::UsePulled]);
+    ours.finish_io().unwrap();
+
+    assert!(ours.pulled.is_none());
+    assert!(ours.commands.contains(&name("climb")));
+    assert!(!ours.commands.contains(&name("shoot")));
+    assert_same(&ours, &laptop.open());
+}